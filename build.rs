@@ -0,0 +1,53 @@
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        let mut config = cbindgen::Config {
+            language: cbindgen::Language::C,
+            include_guard: Some("KICK_CLIENT_H".to_string()),
+            autogen_warning: Some(
+                "/* Generated from src/ffi.rs by the build script, do not edit. */".to_string(),
+            ),
+            cpp_compat: true,
+            usize_is_size_t: true,
+            ..Default::default()
+        };
+        config
+            .export
+            .rename
+            .insert("FfiClient".to_string(), "kick_client".to_string());
+        let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src("src/ffi.rs")
+            .generate()
+            .expect("failed to generate the C header")
+            .write_to_file(format!("{}/kick_client.h", out_dir));
+    }
+    #[cfg(feature = "protobuf")]
+    {
+        println!("cargo:rerun-if-changed=proto/kick_client.proto");
+        // protox compiles the schema without requiring `protoc` to be installed.
+        #[cfg(not(feature = "grpc"))]
+        {
+            let descriptors = protox::compile(["proto/kick_client.proto"], ["proto"])
+                .expect("proto/kick_client.proto is invalid");
+            prost_build::Config::new()
+                .compile_fds(descriptors)
+                .expect("failed to generate protobuf messages");
+        }
+        // The service is generated along with the messages, in the same package.
+        #[cfg(feature = "grpc")]
+        {
+            println!("cargo:rerun-if-changed=proto/chat_service.proto");
+            let descriptors = protox::compile(
+                ["proto/kick_client.proto", "proto/chat_service.proto"],
+                ["proto"],
+            )
+            .expect("proto/chat_service.proto is invalid");
+            tonic_build::configure()
+                .compile_fds(descriptors)
+                .expect("failed to generate the gRPC service");
+        }
+    }
+}
@@ -0,0 +1,13 @@
+#![no_main]
+
+use kick_client::KickChatMessage;
+use libfuzzer_sys::fuzz_target;
+
+// Frames come from the network, so parsing them, including the JSON strings nested in
+// their `data`, must never panic, whatever the input.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(message) = serde_json::from_slice::<KickChatMessage>(data) {
+        let _ = message.chatroom_id();
+        let _ = serde_json::to_string(&message);
+    }
+});
@@ -0,0 +1,70 @@
+#ifndef KICK_CLIENT_H
+#define KICK_CLIENT_H
+
+/* Generated from src/ffi.rs by the build script, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A client reading chatrooms in a background thread, `kick_client` in C.
+ */
+typedef struct kick_client kick_client;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates a client subscribed to `chatroom_ids_len` chatrooms of `chatroom_ids`, which
+ * connects in the background to `url`, or Kick's server if `url` is `NULL`. Returns `NULL`
+ * if `url` isn't UTF-8 or the background thread cannot be started.
+ *
+ * # Safety
+ *
+ * `url` must be `NULL` or a NUL-terminated string, and `chatroom_ids` must point to
+ * `chatroom_ids_len` IDs, or may be `NULL` if `chatroom_ids_len` is 0.
+ */
+struct kick_client *kick_client_new(const char *url,
+                                    const uint64_t *chatroom_ids,
+                                    size_t chatroom_ids_len);
+
+/**
+ * Waits up to `timeout_ms` milliseconds for the next message, returning it as a JSON
+ * string to be freed with `kick_client_string_free`. Returns `NULL` if no message was
+ * received in time, or `client` is `NULL`. A timeout of 0 only returns a message that
+ * was already received.
+ *
+ * # Safety
+ *
+ * `client` must be `NULL` or a client returned by `kick_client_new` and not yet freed,
+ * which isn't polled from another thread at the same time.
+ */
+char *kick_client_poll(struct kick_client *client, uint32_t timeout_ms);
+
+/**
+ * Frees a string returned by `kick_client_poll`. Does nothing if `string` is `NULL`.
+ *
+ * # Safety
+ *
+ * `string` must be `NULL` or a string returned by `kick_client_poll` and not yet freed.
+ */
+void kick_client_string_free(char *string);
+
+/**
+ * Closes the connection and frees a client. Does nothing if `client` is `NULL`.
+ *
+ * # Safety
+ *
+ * `client` must be `NULL` or a client returned by `kick_client_new` and not yet freed.
+ */
+void kick_client_free(struct kick_client *client);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KICK_CLIENT_H */
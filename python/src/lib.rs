@@ -0,0 +1,98 @@
+//! The `kick_client` Python module, reading live chat as an async iterator of dicts.
+//!
+//! ```python
+//! import asyncio
+//! import kick_client
+//!
+//! async def main():
+//!     async for event in kick_client.connect([668]):
+//!         if event["kind"] == "chat":
+//!             print(event["data"]["sender"]["username"], event["data"]["content"])
+//!
+//! asyncio.run(main())
+//! ```
+//!
+//! Every event is the JSON form of a `KickChatMessage`, as `encoding::to_json_value`
+//! returns it, with the `kind` of its data and the ID of its chatroom added.
+
+use kick_client::encoding::to_json_value;
+use kick_client::reconnect::ReconnectingClient;
+use kick_client::{KickChatMessage, DEFAULT_WEBSOCKET_URL};
+use pyo3::exceptions::{PyStopAsyncIteration, PyValueError};
+use pyo3::prelude::*;
+use serde_json::Value;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Live chat of a set of chatrooms, reconnecting as needed until it is closed.
+#[pyclass(module = "kick_client")]
+struct ChatStream {
+    /// The client, or `None` once the stream is closed.
+    client: Arc<Mutex<Option<ReconnectingClient>>>,
+}
+
+#[pymethods]
+impl ChatStream {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = Arc::clone(&self.client);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let mut client = client.lock().await;
+            let message = match client.as_mut() {
+                Some(client) => client.read_message().await.ok().flatten(),
+                None => None,
+            };
+            let Some(message) = message else {
+                return Err(PyStopAsyncIteration::new_err(()));
+            };
+            let event = to_event(&message)?;
+            Python::with_gil(|py| {
+                pythonize::pythonize(py, &event)
+                    .map(Bound::unbind)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))
+            })
+        })
+    }
+
+    /// Closes the connection, ending the iteration once the event being read, if any, is
+    /// received.
+    fn close<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let client = Arc::clone(&self.client);
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            client.lock().await.take();
+            Ok(())
+        })
+    }
+}
+
+/// Returns the event dict of a message, as JSON.
+fn to_event(message: &KickChatMessage) -> PyResult<Value> {
+    let mut event = to_json_value(message).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    if let Value::Object(fields) = &mut event {
+        fields.insert("kind".to_string(), message.data.kind().into());
+        fields.insert("chatroom_id".to_string(), message.chatroom_id().into());
+    }
+    Ok(event)
+}
+
+/// Subscribes to the chatrooms, connecting to `url` or Kick's server on the first read.
+#[pyfunction]
+#[pyo3(signature = (chatroom_ids, url = None))]
+fn connect(chatroom_ids: Vec<u64>, url: Option<String>) -> ChatStream {
+    let url = url.unwrap_or_else(|| DEFAULT_WEBSOCKET_URL.to_string());
+    ChatStream {
+        client: Arc::new(Mutex::new(Some(ReconnectingClient::new(url, chatroom_ids)))),
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "kick_client")]
+fn kick_client_py(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add("DEFAULT_WEBSOCKET_URL", DEFAULT_WEBSOCKET_URL)?;
+    module.add_class::<ChatStream>()?;
+    module.add_function(wrap_pyfunction!(connect, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,871 @@
+use crate::auth::{Authentication, Credentials, SessionAuth, TokenManager};
+use crate::ratelimit::RateLimiter;
+use crate::{ChatMessageEventData, ChatSender, KickError};
+use futures_util::future::BoxFuture;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Base URL of Kick's website API.
+const DEFAULT_BASE_URL: &str = "https://kick.com";
+
+/// An HTTP request prepared by one of the REST clients.
+#[derive(Debug, Clone)]
+pub struct HttpRequest {
+    pub method: Method,
+    /// The full URL, including the query string.
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+/// The response to an `HttpRequest`.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Returns `true` if the status is in the 2xx range.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Parses the body as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the body is not valid JSON for `T`.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, KickError> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+/// Performs the HTTP requests of the REST clients.
+///
+/// Kick's REST API sits behind Cloudflare, which sometimes requires a specially configured
+/// client, a headless browser or rotating proxies to get through. Implementing this trait
+/// and passing it to `with_transport` makes every REST client use it. `reqwest::Client`
+/// is used by default.
+pub trait HttpTransport: Send + Sync + 'static {
+    /// Sends the request and returns the response, whatever its status.
+    ///
+    /// # Errors
+    ///
+    /// This function should return `KickError::HttpError` if no response was received.
+    fn send(
+        &self,
+        request: HttpRequest,
+    ) -> impl Future<Output = Result<HttpResponse, KickError>> + Send;
+}
+
+impl HttpTransport for reqwest::Client {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, KickError> {
+        let mut builder = self.request(request.method, &request.url);
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = request.body {
+            builder = builder.body(body);
+        }
+
+        let response = builder.send().await?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                )
+            })
+            .collect();
+        let body = response.bytes().await?.to_vec();
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// Looks up the chatroom ID of a channel by its slug, through Kick's public channel
+/// endpoint, which requires no authentication.
+///
+/// # Errors
+///
+/// This function will return an error if the request fails, or the channel doesn't exist.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// let chatroom_id = kick_client::api::chatroom_id(&reqwest::Client::new(), "xqc").await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn chatroom_id<T: HttpTransport>(transport: &T, slug: &str) -> Result<u32, KickError> {
+    chatroom_id_from(transport, DEFAULT_BASE_URL, slug).await
+}
+
+/// Looks up the chatroom ID of a channel by its slug, through the public channel endpoint
+/// of the website at `base_url`, e.g. the one a `KickApi` was given with `with_base_url`.
+///
+/// # Errors
+///
+/// This function will return an error if the request fails, or the channel doesn't exist.
+pub async fn chatroom_id_from<T: HttpTransport>(
+    transport: &T,
+    base_url: &str,
+    slug: &str,
+) -> Result<u32, KickError> {
+    #[derive(Deserialize)]
+    struct Channel {
+        chatroom: Chatroom,
+    }
+
+    #[derive(Deserialize)]
+    struct Chatroom {
+        id: u32,
+    }
+
+    let response = transport
+        .send(HttpRequest {
+            method: Method::GET,
+            url: format!(
+                "{}/api/v2/channels/{}",
+                base_url.trim_end_matches('/'),
+                slug
+            ),
+            headers: vec![("Accept".to_string(), "application/json".to_string())],
+            body: None,
+        })
+        .await?;
+    if !response.is_success() {
+        return Err(KickError::ApiError {
+            status: response.status,
+            body: String::from_utf8_lossy(&response.body).into_owned(),
+        });
+    }
+    Ok(response.json::<Channel>()?.chatroom.id)
+}
+
+/// An authenticated client for Kick's REST API, used for moderation actions.
+pub struct KickApi<T = reqwest::Client> {
+    /// The client requests are sent through.
+    rest: RestClient<T>,
+    /// The limiter outgoing chat messages wait for, if any.
+    limiter: Option<RateLimiter>,
+    /// The slug of the channel of each known chatroom.
+    channels: HashMap<u32, String>,
+}
+
+impl<T> Clone for KickApi<T> {
+    fn clone(&self) -> Self {
+        Self {
+            rest: self.rest.clone(),
+            limiter: self.limiter.clone(),
+            channels: self.channels.clone(),
+        }
+    }
+}
+
+impl KickApi {
+    /// Creates a new instance of `KickApi` authenticated with the given bearer token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The bearer token of the account performing the actions.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), kick_client::KickError> {
+    /// use kick_client::api::KickApi;
+    ///
+    /// let api = KickApi::new("token");
+    /// api.unpin_message("xqc").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(token: impl Into<String>) -> Self {
+        Self::from_tokens(TokenManager::new(Credentials::new(token)))
+    }
+
+    /// Creates a new instance of `KickApi` authenticated through the given token manager.
+    ///
+    /// Tokens are refreshed by the manager before they expire, and once more if Kick
+    /// rejects a request as unauthorized.
+    pub fn from_tokens(tokens: TokenManager) -> Self {
+        Self {
+            rest: RestClient::new(DEFAULT_BASE_URL, tokens.into()),
+            limiter: None,
+            channels: HashMap::new(),
+        }
+    }
+
+    /// Creates a new instance of `KickApi` authenticated with a web session.
+    ///
+    /// The session is reloaded from its source once if Kick rejects a request as
+    /// unauthorized.
+    pub fn from_session(session: SessionAuth) -> Self {
+        Self {
+            rest: RestClient::new(DEFAULT_BASE_URL, session.into()),
+            limiter: None,
+            channels: HashMap::new(),
+        }
+    }
+}
+
+impl<T: HttpTransport> KickApi<T> {
+    /// Returns how requests are authenticated.
+    pub fn authentication(&self) -> &Authentication {
+        &self.rest.auth
+    }
+
+    /// Returns a `ChannelAuthorizer` sharing this client's credentials and transport.
+    pub fn channel_authorizer(&self) -> ChannelAuthorizer<T> {
+        ChannelAuthorizer { api: self.clone() }
+    }
+
+    /// Overrides the base URL endpoints are resolved against. `chatroom_id_from` looks up
+    /// chatroom IDs on the same website.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.rest = self.rest.with_base_url(base_url);
+        self
+    }
+
+    /// Sends requests through the given transport instead of the default `reqwest::Client`.
+    pub fn with_transport<U: HttpTransport>(self, transport: U) -> KickApi<U> {
+        KickApi {
+            rest: self.rest.with_transport(transport),
+            limiter: self.limiter,
+            channels: self.channels,
+        }
+    }
+
+    /// Sets the slug of the channel a chatroom belongs to, so calls taking a chatroom ID
+    /// can reach endpoints Kick addresses by channel, such as `clear_chat`.
+    pub fn with_channel(mut self, chatroom_id: u32, channel: impl Into<String>) -> Self {
+        self.channels.insert(chatroom_id, channel.into());
+        self
+    }
+
+    /// Returns the slug of the channel a chatroom belongs to, if it was set with
+    /// `with_channel`.
+    pub fn channel(&self, chatroom_id: u32) -> Option<&str> {
+        self.channels.get(&chatroom_id).map(String::as_str)
+    }
+
+    /// Makes `send_message` wait for the given limiter before sending, so messages aren't
+    /// dropped by Kick for being sent too fast.
+    ///
+    /// Pass every received message to `RateLimiter::observe` to keep slow mode current.
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Returns the limiter outgoing chat messages wait for, if any.
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.limiter.as_ref()
+    }
+
+    /// Sends a chat message to a chatroom, waiting for the rate limiter first if one is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `chatroom_id` - The ID of the chatroom.
+    /// * `content` - The text of the message.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn send_message(&self, chatroom_id: u32, content: &str) -> Result<(), KickError> {
+        if let Some(limiter) = &self.limiter {
+            limiter.acquire(chatroom_id).await;
+        }
+        self.send_message_now(chatroom_id, content).await
+    }
+
+    /// Sends a chat message without waiting for the rate limiter, for callers that
+    /// already acquired it.
+    pub(crate) async fn send_message_now(
+        &self,
+        chatroom_id: u32,
+        content: &str,
+    ) -> Result<(), KickError> {
+        let body = serde_json::json!({
+            "content": content,
+            "type": "message",
+        });
+        self.rest
+            .send(
+                Method::POST,
+                &format!("/api/v2/messages/send/{}", chatroom_id),
+                Some(&body),
+            )
+            .await
+    }
+
+    /// Pins a chat message in the channel's chatroom.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The slug of the channel the message was sent in.
+    /// * `message` - The message to pin, as received from the chatroom.
+    /// * `duration` - For how long the message stays pinned, in seconds.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn pin_message(
+        &self,
+        channel: &str,
+        message: &ChatMessageEventData,
+        duration: u64,
+    ) -> Result<(), KickError> {
+        let body = serde_json::json!({
+            "message": message,
+            "duration": duration,
+        });
+
+        self.rest
+            .send(
+                Method::POST,
+                &format!("/api/v2/channels/{}/pinned-message", channel),
+                Some(&body),
+            )
+            .await
+    }
+
+    /// Unpins the currently pinned message in the channel's chatroom.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The slug of the channel.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn unpin_message(&self, channel: &str) -> Result<(), KickError> {
+        self.rest
+            .send::<()>(
+                Method::DELETE,
+                &format!("/api/v2/channels/{}/pinned-message", channel),
+                None,
+            )
+            .await
+    }
+
+    /// Enables or disables slow mode in the channel's chatroom.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The slug of the channel.
+    /// * `message_interval` - The minimum number of seconds between a user's messages,
+    ///   or `None` to disable slow mode.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn set_slow_mode(
+        &self,
+        channel: &str,
+        message_interval: Option<u64>,
+    ) -> Result<(), KickError> {
+        let body = match message_interval {
+            Some(interval) => serde_json::json!({
+                "slow_mode": true,
+                "message_interval": interval,
+            }),
+            None => serde_json::json!({ "slow_mode": false }),
+        };
+        self.update_chatroom(channel, &body).await
+    }
+
+    /// Enables or disables followers-only mode in the channel's chatroom.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The slug of the channel.
+    /// * `min_duration` - How many minutes a user must have followed the channel before
+    ///   chatting, or `None` to disable followers-only mode.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn set_followers_mode(
+        &self,
+        channel: &str,
+        min_duration: Option<u64>,
+    ) -> Result<(), KickError> {
+        let body = match min_duration {
+            Some(duration) => serde_json::json!({
+                "followers_mode": true,
+                "following_min_duration": duration,
+            }),
+            None => serde_json::json!({ "followers_mode": false }),
+        };
+        self.update_chatroom(channel, &body).await
+    }
+
+    /// Enables or disables subscribers-only mode in the channel's chatroom.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn set_subscribers_mode(
+        &self,
+        channel: &str,
+        enabled: bool,
+    ) -> Result<(), KickError> {
+        let body = serde_json::json!({ "subscribers_mode": enabled });
+        self.update_chatroom(channel, &body).await
+    }
+
+    /// Enables or disables emotes-only mode in the channel's chatroom.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn set_emotes_mode(&self, channel: &str, enabled: bool) -> Result<(), KickError> {
+        let body = serde_json::json!({ "emotes_mode": enabled });
+        self.update_chatroom(channel, &body).await
+    }
+
+    /// Clears all messages in a chatroom.
+    ///
+    /// Subscribers of the chatroom receive a `ChatroomClear` message once the chat is cleared.
+    ///
+    /// # Arguments
+    ///
+    /// * `chatroom_id` - The ID of the chatroom, whose channel was set with `with_channel`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the channel of the chatroom is unknown, or
+    /// the request fails or Kick rejects it.
+    pub async fn clear_chat(&self, chatroom_id: u32) -> Result<(), KickError> {
+        let channel = self.channel(chatroom_id).ok_or_else(|| {
+            KickError::ConfigError(format!(
+                "the channel of chatroom {} is unknown",
+                chatroom_id
+            ))
+        })?;
+        self.clear_channel_chat(channel).await
+    }
+
+    /// Clears all messages in the channel's chatroom.
+    ///
+    /// Subscribers of the chatroom receive a `ChatroomClear` message once the chat is cleared.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The slug of the channel.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn clear_channel_chat(&self, channel: &str) -> Result<(), KickError> {
+        let body = serde_json::json!({ "command": "clear" });
+        self.rest
+            .send(
+                Method::POST,
+                &format!("/api/v2/channels/{}/chat-commands", channel),
+                Some(&body),
+            )
+            .await
+    }
+
+    /// Starts a poll in the channel's chatroom.
+    ///
+    /// Subscribers of the chatroom receive `PollUpdate` messages while the poll is running.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The slug of the channel.
+    /// * `title` - The question of the poll.
+    /// * `options` - The labels of the options viewers can vote for.
+    /// * `duration` - For how long the poll accepts votes, in seconds.
+    /// * `result_display_duration` - For how long the results stay visible after the poll
+    ///   ends, in seconds.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn create_poll(
+        &self,
+        channel: &str,
+        title: &str,
+        options: &[&str],
+        duration: u32,
+        result_display_duration: u32,
+    ) -> Result<(), KickError> {
+        let body = serde_json::json!({
+            "title": title,
+            "options": options,
+            "duration": duration,
+            "result_display_duration": result_display_duration,
+        });
+        self.rest
+            .send(
+                Method::POST,
+                &format!("/api/v2/channels/{}/polls", channel),
+                Some(&body),
+            )
+            .await
+    }
+
+    /// Cancels the running poll in the channel's chatroom.
+    ///
+    /// Subscribers of the chatroom receive a `PollDelete` message once the poll is removed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn delete_poll(&self, channel: &str) -> Result<(), KickError> {
+        self.rest
+            .send::<()>(
+                Method::DELETE,
+                &format!("/api/v2/channels/{}/polls", channel),
+                None,
+            )
+            .await
+    }
+
+    /// Casts a vote in the running poll of the channel's chatroom.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The slug of the channel.
+    /// * `option_id` - The `id` of the `PollOption` to vote for.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it,
+    /// e.g. because no poll is running or the account has already voted.
+    pub async fn vote(&self, channel: &str, option_id: u32) -> Result<(), KickError> {
+        let body = serde_json::json!({ "id": option_id });
+        self.rest
+            .send(
+                Method::POST,
+                &format!("/api/v2/channels/{}/polls/vote", channel),
+                Some(&body),
+            )
+            .await
+    }
+
+    /// Deletes a chat message.
+    ///
+    /// Subscribers of the chatroom receive a `DeletedMessage` message once it is deleted.
+    ///
+    /// # Arguments
+    ///
+    /// * `chatroom_id` - The ID of the chatroom the message was sent in.
+    /// * `message_id` - The `id` of the message.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn delete_message(
+        &self,
+        chatroom_id: u32,
+        message_id: &str,
+    ) -> Result<(), KickError> {
+        self.rest
+            .send::<()>(
+                Method::DELETE,
+                &format!("/api/v2/chatrooms/{}/messages/{}", chatroom_id, message_id),
+                None,
+            )
+            .await
+    }
+
+    /// Times a user out of the channel's chatroom.
+    ///
+    /// Subscribers of the chatroom receive a `UserBanned` message with `permanent` unset.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The slug of the channel.
+    /// * `username` - The username of the user to time out.
+    /// * `duration` - For how long the user is timed out, in minutes.
+    /// * `reason` - The reason shown to moderators, if any.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn timeout_user(
+        &self,
+        channel: &str,
+        username: &str,
+        duration: u64,
+        reason: Option<&str>,
+    ) -> Result<(), KickError> {
+        let body = serde_json::json!({
+            "banned_username": username,
+            "duration": duration,
+            "permanent": false,
+            "reason": reason,
+        });
+        self.rest
+            .send(
+                Method::POST,
+                &format!("/api/v2/channels/{}/bans", channel),
+                Some(&body),
+            )
+            .await
+    }
+
+    /// Permanently bans a user from the channel's chatroom.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - The slug of the channel.
+    /// * `username` - The username of the user to ban.
+    /// * `reason` - The reason shown to moderators, if any.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn ban_user(
+        &self,
+        channel: &str,
+        username: &str,
+        reason: Option<&str>,
+    ) -> Result<(), KickError> {
+        let body = serde_json::json!({
+            "banned_username": username,
+            "permanent": true,
+            "reason": reason,
+        });
+        self.rest
+            .send(
+                Method::POST,
+                &format!("/api/v2/channels/{}/bans", channel),
+                Some(&body),
+            )
+            .await
+    }
+
+    /// Lifts a ban or timeout of a user in the channel's chatroom.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn unban_user(&self, channel: &str, username: &str) -> Result<(), KickError> {
+        self.rest
+            .send::<()>(
+                Method::DELETE,
+                &format!("/api/v2/channels/{}/bans/{}", channel, username),
+                None,
+            )
+            .await
+    }
+
+    /// Applies a partial update to the channel's chatroom settings.
+    async fn update_chatroom(
+        &self,
+        channel: &str,
+        body: &serde_json::Value,
+    ) -> Result<(), KickError> {
+        self.rest
+            .send(
+                Method::PUT,
+                &format!("/api/v2/channels/{}/chatroom", channel),
+                Some(body),
+            )
+            .await
+    }
+}
+
+impl<T: HttpTransport> ChatSender for KickApi<T> {
+    fn send_message<'a>(
+        &'a self,
+        chatroom_id: u32,
+        content: &'a str,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        Box::pin(KickApi::send_message(self, chatroom_id, content))
+    }
+}
+
+/// The authenticated request plumbing shared by the REST clients.
+pub(crate) struct RestClient<T> {
+    /// The transport requests are sent through.
+    http: Arc<T>,
+    /// The base URL every endpoint path is appended to.
+    base_url: String,
+    /// How every request is authenticated.
+    pub(crate) auth: Authentication,
+}
+
+impl<T> Clone for RestClient<T> {
+    fn clone(&self) -> Self {
+        Self {
+            http: self.http.clone(),
+            base_url: self.base_url.clone(),
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+impl RestClient<reqwest::Client> {
+    pub(crate) fn new(base_url: &str, auth: Authentication) -> Self {
+        Self {
+            http: Arc::new(reqwest::Client::new()),
+            base_url: base_url.to_string(),
+            auth,
+        }
+    }
+}
+
+impl<T: HttpTransport> RestClient<T> {
+    pub(crate) fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub(crate) fn with_transport<U: HttpTransport>(self, transport: U) -> RestClient<U> {
+        RestClient {
+            http: Arc::new(transport),
+            base_url: self.base_url,
+            auth: self.auth,
+        }
+    }
+
+    /// Sends an authenticated request and checks that Kick accepted it.
+    pub(crate) async fn send<B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&B>,
+    ) -> Result<(), KickError> {
+        self.execute(method, path, &[], body).await.map(|_| ())
+    }
+
+    /// Sends an authenticated request, retrying once with renewed credentials if it
+    /// is rejected as unauthorized, and returns the successful response.
+    pub(crate) async fn execute<B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&B>,
+    ) -> Result<HttpResponse, KickError> {
+        let body = body.map(serde_json::to_vec).transpose()?;
+        let mut response = self
+            .attempt(method.clone(), path, query, body.clone())
+            .await?;
+        if response.status == 401 && self.auth.renew().await? {
+            response = self.attempt(method, path, query, body).await?;
+        }
+
+        if response.is_success() {
+            Ok(response)
+        } else {
+            Err(KickError::ApiError {
+                status: response.status,
+                body: String::from_utf8_lossy(&response.body).into_owned(),
+            })
+        }
+    }
+
+    async fn attempt(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<Vec<u8>>,
+    ) -> Result<HttpResponse, KickError> {
+        let mut url = reqwest::Url::parse(&format!("{}{}", self.base_url, path))
+            .map_err(|e| KickError::HttpError(Box::new(e)))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+
+        let mut headers = vec![("Accept".to_string(), "application/json".to_string())];
+        if body.is_some() {
+            headers.push(("Content-Type".to_string(), "application/json".to_string()));
+        }
+        headers.extend(self.auth.headers().await?);
+
+        self.http
+            .send(HttpRequest {
+                method,
+                url: url.into(),
+                headers,
+                body,
+            })
+            .await
+    }
+}
+
+/// Signs subscriptions to Kick's private Pusher channels.
+///
+/// Shares its credentials with the `KickApi` it was created from, so a token refreshed by
+/// either is picked up by both.
+pub struct ChannelAuthorizer<T = reqwest::Client> {
+    api: KickApi<T>,
+}
+
+impl<T> Clone for ChannelAuthorizer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            api: self.api.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ChannelAuthResponse {
+    auth: String,
+}
+
+impl ChannelAuthorizer {
+    /// Creates a new instance of `ChannelAuthorizer` authenticated through the given token
+    /// manager.
+    pub fn new(tokens: TokenManager) -> Self {
+        KickApi::from_tokens(tokens).channel_authorizer()
+    }
+}
+
+impl<T: HttpTransport> ChannelAuthorizer<T> {
+    /// Requests the signature needed to subscribe to a private channel.
+    ///
+    /// # Arguments
+    ///
+    /// * `socket_id` - The socket ID received in `pusher:connection_established`.
+    /// * `channel_name` - The name of the private channel, e.g. `private-chatroom_1234`.
+    ///
+    /// # Returns
+    ///
+    /// The value to put in the `auth` field of the `pusher:subscribe` message.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn authorize(
+        &self,
+        socket_id: &str,
+        channel_name: &str,
+    ) -> Result<String, KickError> {
+        let body = serde_json::json!({
+            "socket_id": socket_id,
+            "channel_name": channel_name,
+        });
+        let response = self
+            .api
+            .rest
+            .execute(Method::POST, "/broadcasting/auth", &[], Some(&body))
+            .await?;
+        let auth: ChannelAuthResponse = response.json()?;
+        Ok(auth.auth)
+    }
+}
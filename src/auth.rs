@@ -0,0 +1,534 @@
+use crate::KickError;
+use futures_util::future::BoxFuture;
+use std::future::Future;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+/// How long before expiry a token is refreshed by default.
+const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_secs(60);
+/// How long the background refresh waits before trying again after a failure.
+const REFRESH_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// An access token together with what is needed to renew it.
+#[derive(Clone, Debug)]
+pub struct Credentials {
+    /// The bearer token sent with authenticated requests.
+    pub access_token: String,
+    /// The token used to obtain a new access token, if the issuer provided one.
+    pub refresh_token: Option<String>,
+    /// When the access token stops being accepted, if known.
+    pub expires_at: Option<Instant>,
+}
+
+impl Credentials {
+    /// Creates credentials consisting of an access token that never expires.
+    pub fn new(access_token: impl Into<String>) -> Self {
+        Self {
+            access_token: access_token.into(),
+            refresh_token: None,
+            expires_at: None,
+        }
+    }
+
+    /// Sets the refresh token used to renew the access token.
+    pub fn with_refresh_token(mut self, refresh_token: impl Into<String>) -> Self {
+        self.refresh_token = Some(refresh_token.into());
+        self
+    }
+
+    /// Sets the access token to expire after `expires_in` from now.
+    pub fn expires_in(mut self, expires_in: Duration) -> Self {
+        self.expires_at = Some(Instant::now() + expires_in);
+        self
+    }
+
+    /// Returns `true` if the access token expires within `margin` from now.
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Instant::now() + margin)
+    }
+}
+
+/// Exchanges a refresh token for a new set of credentials.
+///
+/// Implemented for any `Fn(String) -> impl Future<Output = Result<Credentials, KickError>>`,
+/// so a closure calling the token endpoint of choice is enough.
+pub trait TokenRefresher: Send + Sync + 'static {
+    /// Obtains new credentials using the given refresh token.
+    fn refresh(&self, refresh_token: String) -> BoxFuture<'static, Result<Credentials, KickError>>;
+}
+
+impl<F, Fut> TokenRefresher for F
+where
+    F: Fn(String) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Credentials, KickError>> + Send + 'static,
+{
+    fn refresh(&self, refresh_token: String) -> BoxFuture<'static, Result<Credentials, KickError>> {
+        Box::pin(self(refresh_token))
+    }
+}
+
+/// Stores credentials and refreshes them before they expire.
+///
+/// Credentials are refreshed when a token is requested within the refresh margin of
+/// their expiry. `refresh_in_background` also refreshes them ahead of expiry, so a bot
+/// which only sends messages now and then doesn't wait on the token endpoint.
+///
+/// Cloning a `TokenManager` is cheap and all clones share the same credentials and
+/// configuration, so one manager can be handed to every component that authenticates
+/// against Kick.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::auth::{Credentials, TokenManager};
+/// use std::time::Duration;
+///
+/// let credentials = Credentials::new("access")
+///     .with_refresh_token("refresh")
+///     .expires_in(Duration::from_secs(3600));
+/// let tokens = TokenManager::new(credentials).with_refresher(|refresh_token: String| async move {
+///     // Call the token endpoint with `refresh_token` here.
+///     Ok(Credentials::new("new access").with_refresh_token(refresh_token))
+/// });
+/// tokens.refresh_in_background();
+/// let token = tokens.access_token().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TokenManager {
+    inner: Arc<TokenManagerInner>,
+}
+
+struct TokenManagerInner {
+    /// The current credentials, locked while a refresh is in flight.
+    credentials: Mutex<Credentials>,
+    /// How the credentials are refreshed.
+    config: std::sync::Mutex<RefreshConfig>,
+}
+
+#[derive(Clone)]
+struct RefreshConfig {
+    /// Used to renew the credentials, if configured.
+    refresher: Option<Arc<dyn TokenRefresher>>,
+    /// How long before expiry the credentials are refreshed.
+    refresh_margin: Duration,
+}
+
+impl TokenManager {
+    /// Creates a new instance of `TokenManager` holding the given credentials.
+    ///
+    /// Without a refresher the credentials are used as-is until they expire.
+    pub fn new(credentials: Credentials) -> Self {
+        Self {
+            inner: Arc::new(TokenManagerInner {
+                credentials: Mutex::new(credentials),
+                config: std::sync::Mutex::new(RefreshConfig {
+                    refresher: None,
+                    refresh_margin: DEFAULT_REFRESH_MARGIN,
+                }),
+            }),
+        }
+    }
+
+    /// Sets the refresher used to renew the credentials, for every clone of the manager.
+    pub fn with_refresher(self, refresher: impl TokenRefresher) -> Self {
+        self.lock_config().refresher = Some(Arc::new(refresher));
+        self
+    }
+
+    /// Sets how long before expiry the credentials are refreshed, for every clone of the
+    /// manager. Defaults to one minute.
+    pub fn with_refresh_margin(self, refresh_margin: Duration) -> Self {
+        self.lock_config().refresh_margin = refresh_margin;
+        self
+    }
+
+    /// Starts a task refreshing the credentials whenever they come within the refresh
+    /// margin of their expiry, retrying failed refreshes every 10 seconds. The task stops
+    /// once the credentials don't expire or cannot be refreshed, or every clone of the
+    /// manager is dropped; abort the returned handle to stop it earlier.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called outside of a Tokio runtime.
+    pub fn refresh_in_background(&self) -> JoinHandle<()> {
+        crate::trace::spawn(
+            "kick_client::auth",
+            refresh_ahead(Arc::downgrade(&self.inner)),
+        )
+    }
+
+    /// Returns a valid access token, refreshing the credentials first if they are about
+    /// to expire.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the credentials are about to expire and
+    /// cannot be refreshed.
+    pub async fn access_token(&self) -> Result<String, KickError> {
+        let refresh_margin = self.lock_config().refresh_margin;
+        let mut credentials = self.inner.credentials.lock().await;
+        if credentials.expires_within(refresh_margin) {
+            self.refresh_locked(&mut credentials).await?;
+        }
+        Ok(credentials.access_token.clone())
+    }
+
+    /// Refreshes the credentials regardless of their expiry, e.g. after a request was
+    /// rejected as unauthorized.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if no refresher or refresh token is available,
+    /// or the refresher fails.
+    pub async fn refresh(&self) -> Result<(), KickError> {
+        let mut credentials = self.inner.credentials.lock().await;
+        self.refresh_locked(&mut credentials).await
+    }
+
+    /// Returns `true` if the manager is able to refresh its credentials.
+    pub async fn can_refresh(&self) -> bool {
+        let has_refresher = self.lock_config().refresher.is_some();
+        has_refresher && self.inner.credentials.lock().await.refresh_token.is_some()
+    }
+
+    /// Returns a copy of the current credentials.
+    pub async fn credentials(&self) -> Credentials {
+        self.inner.credentials.lock().await.clone()
+    }
+
+    async fn refresh_locked(&self, credentials: &mut Credentials) -> Result<(), KickError> {
+        let refresher = self
+            .lock_config()
+            .refresher
+            .clone()
+            .ok_or_else(|| KickError::AuthError("no token refresher configured".to_string()))?;
+        let refresh_token = credentials
+            .refresh_token
+            .clone()
+            .ok_or_else(|| KickError::AuthError("no refresh token available".to_string()))?;
+
+        let mut refreshed = refresher.refresh(refresh_token.clone()).await?;
+        // Some issuers only hand out a new refresh token when the old one is rotated.
+        if refreshed.refresh_token.is_none() {
+            refreshed.refresh_token = Some(refresh_token);
+        }
+        *credentials = refreshed;
+        Ok(())
+    }
+
+    fn lock_config(&self) -> std::sync::MutexGuard<'_, RefreshConfig> {
+        self.inner.config.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Refreshes the credentials of a manager ahead of their expiry for as long as it exists.
+async fn refresh_ahead(inner: Weak<TokenManagerInner>) {
+    let upgrade = || inner.upgrade().map(|inner| TokenManager { inner });
+    loop {
+        // The manager mustn't be kept alive while waiting.
+        let refresh_at = {
+            let Some(tokens) = upgrade() else {
+                return;
+            };
+            if !tokens.can_refresh().await {
+                return;
+            }
+            let Some(expires_at) = tokens.credentials().await.expires_at else {
+                return;
+            };
+            let refresh_margin = tokens.lock_config().refresh_margin;
+            expires_at
+                .checked_sub(refresh_margin)
+                .unwrap_or_else(Instant::now)
+        };
+        tokio::time::sleep_until(refresh_at.into()).await;
+
+        let Some(tokens) = upgrade() else {
+            return;
+        };
+        // Only refreshes if the credentials weren't refreshed meanwhile.
+        let refreshed = tokens.access_token().await.is_ok();
+        let refresh_margin = tokens.lock_config().refresh_margin;
+        let expiring = tokens.credentials().await.expires_within(refresh_margin);
+        drop(tokens);
+        if !refreshed || expiring {
+            tokio::time::sleep(REFRESH_RETRY_DELAY).await;
+        }
+    }
+}
+
+impl From<Credentials> for TokenManager {
+    fn from(credentials: Credentials) -> Self {
+        TokenManager::new(credentials)
+    }
+}
+
+/// Web session credentials copied from a browser logged in to Kick.
+///
+/// Many of the unofficial endpoints only accept requests carrying the session's bearer
+/// token, XSRF token and cookies.
+#[derive(Clone, Debug, Default)]
+pub struct Session {
+    /// The bearer token of the session, stored in the `session_token` cookie.
+    pub bearer_token: String,
+    /// The XSRF token, stored in the `XSRF-TOKEN` cookie.
+    pub xsrf_token: String,
+    /// All cookies of the session, sent along with every request.
+    pub cookies: Vec<(String, String)>,
+}
+
+impl Session {
+    /// Creates a session from its cookies, extracting the bearer and XSRF tokens.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `session_token` or `XSRF-TOKEN` cookie
+    /// is missing.
+    pub fn from_cookies(cookies: Vec<(String, String)>) -> Result<Self, KickError> {
+        let cookie = |name: &str| {
+            cookies
+                .iter()
+                .find(|(cookie, _)| cookie == name)
+                .map(|(_, value)| percent_decode(value))
+                .ok_or_else(|| KickError::AuthError(format!("missing {} cookie", name)))
+        };
+        Ok(Self {
+            bearer_token: cookie("session_token")?,
+            xsrf_token: cookie("XSRF-TOKEN")?,
+            cookies,
+        })
+    }
+
+    /// Parses a cookie export of a browser session.
+    ///
+    /// Both the JSON format of common cookie editor extensions (an array of objects with
+    /// `name` and `value`) and the Netscape `cookies.txt` format are supported.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the export cannot be parsed or lacks the
+    /// session cookies.
+    pub fn from_cookie_export(export: &str) -> Result<Self, KickError> {
+        #[derive(serde::Deserialize)]
+        struct ExportedCookie {
+            name: String,
+            value: String,
+        }
+
+        let cookies = if export.trim_start().starts_with('[') {
+            serde_json::from_str::<Vec<ExportedCookie>>(export)?
+                .into_iter()
+                .map(|cookie| (cookie.name, cookie.value))
+                .collect()
+        } else {
+            export
+                .lines()
+                // HttpOnly cookies, such as `session_token`, are exported with this prefix.
+                .map(|line| line.strip_prefix("#HttpOnly_").unwrap_or(line))
+                .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+                .filter_map(|line| {
+                    let fields: Vec<&str> = line.split('\t').collect();
+                    match fields.as_slice() {
+                        [_, _, _, _, _, name, value] => {
+                            Some((name.to_string(), value.trim_end().to_string()))
+                        }
+                        _ => None,
+                    }
+                })
+                .collect()
+        };
+        Self::from_cookies(cookies)
+    }
+
+    /// Returns the value of the `Cookie` header for this session.
+    pub fn cookie_header(&self) -> String {
+        self.cookies
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Supplies the current web session, e.g. by reading a browser export.
+///
+/// Implemented for any `Fn() -> impl Future<Output = Result<Session, KickError>>`.
+pub trait SessionSource: Send + Sync + 'static {
+    /// Loads the current session.
+    fn load(&self) -> BoxFuture<'static, Result<Session, KickError>>;
+}
+
+impl<F, Fut> SessionSource for F
+where
+    F: Fn() -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<Session, KickError>> + Send + 'static,
+{
+    fn load(&self) -> BoxFuture<'static, Result<Session, KickError>> {
+        Box::pin(self())
+    }
+}
+
+/// Authenticates requests with a web session, reloading it from its source when Kick
+/// rejects it.
+///
+/// Cloning a `SessionAuth` is cheap and all clones share the same session.
+#[derive(Clone)]
+pub struct SessionAuth {
+    /// Where the session is loaded from.
+    source: Arc<dyn SessionSource>,
+    /// The session last loaded from the source.
+    session: Arc<Mutex<Option<Session>>>,
+}
+
+impl SessionAuth {
+    /// Creates a new instance of `SessionAuth` loading the session from `source` on first use.
+    pub fn new(source: impl SessionSource) -> Self {
+        Self {
+            source: Arc::new(source),
+            session: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Creates a new instance of `SessionAuth` using a fixed session.
+    pub fn from_session(session: Session) -> Self {
+        let auth = Self::new({
+            let session = session.clone();
+            move || {
+                let session = session.clone();
+                async move { Ok(session) }
+            }
+        });
+        auth.session
+            .try_lock()
+            .expect("a new session is not locked")
+            .replace(session);
+        auth
+    }
+
+    /// Creates a new instance of `SessionAuth` reading the session from a cookie export
+    /// file, see `Session::from_cookie_export`.
+    ///
+    /// The file is read again whenever the session is rejected, so replacing it with a
+    /// fresh export is enough to recover a long-running client.
+    pub fn from_cookie_file(path: impl Into<std::path::PathBuf>) -> Self {
+        let path = path.into();
+        Self::new(move || {
+            let path = path.clone();
+            async move {
+                let export = tokio::fs::read_to_string(&path).await?;
+                Session::from_cookie_export(&export)
+            }
+        })
+    }
+
+    /// Returns the current session, loading it from the source if needed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the session cannot be loaded.
+    pub async fn session(&self) -> Result<Session, KickError> {
+        let mut session = self.session.lock().await;
+        if let Some(session) = session.as_ref() {
+            return Ok(session.clone());
+        }
+        let loaded = self.source.load().await?;
+        *session = Some(loaded.clone());
+        Ok(loaded)
+    }
+
+    /// Loads the session from the source again.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the session cannot be loaded.
+    pub async fn reload(&self) -> Result<(), KickError> {
+        let loaded = self.source.load().await?;
+        *self.session.lock().await = Some(loaded);
+        Ok(())
+    }
+}
+
+/// How requests to Kick's REST API are authenticated.
+#[derive(Clone)]
+pub enum Authentication {
+    /// A bearer token managed by a `TokenManager`.
+    Bearer(TokenManager),
+    /// A web session copied from a browser.
+    Session(SessionAuth),
+}
+
+impl Authentication {
+    /// Returns the headers authenticating a request.
+    pub(crate) async fn headers(&self) -> Result<Vec<(String, String)>, KickError> {
+        match self {
+            Authentication::Bearer(tokens) => Ok(vec![(
+                "Authorization".to_string(),
+                format!("Bearer {}", tokens.access_token().await?),
+            )]),
+            Authentication::Session(auth) => {
+                let session = auth.session().await?;
+                Ok(vec![
+                    (
+                        "Authorization".to_string(),
+                        format!("Bearer {}", session.bearer_token),
+                    ),
+                    ("X-XSRF-TOKEN".to_string(), session.xsrf_token.clone()),
+                    ("Cookie".to_string(), session.cookie_header()),
+                ])
+            }
+        }
+    }
+
+    /// Renews the credentials after a request was rejected as unauthorized, returning
+    /// `false` if there is nothing to renew them with.
+    pub(crate) async fn renew(&self) -> Result<bool, KickError> {
+        match self {
+            Authentication::Bearer(tokens) => {
+                if !tokens.can_refresh().await {
+                    return Ok(false);
+                }
+                tokens.refresh().await?;
+            }
+            Authentication::Session(auth) => auth.reload().await?,
+        }
+        Ok(true)
+    }
+}
+
+impl From<TokenManager> for Authentication {
+    fn from(tokens: TokenManager) -> Self {
+        Authentication::Bearer(tokens)
+    }
+}
+
+impl From<SessionAuth> for Authentication {
+    fn from(auth: SessionAuth) -> Self {
+        Authentication::Session(auth)
+    }
+}
+
+/// Decodes the `%XX` escapes browsers use in cookie values.
+fn percent_decode(value: &str) -> String {
+    let hex = |byte: u8| (byte as char).to_digit(16).map(|digit| digit as u8);
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            if let (Some(high), Some(low)) = (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                decoded.push(high << 4 | low);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,261 @@
+#[cfg(feature = "api")]
+use crate::api::{HttpTransport, KickApi};
+use crate::commands::Permission;
+#[cfg(feature = "api")]
+use crate::KickError;
+use crate::{ChatMessageEventData, KickChatMessage, MessageData};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Why a message was flagged as spam.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpamReason {
+    /// The user sent `count` messages within `window`.
+    Flood { count: usize, window: Duration },
+    /// The user sent `count` messages at least `similarity` alike within `window`.
+    Repeated {
+        count: usize,
+        window: Duration,
+        similarity: f64,
+    },
+}
+
+/// A message flagged by a `SpamDetector`.
+#[derive(Debug, Clone)]
+pub struct SpamDetected {
+    /// The message that crossed a limit.
+    pub message: ChatMessageEventData,
+    /// Which limit was crossed.
+    pub reason: SpamReason,
+}
+
+/// Detects users flooding a chatroom or repeating the same message.
+///
+/// Each user's recent messages are tracked per chatroom over sliding windows. Once a
+/// message is flagged, the history of its sender is reset so the following messages are
+/// judged afresh.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::automod::SpamDetector;
+/// use std::time::Duration;
+///
+/// let mut detector = SpamDetector::new().with_flood_limit(5, Duration::from_secs(10));
+/// while let Some(message) = client.read_message().await? {
+///     if let Some(spam) = detector.check(&message) {
+///         println!("{} is spamming: {:?}", spam.message.sender.username, spam.reason);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SpamDetector {
+    /// The number of messages allowed within `flood_window`.
+    flood_max: usize,
+    flood_window: Duration,
+    /// The number of similar messages allowed within `repeat_window`.
+    repeat_max: usize,
+    repeat_window: Duration,
+    /// How alike two messages must be to count as repeated, from 0 to 1.
+    similarity: f64,
+    /// Senders at or above this level are never flagged.
+    exempt: Permission,
+    /// The recent messages of each user in each chatroom, oldest first.
+    history: HashMap<(u32, u32), VecDeque<(Instant, String)>>,
+}
+
+impl SpamDetector {
+    /// Creates a new instance of `SpamDetector` flagging more than 6 messages in 10
+    /// seconds, or more than 3 near-identical messages in a minute. Moderators are exempt.
+    pub fn new() -> Self {
+        Self {
+            flood_max: 6,
+            flood_window: Duration::from_secs(10),
+            repeat_max: 3,
+            repeat_window: Duration::from_secs(60),
+            similarity: 0.9,
+            exempt: Permission::Moderator,
+            history: HashMap::new(),
+        }
+    }
+
+    /// Sets how many messages a user may send within `window`.
+    pub fn with_flood_limit(mut self, max_messages: usize, window: Duration) -> Self {
+        self.flood_max = max_messages;
+        self.flood_window = window;
+        self
+    }
+
+    /// Sets how many messages at least `similarity` alike a user may send within `window`.
+    /// `similarity` ranges from 0 (anything) to 1 (identical after normalizing case and
+    /// whitespace).
+    pub fn with_repeat_limit(
+        mut self,
+        max_repeats: usize,
+        window: Duration,
+        similarity: f64,
+    ) -> Self {
+        self.repeat_max = max_repeats;
+        self.repeat_window = window;
+        self.similarity = similarity;
+        self
+    }
+
+    /// Sets the permission level at or above which senders are never flagged.
+    pub fn with_exempt(mut self, exempt: Permission) -> Self {
+        self.exempt = exempt;
+        self
+    }
+
+    /// Records a received message, returning a signal if it makes its sender a spammer.
+    pub fn check(&mut self, message: &KickChatMessage) -> Option<SpamDetected> {
+        match &message.data {
+            MessageData::ChatMessage(data) => self.check_chat_message(data),
+            _ => None,
+        }
+    }
+
+    /// Records a chat message, returning a signal if it makes its sender a spammer.
+    pub fn check_chat_message(&mut self, message: &ChatMessageEventData) -> Option<SpamDetected> {
+        if Permission::of(&message.sender) >= self.exempt {
+            return None;
+        }
+        let now = Instant::now();
+        let content = normalize(message.content.as_deref().unwrap_or_default());
+        let key = (message.chatroom_id, message.sender.id);
+        let history = self.history.entry(key).or_default();
+
+        let longest_window = self.flood_window.max(self.repeat_window);
+        while history
+            .front()
+            .is_some_and(|(sent_at, _)| now.duration_since(*sent_at) > longest_window)
+        {
+            history.pop_front();
+        }
+        history.push_back((now, content));
+
+        let (_, content) = history.back()?;
+        let within = |window: Duration| {
+            history
+                .iter()
+                .filter(move |(sent_at, _)| now.duration_since(*sent_at) <= window)
+        };
+
+        let count = within(self.flood_window).count();
+        let reason = if count > self.flood_max {
+            Some(SpamReason::Flood {
+                count,
+                window: self.flood_window,
+            })
+        } else {
+            let repeats = within(self.repeat_window)
+                .filter(|(_, other)| similarity(content, other) >= self.similarity)
+                .count();
+            (repeats > self.repeat_max).then_some(SpamReason::Repeated {
+                count: repeats,
+                window: self.repeat_window,
+                similarity: self.similarity,
+            })
+        };
+
+        let reason = reason?;
+        self.history.remove(&key);
+        Some(SpamDetected {
+            message: message.clone(),
+            reason,
+        })
+    }
+
+    /// Forgets users who haven't sent a message recently, bounding memory use in busy
+    /// chatrooms.
+    pub fn purge_expired(&mut self) {
+        let now = Instant::now();
+        let longest_window = self.flood_window.max(self.repeat_window);
+        self.history.retain(|_, history| {
+            history
+                .back()
+                .is_some_and(|(sent_at, _)| now.duration_since(*sent_at) <= longest_window)
+        });
+    }
+}
+
+impl Default for SpamDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lowercases a message and collapses its whitespace.
+fn normalize(content: &str) -> String {
+    content
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the Sørensen–Dice coefficient of the character bigrams of two messages.
+fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |s: &str| {
+        let chars: Vec<char> = s.chars().collect();
+        chars
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<HashSet<_>>()
+    };
+    let (a, b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(&b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+/// Times out the senders of messages flagged by a `SpamDetector`.
+#[cfg(feature = "api")]
+pub struct AutoTimeout<T = reqwest::Client> {
+    api: KickApi<T>,
+    /// For how long spammers are timed out, in minutes.
+    duration: u64,
+}
+
+#[cfg(feature = "api")]
+impl<T: HttpTransport> AutoTimeout<T> {
+    /// Creates a new instance of `AutoTimeout` timing spammers out for `duration` minutes.
+    ///
+    /// Kick times users out of a channel rather than a chatroom, so the channel of each
+    /// moderated chatroom must be set with `KickApi::with_channel`.
+    pub fn new(api: KickApi<T>, duration: u64) -> Self {
+        Self { api, duration }
+    }
+
+    /// Times out the sender of a flagged message, returning `false` if the channel of its
+    /// chatroom is unknown to the API.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn apply(&self, spam: &SpamDetected) -> Result<bool, KickError> {
+        let Some(channel) = self.api.channel(spam.message.chatroom_id) else {
+            return Ok(false);
+        };
+        let reason = match spam.reason {
+            SpamReason::Flood { .. } => "Flooding the chat",
+            SpamReason::Repeated { .. } => "Repeating messages",
+        };
+        self.api
+            .timeout_user(
+                channel,
+                &spam.message.sender.username,
+                self.duration,
+                Some(reason),
+            )
+            .await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,141 @@
+use crate::{KickChatMessage, MessageData, User, UserBannedEventData};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// A ban or timeout of a user in a chatroom.
+#[derive(Debug, Clone)]
+pub struct Ban {
+    /// The banned user.
+    pub user: User,
+    /// The moderator who banned the user.
+    pub banned_by: User,
+    /// When the timeout ends, or `None` for a permanent ban or a timeout too long to
+    /// represent.
+    pub expires_at: Option<Instant>,
+    /// When the ban was received.
+    pub banned_at: Instant,
+}
+
+impl Ban {
+    /// Returns `true` if the ban is permanent rather than a timeout.
+    pub fn is_permanent(&self) -> bool {
+        self.expires_at.is_none()
+    }
+
+    /// Returns how long the timeout lasts from now, or `None` for a permanent ban.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.expires_at
+            .map(|expires_at| expires_at.saturating_duration_since(Instant::now()))
+    }
+
+    fn is_active(&self, now: Instant) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+/// Keeps the currently banned and timed out users of each chatroom from `UserBanned` and
+/// `UserUnbanned` messages. Timeouts expire on their own.
+///
+/// Ban messages don't name their chatroom, so it is taken from the channel the message
+/// was received on.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::bans::BanList;
+///
+/// let mut bans = BanList::new();
+/// while let Some(message) = client.read_message().await? {
+///     bans.observe(&message);
+/// }
+/// println!("{} users banned", bans.bans(1234).count());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct BanList {
+    /// The bans of each chatroom, keyed by chatroom ID and user ID.
+    bans: HashMap<(u32, u32), Ban>,
+}
+
+impl BanList {
+    /// Creates a new, empty instance of `BanList`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the ban list from a received message.
+    pub fn observe(&mut self, message: &KickChatMessage) {
+        let Some(chatroom_id) = message.chatroom_id() else {
+            return;
+        };
+        match &message.data {
+            MessageData::UserBanned(data) => self.ban(chatroom_id, data),
+            MessageData::UserUnbanned(data) => self.unban(chatroom_id, data.user.id),
+            _ => {}
+        }
+    }
+
+    /// Records a ban or timeout in a chatroom. Timeout durations are in minutes.
+    pub fn ban(&mut self, chatroom_id: u32, data: &UserBannedEventData) {
+        let now = Instant::now();
+        let expires_at = match (data.permanent, data.duration) {
+            // A timeout too long to represent is kept until the user is unbanned.
+            (false, Some(minutes)) => minutes
+                .checked_mul(60)
+                .and_then(|secs| now.checked_add(Duration::from_secs(secs))),
+            _ => None,
+        };
+        self.bans.insert(
+            (chatroom_id, data.user.id),
+            Ban {
+                user: data.user.clone(),
+                banned_by: data.banned_by.clone(),
+                expires_at,
+                banned_at: now,
+            },
+        );
+    }
+
+    /// Removes the ban of a user in a chatroom.
+    pub fn unban(&mut self, chatroom_id: u32, user_id: u32) {
+        self.bans.remove(&(chatroom_id, user_id));
+    }
+
+    /// Returns the active ban of a user in a chatroom.
+    pub fn get(&self, chatroom_id: u32, user_id: u32) -> Option<&Ban> {
+        self.bans
+            .get(&(chatroom_id, user_id))
+            .filter(|ban| ban.is_active(Instant::now()))
+    }
+
+    /// Returns `true` if a user is banned or timed out in a chatroom.
+    pub fn is_banned(&self, chatroom_id: u32, user_id: u32) -> bool {
+        self.get(chatroom_id, user_id).is_some()
+    }
+
+    /// Returns the active bans of a chatroom.
+    pub fn bans(&self, chatroom_id: u32) -> impl Iterator<Item = &Ban> {
+        let now = Instant::now();
+        self.bans
+            .iter()
+            .filter(move |((id, _), ban)| *id == chatroom_id && ban.is_active(now))
+            .map(|(_, ban)| ban)
+    }
+
+    /// Returns the active bans of a user, with the IDs of their chatrooms.
+    pub fn bans_of(&self, user_id: u32) -> impl Iterator<Item = (u32, &Ban)> {
+        let now = Instant::now();
+        self.bans
+            .iter()
+            .filter(move |((_, id), ban)| *id == user_id && ban.is_active(now))
+            .map(|((chatroom_id, _), ban)| (*chatroom_id, ban))
+    }
+
+    /// Forgets expired timeouts.
+    pub fn purge_expired(&mut self) {
+        let now = Instant::now();
+        self.bans.retain(|_, ban| ban.is_active(now));
+    }
+}
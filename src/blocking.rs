@@ -0,0 +1,142 @@
+//! A synchronous client, for command line tools and codebases which don't use async.
+//!
+//! `BlockingKickClient` owns a single-threaded Tokio runtime and drives a message source on
+//! it whenever a message is requested, so chat can be read from plain threads:
+//!
+//! ```no_run
+//! # fn run() -> Result<(), kick_client::KickError> {
+//! use kick_client::blocking::BlockingKickClient;
+//!
+//! let mut client = BlockingKickClient::connect(kick_client::DEFAULT_WEBSOCKET_URL, vec![668])?;
+//! loop {
+//!     println!("{}", client.recv()?);
+//! }
+//! # }
+//! ```
+//!
+//! The methods must not be called from within an asynchronous runtime.
+
+use crate::reconnect::ReconnectingClient;
+use crate::transport::ConnectOptions;
+use crate::{KickChatMessage, KickClient, KickError, MessageSource};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// A synchronous client reading messages from `S`, a `KickClient` by default.
+pub struct BlockingKickClient<S = KickClient> {
+    runtime: Runtime,
+    source: S,
+}
+
+impl BlockingKickClient {
+    /// Connects to `url` and subscribes to the chatrooms.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the runtime cannot be started, or connecting
+    /// or subscribing fails.
+    pub fn connect(url: &str, channel_ids: Vec<u64>) -> Result<Self, KickError> {
+        Self::connect_with(url, channel_ids, &ConnectOptions::default())
+    }
+
+    /// Connects to `url` as configured by `options`, and subscribes to the chatrooms.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the runtime cannot be started, or connecting
+    /// or subscribing fails.
+    pub fn connect_with(
+        url: &str,
+        channel_ids: Vec<u64>,
+        options: &ConnectOptions,
+    ) -> Result<Self, KickError> {
+        let runtime = runtime()?;
+        let source = runtime.block_on(KickClient::connect_with(url, channel_ids, options))?;
+        Ok(Self { runtime, source })
+    }
+}
+
+impl BlockingKickClient<ReconnectingClient> {
+    /// Reads from a `ReconnectingClient`, which connects on the first read.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the runtime cannot be started.
+    pub fn reconnecting(client: ReconnectingClient) -> Result<Self, KickError> {
+        Self::from_source(client)
+    }
+}
+
+impl<S: MessageSource> BlockingKickClient<S> {
+    /// Reads from any message source, such as a `recording::ReplayClient`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the runtime cannot be started.
+    pub fn from_source(source: S) -> Result<Self, KickError> {
+        Ok(Self {
+            runtime: runtime()?,
+            source,
+        })
+    }
+
+    /// Blocks until the next message is received.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `KickError::StreamEnded` once the source is exhausted, or
+    /// another error if reading from it fails.
+    pub fn recv(&mut self) -> Result<KickChatMessage, KickError> {
+        self.runtime
+            .block_on(self.source.read_message())?
+            .ok_or(KickError::StreamEnded)
+    }
+
+    /// Blocks until the next message is received, or returns `None` after `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `KickError::StreamEnded` once the source is exhausted, or
+    /// another error if reading from it fails.
+    pub fn recv_timeout(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<Option<KickChatMessage>, KickError> {
+        let read = self.source.read_message();
+        match self
+            .runtime
+            .block_on(async { tokio::time::timeout(timeout, read).await })
+        {
+            Ok(message) => message?.ok_or(KickError::StreamEnded).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Returns the source messages are read from, e.g. to query chatroom states.
+    pub fn get_ref(&self) -> &S {
+        &self.source
+    }
+
+    /// Returns the source messages are read from, mutably.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+}
+
+impl<S: MessageSource> Iterator for BlockingKickClient<S> {
+    type Item = Result<KickChatMessage, KickError>;
+
+    /// Blocks until the next message is received, ending once the source is exhausted.
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.recv() {
+            Err(KickError::StreamEnded) => None,
+            result => Some(result),
+        }
+    }
+}
+
+fn runtime() -> Result<Runtime, KickError> {
+    Ok(tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?)
+}
@@ -0,0 +1,159 @@
+use crate::api::{HttpTransport, KickApi};
+use crate::commands::{Command, CommandContext, Commands};
+use crate::queue::MessageQueue;
+use crate::ratelimit::RateLimiter;
+use crate::reconnect::ReconnectingClient;
+use crate::trace::{spawn, Instrument};
+use crate::{KickChatMessage, KickError, DEFAULT_WEBSOCKET_URL};
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// How many command handlers may run at once by default.
+const DEFAULT_MAX_CONCURRENT_HANDLERS: usize = 32;
+
+type MessageHandler = Box<dyn Fn(&KickChatMessage) + Send + Sync>;
+
+/// A chat bot reading chatrooms and answering commands.
+///
+/// `KickBot` connects to the chatrooms through a `ReconnectingClient`, dispatches
+/// commands and sends replies through a rate limited `MessageQueue`.
+///
+/// Command handlers run in their own tasks, so a slow handler doesn't hold up reading
+/// chat, and may finish out of order. Once too many are running, reading waits for one
+/// to finish.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() {
+/// use kick_client::bot::KickBot;
+///
+/// let mut bot = KickBot::new("token", vec![1234]);
+/// bot.command("ping", |ctx| async move { ctx.reply("pong").await });
+/// bot.run().await;
+/// # }
+/// ```
+pub struct KickBot<T = reqwest::Client> {
+    url: String,
+    chatroom_ids: Vec<u64>,
+    api: KickApi<T>,
+    commands: Commands,
+    on_message: Option<MessageHandler>,
+    max_concurrent_handlers: usize,
+}
+
+impl KickBot {
+    /// Creates a new instance of `KickBot` for the given chatrooms, sending messages with
+    /// the given bearer token.
+    pub fn new(token: impl Into<String>, chatroom_ids: Vec<u64>) -> Self {
+        Self::from_api(KickApi::new(token), chatroom_ids)
+    }
+}
+
+impl<T: HttpTransport> KickBot<T> {
+    /// Creates a new instance of `KickBot` for the given chatrooms, sending messages
+    /// through `api`. A default `RateLimiter` is added if `api` has none.
+    pub fn from_api(api: KickApi<T>, chatroom_ids: Vec<u64>) -> Self {
+        let api = match api.rate_limiter() {
+            Some(_) => api,
+            None => api.with_rate_limiter(RateLimiter::default()),
+        };
+        Self {
+            url: DEFAULT_WEBSOCKET_URL.to_string(),
+            chatroom_ids,
+            api,
+            commands: Commands::new("!"),
+            on_message: None,
+            max_concurrent_handlers: DEFAULT_MAX_CONCURRENT_HANDLERS,
+        }
+    }
+
+    /// Sets the WebSocket URL to connect to.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    /// Sets the prefix commands start with, `!` by default.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.commands = self.commands.with_prefix(prefix);
+        self
+    }
+
+    /// Sets how many command handlers may run at once, 32 by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is zero.
+    pub fn with_max_concurrent_handlers(mut self, max: usize) -> Self {
+        assert!(max > 0, "max must be positive");
+        self.max_concurrent_handlers = max;
+        self
+    }
+
+    /// Returns the API the bot sends messages through, e.g. for use in command handlers.
+    pub fn api(&self) -> &KickApi<T> {
+        &self.api
+    }
+
+    /// Registers a command without aliases or cooldowns.
+    pub fn command<F, Fut>(&mut self, name: impl Into<String>, handler: F) -> &mut Self
+    where
+        F: Fn(CommandContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), KickError>> + Send + 'static,
+    {
+        self.commands.command(name, handler);
+        self
+    }
+
+    /// Registers a command.
+    pub fn register(&mut self, command: Command) -> &mut Self {
+        self.commands.register(command);
+        self
+    }
+
+    /// Sets a callback invoked with every received message, before commands are dispatched.
+    pub fn on_message(
+        &mut self,
+        handler: impl Fn(&KickChatMessage) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.on_message = Some(Box::new(handler));
+        self
+    }
+
+    /// Runs the bot forever, reconnecting whenever the connection fails or drops.
+    pub async fn run(self) {
+        let queue = MessageQueue::new();
+        let commands = Arc::new(self.commands.with_sender(queue.clone()));
+        let handlers = Arc::new(Semaphore::new(self.max_concurrent_handlers));
+        let limiter = self.api.rate_limiter().cloned();
+
+        let read = async {
+            let mut client = ReconnectingClient::new(&self.url, self.chatroom_ids.clone());
+            while let Ok(Some(message)) = client.read_message().await {
+                if let Some(limiter) = &limiter {
+                    limiter.observe(&message);
+                }
+                if let Some(on_message) = &self.on_message {
+                    on_message(&message);
+                }
+                // Waits for a running handler to finish rather than spawning without bound.
+                let Ok(permit) = handlers.clone().acquire_owned().await else {
+                    break;
+                };
+                let commands = commands.clone();
+                let span = span!("dispatch", kind = message.data.kind());
+                spawn(
+                    "dispatch",
+                    async move {
+                        commands.dispatch(&message).await;
+                        drop(permit);
+                    }
+                    .instrument(span),
+                );
+            }
+        };
+        futures_util::future::join(queue.run(self.api.clone()), read).await;
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{ChatMessageEventData, ChatMessageSenderBadge, KickChatMessage, MessageData};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime};
+
+/// A bounded cache of recent chat messages, used to recover the content and sender of
+/// deleted messages, which `DeletedMessage` messages only reference by ID.
+///
+/// Once full, the oldest message is evicted for each new one.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::MessageData;
+///
+/// let mut client = client.with_message_cache(10_000);
+/// while let Some(message) = client.read_message().await? {
+///     if let MessageData::DeletedMessage(data) = message.data {
+///         if let Some(original) = data.original {
+///             println!("{} deleted: {:?}", original.sender.username, original.content);
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MessageCache {
+    capacity: usize,
+    messages: HashMap<String, ChatMessageEventData>,
+    /// The IDs of the cached messages, oldest first. May hold IDs of removed messages,
+    /// which are compacted away once it grows to twice the capacity.
+    order: VecDeque<String>,
+}
+
+impl MessageCache {
+    /// Creates a new instance of `MessageCache` holding up to `capacity` messages.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            messages: HashMap::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Caches a chat message, evicting the oldest one if the cache is full.
+    pub fn insert(&mut self, message: ChatMessageEventData) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.messages.contains_key(&message.id) {
+            self.messages.insert(message.id.clone(), message);
+            return;
+        }
+        while self.messages.len() >= self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.messages.remove(&oldest);
+        }
+        if self.order.len() >= self.capacity * 2 {
+            let messages = &self.messages;
+            self.order.retain(|id| messages.contains_key(id));
+        }
+        self.order.push_back(message.id.clone());
+        self.messages.insert(message.id.clone(), message);
+    }
+
+    /// Returns a cached message by ID.
+    pub fn get(&self, id: &str) -> Option<&ChatMessageEventData> {
+        self.messages.get(id)
+    }
+
+    /// Removes a cached message by ID and returns it.
+    pub fn remove(&mut self, id: &str) -> Option<ChatMessageEventData> {
+        self.messages.remove(id)
+    }
+
+    /// Returns the number of cached messages.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Returns `true` if no messages are cached.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Caches a received chat message, or fills in the `original` of a received
+    /// `DeletedMessage` message from the cache.
+    pub fn enrich(&mut self, message: &mut KickChatMessage) {
+        match &mut message.data {
+            MessageData::ChatMessage(data) => self.insert(data.clone()),
+            MessageData::DeletedMessage(data) if data.original.is_none() => {
+                data.original = self.remove(&data.message.id);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// What a `UserCache` knows about a chatter in a chatroom.
+#[derive(Debug, Clone)]
+pub struct Chatter {
+    /// The ID of the user.
+    pub id: u32,
+    /// The username of the user, as of their latest message.
+    pub username: String,
+    /// The badges of the user, as of their latest message.
+    pub badges: Vec<ChatMessageSenderBadge>,
+    /// When the user's first message was received.
+    pub first_seen: SystemTime,
+    /// When the user's latest message was received.
+    pub last_seen: SystemTime,
+    /// The number of messages received from the user.
+    pub message_count: u64,
+}
+
+impl Chatter {
+    /// Returns `true` if only one message was received from the user, e.g. to highlight
+    /// new chatters.
+    pub fn is_new(&self) -> bool {
+        self.message_count == 1
+    }
+}
+
+/// Keeps track of the users chatting in each chatroom.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::cache::UserCache;
+///
+/// let mut users = UserCache::new();
+/// while let Some(message) = client.read_message().await? {
+///     if let Some(chatter) = users.observe(&message) {
+///         if chatter.is_new() {
+///             println!("Welcome, {}!", chatter.username);
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct UserCache {
+    /// The chatters of each chatroom, keyed by chatroom ID and user ID.
+    chatters: HashMap<(u32, u32), Chatter>,
+}
+
+impl UserCache {
+    /// Creates a new, empty instance of `UserCache`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the sender of a received chat message, returning what is known about them.
+    pub fn observe(&mut self, message: &KickChatMessage) -> Option<&Chatter> {
+        match &message.data {
+            MessageData::ChatMessage(data) => Some(self.record(data)),
+            _ => None,
+        }
+    }
+
+    /// Records the sender of a chat message, returning what is known about them.
+    pub fn record(&mut self, message: &ChatMessageEventData) -> &Chatter {
+        let now = SystemTime::now();
+        let sender = &message.sender;
+        let chatter = self
+            .chatters
+            .entry((message.chatroom_id, sender.id))
+            .or_insert_with(|| Chatter {
+                id: sender.id,
+                username: sender.username.clone(),
+                badges: Vec::new(),
+                first_seen: now,
+                last_seen: now,
+                message_count: 0,
+            });
+        chatter.username.clone_from(&sender.username);
+        chatter.badges.clone_from(&sender.identity.badges);
+        chatter.last_seen = now;
+        chatter.message_count += 1;
+        chatter
+    }
+
+    /// Returns what is known about a user in a chatroom.
+    pub fn get(&self, chatroom_id: u32, user_id: u32) -> Option<&Chatter> {
+        self.chatters.get(&(chatroom_id, user_id))
+    }
+
+    /// Returns what is known about a user in a chatroom, looked up case-insensitively by
+    /// username.
+    pub fn find_by_username(&self, chatroom_id: u32, username: &str) -> Option<&Chatter> {
+        self.chatters(chatroom_id)
+            .find(|chatter| chatter.username.eq_ignore_ascii_case(username))
+    }
+
+    /// Returns the known chatters of a chatroom, in no particular order.
+    pub fn chatters(&self, chatroom_id: u32) -> impl Iterator<Item = &Chatter> {
+        self.chatters
+            .iter()
+            .filter(move |((id, _), _)| *id == chatroom_id)
+            .map(|(_, chatter)| chatter)
+    }
+
+    /// Returns the number of known chatters across all chatrooms.
+    pub fn len(&self) -> usize {
+        self.chatters.len()
+    }
+
+    /// Returns `true` if no chatters are known.
+    pub fn is_empty(&self) -> bool {
+        self.chatters.is_empty()
+    }
+
+    /// Forgets chatters who haven't sent a message within `max_age`, bounding memory use.
+    pub fn retain_seen_within(&mut self, max_age: Duration) {
+        let now = SystemTime::now();
+        self.chatters.retain(|_, chatter| {
+            now.duration_since(chatter.last_seen)
+                .map_or(true, |age| age <= max_age)
+        });
+    }
+}
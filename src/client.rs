@@ -0,0 +1,540 @@
+use crate::cache::MessageCache;
+use crate::metrics::Metrics;
+use crate::recording::Recorder;
+use crate::state::{ChatroomState, ChatroomStateTracker};
+use crate::trace::Instrument;
+#[cfg(feature = "client")]
+use crate::transport::{ConnectOptions, WebSocketTransport};
+use crate::transport::{Frame, Transport};
+use crate::{
+    parse_frame, KickChatMessage, KickError, MessageData, MessageSource,
+    PusherConnectionEstablishedEventData, RawEvent,
+};
+use bytes::Bytes;
+use std::collections::{HashSet, VecDeque};
+#[cfg(feature = "client")]
+use std::error::Error;
+use std::time::Duration;
+
+/// How many frames received while waiting for subscriptions are kept by default.
+const DEFAULT_PENDING_LIMIT: usize = 1024;
+
+/// How long a connection may stay silent before it is pinged, until Pusher announces its
+/// activity timeout. This is Pusher's default.
+const DEFAULT_ACTIVITY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The transport `KickClient` connects through by default: a Tokio WebSocket connection,
+/// or else the `async-std` or browser one.
+#[cfg(feature = "client")]
+type DefaultTransport = WebSocketTransport;
+#[cfg(all(not(feature = "client"), feature = "async-std"))]
+type DefaultTransport = crate::transport::AsyncStdTransport;
+#[cfg(all(not(feature = "client"), not(feature = "async-std"), feature = "wasm"))]
+type DefaultTransport = crate::transport::BrowserTransport;
+
+/// A WebSocket client for connecting to and reading messages from Kick chatroom.
+pub struct KickClient<T = DefaultTransport> {
+    #[allow(dead_code)]
+    /// The WebSocket URL used to connect to the Kick server.
+    url: String,
+    /// The channel ID for the subscribed chatroom.
+    channel_ids: Vec<u64>,
+    /// The transport messages are received through.
+    transport: T,
+    /// The chat mode configuration of the subscribed chatrooms.
+    chatroom_states: ChatroomStateTracker,
+    /// Recent chat messages, kept to fill in deleted messages, if enabled.
+    message_cache: Option<MessageCache>,
+    /// The metrics updated with every read message, if any.
+    metrics: Option<Metrics>,
+    /// The recorder every received frame is written to, if any.
+    recorder: Option<Recorder>,
+    /// The telemetry subscriptions and read messages are recorded to, if any.
+    #[cfg(feature = "otel")]
+    telemetry: Option<crate::otel::Telemetry>,
+    /// Text frames received while waiting for subscriptions, not read yet.
+    pending: VecDeque<Bytes>,
+    /// How many pending frames are kept, the oldest ones being dropped beyond it.
+    pending_limit: usize,
+    /// Whether frames with fields the typed events don't have are read as unsupported.
+    strict: bool,
+    /// The data of the `pusher:connection_established` event, once received.
+    connection: Option<PusherConnectionEstablishedEventData>,
+    /// How long the connection may stay silent before it is pinged, if overridden.
+    ping_interval: Option<Duration>,
+}
+
+#[cfg(feature = "client")]
+impl KickClient {
+    /// Creates a new instance of `KickClient` and automatically establishes a WebSocket connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The WebSocket URL to connect to.
+    /// * `channel_ids` - The IDs of the chatrooms to subscribe to.
+    ///
+    /// # Returns
+    ///
+    /// A `KickClient` instance ready to receive messages.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the WebSocket connection fails.
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run() {
+    /// use kick_client::KickClient;
+    ///
+    /// let mut client = KickClient::new("wss://ws-us2.pusher.com/app/32cbd69e4b950bf97679?protocol=7&client=js&version=8.4.0-rc2&flash=false", vec![281473]).await.unwrap();
+    /// while let Some(message) = client.read_message().await.unwrap() {
+    ///     println!("{}", message);
+    /// }
+    /// # }
+    /// ```
+    pub async fn new(url: &str, channel_ids: Vec<u64>) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::connect(url, channel_ids).await?)
+    }
+
+    /// Creates a new instance of `KickClient`, establishing the WebSocket connection as
+    /// configured by `options`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if connecting or subscribing fails.
+    pub async fn connect_with(
+        url: &str,
+        channel_ids: Vec<u64>,
+        options: &ConnectOptions,
+    ) -> Result<Self, KickError> {
+        let span = span!("connect", %url, chatrooms = channel_ids.len());
+        #[cfg(feature = "otel")]
+        let chatrooms = channel_ids.len();
+        let connect = async {
+            let transport = WebSocketTransport::connect_with(url, options).await?;
+            let client = Self::unsubscribed(url, transport);
+            #[cfg(feature = "otel")]
+            let client = match options.telemetry() {
+                Some(telemetry) => client.with_telemetry(telemetry.clone()),
+                None => client,
+            };
+            let mut client = client.subscribe_all(channel_ids).await?;
+            if let Some(timeout) = options.subscribe_timeout() {
+                client.wait_for_subscriptions(timeout).await?;
+            }
+            Ok(client)
+        }
+        .instrument(span);
+        #[cfg(feature = "otel")]
+        if let Some(telemetry) = options.telemetry() {
+            return telemetry.connect(url, chatrooms, connect).await;
+        }
+        connect.await
+    }
+
+    /// Returns the HTTP response of the server to the WebSocket upgrade, whose headers
+    /// help debugging connections refused by Cloudflare.
+    pub fn handshake_response(&self) -> Option<&tungstenite::handshake::client::Response> {
+        self.transport.handshake_response()
+    }
+}
+
+impl<T: Transport> KickClient<T> {
+    /// Creates a new instance of `KickClient` connected through a transport of type `T`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if connecting or subscribing fails.
+    pub async fn connect(url: &str, channel_ids: Vec<u64>) -> Result<Self, KickError> {
+        let span = span!("connect", %url, chatrooms = channel_ids.len());
+        async {
+            let transport = T::connect(url).await?;
+            Self::from_transport(url, transport, channel_ids).await
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Creates a new instance of `KickClient` on an established transport, subscribing to
+    /// the chatrooms through it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if subscribing fails.
+    pub async fn from_transport(
+        url: &str,
+        transport: T,
+        channel_ids: Vec<u64>,
+    ) -> Result<Self, KickError> {
+        Self::unsubscribed(url, transport)
+            .subscribe_all(channel_ids)
+            .await
+    }
+
+    /// Creates a new instance of `KickClient` on an established transport, subscribed to
+    /// no chatrooms yet.
+    fn unsubscribed(url: &str, transport: T) -> Self {
+        Self {
+            url: url.to_string(),
+            channel_ids: Vec::new(),
+            transport,
+            chatroom_states: ChatroomStateTracker::new(),
+            message_cache: None,
+            metrics: None,
+            recorder: None,
+            #[cfg(feature = "otel")]
+            telemetry: None,
+            pending: VecDeque::new(),
+            pending_limit: DEFAULT_PENDING_LIMIT,
+            strict: false,
+            connection: None,
+            ping_interval: None,
+        }
+    }
+
+    /// Subscribes to the chatrooms a client is created with.
+    async fn subscribe_all(mut self, channel_ids: Vec<u64>) -> Result<Self, KickError> {
+        for channel_id in &channel_ids {
+            self.send_subscribe(*channel_id).await?;
+        }
+        self.channel_ids = channel_ids;
+        Ok(self)
+    }
+
+    async fn send_subscribe(&mut self, channel_id: u64) -> Result<(), KickError> {
+        #[cfg(feature = "otel")]
+        if let Some(telemetry) = &self.telemetry {
+            telemetry.subscribing(channel_id);
+        }
+        self.transport
+            .send(pusher_command("pusher:subscribe", channel_id))
+            .instrument(span!("subscribe", channel_id))
+            .await
+    }
+
+    /// Subscribes to another chatroom, unless already subscribed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the subscription cannot be sent.
+    pub async fn subscribe(&mut self, channel_id: u64) -> Result<(), KickError> {
+        if !self.channel_ids.contains(&channel_id) {
+            self.send_subscribe(channel_id).await?;
+            self.channel_ids.push(channel_id);
+        }
+        Ok(())
+    }
+
+    /// Unsubscribes from a chatroom, unless not subscribed. Messages Pusher sent before
+    /// handling the request may still be read.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request cannot be sent.
+    pub async fn unsubscribe(&mut self, channel_id: u64) -> Result<(), KickError> {
+        if self.channel_ids.contains(&channel_id) {
+            self.transport
+                .send(pusher_command("pusher:unsubscribe", channel_id))
+                .instrument(span!("unsubscribe", channel_id))
+                .await?;
+            self.channel_ids.retain(|id| *id != channel_id);
+        }
+        Ok(())
+    }
+
+    /// Sends a raw text frame, such as a Pusher event.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the frame cannot be sent.
+    pub async fn send_raw(&mut self, text: impl Into<String>) -> Result<(), KickError> {
+        self.transport.send(text.into()).await
+    }
+
+    /// Returns the IDs of the subscribed chatrooms.
+    pub fn channel_ids(&self) -> &[u64] {
+        &self.channel_ids
+    }
+
+    /// Closes the connection, telling Pusher when the transport can.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the close request cannot be sent.
+    pub async fn close(&mut self) -> Result<(), KickError> {
+        self.transport.close().await
+    }
+
+    /// Waits until Pusher confirms the subscription to every chatroom. The messages
+    /// received meanwhile, including the confirmations, are still returned by
+    /// `read_message`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `KickError::TimeoutError` if the confirmations take
+    /// longer than `timeout`, or another error if the connection fails or closes.
+    pub async fn wait_for_subscriptions(&mut self, timeout: Duration) -> Result<(), KickError> {
+        let mut waiting: HashSet<String> = self
+            .channel_ids
+            .iter()
+            .map(|id| format!("chatrooms.{}.v2", id))
+            .collect();
+        let wait = async {
+            while !waiting.is_empty() {
+                match self.transport.next_frame().await? {
+                    Some(Frame::Text(text)) => {
+                        let message = parse_frame(&text);
+                        self.observe_connection(&message);
+                        #[cfg(feature = "otel")]
+                        if let Some(telemetry) = &self.telemetry {
+                            telemetry.confirmed(&message);
+                        }
+                        if let (MessageData::PusherSubscriptionSucceeded(_), Some(channel)) =
+                            (&message.data, &message.channel)
+                        {
+                            waiting.remove(channel);
+                        }
+                        self.pending.push_back(text);
+                        if self.pending.len() > self.pending_limit {
+                            self.pending.pop_front();
+                            if let Some(metrics) = &self.metrics {
+                                metrics.record_dropped(1);
+                            }
+                        }
+                    }
+                    Some(_) => {}
+                    None => return Err(KickError::StreamEnded),
+                }
+            }
+            Ok(())
+        };
+        tokio::time::timeout(timeout, wait).await.map_err(|_| {
+            KickError::TimeoutError(format!(
+                "subscriptions weren't confirmed within {:?}",
+                timeout
+            ))
+        })?
+    }
+
+    /// Reads the next message from the WebSocket stream and returns a parsed `KickChatMessage`.
+    ///
+    /// # Returns
+    ///
+    /// A `KickChatMessage` if a valid message is received, or `None` if the stream ends.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the WebSocket stream encounters an error.
+    pub async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        Ok(self.read_raw().await?.map(|event| event.parsed))
+    }
+
+    /// Reads the next message like `read_message`, along with the text frame it was parsed
+    /// from, as received.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the WebSocket stream encounters an error.
+    pub async fn read_raw(&mut self) -> Result<Option<RawEvent>, KickError> {
+        let frame = match self.pending.pop_front() {
+            Some(text) => Some(Frame::Text(text)),
+            None => self.next_frame().instrument(span!("read")).await?,
+        };
+        if let Some(frame) = frame {
+            match frame {
+                Frame::Text(text) => {
+                    let span = span!("parse", len = text.len());
+                    let _entered = span.enter();
+                    if let Some(recorder) = &mut self.recorder {
+                        recorder.record(&String::from_utf8_lossy(&text))?;
+                    }
+                    let mut parsed_message = parse_frame(&text);
+                    if self.strict {
+                        parsed_message = crate::strict::check(parsed_message, &text);
+                    }
+                    self.observe_connection(&parsed_message);
+                    self.chatroom_states.observe(&parsed_message);
+                    if let Some(cache) = &mut self.message_cache {
+                        cache.enrich(&mut parsed_message);
+                    }
+                    if let Some(metrics) = &self.metrics {
+                        metrics.observe(&parsed_message);
+                    }
+                    #[cfg(feature = "otel")]
+                    if let Some(telemetry) = &self.telemetry {
+                        telemetry.observe(&parsed_message);
+                    }
+                    Ok(Some(RawEvent {
+                        raw: text,
+                        parsed: parsed_message,
+                    }))
+                }
+                _ => Ok(Some(RawEvent {
+                    raw: Bytes::new(),
+                    parsed: KickChatMessage {
+                        data: MessageData::Unknown(None),
+                        channel: None,
+                    },
+                })),
+            }
+        } else {
+            Err(KickError::StreamEnded)
+        }
+    }
+
+    /// Receives the next frame, sending a `pusher:ping` whenever nothing was received for
+    /// the ping interval, as Pusher expects from idle clients. Pings rely on Tokio's timers,
+    /// so they are only sent within a Tokio runtime.
+    async fn next_frame(&mut self) -> Result<Option<Frame>, KickError> {
+        #[cfg(feature = "client")]
+        if tokio::runtime::Handle::try_current().is_ok() {
+            loop {
+                let interval = self.ping_interval();
+                if interval.is_zero() {
+                    break;
+                }
+                match tokio::time::timeout(interval, self.transport.next_frame()).await {
+                    Ok(frame) => return frame,
+                    Err(_) => self.transport.send(PING.to_string()).await?,
+                }
+            }
+        }
+        self.transport.next_frame().await
+    }
+
+    /// Keeps the data of the `pusher:connection_established` event.
+    fn observe_connection(&mut self, message: &KickChatMessage) {
+        if let MessageData::PusherConnectionEstablished(data) = &message.data {
+            self.connection = Some(data.clone());
+        }
+    }
+
+    /// Returns the ID Pusher gave the connection, which private channels must be
+    /// authorized for, or `None` until `pusher:connection_established` is read.
+    pub fn socket_id(&self) -> Option<&str> {
+        self.connection.as_ref().map(|data| data.socket_id.as_str())
+    }
+
+    /// Returns the activity timeout Pusher announced, after which it expects idle clients to
+    /// ping, or `None` until `pusher:connection_established` is read.
+    pub fn activity_timeout(&self) -> Option<Duration> {
+        let data = self.connection.as_ref()?;
+        Some(Duration::from_secs(data.activity_timeout.into()))
+    }
+
+    /// Returns how long the connection may stay silent before a `pusher:ping` is sent: the
+    /// interval set with `with_ping_interval`, or else the activity timeout Pusher
+    /// announced, 120 seconds until then.
+    pub fn ping_interval(&self) -> Duration {
+        self.ping_interval
+            .or_else(|| self.activity_timeout())
+            .unwrap_or(DEFAULT_ACTIVITY_TIMEOUT)
+    }
+
+    /// Pings after `interval` of silence instead of Pusher's activity timeout, e.g. sooner
+    /// to keep NAT mappings of mobile networks alive. A zero interval disables pings.
+    pub fn with_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Keeps up to `capacity` recent chat messages, so the `original` of `DeletedMessage`
+    /// messages is filled in when the deleted message was read before.
+    pub fn with_message_cache(mut self, capacity: usize) -> Self {
+        self.message_cache = Some(MessageCache::new(capacity));
+        self
+    }
+
+    /// Updates `metrics` with every read message.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Sets how many frames received while waiting for subscriptions are kept to be read,
+    /// 1024 by default. The oldest ones are dropped beyond it, and counted in the metrics.
+    pub fn with_pending_limit(mut self, limit: usize) -> Self {
+        self.pending_limit = limit;
+        self
+    }
+
+    /// Reads frames with fields the typed events don't have as `MessageData::Unsupported`,
+    /// with an error listing them, instead of ignoring these fields. Off by default.
+    pub fn with_strict_parsing(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Returns the metrics updated with every read message, if any.
+    #[cfg(feature = "handle")]
+    pub(crate) fn metrics(&self) -> Option<&Metrics> {
+        self.metrics.as_ref()
+    }
+
+    /// Writes every received text frame to `recorder` before parsing it.
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Records subscription latencies and read messages to `telemetry`. Clients connected
+    /// with `ConnectOptions::with_telemetry` record to it already.
+    #[cfg(feature = "otel")]
+    pub fn with_telemetry(mut self, telemetry: crate::otel::Telemetry) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Returns the chat mode configuration of a subscribed chatroom, or `None` until the
+    /// first `ChatroomUpdated` message for it is read.
+    pub fn chatroom_state(&self, chatroom_id: u32) -> Option<ChatroomState> {
+        self.chatroom_states.get(chatroom_id)
+    }
+
+    /// Returns a receiver notified whenever the chat mode configuration of a subscribed
+    /// chatroom changes. Updates are only applied while messages are being read.
+    pub fn watch_chatroom_state(
+        &self,
+        chatroom_id: u32,
+    ) -> tokio::sync::watch::Receiver<Option<ChatroomState>> {
+        self.chatroom_states.watch(chatroom_id)
+    }
+}
+
+#[cfg(feature = "tokio-handling")]
+impl<T: Transport + 'static> KickClient<T> {
+    /// If the `tokio-handling` feature is enabled, this function spawns a task that handles
+    /// incoming messages and invokes the provided callback for each message.
+    pub fn start_handling<F>(mut self, callback: F)
+    where
+        F: Fn(KickChatMessage) + Send + Sync + 'static,
+    {
+        crate::trace::spawn("kick_client::start_handling", async move {
+            while let Ok(Some(message)) = self.read_message().await {
+                callback(message);
+            }
+        });
+    }
+}
+
+/// The frame pinging Pusher, which answers with `pusher:pong`.
+#[cfg(feature = "client")]
+const PING: &str = r#"{"event":"pusher:ping","data":{}}"#;
+
+/// Returns a Pusher `event`, such as `pusher:subscribe`, for the channel of a chatroom.
+fn pusher_command(event: &str, channel_id: u64) -> String {
+    serde_json::json!({
+        "event": event,
+        "data": {
+            "auth": "",
+            "channel": format!("chatrooms.{}.v2", channel_id)
+        }
+    })
+    .to_string()
+}
+
+impl<T: Transport> MessageSource for KickClient<T> {
+    async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        KickClient::read_message(self).await
+    }
+}
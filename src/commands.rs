@@ -0,0 +1,699 @@
+use crate::cooldown::Cooldowns;
+use crate::{
+    ChatMessageEventData, ChatMessageSender, ChatSender, KickChatMessage, KickError, MessageData,
+    MessageSource,
+};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How many invocations `Commands::dispatch` checks between purges of expired cooldowns.
+const PURGE_INTERVAL: usize = 1000;
+
+type Handler =
+    Arc<dyn Fn(CommandContext) -> BoxFuture<'static, Result<(), KickError>> + Send + Sync>;
+
+/// A chat message that invokes a command, e.g. `!so xqc` with the prefix `!`.
+#[derive(Debug, Clone)]
+pub struct Invocation {
+    /// The name of the invoked command, in lowercase.
+    pub name: String,
+    /// The arguments following the name, separated by whitespace except within double
+    /// quotes, as `Args` splits them.
+    pub args: Vec<String>,
+    /// Everything following the name, with surrounding whitespace trimmed.
+    pub raw_args: String,
+    /// The chat message containing the invocation.
+    pub message: ChatMessageEventData,
+}
+
+impl Invocation {
+    /// Parses a chat message as a command invocation, returning `None` if its content
+    /// doesn't start with `prefix` directly followed by a name.
+    pub fn parse(prefix: &str, message: &ChatMessageEventData) -> Option<Self> {
+        let rest = message
+            .content
+            .as_deref()?
+            .trim_start()
+            .strip_prefix(prefix)?;
+        let (name, raw_args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if name.is_empty() {
+            return None;
+        }
+        let raw_args = raw_args.trim();
+        Some(Self {
+            name: name.to_lowercase(),
+            args: std::iter::from_fn({
+                let mut args = Args::new(raw_args);
+                move || args.next_arg().map(String::from)
+            })
+            .collect(),
+            raw_args: raw_args.to_string(),
+            message: message.clone(),
+        })
+    }
+
+    /// Returns the user who invoked the command.
+    pub fn sender(&self) -> &ChatMessageSender {
+        &self.message.sender
+    }
+
+    /// Returns the ID of the chatroom the command was invoked in.
+    pub fn chatroom_id(&self) -> u32 {
+        self.message.chatroom_id
+    }
+}
+
+/// Who may use a command, from least to most privileged. Each level includes the ones
+/// above it, so a moderator may use `Permission::Vip` commands.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    /// Anyone in the chatroom.
+    #[default]
+    Everyone,
+    /// Subscribers, including founders and OGs.
+    Subscriber,
+    /// VIPs of the channel.
+    Vip,
+    /// Moderators of the channel.
+    Moderator,
+    /// The owner of the channel.
+    Broadcaster,
+}
+
+impl Permission {
+    /// Returns the highest permission level the badges of a sender grant.
+    pub fn of(sender: &ChatMessageSender) -> Self {
+        sender
+            .identity
+            .badges
+            .iter()
+            .map(|badge| match badge.badge_type() {
+                "broadcaster" => Permission::Broadcaster,
+                "moderator" => Permission::Moderator,
+                "vip" => Permission::Vip,
+                "subscriber" | "founder" | "og" => Permission::Subscriber,
+                _ => Permission::Everyone,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// What a command handler is given when its command is invoked.
+#[derive(Clone)]
+pub struct CommandContext {
+    /// The invocation that triggered the handler.
+    pub invocation: Invocation,
+    sender: Option<Arc<dyn ChatSender>>,
+}
+
+impl CommandContext {
+    /// Parses the arguments of the invocation, e.g. as `(Username, Duration, Option<Rest>)`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `KickError::UsageError` if an argument is missing or
+    /// invalid. Returning it from a handler makes `Commands` reply with the usage of the
+    /// command.
+    pub fn args<A: FromArgs>(&self) -> Result<A, KickError> {
+        A::from_args(&mut Args::new(&self.invocation.raw_args))
+    }
+
+    /// Sends a message to the chatroom the command was invoked in.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the `Commands` dispatching the invocation
+    /// has no `ChatSender`, or if the message cannot be sent.
+    pub async fn reply(&self, content: &str) -> Result<(), KickError> {
+        let sender = self.sender.as_ref().ok_or(KickError::NoChatSender)?;
+        sender
+            .send_message(self.invocation.chatroom_id(), content)
+            .await
+    }
+}
+
+/// A chat command: a name, its aliases, cooldowns and the handler run when it is invoked.
+pub struct Command {
+    name: String,
+    aliases: Vec<String>,
+    global_cooldown: Option<Duration>,
+    user_cooldown: Option<Duration>,
+    usage: Option<String>,
+    permission: Permission,
+    allowed_users: HashSet<u32>,
+    handler: Handler,
+}
+
+impl Command {
+    /// Creates a new instance of `Command` running `handler` when invoked by `name`.
+    /// Names are matched case-insensitively.
+    pub fn new<F, Fut>(name: impl Into<String>, handler: F) -> Self
+    where
+        F: Fn(CommandContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), KickError>> + Send + 'static,
+    {
+        Self {
+            name: name.into().to_lowercase(),
+            aliases: Vec::new(),
+            global_cooldown: None,
+            user_cooldown: None,
+            usage: None,
+            permission: Permission::Everyone,
+            allowed_users: HashSet::new(),
+            handler: Arc::new(move |context| Box::pin(handler(context))),
+        }
+    }
+
+    /// Creates a new instance of `Command` whose handler is given its parsed arguments.
+    ///
+    /// Invocations with missing or invalid arguments are answered with the usage of the
+    /// command instead of running `handler`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kick_client::commands::{Command, Rest, Username};
+    /// use std::time::Duration;
+    ///
+    /// type TimeoutArgs = (Username, Duration, Option<Rest>);
+    ///
+    /// let command = Command::with_args("timeout", |ctx, (user, duration, _): TimeoutArgs| async move {
+    ///     ctx.reply(&format!("Timing out {} for {}s", user, duration.as_secs()))
+    ///         .await
+    /// })
+    /// .with_usage("<user> <duration> [reason]");
+    /// ```
+    pub fn with_args<A, F, Fut>(name: impl Into<String>, handler: F) -> Self
+    where
+        A: FromArgs + Send + 'static,
+        F: Fn(CommandContext, A) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), KickError>> + Send + 'static,
+    {
+        let handler = Arc::new(handler);
+        Self::new(name, move |context: CommandContext| {
+            let handler = handler.clone();
+            async move {
+                let args = context.args::<A>()?;
+                handler(context, args).await
+            }
+        })
+    }
+
+    /// Adds another name the command can be invoked by.
+    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
+        self.aliases.push(alias.into().to_lowercase());
+        self
+    }
+
+    /// Sets how long the command is unavailable to everyone after anyone uses it.
+    pub fn with_global_cooldown(mut self, cooldown: Duration) -> Self {
+        self.global_cooldown = Some(cooldown);
+        self
+    }
+
+    /// Sets how long the command is unavailable to a user after they use it.
+    pub fn with_user_cooldown(mut self, cooldown: Duration) -> Self {
+        self.user_cooldown = Some(cooldown);
+        self
+    }
+
+    /// Sets the arguments shown after the name when replying with the usage of the command,
+    /// e.g. `<user> [reason]`.
+    pub fn with_usage(mut self, usage: impl Into<String>) -> Self {
+        self.usage = Some(usage.into());
+        self
+    }
+
+    /// Sets the permission level required to use the command.
+    pub fn with_permission(mut self, permission: Permission) -> Self {
+        self.permission = permission;
+        self
+    }
+
+    /// Lets a user use the command regardless of their permission level.
+    pub fn allow_user(mut self, user_id: u32) -> Self {
+        self.allowed_users.insert(user_id);
+        self
+    }
+
+    /// Returns `true` if a sender may use the command.
+    pub fn is_permitted(&self, sender: &ChatMessageSender) -> bool {
+        self.allowed_users.contains(&sender.id) || Permission::of(sender) >= self.permission
+    }
+
+    /// Returns the name of the command.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// What `Commands::dispatch` did with a message.
+#[derive(Debug)]
+pub enum DispatchOutcome {
+    /// The message isn't a chat message starting with the command prefix.
+    NotACommand,
+    /// The message invokes a command that isn't registered.
+    UnknownCommand(Invocation),
+    /// The sender isn't permitted to use the command.
+    NotPermitted(Invocation),
+    /// The command is on cooldown for the sender for the given time.
+    OnCooldown(Duration),
+    /// The handler of the command ran and returned this result.
+    Handled(Result<(), KickError>),
+}
+
+/// A set of chat commands sharing a prefix, dispatched from incoming messages.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(
+/// #     mut client: kick_client::KickClient,
+/// #     api: impl kick_client::ChatSender,
+/// # ) -> Result<(), kick_client::KickError> {
+/// use kick_client::commands::{Command, Commands, Permission};
+/// use std::time::Duration;
+///
+/// let mut commands = Commands::new("!").with_sender(api);
+/// commands.register(
+///     Command::new("ping", |ctx| async move { ctx.reply("pong").await })
+///         .with_user_cooldown(Duration::from_secs(10)),
+/// );
+/// commands.register(
+///     Command::new("clear", |ctx| async move { ctx.reply("Clearing chat").await })
+///         .with_permission(Permission::Moderator),
+/// );
+/// commands.run(&mut client).await
+/// # }
+/// ```
+pub struct Commands {
+    prefix: String,
+    /// The registered commands, keyed by name and by alias.
+    commands: HashMap<String, Arc<Command>>,
+    cooldowns: Mutex<Cooldowns>,
+    /// How many invocations checked the cooldowns, to purge them periodically.
+    invocations: AtomicUsize,
+    sender: Option<Arc<dyn ChatSender>>,
+}
+
+impl Commands {
+    /// Creates a new instance of `Commands` recognizing invocations starting with `prefix`.
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            commands: HashMap::new(),
+            cooldowns: Mutex::new(Cooldowns::new()),
+            invocations: AtomicUsize::new(0),
+            sender: None,
+        }
+    }
+
+    /// Sets what `CommandContext::reply` sends messages through.
+    pub fn with_sender(mut self, sender: impl ChatSender) -> Self {
+        self.sender = Some(Arc::new(sender));
+        self
+    }
+
+    /// Sets the prefix invocations start with.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Returns the prefix invocations start with.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Registers a command, replacing any registered command with the same name or alias.
+    pub fn register(&mut self, command: Command) {
+        let mut cooldowns = self.lock_cooldowns();
+        if let Some(cooldown) = command.global_cooldown {
+            cooldowns.set_global(command.name.clone(), cooldown);
+        }
+        if let Some(cooldown) = command.user_cooldown {
+            cooldowns.set_per_user(command.name.clone(), cooldown);
+        }
+        drop(cooldowns);
+
+        let command = Arc::new(command);
+        for alias in &command.aliases {
+            self.commands.insert(alias.clone(), command.clone());
+        }
+        self.commands.insert(command.name.clone(), command);
+    }
+
+    /// Registers a command without aliases or cooldowns.
+    pub fn command<F, Fut>(&mut self, name: impl Into<String>, handler: F)
+    where
+        F: Fn(CommandContext) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), KickError>> + Send + 'static,
+    {
+        self.register(Command::new(name, handler));
+    }
+
+    /// Returns the command registered under a name or alias.
+    pub fn get(&self, name: &str) -> Option<&Command> {
+        self.commands
+            .get(&name.to_lowercase())
+            .map(|command| &**command)
+    }
+
+    /// Parses a message as an invocation of any command, registered or not.
+    pub fn parse(&self, message: &KickChatMessage) -> Option<Invocation> {
+        match &message.data {
+            MessageData::ChatMessage(data) => Invocation::parse(&self.prefix, data),
+            _ => None,
+        }
+    }
+
+    /// Runs the handler of the command a message invokes, if it is registered and not on
+    /// cooldown for the sender. Handlers returning `KickError::UsageError` don't start the
+    /// cooldowns of the command, and the sender is replied to with its usage.
+    pub async fn dispatch(&self, message: &KickChatMessage) -> DispatchOutcome {
+        let Some(invocation) = self.parse(message) else {
+            return DispatchOutcome::NotACommand;
+        };
+        let Some(command) = self.commands.get(&invocation.name).cloned() else {
+            return DispatchOutcome::UnknownCommand(invocation);
+        };
+        if !command.is_permitted(invocation.sender()) {
+            return DispatchOutcome::NotPermitted(invocation);
+        }
+        let used = {
+            let mut cooldowns = self.lock_cooldowns();
+            if self.invocations.fetch_add(1, Ordering::Relaxed) % PURGE_INTERVAL
+                == PURGE_INTERVAL - 1
+            {
+                cooldowns.purge_expired();
+            }
+            cooldowns.try_use(invocation.sender().id, &command.name)
+        };
+        if let Err(remaining) = used {
+            return DispatchOutcome::OnCooldown(remaining);
+        }
+
+        let context = CommandContext {
+            invocation,
+            sender: self.sender.clone(),
+        };
+        let result = (command.handler)(context.clone()).await;
+        if let Err(KickError::UsageError(err)) = &result {
+            // A mistyped invocation doesn't use the command up.
+            self.lock_cooldowns()
+                .reset(context.invocation.sender().id, &command.name);
+            let reply = match &command.usage {
+                Some(usage) => format!("{}. Usage: {}{} {}", err, self.prefix, command.name, usage),
+                None => err.clone(),
+            };
+            // The usage error is what gets reported, not a failure to send the reply.
+            let _ = context.reply(&reply).await;
+        }
+        DispatchOutcome::Handled(result)
+    }
+
+    /// Forgets usages whose cooldowns have expired. `dispatch` does so every 1000
+    /// invocations.
+    pub fn purge_expired(&self) {
+        self.lock_cooldowns().purge_expired();
+    }
+
+    /// Dispatches every message read from `source` until it ends, ignoring errors returned
+    /// by handlers.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if reading from `source` fails.
+    pub async fn run<S: MessageSource>(&self, source: &mut S) -> Result<(), KickError> {
+        while let Some(message) = source.read_message().await? {
+            self.dispatch(&message).await;
+        }
+        Ok(())
+    }
+
+    fn lock_cooldowns(&self) -> std::sync::MutexGuard<'_, Cooldowns> {
+        self.cooldowns.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A cursor over the arguments of an invocation, consumed by `FromArgs` implementations.
+#[derive(Debug, Clone)]
+pub struct Args<'a> {
+    rest: &'a str,
+    position: usize,
+}
+
+impl<'a> Args<'a> {
+    /// Creates a new instance of `Args` over the arguments of an invocation.
+    pub fn new(raw_args: &'a str) -> Self {
+        Self {
+            rest: raw_args.trim(),
+            position: 0,
+        }
+    }
+
+    /// Consumes and returns the next whitespace-separated argument, or the text between
+    /// double quotes if it is quoted, e.g. `"Dust 2"`.
+    pub fn next_arg(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let quoted = self
+            .rest
+            .strip_prefix('"')
+            .and_then(|quoted| quoted.split_once('"'))
+            .filter(|(_, rest)| rest.is_empty() || rest.starts_with(char::is_whitespace));
+        let (arg, rest) = quoted.unwrap_or_else(|| {
+            self.rest
+                .split_once(char::is_whitespace)
+                .unwrap_or((self.rest, ""))
+        });
+        self.rest = rest.trim_start();
+        self.position += 1;
+        Some(arg)
+    }
+
+    /// Consumes and returns all remaining arguments as they were written.
+    pub fn rest(&mut self) -> &'a str {
+        let rest = std::mem::take(&mut self.rest);
+        if !rest.is_empty() {
+            self.position += 1;
+        }
+        rest
+    }
+
+    /// Returns `true` if all arguments have been consumed.
+    pub fn is_empty(&self) -> bool {
+        self.rest.is_empty()
+    }
+
+    /// Consumes the next argument and parses it with `parse`, describing it as `expected`
+    /// in errors.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `KickError::UsageError` if there is no next argument or
+    /// `parse` rejects it.
+    pub fn parse_next<T>(
+        &mut self,
+        expected: &str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<T, KickError> {
+        let arg = self.next_arg().ok_or_else(|| {
+            KickError::UsageError(format!(
+                "Missing argument {}: expected {}",
+                self.position + 1,
+                expected
+            ))
+        })?;
+        parse(arg).ok_or_else(|| {
+            KickError::UsageError(format!(
+                "Invalid argument {}: expected {}, got \"{}\"",
+                self.position, expected, arg
+            ))
+        })
+    }
+}
+
+/// A type that can be parsed from the arguments of an invocation.
+///
+/// Implemented for integers, `f64`, `bool`, `String` (a word or quoted text), `Duration`
+/// (`90`, `90s`, `5m`, `1h30m`, ...), `Username`, `Rest`, `Option`s of those and tuples of
+/// them.
+pub trait FromArgs: Sized {
+    /// Parses a value, consuming the arguments it is made of.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `KickError::UsageError` if the arguments are missing or
+    /// invalid.
+    fn from_args(args: &mut Args<'_>) -> Result<Self, KickError>;
+}
+
+/// A username, written with or without a leading `@`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Username(pub String);
+
+impl fmt::Display for Username {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// All remaining arguments, e.g. the reason of a ban. Must not be empty.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rest(pub String);
+
+impl fmt::Display for Rest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+macro_rules! from_str_args {
+    ($($ty:ty => $expected:literal),* $(,)?) => {
+        $(
+            impl FromArgs for $ty {
+                fn from_args(args: &mut Args<'_>) -> Result<Self, KickError> {
+                    args.parse_next($expected, |arg| arg.parse().ok())
+                }
+            }
+        )*
+    };
+}
+
+from_str_args!(
+    u8 => "a number",
+    u16 => "a number",
+    u32 => "a number",
+    u64 => "a number",
+    usize => "a number",
+    i8 => "a number",
+    i16 => "a number",
+    i32 => "a number",
+    i64 => "a number",
+    f64 => "a number",
+);
+
+impl FromArgs for bool {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, KickError> {
+        args.parse_next("on or off", |arg| match arg.to_lowercase().as_str() {
+            "on" | "true" | "yes" | "enable" => Some(true),
+            "off" | "false" | "no" | "disable" => Some(false),
+            _ => None,
+        })
+    }
+}
+
+impl FromArgs for String {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, KickError> {
+        args.parse_next("a word", |arg| Some(arg.to_string()))
+    }
+}
+
+impl FromArgs for Duration {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, KickError> {
+        args.parse_next("a duration such as 30s, 10m or 1h", parse_duration)
+    }
+}
+
+impl FromArgs for Username {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, KickError> {
+        args.parse_next("a username", |arg| {
+            let name = arg.strip_prefix('@').unwrap_or(arg);
+            let valid = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            valid.then(|| Username(name.to_string()))
+        })
+    }
+}
+
+impl FromArgs for Rest {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, KickError> {
+        let position = args.position;
+        let rest = args.rest();
+        if rest.is_empty() {
+            return Err(KickError::UsageError(format!(
+                "Missing argument {}: expected some text",
+                position + 1
+            )));
+        }
+        Ok(Rest(rest.to_string()))
+    }
+}
+
+/// `None` if no arguments are left, otherwise the parsed value.
+impl<T: FromArgs> FromArgs for Option<T> {
+    fn from_args(args: &mut Args<'_>) -> Result<Self, KickError> {
+        if args.is_empty() {
+            Ok(None)
+        } else {
+            T::from_args(args).map(Some)
+        }
+    }
+}
+
+/// Ignores the arguments.
+impl FromArgs for () {
+    fn from_args(_: &mut Args<'_>) -> Result<Self, KickError> {
+        Ok(())
+    }
+}
+
+macro_rules! tuple_args {
+    ($($ty:ident),+) => {
+        impl<$($ty: FromArgs),+> FromArgs for ($($ty,)+) {
+            fn from_args(args: &mut Args<'_>) -> Result<Self, KickError> {
+                Ok(($($ty::from_args(args)?,)+))
+            }
+        }
+    };
+}
+
+tuple_args!(A);
+tuple_args!(A, B);
+tuple_args!(A, B, C);
+tuple_args!(A, B, C, D);
+tuple_args!(A, B, C, D, E);
+tuple_args!(A, B, C, D, E, F);
+
+/// Parses a duration such as `90`, `90s`, `5m`, `2h`, `1d` or `1h30m`. Bare numbers are
+/// seconds.
+fn parse_duration(arg: &str) -> Option<Duration> {
+    if let Ok(secs) = arg.parse() {
+        return Some(Duration::from_secs(secs));
+    }
+    let mut total: u64 = 0;
+    let mut rest = arg;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        if digits == 0 {
+            return None;
+        }
+        let value: u64 = rest[..digits].parse().ok()?;
+        let unit = rest[digits..].chars().next()?;
+        let multiplier = match unit.to_ascii_lowercase() {
+            's' => 1,
+            'm' => 60,
+            'h' => 60 * 60,
+            'd' => 24 * 60 * 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        total = total.checked_add(value.checked_mul(multiplier)?)?;
+        rest = &rest[digits + unit.len_utf8()..];
+    }
+    Some(Duration::from_secs(total))
+}
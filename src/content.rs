@@ -0,0 +1,161 @@
+use std::ops::Range;
+
+/// An emote in the content of a chat message, written as `[emote:ID:NAME]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Emote<'a> {
+    /// The ID of the emote.
+    pub id: u64,
+    /// The name of the emote, e.g. `KEKW`.
+    pub name: &'a str,
+    /// Where the emote is written in the content, in bytes.
+    pub range: Range<usize>,
+}
+
+/// Returns the emotes in the content of a chat message, in order.
+///
+/// # Examples
+///
+/// ```
+/// use kick_client::content::emotes;
+///
+/// let emotes = emotes("hi [emote:37226:KEKW] [emote:37227:LULW]");
+/// assert_eq!(emotes.len(), 2);
+/// assert_eq!(emotes[0].name, "KEKW");
+/// ```
+pub fn emotes(content: &str) -> Vec<Emote<'_>> {
+    const OPENING: &str = "[emote:";
+
+    let mut emotes = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = content[offset..].find(OPENING) {
+        let start = offset + found;
+        let inner_start = start + OPENING.len();
+        let Some(length) = content[inner_start..].find(']') else {
+            break;
+        };
+        let inner = &content[inner_start..inner_start + length];
+        let end = inner_start + length + 1;
+        if let Some((id, name)) = inner.split_once(':') {
+            if let Ok(id) = id.parse() {
+                emotes.push(Emote {
+                    id,
+                    name,
+                    range: start..end,
+                });
+                offset = end;
+                continue;
+            }
+        }
+        offset = inner_start;
+    }
+    emotes
+}
+
+/// Returns the content of a chat message with its emotes removed.
+pub fn strip_emotes(content: &str) -> String {
+    let mut stripped = String::with_capacity(content.len());
+    let mut offset = 0;
+    for emote in emotes(content) {
+        stripped.push_str(&content[offset..emote.range.start]);
+        offset = emote.range.end;
+    }
+    stripped.push_str(&content[offset..]);
+    stripped
+}
+
+/// A link in the content of a chat message, with or without a scheme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link<'a> {
+    /// The link as written, without surrounding punctuation.
+    pub text: &'a str,
+    /// Where the link is written in the content, in bytes.
+    pub range: Range<usize>,
+}
+
+impl Link<'_> {
+    /// Returns the host of the link in lowercase, e.g. `kick.com` for
+    /// `https://www.Kick.com/xqc`.
+    pub fn host(&self) -> String {
+        let rest = self
+            .text
+            .split_once("://")
+            .map_or(self.text, |(_, rest)| rest);
+        let host = rest
+            .split(['/', '?', '#', ':'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match host.strip_prefix("www.") {
+            Some(host) => host.to_string(),
+            None => host,
+        }
+    }
+}
+
+/// Returns the links in the content of a chat message, in order.
+///
+/// Besides URLs with a scheme, words shaped like a domain name such as `example.com/page`
+/// count as links. Emotes are never links.
+///
+/// # Examples
+///
+/// ```
+/// use kick_client::content::links;
+///
+/// let links = links("check https://kick.com/xqc and example.org!");
+/// assert_eq!(links.len(), 2);
+/// assert_eq!(links[1].text, "example.org");
+/// assert_eq!(links[0].host(), "kick.com");
+/// ```
+pub fn links(content: &str) -> Vec<Link<'_>> {
+    let emotes = emotes(content);
+    let mut links = Vec::new();
+    let mut offset = 0;
+    for word in content.split_whitespace() {
+        let start = offset + content[offset..].find(word).unwrap_or_default();
+        offset = start + word.len();
+        if emotes
+            .iter()
+            .any(|emote| emote.range.start < offset && start < emote.range.end)
+        {
+            continue;
+        }
+
+        let trimmed_start = word.trim_start_matches(|c: char| "([{<\"'".contains(c));
+        let text = trimmed_start.trim_end_matches(|c: char| ".,!?;:)]}>\"'".contains(c));
+        if is_link(text) {
+            let start = start + (word.len() - trimmed_start.len());
+            links.push(Link {
+                text,
+                range: start..start + text.len(),
+            });
+        }
+    }
+    links
+}
+
+/// Returns `true` if a word is a URL or shaped like a domain name.
+fn is_link(word: &str) -> bool {
+    let lowercase = word.to_lowercase();
+    if ["http://", "https://", "www."]
+        .iter()
+        .any(|prefix| lowercase.starts_with(prefix) && lowercase.len() > prefix.len())
+    {
+        return true;
+    }
+
+    let host = lowercase.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+    let labels: Vec<&str> = host.split('.').collect();
+    let Some((tld, labels)) = labels.split_last() else {
+        return false;
+    };
+    !labels.is_empty()
+        && (2..=24).contains(&tld.len())
+        && tld.chars().all(|c| c.is_ascii_alphabetic())
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        })
+}
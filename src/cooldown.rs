@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Tracks command cooldowns, keyed by command and by (user, command).
+///
+/// A command can have a global cooldown, shared by all users, and a per-user cooldown.
+/// Using a command starts both.
+///
+/// # Examples
+///
+/// ```
+/// use kick_client::cooldown::Cooldowns;
+/// use std::time::Duration;
+///
+/// let mut cooldowns = Cooldowns::new();
+/// cooldowns.set_per_user("clip", Duration::from_secs(30));
+///
+/// assert!(cooldowns.try_use(1, "clip").is_ok());
+/// assert!(cooldowns.try_use(1, "clip").is_err());
+/// assert!(cooldowns.try_use(2, "clip").is_ok());
+/// ```
+#[derive(Debug, Default)]
+pub struct Cooldowns {
+    /// The global cooldown of each command.
+    global: HashMap<String, Duration>,
+    /// The per-user cooldown of each command.
+    per_user: HashMap<String, Duration>,
+    /// When each command was last used by anyone, and by whom.
+    last_used: HashMap<String, (Instant, u32)>,
+    /// When each command was last used by each user.
+    last_used_by: HashMap<(u32, String), Instant>,
+}
+
+impl Cooldowns {
+    /// Creates a new instance of `Cooldowns` without any cooldowns configured.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long a command is unavailable to everyone after anyone uses it.
+    pub fn set_global(&mut self, command: impl Into<String>, cooldown: Duration) {
+        self.global.insert(command.into(), cooldown);
+    }
+
+    /// Sets how long a command is unavailable to a user after they use it.
+    pub fn set_per_user(&mut self, command: impl Into<String>, cooldown: Duration) {
+        self.per_user.insert(command.into(), cooldown);
+    }
+
+    /// Checks whether a user may use a command right now, without starting its cooldowns.
+    ///
+    /// # Errors
+    ///
+    /// Returns the remaining cooldown if the command is not available yet.
+    pub fn check(&self, user_id: u32, command: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let remaining = |cooldown: Option<&Duration>, last_used: Option<&Instant>| match (
+            cooldown, last_used,
+        ) {
+            (Some(cooldown), Some(last_used)) => {
+                (*last_used + *cooldown).saturating_duration_since(now)
+            }
+            _ => Duration::ZERO,
+        };
+
+        let global = remaining(
+            self.global.get(command),
+            self.last_used.get(command).map(|(last_used, _)| last_used),
+        );
+        let per_user = remaining(
+            self.per_user.get(command),
+            self.last_used_by.get(&(user_id, command.to_string())),
+        );
+        let remaining = global.max(per_user);
+        if remaining.is_zero() {
+            Ok(())
+        } else {
+            Err(remaining)
+        }
+    }
+
+    /// Starts the cooldowns of a command used by a user.
+    pub fn trigger(&mut self, user_id: u32, command: &str) {
+        let now = Instant::now();
+        if self.global.contains_key(command) {
+            self.last_used.insert(command.to_string(), (now, user_id));
+        }
+        if self.per_user.contains_key(command) {
+            self.last_used_by
+                .insert((user_id, command.to_string()), now);
+        }
+    }
+
+    /// Starts the cooldowns of a command if the user may use it right now.
+    ///
+    /// # Errors
+    ///
+    /// Returns the remaining cooldown if the command is not available yet.
+    pub fn try_use(&mut self, user_id: u32, command: &str) -> Result<(), Duration> {
+        self.check(user_id, command)?;
+        self.trigger(user_id, command);
+        Ok(())
+    }
+
+    /// Ends the cooldowns a user started by using a command, e.g. because the invocation
+    /// was invalid and shouldn't count. The global cooldown is only ended if the user was
+    /// the last to use the command.
+    pub fn reset(&mut self, user_id: u32, command: &str) {
+        if self
+            .last_used
+            .get(command)
+            .is_some_and(|(_, last_user)| *last_user == user_id)
+        {
+            self.last_used.remove(command);
+        }
+        self.last_used_by.remove(&(user_id, command.to_string()));
+    }
+
+    /// Returns the number of usages whose cooldowns are tracked.
+    pub fn len(&self) -> usize {
+        self.last_used.len() + self.last_used_by.len()
+    }
+
+    /// Returns `true` if no usages are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets usages whose cooldowns have expired, bounding memory use in busy chatrooms.
+    pub fn purge_expired(&mut self) {
+        let now = Instant::now();
+        let global = &self.global;
+        self.last_used.retain(|command, (last_used, _)| {
+            global
+                .get(command)
+                .is_some_and(|cooldown| *last_used + *cooldown > now)
+        });
+        let per_user = &self.per_user;
+        self.last_used_by.retain(|(_, command), last_used| {
+            per_user
+                .get(command)
+                .is_some_and(|cooldown| *last_used + *cooldown > now)
+        });
+    }
+}
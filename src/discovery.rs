@@ -0,0 +1,199 @@
+//! Discovery of the Pusher app key and cluster Kick's web client currently connects with.
+//!
+//! `DEFAULT_WEBSOCKET_URL` embeds the app key Kick used when the crate was released. If
+//! Kick rotates it, `discover` finds the current one in Kick's website, its HTML or the
+//! scripts it loads, so bots can keep connecting without waiting for a new release:
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), kick_client::KickError> {
+//! use kick_client::discovery::{self, PusherConfig};
+//! use kick_client::KickClient;
+//!
+//! let config = discovery::discover(&reqwest::Client::new())
+//!     .await
+//!     .unwrap_or_default();
+//! let client: KickClient = KickClient::connect(&config.websocket_url(), vec![668]).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::api::{HttpRequest, HttpTransport};
+use crate::KickError;
+use reqwest::Method;
+use std::ops::RangeInclusive;
+
+/// The page of Kick's website searched by `discover`.
+const KICK_URL: &str = "https://kick.com";
+/// The most scripts `discover_from` fetches from a page.
+const MAX_SCRIPTS: usize = 32;
+/// How far from an app key its cluster may be found.
+const CLUSTER_DISTANCE: usize = 256;
+/// The length of Pusher app keys: 20 hexadecimal digits.
+const APP_KEY_LEN: usize = 20;
+
+/// The Pusher app key and cluster chat is received through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PusherConfig {
+    /// The app key, e.g. `32cbd69e4b950bf97679`.
+    pub app_key: String,
+    /// The cluster, e.g. `us2`.
+    pub cluster: String,
+}
+
+impl Default for PusherConfig {
+    /// Returns the configuration of `DEFAULT_WEBSOCKET_URL`.
+    fn default() -> Self {
+        Self {
+            app_key: "32cbd69e4b950bf97679".to_string(),
+            cluster: "us2".to_string(),
+        }
+    }
+}
+
+impl PusherConfig {
+    /// Returns the WebSocket URL to connect to, in the shape of `DEFAULT_WEBSOCKET_URL`.
+    pub fn websocket_url(&self) -> String {
+        format!(
+            "wss://ws-{}.pusher.com/app/{}?protocol=7&client=js&version=8.4.0-rc2&flash=false",
+            self.cluster, self.app_key
+        )
+    }
+}
+
+/// Finds the current Pusher configuration in Kick's website.
+///
+/// # Errors
+///
+/// This function will return an error if the website cannot be fetched, or no
+/// configuration is found in it.
+pub async fn discover<T: HttpTransport>(transport: &T) -> Result<PusherConfig, KickError> {
+    discover_from(transport, KICK_URL).await
+}
+
+/// Finds a Pusher configuration in the page at `url`, or else in the scripts it loads.
+///
+/// # Errors
+///
+/// This function will return an error if the page cannot be fetched, or no configuration
+/// is found in it.
+pub async fn discover_from<T: HttpTransport>(
+    transport: &T,
+    url: &str,
+) -> Result<PusherConfig, KickError> {
+    let page = fetch(transport, url).await?;
+    if let Some(config) = find_pusher_config(&page) {
+        return Ok(config);
+    }
+    for script in script_urls(&page, url).into_iter().take(MAX_SCRIPTS) {
+        // A script failing to load doesn't prevent finding the key in another.
+        if let Ok(script) = fetch(transport, &script).await {
+            if let Some(config) = find_pusher_config(&script) {
+                return Ok(config);
+            }
+        }
+    }
+    Err(KickError::ConfigError(format!(
+        "no Pusher configuration found at {}",
+        url
+    )))
+}
+
+/// Finds a Pusher app key with its cluster in HTML or JavaScript, e.g.
+/// `{key:"32cbd69e4b950bf97679",cluster:"us2"}`: a quoted 20 digit hexadecimal key, with
+/// a quoted cluster name such as `us2` or `eu` close to it. If several keys qualify, the
+/// one closest to a cluster name is returned.
+pub fn find_pusher_config(text: &str) -> Option<PusherConfig> {
+    let clusters: Vec<(usize, &str)> = quoted(text, 2..=3)
+        .filter(|(_, value)| is_cluster(value))
+        .collect();
+    quoted(text, APP_KEY_LEN..=APP_KEY_LEN)
+        .filter(|(_, value)| is_app_key(value))
+        .filter_map(|(at, app_key)| {
+            let (distance, cluster) = clusters
+                .iter()
+                .map(|(cluster_at, cluster)| (cluster_at.abs_diff(at), *cluster))
+                .min()?;
+            (distance <= CLUSTER_DISTANCE).then_some((distance, app_key, cluster))
+        })
+        .min_by_key(|(distance, _, _)| *distance)
+        .map(|(_, app_key, cluster)| PusherConfig {
+            app_key: app_key.to_string(),
+            cluster: cluster.to_string(),
+        })
+}
+
+async fn fetch<T: HttpTransport>(transport: &T, url: &str) -> Result<String, KickError> {
+    let response = transport
+        .send(HttpRequest {
+            method: Method::GET,
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: None,
+        })
+        .await?;
+    if !response.is_success() {
+        return Err(KickError::ApiError {
+            status: response.status,
+            body: String::from_utf8_lossy(&response.body).into_owned(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&response.body).into_owned())
+}
+
+/// Returns the values of `len` characters in `text` between matching single or double
+/// quotes, with their offsets. Quotes aren't paired, so stray apostrophes in prose don't
+/// hide the values after them.
+fn quoted(text: &str, len: RangeInclusive<usize>) -> impl Iterator<Item = (usize, &str)> {
+    text.match_indices(['"', '\''])
+        .flat_map(move |(at, quote)| {
+            let rest = &text[at + 1..];
+            len.clone().filter_map(move |n| {
+                let value = rest.get(..n)?;
+                rest[n..].starts_with(quote).then_some((at + 1, value))
+            })
+        })
+}
+
+fn is_app_key(value: &str) -> bool {
+    value.len() == APP_KEY_LEN
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+/// Returns `true` for Pusher cluster names such as `mt1`, `us2`, `eu` or `ap3`.
+fn is_cluster(value: &str) -> bool {
+    let (region, number) = value.split_at(value.len().min(2));
+    ["mt", "us", "eu", "ap", "sa"].contains(&region)
+        && number.len() <= 1
+        && number.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Returns the absolute URLs of the scripts loaded by an HTML page.
+fn script_urls(page: &str, page_url: &str) -> Vec<String> {
+    let origin = page_url
+        .match_indices('/')
+        .nth(2)
+        .map_or(page_url, |(at, _)| &page_url[..at]);
+    page.split("<script")
+        .skip(1)
+        .filter_map(|tag| {
+            let tag = &tag[..tag.find('>')?];
+            let src = tag.split_once("src=")?.1;
+            let quote = src.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let src = &src[1..];
+            Some(src[..src.find(quote)?].to_string())
+        })
+        .map(|src| {
+            if src.starts_with("https://") || src.starts_with("http://") {
+                src
+            } else if let Some(rest) = src.strip_prefix("//") {
+                format!("https://{}", rest)
+            } else if src.starts_with('/') {
+                format!("{}{}", origin, src)
+            } else {
+                format!("{}/{}", origin, src)
+            }
+        })
+        .collect()
+}
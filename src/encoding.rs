@@ -0,0 +1,64 @@
+use crate::{KickChatMessage, KickError};
+use serde_json::Value;
+
+/// Returns a message as a JSON value, with `data` as a nested object rather than the JSON
+/// string Kick sends, which `KickChatMessage` still deserializes from. Easier to consume
+/// from other languages, e.g. in a browser.
+///
+/// # Errors
+///
+/// This function will return an error if the message cannot be serialized.
+pub fn to_json_value(message: &KickChatMessage) -> Result<Value, KickError> {
+    let mut value = serde_json::to_value(message)?;
+    if let Some(data) = value.get_mut("data") {
+        if let Some(nested) = data.as_str().and_then(|s| serde_json::from_str(s).ok()) {
+            *data = nested;
+        }
+    }
+    Ok(value)
+}
+
+/// Encodes a message as MessagePack. Unlike in JSON, `data` is encoded as a nested value
+/// rather than a JSON string.
+///
+/// # Errors
+///
+/// This function will return an error if the message cannot be encoded.
+#[cfg(feature = "msgpack")]
+pub fn to_msgpack(message: &KickChatMessage) -> Result<Vec<u8>, KickError> {
+    rmp_serde::to_vec_named(message).map_err(|e| KickError::EncodingError(Box::new(e)))
+}
+
+/// Decodes a message encoded by `to_msgpack`.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes aren't a valid encoded message.
+#[cfg(feature = "msgpack")]
+pub fn from_msgpack(bytes: &[u8]) -> Result<KickChatMessage, KickError> {
+    rmp_serde::from_slice(bytes).map_err(|e| KickError::EncodingError(Box::new(e)))
+}
+
+/// Encodes a message as CBOR. Unlike in JSON, `data` is encoded as a nested value rather
+/// than a JSON string.
+///
+/// # Errors
+///
+/// This function will return an error if the message cannot be encoded.
+#[cfg(feature = "cbor")]
+pub fn to_cbor(message: &KickChatMessage) -> Result<Vec<u8>, KickError> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(message, &mut bytes)
+        .map_err(|e| KickError::EncodingError(Box::new(e)))?;
+    Ok(bytes)
+}
+
+/// Decodes a message encoded by `to_cbor`.
+///
+/// # Errors
+///
+/// This function will return an error if the bytes aren't a valid encoded message.
+#[cfg(feature = "cbor")]
+pub fn from_cbor(bytes: &[u8]) -> Result<KickChatMessage, KickError> {
+    ciborium::from_reader(bytes).map_err(|e| KickError::EncodingError(Box::new(e)))
+}
@@ -0,0 +1,328 @@
+use crate::{
+    AdvancedBotProtection, ChatMessageEventData, ChatMessageSender, ChatMessageSenderBadge,
+    ChatMessageSenderIdentity, ChatroomClearEventData, ChatroomUpdatedEventData, DeletedMessage,
+    DeletedMessageEventData, EmotesMode, FollowersMode, KickChatMessage, MessageData, Poll,
+    PollOption, PollUpdateEventData, SlowMode, SubscribersMode, User, UserBannedEventData,
+    UserUnbannedEventData,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Builds a realistic chat message, filling in every field not set explicitly.
+///
+/// # Examples
+///
+/// ```
+/// use kick_client::ChatMessageEventData;
+///
+/// let message = ChatMessageEventData::builder("!ping")
+///     .with_chatroom_id(1234)
+///     .with_sender(42, "SomeViewer")
+///     .with_badge("moderator")
+///     .into_message();
+/// assert_eq!(message.chatroom_id(), Some(1234));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChatMessageBuilder {
+    data: ChatMessageEventData,
+}
+
+impl ChatMessageEventData {
+    /// Returns a builder for a chat message with the given content, sent now by a viewer
+    /// without badges.
+    pub fn builder(content: impl Into<String>) -> ChatMessageBuilder {
+        ChatMessageBuilder {
+            data: ChatMessageEventData {
+                id: id(),
+                chatroom_id: 1,
+                content: Some(content.into()),
+                r#type: Some("message".to_string()),
+                created_at: Some(now()),
+                sender: sender(1, "viewer"),
+            },
+        }
+    }
+}
+
+impl ChatMessageBuilder {
+    /// Sets the ID of the message.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.data.id = id.into();
+        self
+    }
+
+    /// Sets the chatroom the message is sent to.
+    pub fn with_chatroom_id(mut self, chatroom_id: u32) -> Self {
+        self.data.chatroom_id = chatroom_id;
+        self
+    }
+
+    /// Sets the sender of the message, keeping their badges.
+    pub fn with_sender(mut self, id: u32, username: impl Into<String>) -> Self {
+        let badges = std::mem::take(&mut self.data.sender.identity.badges);
+        self.data.sender = sender(id, username);
+        self.data.sender.identity.badges = badges;
+        self
+    }
+
+    /// Adds a badge of the given type, e.g. `moderator` or `vip`, to the sender.
+    pub fn with_badge(mut self, badge_type: impl Into<String>) -> Self {
+        let r#type = badge_type.into();
+        self.data
+            .sender
+            .identity
+            .badges
+            .push(ChatMessageSenderBadge::SimpleBadge {
+                text: badge_text(&r#type),
+                r#type,
+            });
+        self
+    }
+
+    /// Adds a subscriber badge for the given number of months to the sender.
+    pub fn with_subscriber_badge(mut self, months: u32) -> Self {
+        self.data
+            .sender
+            .identity
+            .badges
+            .push(ChatMessageSenderBadge::FullBadge {
+                r#type: "subscriber".to_string(),
+                text: "Subscriber".to_string(),
+                count: Some(months),
+            });
+        self
+    }
+
+    /// Sets the username color of the sender.
+    pub fn with_color(mut self, color: impl Into<String>) -> Self {
+        self.data.sender.identity.color = Some(color.into());
+        self
+    }
+
+    /// Sets when the message was sent, as an RFC 3339 timestamp.
+    pub fn with_created_at(mut self, created_at: impl Into<String>) -> Self {
+        self.data.created_at = Some(created_at.into());
+        self
+    }
+
+    /// Sets the type of the message, e.g. `reply`.
+    pub fn with_type(mut self, r#type: impl Into<String>) -> Self {
+        self.data.r#type = Some(r#type.into());
+        self
+    }
+
+    /// Returns the built chat message data.
+    pub fn build(self) -> ChatMessageEventData {
+        self.data
+    }
+
+    /// Returns the built chat message, as received on its chatroom's channel.
+    pub fn into_message(self) -> KickChatMessage {
+        message(self.data.chatroom_id, MessageData::ChatMessage(self.data))
+    }
+}
+
+/// Returns a message as received on a chatroom's `chatrooms.{id}.v2` channel.
+pub fn message(chatroom_id: u32, data: MessageData) -> KickChatMessage {
+    KickChatMessage {
+        data,
+        channel: Some(format!("chatrooms.{}.v2", chatroom_id)),
+    }
+}
+
+/// Returns a chat message sent now by a viewer without badges. The ID of the viewer is
+/// derived from their username, so their messages share it.
+pub fn chat_message(chatroom_id: u32, username: &str, content: &str) -> KickChatMessage {
+    ChatMessageEventData::builder(content)
+        .with_chatroom_id(chatroom_id)
+        .with_sender(user_id(username), username)
+        .into_message()
+}
+
+/// Returns a chat message sender without badges.
+pub fn sender(id: u32, username: impl Into<String>) -> ChatMessageSender {
+    let username = username.into();
+    ChatMessageSender {
+        id,
+        slug: Some(username.to_lowercase()),
+        username,
+        identity: ChatMessageSenderIdentity {
+            color: Some("#75FD46".to_string()),
+            badges: Vec::new(),
+        },
+    }
+}
+
+/// Returns a user, with an ID derived from their username.
+pub fn user(username: &str) -> User {
+    User {
+        id: user_id(username),
+        username: username.to_string(),
+        slug: username.to_lowercase(),
+    }
+}
+
+/// Returns a ban of `username` by `banned_by`, for `duration` minutes or permanently.
+pub fn user_banned(
+    chatroom_id: u32,
+    username: &str,
+    banned_by: &str,
+    duration: Option<u64>,
+) -> KickChatMessage {
+    message(
+        chatroom_id,
+        MessageData::UserBanned(UserBannedEventData {
+            id: id(),
+            user: user(username),
+            banned_by: user(banned_by),
+            permanent: duration.is_none(),
+            duration,
+            expires_at: None,
+        }),
+    )
+}
+
+/// Returns an unban of `username` by `unbanned_by`.
+pub fn user_unbanned(chatroom_id: u32, username: &str, unbanned_by: &str) -> KickChatMessage {
+    message(
+        chatroom_id,
+        MessageData::UserUnbanned(UserUnbannedEventData {
+            id: id(),
+            user: user(username),
+            unbanned_by: user(unbanned_by),
+            permanent: false,
+        }),
+    )
+}
+
+/// Returns the deletion of a chat message by a moderator.
+pub fn message_deleted(chatroom_id: u32, message_id: impl Into<String>) -> KickChatMessage {
+    message(
+        chatroom_id,
+        MessageData::DeletedMessage(DeletedMessageEventData {
+            id: id(),
+            message: DeletedMessage {
+                id: message_id.into(),
+            },
+            ai_moderated: false,
+            violated_rules: None,
+            original: None,
+        }),
+    )
+}
+
+/// Returns the clearing of a chatroom.
+pub fn chatroom_clear(chatroom_id: u32) -> KickChatMessage {
+    message(
+        chatroom_id,
+        MessageData::ChatroomClear(ChatroomClearEventData { id: id() }),
+    )
+}
+
+/// Returns the settings of a chatroom with every chat mode disabled, to be adjusted and
+/// wrapped in `MessageData::ChatroomUpdated`.
+pub fn chatroom_updated(chatroom_id: u32) -> ChatroomUpdatedEventData {
+    ChatroomUpdatedEventData {
+        id: chatroom_id,
+        slow_mode: SlowMode {
+            enabled: false,
+            message_interval: 0,
+        },
+        subscribers_mode: SubscribersMode { enabled: false },
+        followers_mode: FollowersMode {
+            enabled: false,
+            min_duration: 0,
+        },
+        emotes_mode: EmotesMode { enabled: false },
+        advanced_bot_protection: AdvancedBotProtection {
+            enabled: false,
+            remaining_time: 0,
+        },
+    }
+}
+
+/// Returns an update of a running poll, with its options and their votes.
+pub fn poll_update(
+    chatroom_id: u32,
+    title: &str,
+    options: &[(&str, u32)],
+    duration: u32,
+    remaining: u32,
+) -> KickChatMessage {
+    message(
+        chatroom_id,
+        MessageData::PollUpdate(PollUpdateEventData {
+            poll: Poll {
+                title: title.to_string(),
+                options: options
+                    .iter()
+                    .zip(0..)
+                    .map(|((label, votes), id)| PollOption {
+                        id,
+                        label: label.to_string(),
+                        votes: *votes,
+                    })
+                    .collect(),
+                duration,
+                remaining,
+                result_display_duration: 15,
+                has_voted: Some(false),
+                voted_option_id: None,
+            },
+        }),
+    )
+}
+
+/// Returns a new unique ID, formatted like the UUIDs Kick assigns to messages.
+pub fn id() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    format!("00000000-0000-4000-8000-{:012x}", n)
+}
+
+/// Returns a stable user ID derived from a username.
+fn user_id(username: &str) -> u32 {
+    // FNV-1a, so IDs are the same across runs.
+    username
+        .to_lowercase()
+        .bytes()
+        .fold(0x811c_9dc5, |hash, byte| {
+            (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+        })
+}
+
+/// Returns the display text of a badge type, e.g. `Moderator` for `moderator`.
+fn badge_text(badge_type: &str) -> String {
+    let mut chars = badge_type.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Returns the current time as an RFC 3339 timestamp, as Kick formats `created_at`.
+fn now() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Converts days since the epoch to a civil date, after Howard Hinnant's algorithm.
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+00:00",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
@@ -0,0 +1,150 @@
+//! A C API, for embedding the client in OBS plugins and C++ tools.
+//!
+//! A client is created with `kick_client_new`, connecting in a background thread and
+//! reconnecting as a `ReconnectingClient` does. Its messages are polled as JSON strings,
+//! shaped as `encoding::to_json_value` returns them, and freed by the caller:
+//!
+//! ```c
+//! #include "kick_client.h"
+//!
+//! uint64_t chatroom_ids[] = {668};
+//! kick_client *client = kick_client_new(NULL, chatroom_ids, 1);
+//! for (;;) {
+//!     char *event = kick_client_poll(client, 100);
+//!     if (event) {
+//!         puts(event);
+//!         kick_client_string_free(event);
+//!     }
+//! }
+//! kick_client_free(client);
+//! ```
+//!
+//! The header is checked in as `include/kick_client.h`, and regenerated by the build
+//! script with the `ffi` feature. The library itself is built with
+//! `cargo rustc --release --features ffi --crate-type cdylib`, or `staticlib`.
+
+use crate::encoding::to_json_value;
+use crate::reconnect::ReconnectingClient;
+use crate::trace::Instrument;
+use crate::{KickChatMessage, DEFAULT_WEBSOCKET_URL};
+use std::ffi::{c_char, CStr, CString};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+
+/// How many messages are buffered before the connection waits for them to be polled.
+const MESSAGE_BUFFER: usize = 1024;
+
+/// A client reading chatrooms in a background thread, `kick_client` in C.
+pub struct FfiClient {
+    /// The runtime driving the connection, shut down when the client is freed.
+    runtime: Runtime,
+    messages: mpsc::Receiver<KickChatMessage>,
+}
+
+/// Creates a client subscribed to `chatroom_ids_len` chatrooms of `chatroom_ids`, which
+/// connects in the background to `url`, or Kick's server if `url` is `NULL`. Returns `NULL`
+/// if `url` isn't UTF-8 or the background thread cannot be started.
+///
+/// # Safety
+///
+/// `url` must be `NULL` or a NUL-terminated string, and `chatroom_ids` must point to
+/// `chatroom_ids_len` IDs, or may be `NULL` if `chatroom_ids_len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn kick_client_new(
+    url: *const c_char,
+    chatroom_ids: *const u64,
+    chatroom_ids_len: usize,
+) -> *mut FfiClient {
+    if chatroom_ids.is_null() && chatroom_ids_len > 0 {
+        return std::ptr::null_mut();
+    }
+    let url = if url.is_null() {
+        DEFAULT_WEBSOCKET_URL
+    } else {
+        match CStr::from_ptr(url).to_str() {
+            Ok(url) => url,
+            Err(_) => return std::ptr::null_mut(),
+        }
+    };
+    let chatroom_ids = match chatroom_ids_len {
+        0 => Vec::new(),
+        len => std::slice::from_raw_parts(chatroom_ids, len).to_vec(),
+    };
+    let Ok(runtime) = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .enable_all()
+        .build()
+    else {
+        return std::ptr::null_mut();
+    };
+
+    let (sender, messages) = mpsc::channel(MESSAGE_BUFFER);
+    let mut client = ReconnectingClient::new(url, chatroom_ids);
+    let guard = runtime.enter();
+    crate::trace::spawn("kick_client::ffi::client", async move {
+        while let Ok(Some(message)) = client.read_message().await {
+            if sender
+                .send(message)
+                .instrument(span!("dispatch"))
+                .await
+                .is_err()
+            {
+                return;
+            }
+        }
+    });
+    drop(guard);
+    Box::into_raw(Box::new(FfiClient { runtime, messages }))
+}
+
+/// Waits up to `timeout_ms` milliseconds for the next message, returning it as a JSON
+/// string to be freed with `kick_client_string_free`. Returns `NULL` if no message was
+/// received in time, or `client` is `NULL`. A timeout of 0 only returns a message that
+/// was already received.
+///
+/// # Safety
+///
+/// `client` must be `NULL` or a client returned by `kick_client_new` and not yet freed,
+/// which isn't polled from another thread at the same time.
+#[no_mangle]
+pub unsafe extern "C" fn kick_client_poll(client: *mut FfiClient, timeout_ms: u32) -> *mut c_char {
+    let Some(client) = client.as_mut() else {
+        return std::ptr::null_mut();
+    };
+    let timeout = Duration::from_millis(timeout_ms.into());
+    let received = client
+        .runtime
+        .block_on(async { tokio::time::timeout(timeout, client.messages.recv()).await });
+    let Ok(Some(message)) = received else {
+        return std::ptr::null_mut();
+    };
+    to_json_value(&message)
+        .ok()
+        .and_then(|json| CString::new(json.to_string()).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Frees a string returned by `kick_client_poll`. Does nothing if `string` is `NULL`.
+///
+/// # Safety
+///
+/// `string` must be `NULL` or a string returned by `kick_client_poll` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn kick_client_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+/// Closes the connection and frees a client. Does nothing if `client` is `NULL`.
+///
+/// # Safety
+///
+/// `client` must be `NULL` or a client returned by `kick_client_new` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn kick_client_free(client: *mut FfiClient) {
+    if !client.is_null() {
+        drop(Box::from_raw(client));
+    }
+}
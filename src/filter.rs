@@ -0,0 +1,235 @@
+#[cfg(feature = "api")]
+use crate::api::{HttpTransport, KickApi};
+use crate::commands::Permission;
+use crate::content::{emotes, strip_emotes};
+use crate::{ChatMessageEventData, KickChatMessage, KickError, MessageData};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// What to do with messages caught by a `MessageFilter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterAction {
+    /// Only report the message.
+    #[default]
+    Flag,
+    /// Delete the message through the moderation API.
+    Delete,
+}
+
+/// The rules of a `MessageFilter`, usually loaded from a JSON file.
+///
+/// ```json
+/// {
+///     "blocked_words": ["badword"],
+///     "patterns": ["(?i)free\\s+followers"],
+///     "max_emotes": 10,
+///     "max_caps_ratio": 0.7,
+///     "action": "delete"
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterConfig {
+    /// Words that may not appear in messages, matched case-insensitively as whole words.
+    pub blocked_words: Vec<String>,
+    /// Regular expressions messages may not match.
+    pub patterns: Vec<String>,
+    /// The maximum number of emotes in a message.
+    pub max_emotes: Option<usize>,
+    /// The maximum share of uppercase letters among the letters of a message, from 0 to 1.
+    pub max_caps_ratio: Option<f64>,
+    /// The minimum number of letters a message needs for `max_caps_ratio` to apply.
+    pub caps_min_letters: usize,
+    /// Senders at or above this level are never caught.
+    pub exempt: Permission,
+    /// What to do with caught messages.
+    pub action: FilterAction,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            blocked_words: Vec::new(),
+            patterns: Vec::new(),
+            max_emotes: None,
+            max_caps_ratio: None,
+            caps_min_letters: 10,
+            exempt: Permission::Moderator,
+            action: FilterAction::Flag,
+        }
+    }
+}
+
+/// Which rule of a `MessageFilter` a message broke.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterReason {
+    /// The message contains a blocked word.
+    BlockedWord(String),
+    /// The message matches a pattern.
+    Pattern(String),
+    /// The message contains this many emotes.
+    TooManyEmotes(usize),
+    /// This share of the letters of the message is uppercase.
+    TooManyCaps(f64),
+}
+
+/// A message caught by a `MessageFilter`.
+#[derive(Debug, Clone)]
+pub struct FilterMatch {
+    /// The caught message.
+    pub message: ChatMessageEventData,
+    /// Which rule the message broke.
+    pub reason: FilterReason,
+    /// What should be done with the message.
+    pub action: FilterAction,
+}
+
+#[cfg(feature = "api")]
+impl FilterMatch {
+    /// Carries out the action of the match, returning `true` if the message was deleted.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn apply<T: HttpTransport>(&self, api: &KickApi<T>) -> Result<bool, KickError> {
+        match self.action {
+            FilterAction::Flag => Ok(false),
+            FilterAction::Delete => {
+                api.delete_message(self.message.chatroom_id, &self.message.id)
+                    .await?;
+                Ok(true)
+            }
+        }
+    }
+}
+
+/// Checks chat messages against blocked words, patterns, and emote and caps limits.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::filter::MessageFilter;
+///
+/// let filter = MessageFilter::from_file("filter.json")?;
+/// while let Some(message) = client.read_message().await? {
+///     if let Some(caught) = filter.check(&message) {
+///         println!("{}: {:?}", caught.message.sender.username, caught.reason);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageFilter {
+    config: FilterConfig,
+    /// The blocked words, in lowercase.
+    blocked_words: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl MessageFilter {
+    /// Creates a new instance of `MessageFilter` applying the given rules.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `KickError::ConfigError` if a pattern is not a valid
+    /// regular expression.
+    pub fn new(config: FilterConfig) -> Result<Self, KickError> {
+        let patterns = config
+            .patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    KickError::ConfigError(format!("invalid pattern {:?}: {}", pattern, e))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            blocked_words: config
+                .blocked_words
+                .iter()
+                .map(|word| word.to_lowercase())
+                .collect(),
+            patterns,
+            config,
+        })
+    }
+
+    /// Creates a new instance of `MessageFilter` applying the rules of a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be read, or it or one of its
+    /// patterns is invalid.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, KickError> {
+        let config = serde_json::from_slice(&std::fs::read(path)?)?;
+        Self::new(config)
+    }
+
+    /// Returns the rules of the filter.
+    pub fn config(&self) -> &FilterConfig {
+        &self.config
+    }
+
+    /// Checks a received message, returning a match if it breaks a rule.
+    pub fn check(&self, message: &KickChatMessage) -> Option<FilterMatch> {
+        match &message.data {
+            MessageData::ChatMessage(data) => self.check_chat_message(data),
+            _ => None,
+        }
+    }
+
+    /// Checks a chat message, returning a match if it breaks a rule.
+    pub fn check_chat_message(&self, message: &ChatMessageEventData) -> Option<FilterMatch> {
+        if Permission::of(&message.sender) >= self.config.exempt {
+            return None;
+        }
+        let reason = self.reason(message.content.as_deref().unwrap_or_default())?;
+        Some(FilterMatch {
+            message: message.clone(),
+            reason,
+            action: self.config.action,
+        })
+    }
+
+    fn reason(&self, content: &str) -> Option<FilterReason> {
+        let text = strip_emotes(content);
+        let lowercase = text.to_lowercase();
+        let blocked_word = lowercase
+            .split(|c: char| !c.is_alphanumeric())
+            .find_map(|word| self.blocked_words.iter().find(|blocked| *blocked == word));
+        if let Some(word) = blocked_word {
+            return Some(FilterReason::BlockedWord(word.clone()));
+        }
+
+        if let Some(pattern) = self
+            .patterns
+            .iter()
+            .find(|pattern| pattern.is_match(content))
+        {
+            return Some(FilterReason::Pattern(pattern.as_str().to_string()));
+        }
+
+        if let Some(max_emotes) = self.config.max_emotes {
+            let count = emotes(content).len();
+            if count > max_emotes {
+                return Some(FilterReason::TooManyEmotes(count));
+            }
+        }
+
+        if let Some(max_caps_ratio) = self.config.max_caps_ratio {
+            let letters = text.chars().filter(|c| c.is_alphabetic()).count();
+            let uppercase = text.chars().filter(|c| c.is_uppercase()).count();
+            if letters >= self.config.caps_min_letters && letters > 0 {
+                let ratio = uppercase as f64 / letters as f64;
+                if ratio > max_caps_ratio {
+                    return Some(FilterReason::TooManyCaps(ratio));
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,209 @@
+//! A gRPC service streaming events to subscribers, for infrastructures standardized on
+//! gRPC.
+//!
+//! The service is defined in `proto/chat_service.proto`, returned by `schema`, and streams
+//! the `Event` messages of the `proto` module from its server-streaming `SubscribeChat`
+//! RPC. Subscribers select the chatrooms and `MessageData::kind`s they receive in the
+//! request. Clients in other languages are generated from the schema as usual, and Rust
+//! clients can use `proto::chat_service_client::ChatServiceClient`.
+
+use crate::proto::chat_service_server::{ChatService, ChatServiceServer};
+use crate::proto::{Event, SubscribeChatRequest};
+use crate::{KickChatMessage, KickError, MessageSource};
+use futures_util::Stream;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tonic::transport::server::TcpIncoming;
+use tonic::{Request, Response, Status};
+
+/// How many events are buffered for a slow subscriber before it misses some.
+const EVENT_BUFFER: usize = 1024;
+
+/// Returns the `.proto` schema of the service. It imports `kick_client.proto`, returned by
+/// `proto::schema`.
+pub fn schema() -> &'static str {
+    include_str!("../proto/chat_service.proto")
+}
+
+/// An event converted once for every subscriber.
+struct Converted {
+    chatroom_id: Option<u32>,
+    kind: &'static str,
+    event: Event,
+}
+
+/// The `ChatService` implementation, handing events to every subscriber.
+///
+/// Clones share their subscribers, so one clone can be served while another sends events.
+#[derive(Clone)]
+pub struct ChatStream {
+    events: broadcast::Sender<Arc<Converted>>,
+}
+
+impl Default for ChatStream {
+    fn default() -> Self {
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        Self { events }
+    }
+}
+
+impl ChatStream {
+    /// Creates a new instance of `ChatStream` with no subscribers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the service to add to a `tonic` server.
+    pub fn into_service(self) -> ChatServiceServer<Self> {
+        ChatServiceServer::new(self)
+    }
+
+    /// Sends a message to the subscribers selecting it, returning how many subscribers
+    /// are connected.
+    pub fn send(&self, message: &KickChatMessage) -> usize {
+        let converted = Converted {
+            chatroom_id: message.chatroom_id(),
+            kind: message.data.kind(),
+            event: Event::from(message),
+        };
+        self.events.send(Arc::new(converted)).unwrap_or(0)
+    }
+
+    /// Returns the number of connected subscribers.
+    pub fn subscribers(&self) -> usize {
+        self.events.receiver_count()
+    }
+
+    /// Sends every message read from `source` to the subscribers, until the source ends.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if reading from the source fails.
+    pub async fn forward<S: MessageSource>(&self, mut source: S) -> Result<(), KickError> {
+        while let Some(message) = source.read_message().await? {
+            self.send(&message);
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl ChatService for ChatStream {
+    type SubscribeChatStream = Pin<Box<dyn Stream<Item = Result<Event, Status>> + Send>>;
+
+    async fn subscribe_chat(
+        &self,
+        request: Request<SubscribeChatRequest>,
+    ) -> Result<Response<Self::SubscribeChatStream>, Status> {
+        let request = request.into_inner();
+        let chatrooms: HashSet<u32> = request.chatroom_ids.into_iter().collect();
+        let kinds: HashSet<String> = request.kinds.into_iter().collect();
+        let selects = move |converted: &Converted| {
+            (chatrooms.is_empty()
+                || converted
+                    .chatroom_id
+                    .is_some_and(|id| chatrooms.contains(&id)))
+                && (kinds.is_empty() || kinds.contains(converted.kind))
+        };
+        let events = self.events.subscribe();
+        let stream = futures_util::stream::unfold(events, move |mut events| {
+            let selects = selects.clone();
+            async move {
+                loop {
+                    match events.recv().await {
+                        Ok(converted) if selects(&converted) => {
+                            return Some((Ok(converted.event.clone()), events));
+                        }
+                        Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => return None,
+                    }
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// A standalone gRPC server serving `ChatService`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::grpc::GrpcServer;
+/// use kick_client::KickClient;
+///
+/// let client = KickClient::new(kick_client::DEFAULT_WEBSOCKET_URL, vec![668]).await.unwrap();
+/// let server = GrpcServer::bind("127.0.0.1:50051").await?;
+/// server.forward(client).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct GrpcServer {
+    service: ChatStream,
+    /// The address the server is listening on.
+    local_addr: SocketAddr,
+    /// The task running the server, stopped when the server is dropped.
+    server: JoinHandle<()>,
+}
+
+impl GrpcServer {
+    /// Starts a server on the given address.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the address cannot be bound.
+    pub async fn bind(addr: impl ToSocketAddrs) -> Result<Self, KickError> {
+        let service = ChatStream::new();
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let router =
+            tonic::transport::Server::builder().add_service(service.clone().into_service());
+        let server = crate::trace::spawn("kick_client::grpc::server", async move {
+            let _ = router
+                .serve_with_incoming(TcpIncoming::from(listener))
+                .await;
+        });
+
+        Ok(Self {
+            service,
+            local_addr,
+            server,
+        })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Returns the service fed by the server, e.g. to send messages from another task.
+    pub fn service(&self) -> ChatStream {
+        self.service.clone()
+    }
+
+    /// Sends a message to its subscribers. See `ChatStream::send`.
+    pub fn send(&self, message: &KickChatMessage) -> usize {
+        self.service.send(message)
+    }
+
+    /// Sends every message read from `source` to the subscribers, until the source ends.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if reading from the source fails.
+    pub async fn forward<S: MessageSource>(&self, source: S) -> Result<(), KickError> {
+        self.service.forward(source).await
+    }
+}
+
+impl Drop for GrpcServer {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
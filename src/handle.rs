@@ -0,0 +1,282 @@
+//! A client running in its own task, controlled through a clonable handle.
+//!
+//! `KickClient` methods take `&mut self`, so a client can only be used by one task at a
+//! time. `KickClient::into_handle` moves the client into a task reading it, and returns a
+//! `ClientHandle` which any number of tasks can clone to subscribe, send frames, query
+//! chatroom states or close the connection, with the messages delivered to a receiver.
+//!
+//! Delivery can be paused without closing the connection, e.g. to freeze an overlay during
+//! an ad break. The client keeps reading while paused, so the connection stays alive, and
+//! the messages it reads are buffered or dropped as the `PausePolicy` says.
+
+use crate::metrics::Metrics;
+use crate::state::ChatroomState;
+use crate::trace::Instrument;
+use crate::transport::Transport;
+use crate::{KickChatMessage, KickClient, KickError};
+use std::collections::VecDeque;
+use tokio::sync::{mpsc, oneshot};
+
+/// How many messages are buffered before the client waits for them to be received.
+const MESSAGE_BUFFER: usize = 1024;
+
+type Reply<T> = oneshot::Sender<T>;
+
+/// What happens to the messages read while delivery is paused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PausePolicy {
+    /// Keep up to this many messages, dropping the oldest ones beyond it, and deliver them
+    /// on resume.
+    Buffer(usize),
+    /// Drop every message.
+    Drop,
+}
+
+/// The messages held back while delivery is paused.
+struct Paused {
+    policy: PausePolicy,
+    buffered: VecDeque<KickChatMessage>,
+    dropped: usize,
+    /// The metrics of the client, counting the dropped messages too.
+    metrics: Option<Metrics>,
+}
+
+impl Paused {
+    fn hold(&mut self, message: KickChatMessage) {
+        self.buffered.push_back(message);
+        self.trim();
+    }
+
+    /// Drops the buffered messages the policy doesn't allow keeping, oldest first.
+    fn trim(&mut self) {
+        let capacity = match self.policy {
+            PausePolicy::Buffer(capacity) => capacity,
+            PausePolicy::Drop => 0,
+        };
+        let excess = self.buffered.len().saturating_sub(capacity);
+        if excess > 0 {
+            self.buffered.drain(..excess);
+            self.dropped += excess;
+            if let Some(metrics) = &self.metrics {
+                metrics.record_dropped(excess as u64);
+            }
+        }
+    }
+}
+
+enum Command {
+    Subscribe(u64, Reply<Result<(), KickError>>),
+    Unsubscribe(u64, Reply<Result<(), KickError>>),
+    Send(String, Reply<Result<(), KickError>>),
+    ChatroomState(u32, Reply<Option<ChatroomState>>),
+    ChannelIds(Reply<Vec<u64>>),
+    Pause(PausePolicy, Reply<()>),
+    Resume(Reply<usize>),
+    Close(Reply<()>),
+}
+
+/// A cheap, clonable handle to a client running in its own task.
+///
+/// The task stops once the connection ends, `close` is called or every handle is dropped.
+/// Commands sent afterwards fail with `KickError::StreamEnded`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::KickClient;
+///
+/// let client: KickClient = KickClient::connect(kick_client::DEFAULT_WEBSOCKET_URL, vec![668]).await?;
+/// let (handle, mut messages) = client.into_handle();
+///
+/// let subscriber = handle.clone();
+/// tokio::spawn(async move { subscriber.subscribe(1234).await });
+/// while let Some(message) = messages.recv().await {
+///     println!("{}", message);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ClientHandle {
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl ClientHandle {
+    /// Subscribes to another chatroom, unless already subscribed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the subscription cannot be sent, or the
+    /// client stopped.
+    pub async fn subscribe(&self, channel_id: u64) -> Result<(), KickError> {
+        self.request(|reply| Command::Subscribe(channel_id, reply))
+            .await?
+    }
+
+    /// Unsubscribes from a chatroom, unless not subscribed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request cannot be sent, or the client
+    /// stopped.
+    pub async fn unsubscribe(&self, channel_id: u64) -> Result<(), KickError> {
+        self.request(|reply| Command::Unsubscribe(channel_id, reply))
+            .await?
+    }
+
+    /// Sends a raw text frame, such as a Pusher event.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the frame cannot be sent, or the client
+    /// stopped.
+    pub async fn send(&self, text: impl Into<String>) -> Result<(), KickError> {
+        let text = text.into();
+        self.request(|reply| Command::Send(text, reply)).await?
+    }
+
+    /// Returns the chat mode configuration of a subscribed chatroom, or `None` until the
+    /// first `ChatroomUpdated` message for it is read.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the client stopped.
+    pub async fn chatroom_state(
+        &self,
+        chatroom_id: u32,
+    ) -> Result<Option<ChatroomState>, KickError> {
+        self.request(|reply| Command::ChatroomState(chatroom_id, reply))
+            .await
+    }
+
+    /// Returns the IDs of the subscribed chatrooms.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the client stopped.
+    pub async fn channel_ids(&self) -> Result<Vec<u64>, KickError> {
+        self.request(Command::ChannelIds).await
+    }
+
+    /// Stops delivering messages without closing the connection. Messages read while
+    /// paused are buffered or dropped according to `policy`. Pausing again changes the
+    /// policy, applying it to the messages buffered so far.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the client stopped.
+    pub async fn pause(&self, policy: PausePolicy) -> Result<(), KickError> {
+        self.request(|reply| Command::Pause(policy, reply)).await
+    }
+
+    /// Delivers the messages buffered while paused, then resumes delivering messages as
+    /// they are read. Returns how many messages were dropped while paused.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the client stopped.
+    pub async fn resume(&self) -> Result<usize, KickError> {
+        self.request(Command::Resume).await
+    }
+
+    /// Closes the connection, returning once the client stopped. The receiver of messages
+    /// ends after the messages received before.
+    pub async fn close(&self) {
+        let _ = self.request(Command::Close).await;
+    }
+
+    /// Returns `true` if the client stopped.
+    pub fn is_closed(&self) -> bool {
+        self.commands.is_closed()
+    }
+
+    async fn request<T>(&self, command: impl FnOnce(Reply<T>) -> Command) -> Result<T, KickError> {
+        let (reply, response) = oneshot::channel();
+        self.commands
+            .send(command(reply))
+            .map_err(|_| KickError::StreamEnded)?;
+        response.await.map_err(|_| KickError::StreamEnded)
+    }
+}
+
+impl<T: Transport + 'static> KickClient<T> {
+    /// Moves the client into a task reading it, returning a handle to control it and a
+    /// receiver for its messages. Messages are dropped while the receiver is dropped.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called outside of a Tokio runtime.
+    pub fn into_handle(self) -> (ClientHandle, mpsc::Receiver<KickChatMessage>) {
+        let (commands, received) = mpsc::unbounded_channel();
+        let (messages, receiver) = mpsc::channel(MESSAGE_BUFFER);
+        crate::trace::spawn("kick_client::handle", run(self, received, messages));
+        (ClientHandle { commands }, receiver)
+    }
+}
+
+async fn run<T: Transport>(
+    mut client: KickClient<T>,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+    messages: mpsc::Sender<KickChatMessage>,
+) {
+    let mut paused: Option<Paused> = None;
+    loop {
+        tokio::select! {
+            command = commands.recv() => match command {
+                Some(Command::Subscribe(channel_id, reply)) => {
+                    let _ = reply.send(client.subscribe(channel_id).await);
+                }
+                Some(Command::Unsubscribe(channel_id, reply)) => {
+                    let _ = reply.send(client.unsubscribe(channel_id).await);
+                }
+                Some(Command::Send(text, reply)) => {
+                    let _ = reply.send(client.send_raw(text).await);
+                }
+                Some(Command::ChatroomState(chatroom_id, reply)) => {
+                    let _ = reply.send(client.chatroom_state(chatroom_id));
+                }
+                Some(Command::ChannelIds(reply)) => {
+                    let _ = reply.send(client.channel_ids().to_vec());
+                }
+                Some(Command::Pause(policy, reply)) => {
+                    let held = paused.get_or_insert_with(|| Paused {
+                        policy,
+                        buffered: VecDeque::new(),
+                        dropped: 0,
+                        metrics: client.metrics().cloned(),
+                    });
+                    held.policy = policy;
+                    held.trim();
+                    let _ = reply.send(());
+                }
+                Some(Command::Resume(reply)) => {
+                    let Some(held) = paused.take() else {
+                        let _ = reply.send(0);
+                        continue;
+                    };
+                    for message in held.buffered {
+                        let _ = messages.send(message).instrument(span!("dispatch")).await;
+                    }
+                    let _ = reply.send(held.dropped);
+                }
+                Some(Command::Close(reply)) => {
+                    commands.close();
+                    drop(client);
+                    let _ = reply.send(());
+                    return;
+                }
+                None => return,
+            },
+            message = client.read_message() => match message {
+                Ok(Some(message)) => match &mut paused {
+                    Some(held) => held.hold(message),
+                    None => {
+                        let _ = messages.send(message).instrument(span!("dispatch")).await;
+                    }
+                },
+                Ok(None) | Err(_) => return,
+            },
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! An HTTP health endpoint for probes to restart wedged bots.
+//!
+//! `/healthz` answers liveness probes: it fails when no message was received for longer
+//! than the maximum message age, as happens when a client is stuck reconnecting or its
+//! connection silently died. `/readyz` answers readiness probes: it fails while the client
+//! is disconnected. Both report the connection state, how long ago the last message was
+//! received and how many times the client reconnected as JSON:
+//!
+//! ```json
+//! {"connected":true,"last_message_age_ms":1520,"reconnects":2}
+//! ```
+//!
+//! A `ReconnectingClient` given the `Health` with `ReconnectingClient::with_health` keeps it
+//! up to date. Other clients can report to it with `Health::connected`, `Health::observe`
+//! and `Health::disconnected`.
+
+use crate::{KickChatMessage, KickError};
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::task::JoinHandle;
+
+/// How long a client may go without receiving a message by default before it is reported
+/// unhealthy. Pusher pongs count, so pinging clients of quiet chatrooms stay healthy.
+const DEFAULT_MAX_MESSAGE_AGE: Duration = Duration::from_secs(300);
+
+/// The health of a client as reported by the endpoints.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    /// Whether the client is connected.
+    pub connected: bool,
+    /// How long ago the last message was received, or `None` if none ever was.
+    pub last_message_age_ms: Option<u64>,
+    /// How many times the client connected again after its first connection.
+    pub reconnects: u64,
+}
+
+/// The connection state and activity of a client, shared with the health endpoints.
+/// Cloning a `Health` is cheap and all clones share the same state.
+#[derive(Clone)]
+pub struct Health {
+    state: Arc<Mutex<HealthState>>,
+}
+
+struct HealthState {
+    max_message_age: Duration,
+    /// When the `Health` was created, from which liveness is measured until a message is
+    /// received.
+    started_at: Instant,
+    connected: bool,
+    /// How many times a connection was established.
+    connections: u64,
+    last_message_at: Option<Instant>,
+}
+
+impl Health {
+    /// Creates a new instance of `Health` for a client which isn't connected yet.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(HealthState {
+                max_message_age: DEFAULT_MAX_MESSAGE_AGE,
+                started_at: Instant::now(),
+                connected: false,
+                connections: 0,
+                last_message_at: None,
+            })),
+        }
+    }
+
+    /// Sets how long the client may go without receiving a message before `/healthz`
+    /// fails, five minutes by default.
+    pub fn with_max_message_age(self, age: Duration) -> Self {
+        self.lock().max_message_age = age;
+        self
+    }
+
+    /// Records that a connection was established.
+    pub fn connected(&self) {
+        let mut state = self.lock();
+        state.connected = true;
+        state.connections += 1;
+    }
+
+    /// Records that the connection was lost.
+    pub fn disconnected(&self) {
+        self.lock().connected = false;
+    }
+
+    /// Records that a message was received.
+    pub fn observe(&self, _message: &KickChatMessage) {
+        self.lock().last_message_at = Some(Instant::now());
+    }
+
+    /// Returns the current health of the client.
+    pub fn report(&self) -> HealthReport {
+        let state = self.lock();
+        HealthReport {
+            connected: state.connected,
+            last_message_age_ms: state
+                .last_message_at
+                .map(|at| at.elapsed().as_millis().try_into().unwrap_or(u64::MAX)),
+            reconnects: state.connections.saturating_sub(1),
+        }
+    }
+
+    /// Returns `true` if a message was received within the maximum message age, or, if
+    /// none was yet, if the `Health` was created within it.
+    pub fn is_live(&self) -> bool {
+        let state = self.lock();
+        state.last_message_at.unwrap_or(state.started_at).elapsed() <= state.max_message_age
+    }
+
+    /// Returns `true` if the client is connected.
+    pub fn is_ready(&self) -> bool {
+        self.lock().connected
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HealthState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns a router serving `/healthz` and `/readyz` from `health`, to be mounted in an
+/// existing application.
+pub fn router(health: Health) -> Router {
+    Router::new()
+        .route("/healthz", get(liveness))
+        .route("/readyz", get(readiness))
+        .with_state(health)
+}
+
+async fn liveness(State(health): State<Health>) -> impl IntoResponse {
+    respond(&health, health.is_live())
+}
+
+async fn readiness(State(health): State<Health>) -> impl IntoResponse {
+    respond(&health, health.is_ready())
+}
+
+fn respond(health: &Health, healthy: bool) -> impl IntoResponse {
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    let body = serde_json::to_string(&health.report()).unwrap_or_default();
+    (status, [(CONTENT_TYPE, "application/json")], body)
+}
+
+/// A standalone HTTP server serving the health endpoints.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::health::{Health, HealthServer};
+/// use kick_client::reconnect::ReconnectingClient;
+/// use kick_client::DEFAULT_WEBSOCKET_URL;
+///
+/// let health = Health::new();
+/// let _server = HealthServer::bind("0.0.0.0:8080", health.clone()).await?;
+/// let mut client = ReconnectingClient::new(DEFAULT_WEBSOCKET_URL, vec![668]).with_health(health);
+/// while let Some(message) = client.read_message().await? {
+///     println!("{}", message);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct HealthServer {
+    /// The address the server is listening on.
+    local_addr: SocketAddr,
+    /// The task running the server, stopped when the server is dropped.
+    server: JoinHandle<()>,
+}
+
+impl HealthServer {
+    /// Starts a server on the given address, reporting `health`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the address cannot be bound.
+    pub async fn bind(addr: impl ToSocketAddrs, health: Health) -> Result<Self, KickError> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let router = router(health);
+        let server = crate::trace::spawn("kick_client::health::server", async move {
+            let _ = axum::serve(listener, router).await;
+        });
+
+        Ok(Self { local_addr, server })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for HealthServer {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
@@ -0,0 +1,646 @@
+//! A local IRC server exposing Kick chatrooms as IRC channels.
+//!
+//! Each chatroom is mapped to a channel, e.g. chatroom 668 to `#xqc`, so existing IRC
+//! clients and bots written for Twitch's IRC interface can read and write Kick chat.
+//! Clients requesting the `message-tags` or `twitch.tv/tags` capability receive chat
+//! messages with Twitch-style tags such as `badges`, `color`, `display-name` and `id`, and
+//! deletions, bans and clears as `CLEARMSG` and `CLEARCHAT`. Other clients receive these as
+//! notices.
+//!
+//! Messages are not read from Kick by the server itself: they are handed to it with
+//! `IrcServer::send`, or read from any `MessageSource` by `IrcServer::forward`. Messages
+//! written by IRC clients are sent with the `ChatSender` the bridge was given, if any.
+//!
+//! Messages sent to a client too slow to keep up are dropped once too many lines are
+//! waiting to be written to it, and counted in `IrcServer::dropped`. Replies to the
+//! client's own commands are always queued.
+
+use crate::{ChatSender, KickChatMessage, KickError, MessageData, MessageSource};
+use futures_util::future::select;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::pin::pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// How many lines may wait to be written to a client by default before messages are
+/// dropped.
+const DEFAULT_CLIENT_BUFFER: usize = 1024;
+
+/// The capabilities clients may request.
+const CAPABILITIES: &[&str] = &["message-tags", "twitch.tv/tags", "twitch.tv/commands"];
+
+/// The configuration of an IRC server, mapping chatrooms to channels.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::irc::IrcBridge;
+/// use kick_client::KickClient;
+///
+/// let client = KickClient::new(kick_client::DEFAULT_WEBSOCKET_URL, vec![668]).await.unwrap();
+/// let server = IrcBridge::new()
+///     .with_channel(668, "xqc")
+///     .bind("127.0.0.1:6667")
+///     .await?;
+/// // Point an IRC client at 127.0.0.1:6667 and `/join #xqc`.
+/// server.forward(client).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct IrcBridge {
+    server_name: String,
+    channels: Vec<(u32, String)>,
+    sender: Option<Arc<dyn ChatSender>>,
+    client_buffer: usize,
+}
+
+impl Default for IrcBridge {
+    fn default() -> Self {
+        Self {
+            server_name: "kick.local".to_string(),
+            channels: Vec::new(),
+            sender: None,
+            client_buffer: DEFAULT_CLIENT_BUFFER,
+        }
+    }
+}
+
+impl IrcBridge {
+    /// Creates a new instance of `IrcBridge` with no channels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Exposes a chatroom as the channel `#name`.
+    pub fn with_channel(mut self, chatroom_id: u32, name: impl AsRef<str>) -> Self {
+        let name = name.as_ref().trim_start_matches('#').to_lowercase();
+        self.channels.push((chatroom_id, name));
+        self
+    }
+
+    /// Sends the messages IRC clients write to channels through `sender`. Without a sender,
+    /// channels are read-only.
+    pub fn with_sender(mut self, sender: impl ChatSender) -> Self {
+        self.sender = Some(Arc::new(sender));
+        self
+    }
+
+    /// Sets the name the server introduces itself with. Defaults to `kick.local`.
+    pub fn with_server_name(mut self, server_name: impl Into<String>) -> Self {
+        self.server_name = server_name.into();
+        self
+    }
+
+    /// Sets how many lines may wait to be written to a client before messages sent to it
+    /// are dropped. Defaults to 1024.
+    pub fn with_client_buffer(mut self, lines: usize) -> Self {
+        self.client_buffer = lines;
+        self
+    }
+
+    /// Starts a server accepting IRC clients on the given address.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the address cannot be bound.
+    pub async fn bind(self, addr: impl ToSocketAddrs) -> Result<IrcServer, KickError> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            bridge: self,
+            clients: Mutex::new(Vec::new()),
+            dropped: AtomicU64::new(0),
+        });
+        let accept = crate::trace::spawn("kick_client::irc::accept", accept_loop(listener, shared.clone()));
+        Ok(IrcServer {
+            local_addr,
+            shared,
+            accept,
+        })
+    }
+
+    fn channel(&self, name: &str) -> Option<u32> {
+        let name = name.trim_start_matches('#').to_lowercase();
+        self.channels
+            .iter()
+            .find(|(_, channel)| *channel == name)
+            .map(|(chatroom_id, _)| *chatroom_id)
+    }
+}
+
+/// A running IRC server, shut down when dropped.
+pub struct IrcServer {
+    local_addr: SocketAddr,
+    shared: Arc<Shared>,
+    accept: JoinHandle<()>,
+}
+
+struct Shared {
+    bridge: IrcBridge,
+    /// The registered clients.
+    clients: Mutex<Vec<Client>>,
+    /// The number of messages dropped because a client had too many lines waiting.
+    dropped: AtomicU64,
+}
+
+struct Client {
+    id: usize,
+    /// Whether the client requested message tags.
+    tags: bool,
+    /// The channels joined, without the leading `#`.
+    joined: HashSet<String>,
+    outgoing: Outgoing,
+}
+
+/// The lines waiting to be written to a client.
+#[derive(Clone)]
+struct Outgoing {
+    lines: mpsc::UnboundedSender<String>,
+    /// How many lines were queued and not written yet.
+    queued: Arc<AtomicUsize>,
+}
+
+impl Outgoing {
+    /// Queues a line, however many are waiting.
+    fn send(&self, line: String) {
+        self.queued.fetch_add(1, Ordering::Relaxed);
+        if self.lines.send(line).is_err() {
+            self.queued.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns `true` if at least `limit` lines are waiting.
+    fn is_full(&self, limit: usize) -> bool {
+        self.queued.load(Ordering::Relaxed) >= limit
+    }
+}
+
+impl IrcServer {
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Returns the number of registered clients.
+    pub fn connected(&self) -> usize {
+        lock(&self.shared.clients).len()
+    }
+
+    /// Returns the number of messages dropped because a client had too many lines waiting.
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Sends a message to every client that joined the channel of its chatroom, returning
+    /// how many clients it was queued for. Messages received on no chatroom, or on a chatroom
+    /// without a channel, are sent to no one.
+    pub fn send(&self, message: &KickChatMessage) -> usize {
+        let bridge = &self.shared.bridge;
+        let Some(chatroom_id) = message.chatroom_id() else {
+            return 0;
+        };
+        let mut sent = 0;
+        for (_, channel) in bridge.channels.iter().filter(|(id, _)| *id == chatroom_id) {
+            let Some(event) = Event::new(message, channel, &bridge.server_name) else {
+                continue;
+            };
+            for client in lock(&self.shared.clients).iter() {
+                if !client.joined.contains(channel) {
+                    continue;
+                }
+                if client.outgoing.is_full(bridge.client_buffer) {
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+                client.outgoing.send(event.line(client.tags));
+                sent += 1;
+            }
+        }
+        sent
+    }
+
+    /// Sends every message read from `source` to the clients, until the source ends.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if reading from the source fails.
+    pub async fn forward<S: MessageSource>(&self, mut source: S) -> Result<(), KickError> {
+        while let Some(message) = source.read_message().await? {
+            self.send(&message);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for IrcServer {
+    fn drop(&mut self) {
+        self.accept.abort();
+        lock(&self.shared.clients).clear();
+    }
+}
+
+fn lock(clients: &Mutex<Vec<Client>>) -> std::sync::MutexGuard<'_, Vec<Client>> {
+    clients.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// A Kick message as an IRC line, with the tags and fallback for clients without tags.
+struct Event {
+    tags: Vec<(&'static str, String)>,
+    line: String,
+    /// The line sent instead to clients that didn't request tags, if it differs.
+    fallback: Option<String>,
+}
+
+impl Event {
+    fn new(message: &KickChatMessage, channel: &str, server: &str) -> Option<Self> {
+        let notice = format!(
+            ":{} NOTICE #{} :{}",
+            server,
+            channel,
+            single_line(&message.to_string())
+        );
+        let event = match &message.data {
+            MessageData::ChatMessage(data) => {
+                let nick = &data.sender.username;
+                let badges = data
+                    .sender
+                    .identity
+                    .badges
+                    .iter()
+                    .map(|badge| {
+                        let count = match badge {
+                            crate::ChatMessageSenderBadge::FullBadge { count, .. } => {
+                                count.unwrap_or(1)
+                            }
+                            crate::ChatMessageSenderBadge::SimpleBadge { .. } => 1,
+                        };
+                        format!("{}/{}", badge.badge_type(), count)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",");
+                let mut tags = vec![("badges", badges)];
+                if let Some(color) = &data.sender.identity.color {
+                    tags.push(("color", color.clone()));
+                }
+                tags.extend([
+                    ("display-name", nick.clone()),
+                    ("id", data.id.clone()),
+                    ("room-id", data.chatroom_id.to_string()),
+                    ("user-id", data.sender.id.to_string()),
+                ]);
+                Self {
+                    tags,
+                    line: format!(
+                        ":{nick}!{nick}@{nick}.kick.com PRIVMSG #{} :{}",
+                        channel,
+                        single_line(data.content.as_deref()?)
+                    ),
+                    fallback: None,
+                }
+            }
+            MessageData::DeletedMessage(data) => Self {
+                tags: vec![("target-msg-id", data.message.id.clone())],
+                line: format!(":{} CLEARMSG #{} :", server, channel),
+                fallback: Some(notice),
+            },
+            MessageData::UserBanned(data) => {
+                let mut tags = vec![("target-user-id", data.user.id.to_string())];
+                if let Some(minutes) = data.duration.filter(|_| !data.permanent) {
+                    tags.insert(0, ("ban-duration", (minutes * 60).to_string()));
+                }
+                Self {
+                    tags,
+                    line: format!(":{} CLEARCHAT #{} :{}", server, channel, data.user.username),
+                    fallback: Some(notice),
+                }
+            }
+            MessageData::ChatroomClear(_) => Self {
+                tags: Vec::new(),
+                line: format!(":{} CLEARCHAT #{}", server, channel),
+                fallback: Some(notice),
+            },
+            _ => Self {
+                tags: Vec::new(),
+                line: notice,
+                fallback: None,
+            },
+        };
+        Some(event)
+    }
+
+    fn line(&self, tags: bool) -> String {
+        if !tags {
+            return self.fallback.clone().unwrap_or_else(|| self.line.clone());
+        }
+        if self.tags.is_empty() {
+            return self.line.clone();
+        }
+        let tags = self
+            .tags
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, escape_tag(value)))
+            .collect::<Vec<_>>()
+            .join(";");
+        format!("@{} {}", tags, self.line)
+    }
+}
+
+/// Escapes a tag value as IRCv3 requires.
+fn escape_tag(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            ';' => escaped.push_str("\\:"),
+            ' ' => escaped.push_str("\\s"),
+            '\\' => escaped.push_str("\\\\"),
+            '\r' => escaped.push_str("\\r"),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Replaces line breaks, which would end an IRC line, with spaces.
+fn single_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+/// A line received from a client, split into its command and parameters.
+struct Command {
+    name: String,
+    params: Vec<String>,
+}
+
+impl Command {
+    fn parse(line: &str) -> Option<Self> {
+        let mut rest = line.trim_end_matches(['\r', '\n']);
+        // Tags and prefixes sent by clients carry nothing the server needs.
+        for marker in ['@', ':'] {
+            if rest.starts_with(marker) {
+                rest = rest.split_once(' ').map_or("", |(_, rest)| rest);
+            }
+        }
+        let (rest, trailing) = match rest.split_once(" :") {
+            Some((rest, trailing)) => (rest, Some(trailing)),
+            None => (rest, None),
+        };
+        let mut words = rest.split(' ').filter(|word| !word.is_empty());
+        let name = words.next()?.to_uppercase();
+        let mut params: Vec<String> = words.map(str::to_string).collect();
+        params.extend(trailing.map(str::to_string));
+        Some(Self { name, params })
+    }
+
+    fn param(&self, index: usize) -> &str {
+        self.params.get(index).map_or("", String::as_str)
+    }
+}
+
+async fn accept_loop(listener: TcpListener, shared: Arc<Shared>) {
+    let mut next_id = 0;
+    while let Ok((stream, _)) = listener.accept().await {
+        next_id += 1;
+        crate::trace::spawn("kick_client::irc::connection", serve(stream, next_id, shared.clone()));
+    }
+}
+
+/// The registration state of a connection.
+#[derive(Default)]
+struct Session {
+    nick: Option<String>,
+    user: bool,
+    /// Whether capability negotiation is in progress, delaying registration.
+    negotiating: bool,
+    /// The capabilities acknowledged.
+    capabilities: Vec<String>,
+    tags: bool,
+    registered: bool,
+}
+
+/// Serves a single client until either side closes the connection.
+async fn serve(stream: TcpStream, id: usize, shared: Arc<Shared>) {
+    let (read, mut write) = stream.into_split();
+    let (lines, mut queue) = mpsc::unbounded_channel::<String>();
+    let outgoing = Outgoing {
+        lines,
+        queued: Arc::new(AtomicUsize::new(0)),
+    };
+
+    let queued = outgoing.queued.clone();
+    let writer = async move {
+        while let Some(mut line) = queue.recv().await {
+            queued.fetch_sub(1, Ordering::Relaxed);
+            line.push_str("\r\n");
+            if write.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    };
+    let reader = async {
+        let mut lines = BufReader::new(read).lines();
+        let mut session = Session::default();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let Some(command) = Command::parse(&line) else {
+                continue;
+            };
+            if !handle(&command, &mut session, id, &shared, &outgoing).await {
+                break;
+            }
+        }
+    };
+    // Stops once the client disconnects, or the server drops the client's queue.
+    let _ = select(pin!(reader), pin!(writer)).await;
+    lock(&shared.clients).retain(|client| client.id != id);
+}
+
+/// Handles a command from a client, returning `false` if the connection is to be closed.
+async fn handle(
+    command: &Command,
+    session: &mut Session,
+    id: usize,
+    shared: &Shared,
+    outgoing: &Outgoing,
+) -> bool {
+    let server = &shared.bridge.server_name;
+    let nick = session.nick.clone().unwrap_or_else(|| "*".to_string());
+    let reply = |line: String| {
+        outgoing.send(line);
+    };
+    let numeric =
+        |code: &str, text: String| reply(format!(":{} {} {} {}", server, code, nick, text));
+
+    match command.name.as_str() {
+        "CAP" => match command.param(0).to_uppercase().as_str() {
+            "LS" => {
+                session.negotiating = true;
+                reply(format!(
+                    ":{} CAP {} LS :{}",
+                    server,
+                    nick,
+                    CAPABILITIES.join(" ")
+                ));
+            }
+            "LIST" => {
+                reply(format!(
+                    ":{} CAP {} LIST :{}",
+                    server,
+                    nick,
+                    session.capabilities.join(" ")
+                ));
+            }
+            "REQ" => {
+                session.negotiating = true;
+                let requested = command.param(1);
+                let caps: Vec<&str> = requested.split(' ').filter(|c| !c.is_empty()).collect();
+                if caps.iter().all(|cap| CAPABILITIES.contains(cap)) {
+                    session
+                        .capabilities
+                        .extend(caps.iter().map(|cap| cap.to_string()));
+                    session.tags = caps.iter().any(|cap| cap.ends_with("tags")) || session.tags;
+                    if let Some(client) = lock(&shared.clients).iter_mut().find(|c| c.id == id) {
+                        client.tags = true;
+                    }
+                    reply(format!(":{} CAP {} ACK :{}", server, nick, requested));
+                } else {
+                    reply(format!(":{} CAP {} NAK :{}", server, nick, requested));
+                }
+            }
+            "END" => {
+                session.negotiating = false;
+                register(session, id, shared, outgoing);
+            }
+            _ => numeric("410", format!("{} :Invalid CAP command", command.param(0))),
+        },
+        "PASS" => {}
+        "NICK" => {
+            let Some(new_nick) = command.params.first() else {
+                numeric("431", ":No nickname given".to_string());
+                return true;
+            };
+            if session.registered {
+                reply(format!(
+                    ":{}!{}@{}.kick.com NICK :{}",
+                    nick, nick, nick, new_nick
+                ));
+            }
+            session.nick = Some(new_nick.clone());
+            register(session, id, shared, outgoing);
+        }
+        "USER" => {
+            session.user = true;
+            register(session, id, shared, outgoing);
+        }
+        "PING" => reply(format!(":{} PONG {} :{}", server, server, command.param(0))),
+        "PONG" => {}
+        "QUIT" => {
+            reply(format!("ERROR :Closing link ({})", nick));
+            return false;
+        }
+        _ if !session.registered => numeric("451", ":You have not registered".to_string()),
+        "JOIN" => {
+            for channel in command.param(0).split(',').filter(|c| !c.is_empty()) {
+                let name = channel.trim_start_matches('#').to_lowercase();
+                let Some(chatroom_id) = shared.bridge.channel(&name) else {
+                    numeric("403", format!("{} :No such channel", channel));
+                    continue;
+                };
+                if let Some(client) = lock(&shared.clients).iter_mut().find(|c| c.id == id) {
+                    client.joined.insert(name.clone());
+                }
+                reply(format!(
+                    ":{}!{}@{}.kick.com JOIN #{}",
+                    nick, nick, nick, name
+                ));
+                numeric("332", format!("#{} :Kick chatroom {}", name, chatroom_id));
+                numeric("353", format!("= #{} :{}", name, nick));
+                numeric("366", format!("#{} :End of /NAMES list", name));
+            }
+        }
+        "PART" => {
+            for channel in command.param(0).split(',').filter(|c| !c.is_empty()) {
+                let name = channel.trim_start_matches('#').to_lowercase();
+                let parted = lock(&shared.clients)
+                    .iter_mut()
+                    .find(|c| c.id == id)
+                    .is_some_and(|client| client.joined.remove(&name));
+                if parted {
+                    reply(format!(
+                        ":{}!{}@{}.kick.com PART #{}",
+                        nick, nick, nick, name
+                    ));
+                } else {
+                    numeric("442", format!("{} :You're not on that channel", channel));
+                }
+            }
+        }
+        "PRIVMSG" => {
+            let target = command.param(0);
+            let Some(chatroom_id) = shared.bridge.channel(target) else {
+                numeric("401", format!("{} :No such nick/channel", target));
+                return true;
+            };
+            let Some(sender) = &shared.bridge.sender else {
+                numeric("404", format!("{} :Cannot send to channel", target));
+                return true;
+            };
+            if let Err(e) = sender.send_message(chatroom_id, command.param(1)).await {
+                reply(format!(
+                    ":{} NOTICE {} :Failed to send message: {}",
+                    server, target, e
+                ));
+            }
+        }
+        "MODE" => {
+            let target = command.param(0);
+            if target.starts_with('#') {
+                numeric("324", format!("{} +", target));
+            } else {
+                numeric("221", "+".to_string());
+            }
+        }
+        "WHO" => numeric("315", format!("{} :End of /WHO list", command.param(0))),
+        name => numeric("421", format!("{} :Unknown command", name)),
+    }
+    true
+}
+
+/// Registers a client once it has sent its nick and user and finished negotiating
+/// capabilities, welcoming it.
+fn register(
+    session: &mut Session,
+    id: usize,
+    shared: &Shared,
+    outgoing: &Outgoing,
+) {
+    let Some(nick) = &session.nick else {
+        return;
+    };
+    if session.registered || !session.user || session.negotiating {
+        return;
+    }
+    session.registered = true;
+    lock(&shared.clients).push(Client {
+        id,
+        tags: session.tags,
+        joined: HashSet::new(),
+        outgoing: outgoing.clone(),
+    });
+
+    let server = &shared.bridge.server_name;
+    for (code, text) in [
+        ("001", format!(":Welcome to Kick chat, {}", nick)),
+        ("002", format!(":Your host is {}", server)),
+        ("003", ":This server bridges Kick chatrooms".to_string()),
+        ("004", format!("{} kick_client", server)),
+        ("422", ":MOTD File is missing".to_string()),
+    ] {
+        outgoing.send(format!(":{} {} {} {}", server, code, nick, text));
+    }
+}
@@ -1,204 +1,290 @@
-use futures_util::stream::SplitStream;
-use futures_util::{SinkExt, StreamExt};
-use serde::de::{DeserializeOwned, Deserializer};
+// `KickError` wraps `tungstenite::Error`, which is large; boxing it would break the public API.
+#![allow(clippy::result_large_err)]
+
+use futures_util::future::BoxFuture;
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{
+    DeserializeOwned, DeserializeSeed, Deserializer, IgnoredAny, IntoDeserializer, MapAccess,
+    SeqAccess, Visitor,
+};
+use serde::ser::Serializer;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
 use std::fmt;
-use tokio::net::TcpStream;
-use tokio_tungstenite::tungstenite::client::IntoClientRequest;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
-use tokio_tungstenite::{tungstenite, MaybeTlsStream, WebSocketStream};
-
-/// A WebSocket client for connecting to and reading messages from Kick chatroom.
-pub struct KickClient {
-    #[allow(dead_code)]
-    /// The WebSocket URL used to connect to the Kick server.
-    url: String,
-    #[allow(dead_code)]
-    /// The channel ID for the subscribed chatroom.
-    channel_ids: Vec<u64>,
-    /// The WebSocket read stream for receiving messages.
-    read_stream: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
-}
-
-impl KickClient {
-    /// Creates a new instance of `KickClient` and automatically establishes a WebSocket connection.
-    ///
-    /// # Arguments
-    ///
-    /// * `url` - The WebSocket URL to connect to.
-    /// * `channel_ids` - The IDs of the chatrooms to subscribe to.
-    ///
-    /// # Returns
-    ///
-    /// A `KickClient` instance ready to receive messages.
+use std::future::Future;
+
+// Declared first, so its `span!` macro is available to the other modules. Every other
+// module using it is only built with one of these features.
+#[cfg(any(
+    feature = "client",
+    feature = "async-std",
+    feature = "wasm",
+    feature = "api",
+    feature = "webhook",
+    feature = "grpc",
+    feature = "shutdown"
+))]
+#[macro_use]
+mod trace;
+
+#[cfg(feature = "api")]
+pub mod api;
+#[cfg(feature = "api")]
+pub mod auth;
+pub mod automod;
+pub mod bans;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(all(feature = "api", feature = "client"))]
+pub mod bot;
+pub mod cache;
+#[cfg(any(feature = "client", feature = "async-std", feature = "wasm"))]
+mod client;
+pub mod commands;
+pub mod content;
+pub mod cooldown;
+#[cfg(feature = "api")]
+pub mod discovery;
+pub mod encoding;
+#[cfg(feature = "test-util")]
+pub mod fake;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "handle")]
+pub mod handle;
+#[cfg(feature = "health")]
+pub mod health;
+#[cfg(feature = "filter")]
+pub mod filter;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "irc")]
+pub mod irc;
+#[cfg(feature = "manager")]
+pub mod manager;
+pub mod metrics;
+pub mod mock;
+#[cfg(feature = "mock-server")]
+pub mod mock_server;
+#[cfg(feature = "api")]
+pub mod official;
+#[cfg(feature = "otel")]
+pub mod otel;
+pub mod permit;
+pub mod polls;
+#[cfg(feature = "pool")]
+pub mod pool;
+#[cfg(feature = "proxy")]
+pub mod proxy;
+#[cfg(feature = "protobuf")]
+pub mod proto;
+#[cfg(feature = "api")]
+pub mod queue;
+#[cfg(feature = "client-core")]
+pub mod ratelimit;
+#[cfg(feature = "rebroadcast")]
+pub mod rebroadcast;
+#[cfg(feature = "client")]
+pub mod reconnect;
+#[cfg(feature = "client-core")]
+pub mod recording;
+pub mod render;
+#[cfg(feature = "shutdown")]
+pub mod shutdown;
+pub mod sinks;
+#[cfg(feature = "sse")]
+pub mod sse;
+pub mod state;
+pub mod stats;
+#[cfg(feature = "statsd")]
+pub mod statsd;
+pub mod strict;
+pub mod transport;
+#[cfg(feature = "tui")]
+pub mod tui;
+#[cfg(feature = "webhook")]
+pub mod webhook;
+
+#[cfg(any(feature = "client", feature = "async-std", feature = "wasm"))]
+pub use client::KickClient;
+
+/// The Pusher WebSocket URL Kick's web client connects to.
+pub const DEFAULT_WEBSOCKET_URL: &str = "wss://ws-us2.pusher.com/app/32cbd69e4b950bf97679?protocol=7&client=js&version=8.4.0-rc2&flash=false";
+
+/// A source of parsed Kick messages, such as a live WebSocket connection.
+pub trait MessageSource {
+    /// Reads the next message from the source.
     ///
     /// # Errors
     ///
-    /// This function will return an error if the WebSocket connection fails.
-    /// # Examples
-    ///
-    /// ```
-    /// let mut client = KickClient::new("wss://ws-us2.pusher.com/app/32cbd69e4b950bf97679?protocol=7&client=js&version=8.4.0-rc2&flash=false", [281473]).await.unwrap();
-    /// while let Some(message) = client.read_message().await.unwrap() {
-    ///     println!("{:?}", message);
-    /// }
-    /// ```
-    pub async fn new(url: &str, channel_ids: Vec<u64>) -> Result<Self, Box<dyn Error>> {
-        let request = url.into_client_request()?;
-        let (ws_stream, _) = connect_async(request).await?;
-        let (mut write, read) = ws_stream.split();
-
-        // Create a subscription message
-        for channel_id in channel_ids.clone() {
-            let subscribe_message = serde_json::json!({
-                "event": "pusher:subscribe",
-                "data": {
-                    "auth": "",
-                    "channel": format!("chatrooms.{}.v2", channel_id)
-                }
-            });
-    
-            write
-                .send(Message::Text(subscribe_message.to_string().into()))
-                .await?;
-        }
+    /// This function will return `KickError::StreamEnded` once the source is exhausted, or
+    /// another error if reading from it fails.
+    fn read_message(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<KickChatMessage>, KickError>> + Send;
+}
 
-        Ok(Self {
-            url: url.to_string(),
-            channel_ids,
-            read_stream: read,
-        })
+impl<S: MessageSource + Send + ?Sized> MessageSource for &mut S {
+    fn read_message(
+        &mut self,
+    ) -> impl Future<Output = Result<Option<KickChatMessage>, KickError>> + Send {
+        (**self).read_message()
     }
+}
 
-    /// Reads the next message from the WebSocket stream and returns a parsed `KickChatMessage`.
-    ///
-    /// # Returns
-    ///
-    /// A `KickChatMessage` if a valid message is received, or `None` if the stream ends.
+/// Something able to send chat messages, such as `api::KickApi`.
+pub trait ChatSender: Send + Sync + 'static {
+    /// Sends a chat message to a chatroom.
     ///
     /// # Errors
     ///
-    /// This function will return an error if the WebSocket stream encounters an error.
-    pub async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
-    if let Some(msg) = self.read_stream.next().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                match serde_json::from_str::<KickChatMessage>(&text) {
-                    Ok(parsed_message) => {
-                        Ok(Some(parsed_message))
-                    }
-                    Err(e) => {
-                        Ok(Some(KickChatMessage {
-                            data: MessageData::Unsupported(Some(text.to_string()), e.to_string()),
-                            channel: None,
-                        }))
-                    }
-                }
-            }
-            Err(e) => Err(KickError::WebSocketError(e)),
-            _ => Ok(Some(KickChatMessage {
-                data: MessageData::Unknown(None),
-                channel: None,
-            })),
-        }
-    } else {
-        println!("WebSocket stream ended");
-        Err(KickError::StreamEnded)
-    }
-}
-
-    /// If the `tokio-handling` feature is enabled, this function spawns a task that handles
-    /// incoming messages and invokes the provided callback for each message.
-    #[cfg(feature = "tokio-handling")]
-    pub fn start_handling<F>(mut self, callback: F)
-    where
-        F: Fn(KickMessage) + Send + Sync + 'static,
-    {
-        tokio::spawn(async move {
-            while let Ok(Some(message)) = self.read_message().await {
-                callback(message);
-            }
-        });
-    }
+    /// This function will return an error if the message cannot be sent.
+    fn send_message<'a>(
+        &'a self,
+        chatroom_id: u32,
+        content: &'a str,
+    ) -> BoxFuture<'a, Result<(), KickError>>;
 }
 
 /// Enum representing different types of messages received from the WebSocket.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[serde(tag = "event", content = "data")]
 pub enum MessageData {
     /// A chat message received in the chatroom.
     #[serde(rename = "App\\Events\\ChatMessageEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<ChatMessageEventData>"))]
     ChatMessage(ChatMessageEventData),
     /// A message indicating that user's message was deleted.
     #[serde(rename = "App\\Events\\MessageDeletedEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<DeletedMessageEventData>"))]
     DeletedMessage(DeletedMessageEventData),
     /// A message indicating that a user was banned from the chatroom.
     #[serde(rename = "App\\Events\\UserBannedEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<UserBannedEventData>"))]
     UserBanned(UserBannedEventData),
     /// A message indicating that a user was unbanned from the chatroom.
     #[serde(rename = "App\\Events\\UserUnbannedEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<UserUnbannedEventData>"))]
     UserUnbanned(UserUnbannedEventData),
     /// A message indicating that the chatroom was updated.
     #[serde(rename = "App\\Events\\ChatroomUpdatedEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<ChatroomUpdatedEventData>"))]
     ChatroomUpdated(ChatroomUpdatedEventData),
     /// A message indicating that the chatroom was cleared.
     #[serde(rename = "App\\Events\\ChatroomClearEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<ChatroomClearEventData>"))]
     ChatroomClear(ChatroomClearEventData),
     /// A message indicating that a poll was updated.
     #[serde(rename = "App\\Events\\PollUpdateEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<PollUpdateEventData>"))]
     PollUpdate(PollUpdateEventData),
     /// A message indicating that a poll was deleted.
     #[serde(rename = "App\\Events\\PollDeleteEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<PollDeleteEventData>"))]
     PollDelete(PollDeleteEventData),
     /// A message indicating that the connection was established.
     #[serde(rename = "pusher:connection_established")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<PusherConnectionEstablishedEventData>"))]
     PusherConnectionEstablished(PusherConnectionEstablishedEventData),
     /// A message indicating that a subscription was pushed and was successful.
     #[serde(rename = "pusher_internal:subscription_succeeded")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<PusherSubscriptionSucceededEventData>"))]
     PusherSubscriptionSucceeded(PusherSubscriptionSucceededEventData),
     /// A messenge indicating that the connection is still alive.
     #[serde(rename = "pusher:pong")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<PusherPongEventData>"))]
     PusherPong(PusherPongEventData),
     /// A messenge indicating that someone purchased subscription in the channel.
     #[serde(rename = "App\\Events\\SubscriptionEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<SubscriptionEventData>"))]
     SubscriptionEvent(SubscriptionEventData),
     /// A messenge indicating that someone's message was unpinned.
     #[serde(rename = "App\\Events\\PinnedMessageDeletedEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<PinnedMessageDeletedEventData>"))]
     PinnedMessageDeletedEvent(PinnedMessageDeletedEventData),
     /// A messenge indicating that someone's message was pinned.
     #[serde(rename = "App\\Events\\PinnedMessageCreatedEvent")]
     #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<PinnedMessageCreatedEventData>"))]
     PinnedMessageCreatedEvent(PinnedMessageCreatedEventData),
-    /// A message of unknown type.
-    Unknown(Option<String>),
-    /// A message of unsupported type yet. Feel free to submit it to me.
+    /// A messenge indicating that someone gifted subscriptions in the channel.
+    #[serde(rename = "App\\Events\\GiftedSubscriptionsEvent")]
+    #[serde(deserialize_with = "json_string_to_struct")]
+    #[serde(serialize_with = "struct_to_json_string")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "json_string_schema::<GiftedSubscriptionsEventData>"))]
+    GiftedSubscriptions(GiftedSubscriptionsEventData),
+    /// A message indicating that someone followed the channel. Only delivered through webhooks.
+    #[serde(rename = "channel.followed")]
+    ChannelFollowed(ChannelFollowedEventData),
+    /// A message indicating that the channel went live or offline. Only delivered through
+    /// webhooks.
+    #[serde(rename = "livestream.status.updated")]
+    LivestreamStatusUpdated(LivestreamStatusEventData),
+    /// A marker inserted by `reconnect::ReconnectingClient` after being disconnected long
+    /// enough for messages to have been missed.
+    #[serde(rename = "kick_client:possible_gap")]
+    PossibleGap(PossibleGapData),
+    /// A message of unknown type, with its event and data, or `None` for frames without
+    /// any, such as close frames.
+    Unknown(Option<UnknownEventData>),
+    /// A message which couldn't be parsed. Feel free to submit it to me.
     Unsupported(Option<String>, String)
 }
 
 /// Data structure containing the content of a message.
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// Serializes to the frame Kick sends, with `data` encoded as a JSON string. Frames that
+/// couldn't be parsed serialize to the frame as received, and unknown events to their
+/// event and data.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct KickChatMessage {
-    #[serde(flatten)]
+    #[cfg_attr(feature = "schemars", serde(flatten))]
     pub data: MessageData,
     /// Channel id.
     pub channel: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A message along with the text frame it was parsed from, so the exact bytes received can
+/// be archived while handling the typed message.
+#[derive(Debug, Clone)]
+pub struct RawEvent {
+    /// The text frame as received, or empty for other frames, such as close frames.
+    pub raw: bytes::Bytes,
+    /// The message parsed from `raw`.
+    pub parsed: KickChatMessage,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ChatMessageEventData {
     pub id: String,
     pub chatroom_id: u32,
@@ -208,21 +294,29 @@ pub struct ChatMessageEventData {
     pub sender: ChatMessageSender,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ChatMessageSender {
     pub id: u32,
     pub username: String,
     pub slug: Option<String>,
+    #[serde(default)]
     pub identity: ChatMessageSenderIdentity,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ChatMessageSenderIdentity {
     pub color: Option<String>,
+    #[serde(default)]
     pub badges: Vec<ChatMessageSenderBadge>,
 }
 
-#[derive(Serialize, Debug)]
+/// A badge of a chat message sender. Badges with a `count` field, even a null one, are
+/// `FullBadge`s, so badges serialize back to the JSON they were parsed from.
+#[derive(Serialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[serde(untagged)]
 pub enum ChatMessageSenderBadge {
     FullBadge {
         r#type: String,
@@ -235,132 +329,199 @@ pub enum ChatMessageSenderBadge {
     },
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct UserBannedEventData {
     pub id: String,
     pub user: User,
     pub banned_by: User,
+    #[serde(default)]
     pub permanent: bool,
     pub duration: Option<u64>,
     pub expires_at: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct UserUnbannedEventData {
     pub id: String,
     pub user: User,
     pub unbanned_by: User,
+    #[serde(default)]
     pub permanent: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct User {
     pub id: u32,
     pub username: String,
     pub slug: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ChatroomUpdatedEventData {
     pub id: u32,
+    #[serde(default)]
     pub slow_mode: SlowMode,
+    #[serde(default)]
     pub subscribers_mode: SubscribersMode,
+    #[serde(default)]
     pub followers_mode: FollowersMode,
+    #[serde(default)]
     pub emotes_mode: EmotesMode,
+    /// Missing from the updates of chatrooms which never enabled it, and read as disabled.
+    #[serde(default)]
     pub advanced_bot_protection: AdvancedBotProtection,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SlowMode {
     pub enabled: bool,
+    #[serde(default)]
     pub message_interval: u64,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SubscribersMode {
     pub enabled: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct FollowersMode {
     pub enabled: bool,
+    #[serde(default)]
     pub min_duration: u64,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct EmotesMode {
     pub enabled: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct AdvancedBotProtection {
     pub enabled: bool,
+    #[serde(default)]
     pub remaining_time: u64,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct ChatroomClearEventData {
     pub id: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DeletedMessageEventData {
     pub id: String,
     pub message: DeletedMessage,
-    #[serde(rename = "aiModerated")]
+    #[serde(rename = "aiModerated", default)]
     pub ai_moderated: bool,
     #[serde(rename = "violatedRules")]
-    pub violated_rules: Option<Vec<String>>
+    pub violated_rules: Option<Vec<String>>,
+    /// The deleted message, filled in by clients with a message cache that still held it.
+    #[serde(skip)]
+    pub original: Option<ChatMessageEventData>
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct DeletedMessage {
     pub id: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PollUpdateEventData {
     pub poll: Poll,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct Poll {
     pub title: String,
     pub options: Vec<PollOption>,
+    /// The remaining fields are missing from the updates sent once a poll ended.
+    #[serde(default)]
     pub duration: u32,
+    #[serde(default)]
     pub remaining: u32,
+    #[serde(default)]
     pub result_display_duration: u32,
     pub has_voted: Option<bool>,
     pub voted_option_id: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PollOption {
     pub id: u32,
     pub label: String,
+    #[serde(default)]
     pub votes: u32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PollDeleteEventData {}
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PusherConnectionEstablishedEventData {
     pub socket_id: String,
     pub activity_timeout: u32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PusherSubscriptionSucceededEventData {}
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct SubscriptionEventData {
     pub chatroom_id: u32,
     pub username: String,
     pub months: u32
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct GiftedSubscriptionsEventData {
+    pub chatroom_id: u32,
+    #[serde(default)]
+    pub gifted_usernames: Vec<String>,
+    pub gifter_username: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct ChannelFollowedEventData {
+    pub broadcaster_user_id: u64,
+    pub follower_user_id: u64,
+    pub follower_username: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct LivestreamStatusEventData {
+    pub broadcaster_user_id: u64,
+    pub broadcaster_username: String,
+    pub is_live: bool,
+    pub title: String,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct StreamHostEventData {
     pub chatroom_id: u32,
     pub optional_message: Option<String>,
@@ -368,10 +529,12 @@ pub struct StreamHostEventData {
     pub host_username: String
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PinnedMessageDeletedEventData {}
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PinnedMessageCreatedEventData {
     pub message: ChatMessageEventData,
     pub duration: String,
@@ -379,9 +542,28 @@ pub struct PinnedMessageCreatedEventData {
     pub pinned_by: ChatMessageSender,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct PusherPongEventData {}
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct PossibleGapData {
+    /// How long the client was disconnected for, in milliseconds.
+    pub downtime_ms: u64,
+}
+
+/// An event `MessageData` has no variant for yet.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+pub struct UnknownEventData {
+    /// The name of the event, e.g. `App\Events\StreamHostEvent`.
+    pub event: String,
+    /// The data of the event, decoded from the JSON string Kick encodes it as. Data which
+    /// isn't JSON is kept as a string.
+    pub data: serde_json::Value,
+}
+
 impl<'de> Deserialize<'de> for ChatMessageSenderBadge {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -391,7 +573,16 @@ impl<'de> Deserialize<'de> for ChatMessageSenderBadge {
         struct BadgeHelper {
             r#type: String,
             text: String,
-            count: Option<u32>,
+            /// `None` if the field is missing, `Some(None)` if it is null.
+            #[serde(default, deserialize_with = "present_option")]
+            count: Option<Option<u32>>,
+        }
+
+        fn present_option<'de, D>(deserializer: D) -> Result<Option<Option<u32>>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            Option::<u32>::deserialize(deserializer).map(Some)
         }
 
         let helper = BadgeHelper::deserialize(deserializer)?;
@@ -399,7 +590,7 @@ impl<'de> Deserialize<'de> for ChatMessageSenderBadge {
             Ok(ChatMessageSenderBadge::FullBadge {
                 r#type: helper.r#type,
                 text: helper.text,
-                count: Some(count),
+                count,
             })
         } else {
             Ok(ChatMessageSenderBadge::SimpleBadge {
@@ -409,44 +600,614 @@ impl<'de> Deserialize<'de> for ChatMessageSenderBadge {
         }
     }
 }
+
+impl ChatMessageSenderBadge {
+    /// Returns the type of the badge, e.g. `moderator` or `subscriber`.
+    pub fn badge_type(&self) -> &str {
+        match self {
+            ChatMessageSenderBadge::FullBadge { r#type, .. } => r#type,
+            ChatMessageSenderBadge::SimpleBadge { r#type, .. } => r#type,
+        }
+    }
+
+    /// Returns the display text of the badge, e.g. `Moderator`.
+    pub fn text(&self) -> &str {
+        match self {
+            ChatMessageSenderBadge::FullBadge { text, .. } => text,
+            ChatMessageSenderBadge::SimpleBadge { text, .. } => text,
+        }
+    }
+}
+
+impl ChatMessageSender {
+    /// Returns `true` if the sender has a badge of the given type.
+    pub fn has_badge(&self, badge_type: &str) -> bool {
+        self.identity
+            .badges
+            .iter()
+            .any(|badge| badge.badge_type() == badge_type)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ChatMessageEventData {
+    /// Returns when the message was sent, parsed from `created_at`.
+    pub fn created_at_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let created_at = self.created_at.as_deref()?;
+        chrono::DateTime::parse_from_rfc3339(created_at)
+            .ok()
+            .map(|time| time.with_timezone(&chrono::Utc))
+    }
+
+    /// Returns how long ago the message was sent, or zero if `created_at` is in the future
+    /// because of clock skew.
+    pub fn latency(&self) -> Option<std::time::Duration> {
+        let elapsed = chrono::Utc::now() - self.created_at_time()?;
+        Some(elapsed.to_std().unwrap_or_default())
+    }
+}
+
+impl<'de> Deserialize<'de> for KickChatMessage {
+    /// Deserializes a frame in a single pass when `event` comes before `data`, as it does in
+    /// the frames Kick sends: `data` is then decoded straight into the type of the event. A
+    /// `data` coming first is buffered until `event` tells its type.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(FrameVisitor)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum FrameField {
+    Event,
+    Data,
+    Channel,
+    #[serde(other)]
+    Other,
+}
+
+struct FrameVisitor;
+
+impl<'de> Visitor<'de> for FrameVisitor {
+    type Value = KickChatMessage;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a Pusher frame")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<KickChatMessage, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut event: Option<String> = None;
+        let mut data = None;
+        let mut buffered: Option<serde_json::Value> = None;
+        let mut channel = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                FrameField::Event => event = Some(map.next_value()?),
+                FrameField::Data => match &event {
+                    Some(event) => data = Some(map.next_value_seed(DataSeed(event))?),
+                    None => buffered = Some(map.next_value()?),
+                },
+                FrameField::Channel => channel = map.next_value()?,
+                FrameField::Other => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let data = match data {
+            Some(data) => data,
+            None => {
+                let event = event.ok_or_else(|| serde::de::Error::missing_field("event"))?;
+                if !EVENTS.contains(&event.as_str()) {
+                    let data = buffered.map_or(serde_json::Value::Null, decode_unknown_data);
+                    MessageData::Unknown(Some(UnknownEventData { event, data }))
+                } else {
+                    MessageData::deserialize(MapAccessDeserializer::new(Tagged {
+                        event: Some(&event),
+                        data: buffered,
+                    }))
+                    .map_err(serde::de::Error::custom)?
+                }
+            }
+        };
+        Ok(KickChatMessage { data, channel })
+    }
+}
+
+/// The events `MessageData` deserializes into one of its variants, as named in frames.
+/// Other events are kept as `MessageData::Unknown`.
+const EVENTS: &[&str] = &[
+    "App\\Events\\ChatMessageEvent",
+    "App\\Events\\MessageDeletedEvent",
+    "App\\Events\\UserBannedEvent",
+    "App\\Events\\UserUnbannedEvent",
+    "App\\Events\\ChatroomUpdatedEvent",
+    "App\\Events\\ChatroomClearEvent",
+    "App\\Events\\PollUpdateEvent",
+    "App\\Events\\PollDeleteEvent",
+    "pusher:connection_established",
+    "pusher_internal:subscription_succeeded",
+    "pusher:pong",
+    "App\\Events\\SubscriptionEvent",
+    "App\\Events\\PinnedMessageDeletedEvent",
+    "App\\Events\\PinnedMessageCreatedEvent",
+    "App\\Events\\GiftedSubscriptionsEvent",
+    "channel.followed",
+    "livestream.status.updated",
+    "kick_client:possible_gap",
+    // As serialized by the derived implementation, e.g. for `Unknown(None)`.
+    "Unknown",
+    "Unsupported",
+];
+
+/// Deserializes the `data` of a frame as the type of its `event`.
+struct DataSeed<'a>(&'a str);
+
+impl<'de> DeserializeSeed<'de> for DataSeed<'_> {
+    type Value = MessageData;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<MessageData, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if !EVENTS.contains(&self.0) {
+            let data = serde_json::Value::deserialize(deserializer)?;
+            return Ok(MessageData::Unknown(Some(UnknownEventData {
+                event: self.0.to_string(),
+                data: decode_unknown_data(data),
+            })));
+        }
+        MessageData::deserialize(MapAccessDeserializer::new(Tagged {
+            event: Some(self.0),
+            data: Some(deserializer),
+        }))
+    }
+}
+
+/// Decodes the `data` of an unknown event from the JSON string Kick encodes it as, keeping
+/// strings which aren't JSON as they are.
+fn decode_unknown_data(data: serde_json::Value) -> serde_json::Value {
+    match data {
+        serde_json::Value::String(s) => {
+            serde_json::from_str(&s).unwrap_or(serde_json::Value::String(s))
+        }
+        data => data,
+    }
+}
+
+/// Gives `MessageData` an `event` and, if any, its `data`, in the order its derived
+/// implementation reads them without buffering.
+struct Tagged<'a, D> {
+    event: Option<&'a str>,
+    data: Option<D>,
+}
+
+impl<'de, D> MapAccess<'de> for Tagged<'_, D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, D::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let key = match (&self.event, &self.data) {
+            (Some(_), _) => "event",
+            (None, Some(_)) => "data",
+            (None, None) => return Ok(None),
+        };
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, D::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        if let Some(event) = self.event.take() {
+            return seed.deserialize(event.into_deserializer());
+        }
+        match self.data.take() {
+            Some(data) => seed.deserialize(data),
+            None => Err(serde::de::Error::custom("value requested before key")),
+        }
+    }
+}
+
+impl Serialize for KickChatMessage {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct Envelope<'a> {
+            #[serde(flatten)]
+            data: &'a MessageData,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            channel: Option<&'a str>,
+        }
+
+        #[derive(Serialize)]
+        struct UnknownEnvelope<'a> {
+            event: &'a str,
+            #[serde(serialize_with = "struct_to_json_string")]
+            data: &'a serde_json::Value,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            channel: Option<&'a str>,
+        }
+
+        if let MessageData::Unsupported(Some(frame), _) = &self.data {
+            if let Ok(frame) = serde_json::from_str::<serde_json::Value>(frame) {
+                return frame.serialize(serializer);
+            }
+        }
+        if let MessageData::Unknown(Some(unknown)) = &self.data {
+            return UnknownEnvelope {
+                event: &unknown.event,
+                data: &unknown.data,
+                channel: self.channel.as_deref(),
+            }
+            .serialize(serializer);
+        }
+        Envelope {
+            data: &self.data,
+            channel: self.channel.as_deref(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl fmt::Display for ChatMessageEventData {
+    /// Formats the message as a chat line, e.g. `[12:03] moderator Bob: hello`, with the
+    /// time as sent in `created_at`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `created_at` is RFC 3339, e.g. `2024-05-01T12:03:56+00:00`.
+        let time = self
+            .created_at
+            .as_deref()
+            .and_then(|created_at| created_at.split_once('T'))
+            .and_then(|(_, time)| time.get(..5));
+        if let Some(time) = time {
+            write!(f, "[{}] ", time)?;
+        }
+        for badge in &self.sender.identity.badges {
+            write!(f, "{} ", badge.badge_type())?;
+        }
+        write!(
+            f,
+            "{}: {}",
+            self.sender.username,
+            self.content.as_deref().unwrap_or_default()
+        )
+    }
+}
+
+impl fmt::Display for MessageData {
+    /// Formats chat messages as chat lines and other messages as short descriptions.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessageData::ChatMessage(data) => write!(f, "{}", data),
+            MessageData::DeletedMessage(data) => match &data.original {
+                Some(original) => write!(
+                    f,
+                    "Message from {} deleted: {}",
+                    original.sender.username,
+                    original.content.as_deref().unwrap_or_default()
+                ),
+                None => write!(f, "Message {} deleted", data.message.id),
+            },
+            MessageData::UserBanned(data) => {
+                write!(
+                    f,
+                    "{} was banned by {}",
+                    data.user.username, data.banned_by.username
+                )?;
+                match data.duration {
+                    Some(minutes) if !data.permanent => write!(f, " for {} minutes", minutes),
+                    _ => write!(f, " permanently"),
+                }
+            }
+            MessageData::UserUnbanned(data) => write!(
+                f,
+                "{} was unbanned by {}",
+                data.user.username, data.unbanned_by.username
+            ),
+            MessageData::ChatroomUpdated(data) => {
+                let mut modes = Vec::new();
+                if data.slow_mode.enabled {
+                    modes.push(format!("slow mode ({}s)", data.slow_mode.message_interval));
+                }
+                if data.followers_mode.enabled {
+                    modes.push(format!(
+                        "followers only ({}m)",
+                        data.followers_mode.min_duration
+                    ));
+                }
+                if data.subscribers_mode.enabled {
+                    modes.push("subscribers only".to_string());
+                }
+                if data.emotes_mode.enabled {
+                    modes.push("emotes only".to_string());
+                }
+                if data.advanced_bot_protection.enabled {
+                    modes.push("bot protection".to_string());
+                }
+                if modes.is_empty() {
+                    write!(f, "Chat modes updated: none")
+                } else {
+                    write!(f, "Chat modes updated: {}", modes.join(", "))
+                }
+            }
+            MessageData::ChatroomClear(_) => write!(f, "Chat cleared"),
+            MessageData::PollUpdate(data) => {
+                write!(f, "Poll \"{}\":", data.poll.title)?;
+                for (i, option) in data.poll.options.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { ", " };
+                    write!(f, "{}{} ({})", separator, option.label, option.votes)?;
+                }
+                Ok(())
+            }
+            MessageData::PollDelete(_) => write!(f, "Poll deleted"),
+            MessageData::PusherConnectionEstablished(data) => {
+                write!(f, "Connected (socket {})", data.socket_id)
+            }
+            MessageData::PusherSubscriptionSucceeded(_) => write!(f, "Subscribed"),
+            MessageData::PusherPong(_) => write!(f, "Pong"),
+            MessageData::SubscriptionEvent(data) => {
+                write!(f, "{} subscribed for {} months", data.username, data.months)
+            }
+            MessageData::PinnedMessageDeletedEvent(_) => write!(f, "Pinned message removed"),
+            MessageData::PinnedMessageCreatedEvent(data) => write!(
+                f,
+                "{} pinned a message from {}: {}",
+                data.pinned_by.username,
+                data.message.sender.username,
+                data.message.content.as_deref().unwrap_or_default()
+            ),
+            MessageData::GiftedSubscriptions(data) => write!(
+                f,
+                "{} gifted {} subscriptions",
+                data.gifter_username.as_deref().unwrap_or("Anonymous"),
+                data.gifted_usernames.len()
+            ),
+            MessageData::ChannelFollowed(data) => write!(f, "{} followed", data.follower_username),
+            MessageData::LivestreamStatusUpdated(data) if data.is_live => {
+                write!(f, "{} went live: {}", data.broadcaster_username, data.title)
+            }
+            MessageData::LivestreamStatusUpdated(data) => {
+                write!(f, "{} went offline", data.broadcaster_username)
+            }
+            MessageData::PossibleGap(data) => write!(
+                f,
+                "Messages may have been missed while disconnected for {}s",
+                data.downtime_ms / 1000
+            ),
+            MessageData::Unknown(Some(unknown)) => write!(f, "Unknown message: {}", unknown.event),
+            MessageData::Unknown(None) => write!(f, "Unknown message"),
+            MessageData::Unsupported(_, err) => write!(f, "Unsupported message: {}", err),
+        }
+    }
+}
+
+impl fmt::Display for KickChatMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.data, &self.channel) {
+            (MessageData::PusherSubscriptionSucceeded(_), Some(channel)) => {
+                write!(f, "Subscribed to {}", channel)
+            }
+            (data, _) => write!(f, "{}", data),
+        }
+    }
+}
+
+impl MessageData {
+    /// Returns a short name for the kind of event, e.g. `chat` or `ban`, for logs, metrics
+    /// and routing.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MessageData::ChatMessage(_) => "chat",
+            MessageData::DeletedMessage(_) => "delete",
+            MessageData::UserBanned(_) => "ban",
+            MessageData::UserUnbanned(_) => "unban",
+            MessageData::ChatroomUpdated(_) => "chatroom_update",
+            MessageData::ChatroomClear(_) => "clear",
+            MessageData::PollUpdate(_) => "poll_update",
+            MessageData::PollDelete(_) => "poll_delete",
+            MessageData::PusherConnectionEstablished(_) => "connection_established",
+            MessageData::PusherSubscriptionSucceeded(_) => "subscription_succeeded",
+            MessageData::PusherPong(_) => "pong",
+            MessageData::SubscriptionEvent(_) => "subscription",
+            MessageData::PinnedMessageDeletedEvent(_) => "pin_delete",
+            MessageData::PinnedMessageCreatedEvent(_) => "pin",
+            MessageData::GiftedSubscriptions(_) => "gifted_subscriptions",
+            MessageData::ChannelFollowed(_) => "follow",
+            MessageData::LivestreamStatusUpdated(_) => "livestream",
+            MessageData::PossibleGap(_) => "gap",
+            MessageData::Unknown(_) => "unknown",
+            MessageData::Unsupported(..) => "unsupported",
+        }
+    }
+}
+
+impl KickChatMessage {
+    /// Returns the ID of the chatroom the message was received on, parsed from its
+    /// `chatrooms.{id}.v2` channel.
+    pub fn chatroom_id(&self) -> Option<u32> {
+        channel_chatroom_id(self.channel.as_deref()?)
+    }
+}
+
+/// Returns the chatroom ID in the name of a Pusher channel, e.g. `chatrooms.668.v2`.
+pub(crate) fn channel_chatroom_id(channel: &str) -> Option<u32> {
+    let id = channel
+        .strip_prefix("chatrooms.")
+        .and_then(|rest| rest.split('.').next())
+        .or_else(|| channel.strip_prefix("chatroom_"))?;
+    id.parse().ok()
+}
+
+/// Parses a text frame received from Kick, keeping frames that fail to parse as
+/// `MessageData::Unsupported`. Only those are copied into a `String`.
+pub(crate) fn parse_frame(frame: &[u8]) -> KickChatMessage {
+    serde_json::from_slice::<KickChatMessage>(frame).unwrap_or_else(|e| KickChatMessage {
+        data: MessageData::Unsupported(
+            Some(String::from_utf8_lossy(frame).into_owned()),
+            e.to_string(),
+        ),
+        channel: None,
+    })
+}
+
+/// Encodes `data` as a JSON string, as Kick does, for human-readable formats. Binary
+/// formats encode it as a nested value instead, so it isn't decoded twice.
+fn struct_to_json_string<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    T: Serialize,
+{
+    if !serializer.is_human_readable() {
+        return value.serialize(serializer);
+    }
+    let s = serde_json::to_string(value).map_err(serde::ser::Error::custom)?;
+    serializer.serialize_str(&s)
+}
+
+/// Describes `data` as a JSON string encoding a `T`, as Kick encodes it.
+#[cfg(feature = "schemars")]
+fn json_string_schema<T: schemars::JsonSchema>(
+    generator: &mut schemars::SchemaGenerator,
+) -> schemars::Schema {
+    schemars::json_schema!({
+        "type": "string",
+        "contentMediaType": "application/json",
+        "contentSchema": generator.subschema_for::<T>(),
+    })
+}
+
+/// Decodes `data` from a JSON string, as Kick encodes it, or from a nested value, as
+/// binary formats encode it. The string is decoded where the deserializer holds it, in the
+/// frame itself or in its unescaping buffer, without being copied first.
 fn json_string_to_struct<'de, D, T>(deserializer: D) -> Result<T, D::Error>
 where
     D: Deserializer<'de>,
     T: DeserializeOwned,
 {
-    let s = String::deserialize(deserializer)?;
-    serde_json::from_str(&s).map_err(|e| {
-        serde::de::Error::custom(e)
-    })}
+    struct JsonString<T>(std::marker::PhantomData<T>);
+
+    impl<'de, T: DeserializeOwned> Visitor<'de> for JsonString<T> {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a JSON string or a nested value")
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<T, E> {
+            serde_json::from_str(v).map_err(E::custom)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<T, A::Error> {
+            T::deserialize(MapAccessDeserializer::new(map))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<T, A::Error> {
+            T::deserialize(SeqAccessDeserializer::new(seq))
+        }
+    }
+
+    deserializer.deserialize_any(JsonString(std::marker::PhantomData))
+}
 
 /// Enum representing possible errors in KickClient.
+///
+/// `WebSocketError` only exists with the `client` or `async-std` feature, so matches need
+/// a wildcard arm to build with any set of features.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum KickError {
+    #[cfg(any(feature = "client", feature = "async-std"))]
     WebSocketError(tungstenite::Error),
     MessageParseError(serde_json::Error),
     StreamEnded,
+    /// The HTTP request to Kick's REST API could not be completed.
+    HttpError(Box<dyn Error + Send + Sync>),
+    /// Kick's REST API answered with a non-success status.
+    ApiError { status: u16, body: String },
+    /// The credentials are missing or could not be refreshed.
+    AuthError(String),
+    /// An I/O operation, such as binding a listener, failed.
+    IoError(std::io::Error),
+    /// A webhook signature is missing, malformed or doesn't match.
+    SignatureError(String),
+    /// A reply was attempted without a `ChatSender` to send it through.
+    NoChatSender,
+    /// The arguments of a chat command are missing or invalid.
+    UsageError(String),
+    /// A configuration is invalid.
+    ConfigError(String),
+    /// A message could not be encoded to or decoded from a binary format.
+    EncodingError(Box<dyn Error + Send + Sync>),
+    /// A sink failed to store or forward messages.
+    SinkError(Box<dyn Error + Send + Sync>),
+    /// A proxy refused or failed to tunnel the connection.
+    ProxyError(String),
+    /// An operation, such as connecting, took longer than its timeout.
+    TimeoutError(String),
 }
 
 impl fmt::Display for KickError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            #[cfg(any(feature = "client", feature = "async-std"))]
             KickError::WebSocketError(err) => write!(f, "WebSocket error: {}", err),
             KickError::MessageParseError(err) => write!(f, "Message parse error: {}", err),
             KickError::StreamEnded => write!(f, "WebSocket stream ended unexpectedly"),
+            KickError::HttpError(err) => write!(f, "HTTP error: {}", err),
+            KickError::ApiError { status, body } => {
+                write!(f, "API error: status {}: {}", status, body)
+            }
+            KickError::AuthError(err) => write!(f, "Authentication error: {}", err),
+            KickError::IoError(err) => write!(f, "I/O error: {}", err),
+            KickError::SignatureError(err) => write!(f, "Signature error: {}", err),
+            KickError::NoChatSender => write!(f, "No chat sender configured"),
+            KickError::UsageError(err) => write!(f, "{}", err),
+            KickError::ConfigError(err) => write!(f, "Configuration error: {}", err),
+            KickError::EncodingError(err) => write!(f, "Encoding error: {}", err),
+            KickError::SinkError(err) => write!(f, "Sink error: {}", err),
+            KickError::ProxyError(err) => write!(f, "Proxy error: {}", err),
+            KickError::TimeoutError(err) => write!(f, "Timed out: {}", err),
         }
     }
 }
 
 impl std::error::Error for KickError {}
 
+#[cfg(any(feature = "client", feature = "async-std"))]
 impl From<tungstenite::Error> for KickError {
     fn from(err: tungstenite::Error) -> Self {
         KickError::WebSocketError(err)
     }
 }
 
+impl From<std::io::Error> for KickError {
+    fn from(err: std::io::Error) -> Self {
+        KickError::IoError(err)
+    }
+}
+
 impl From<serde_json::Error> for KickError {
     fn from(err: serde_json::Error) -> Self {
         KickError::MessageParseError(err)
     }
 }
+
+#[cfg(any(feature = "api", feature = "webhook"))]
+impl From<reqwest::Error> for KickError {
+    fn from(err: reqwest::Error) -> Self {
+        KickError::HttpError(Box::new(err))
+    }
+}
@@ -0,0 +1,325 @@
+//! `kick-client`, a command line tool printing Kick chat.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use kick_client::reconnect::ReconnectingClient;
+use kick_client::recording::{Pacing, Recorder, ReplayClient};
+use kick_client::render::terminal::TerminalFormatter;
+use kick_client::{KickChatMessage, KickClient, KickError, MessageData, MessageSource};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Parser)]
+#[command(
+    name = "kick-client",
+    version,
+    about = "Read Kick chat from the command line"
+)]
+struct Cli {
+    /// The Pusher WebSocket URL to connect to.
+    #[arg(long, global = true, default_value = kick_client::DEFAULT_WEBSOCKET_URL)]
+    url: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the chat of one or more channels as it happens.
+    ///
+    /// When watching several channels, each line starts with the channel it was sent in.
+    Watch {
+        /// The slugs of the channels, e.g. `xqc`, or their chatroom IDs.
+        #[arg(required = true)]
+        channels: Vec<String>,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        reconnect: ReconnectArgs,
+    },
+    /// Write the raw frames received from one or more channels to a JSONL file, until the
+    /// connection closes or Ctrl+C is pressed.
+    Record {
+        /// The slugs of the channels, e.g. `xqc`, or their chatroom IDs.
+        #[arg(required = true)]
+        channels: Vec<String>,
+        /// The file the frames are written to.
+        #[arg(short, long)]
+        output: PathBuf,
+        /// Append to the file instead of overwriting it.
+        #[arg(long)]
+        append: bool,
+    },
+    /// Print a recording made by `record`, parsing it as if it were received live.
+    Replay {
+        /// The recording to replay.
+        file: PathBuf,
+        /// Replay in real time, or this many times faster, instead of all at once.
+        #[arg(long, value_name = "FACTOR")]
+        speed: Option<f64>,
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+}
+
+#[derive(clap::Args)]
+struct OutputArgs {
+    /// How messages are printed.
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// Hide the time messages were sent at.
+    #[arg(long)]
+    no_timestamps: bool,
+    /// Only print these kinds of events, e.g. `chat,bans`.
+    #[arg(long, value_name = "KINDS", value_delimiter = ',', value_parser = kind)]
+    only: Vec<&'static str>,
+}
+
+/// The kinds of events `--only` accepts, as named by `MessageData::kind`.
+const KINDS: &[&str] = &[
+    "chat",
+    "delete",
+    "ban",
+    "unban",
+    "chatroom_update",
+    "clear",
+    "poll_update",
+    "poll_delete",
+    "subscription",
+    "gifted_subscriptions",
+    "pin",
+    "pin_delete",
+    "follow",
+    "livestream",
+    "gap",
+    "unknown",
+    "unsupported",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Colored, with badges, unless `NO_COLOR` is set.
+    Text,
+    /// Without colors or badges.
+    Plain,
+    /// One JSON object per line, with the event's `kind`, `channel`, `chatroom_id`, `data`
+    /// and the time it was received at, `received_at`, in milliseconds since the Unix
+    /// epoch.
+    Json,
+}
+
+#[derive(clap::Args)]
+struct ReconnectArgs {
+    /// Exit once the connection is lost instead of reconnecting.
+    #[arg(long)]
+    no_reconnect: bool,
+    /// The seconds waited before the first reconnection attempt, doubled after each failed
+    /// attempt.
+    #[arg(long, value_name = "SECONDS", default_value = "1", value_parser = seconds)]
+    backoff: Duration,
+    /// The most seconds waited between reconnection attempts.
+    #[arg(long, value_name = "SECONDS", default_value = "60", value_parser = seconds)]
+    max_backoff: Duration,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Watch {
+            channels,
+            output,
+            reconnect,
+        } => watch(&cli.url, &channels, &output, &reconnect).await,
+        Command::Record {
+            channels,
+            output,
+            append,
+        } => record(&cli.url, &channels, &output, append).await,
+        Command::Replay {
+            file,
+            speed,
+            output,
+        } => replay(&file, speed, &output).await,
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn watch(
+    url: &str,
+    channels: &[String],
+    output: &OutputArgs,
+    reconnect: &ReconnectArgs,
+) -> Result<(), KickError> {
+    let chatrooms = resolve(channels).await?;
+    let chatroom_ids: Vec<u64> = chatrooms.iter().map(|(id, _)| (*id).into()).collect();
+    let mut printer = Printer::new(output);
+    if chatrooms.len() > 1 {
+        printer = printer.with_prefixes(chatrooms.into_iter().collect());
+    }
+    if reconnect.no_reconnect {
+        let client: KickClient = KickClient::connect(url, chatroom_ids).await?;
+        read_all(client, |message| printer.print(&message)).await
+    } else {
+        let client = ReconnectingClient::new(url, chatroom_ids)
+            .with_backoff(reconnect.backoff, reconnect.max_backoff);
+        read_all(client, |message| printer.print(&message)).await
+    }
+}
+
+async fn record(
+    url: &str,
+    channels: &[String],
+    output: &Path,
+    append: bool,
+) -> Result<(), KickError> {
+    let chatroom_ids = resolve(channels)
+        .await?
+        .into_iter()
+        .map(|(id, _)| id.into())
+        .collect();
+    let recorder = if append {
+        Recorder::append(output)?
+    } else {
+        Recorder::create(output)?
+    };
+    let client: KickClient = KickClient::connect(url, chatroom_ids).await?;
+    read_all(client.with_recorder(recorder), |_| {}).await
+}
+
+async fn replay(file: &Path, speed: Option<f64>, output: &OutputArgs) -> Result<(), KickError> {
+    let pacing = speed.map_or(Pacing::Instant, Pacing::Accelerated);
+    let printer = Printer::new(output);
+    let replay = ReplayClient::open(file)?.with_pacing(pacing);
+    read_all(replay, |message| printer.print(&message)).await
+}
+
+/// Resolves channel slugs and chatroom IDs to chatroom IDs, paired with the channel as
+/// given.
+async fn resolve(channels: &[String]) -> Result<Vec<(u32, String)>, KickError> {
+    let transport = reqwest::Client::new();
+    let mut chatrooms = Vec::new();
+    for channel in channels {
+        let chatroom_id = match channel.parse::<u32>() {
+            Ok(id) => id,
+            Err(_) => kick_client::api::chatroom_id(&transport, channel).await?,
+        };
+        chatrooms.push((chatroom_id, channel.clone()));
+    }
+    Ok(chatrooms)
+}
+
+/// Parses a number of seconds, such as `1.5`.
+fn seconds(value: &str) -> Result<Duration, String> {
+    let seconds: f64 = value
+        .parse()
+        .map_err(|_| format!("`{}` isn't a number", value))?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| e.to_string())
+}
+
+/// Parses a kind of event, also accepting plurals such as `bans`.
+fn kind(value: &str) -> Result<&'static str, String> {
+    KINDS
+        .iter()
+        .find(|kind| **kind == value || value.strip_suffix('s') == Some(**kind))
+        .copied()
+        .ok_or_else(|| format!("expected one of {}", KINDS.join(", ")))
+}
+
+/// Hands every message read from `source` to `each` until it ends or Ctrl+C is pressed.
+async fn read_all<S: MessageSource>(
+    mut source: S,
+    mut each: impl FnMut(KickChatMessage),
+) -> Result<(), KickError> {
+    loop {
+        let message = tokio::select! {
+            message = source.read_message() => message,
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+        };
+        match message {
+            Ok(Some(message)) => each(message),
+            Ok(None) | Err(KickError::StreamEnded) => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Prints messages in the chosen format, skipping Pusher's own events and non-text
+/// frames.
+struct Printer {
+    format: Format,
+    formatter: TerminalFormatter,
+    /// The kinds of events printed, or every kind if empty.
+    only: Vec<&'static str>,
+    /// The channel names lines are prefixed with, by chatroom ID.
+    prefixes: HashMap<u32, String>,
+}
+
+impl Printer {
+    fn new(output: &OutputArgs) -> Self {
+        let formatter = match output.format {
+            Format::Text => TerminalFormatter::from_env(),
+            Format::Plain | Format::Json => TerminalFormatter::new()
+                .with_colors(false)
+                .with_glyphs(false),
+        };
+        Self {
+            format: output.format,
+            formatter: formatter.with_timestamps(!output.no_timestamps),
+            only: output.only.clone(),
+            prefixes: HashMap::new(),
+        }
+    }
+
+    /// Prefixes the lines of each chatroom with the given channel name.
+    fn with_prefixes(mut self, prefixes: HashMap<u32, String>) -> Self {
+        self.prefixes = prefixes;
+        self
+    }
+
+    fn print(&self, message: &KickChatMessage) {
+        if matches!(
+            message.data,
+            MessageData::PusherConnectionEstablished(_)
+                | MessageData::PusherSubscriptionSucceeded(_)
+                | MessageData::PusherPong(_)
+                | MessageData::Unknown(None)
+        ) || !(self.only.is_empty() || self.only.contains(&message.data.kind()))
+        {
+            return;
+        }
+        if self.format == Format::Json {
+            match kick_client::encoding::to_json_value(message) {
+                Ok(value) => println!("{}", json_line(message, value)),
+                Err(e) => eprintln!("error: {}", e),
+            }
+            return;
+        }
+        let line = self.formatter.format(message);
+        match message.chatroom_id().and_then(|id| self.prefixes.get(&id)) {
+            Some(channel) => println!("[{}] {}", channel, line),
+            None => println!("{}", line),
+        }
+    }
+}
+
+/// Returns a message encoded by `encoding::to_json_value` as a single line of JSON, for
+/// `--format json`.
+fn json_line(message: &KickChatMessage, mut value: serde_json::Value) -> String {
+    let received_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis().try_into().unwrap_or(u64::MAX));
+    if let Some(object) = value.as_object_mut() {
+        object.insert("kind".into(), message.data.kind().into());
+        object.insert("chatroom_id".into(), message.chatroom_id().into());
+        object.insert("received_at".into(), received_at.into());
+    }
+    value.to_string()
+}
@@ -0,0 +1,230 @@
+//! Joining and leaving chatrooms at runtime, with a stream of messages per chatroom.
+//!
+//! `ChatManager` runs a `ReconnectingClient` in a task, subscribing to a chatroom when it
+//! is first joined and unsubscribing once it is left, so a dashboard can open and close a
+//! view per channel without reconnecting. Every `join` returns its own receiver, fed the
+//! messages of that chatroom until it is left or the receiver is dropped.
+//!
+//! `split_by_channel` does the same for a fixed set of chatrooms, handing every message of
+//! an existing client to the receiver of its chatroom.
+
+use crate::reconnect::ReconnectingClient;
+use crate::trace::Instrument;
+use crate::transport::Transport;
+use crate::{KickChatMessage, KickClient, MessageData, MessageSource};
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// How many messages are buffered per receiver before the manager waits for them to be
+/// read.
+const MESSAGE_BUFFER: usize = 256;
+
+enum Command {
+    Join(u64, mpsc::Sender<KickChatMessage>),
+    Leave(u64),
+}
+
+/// Chatrooms joined and left at runtime on one reconnecting connection.
+///
+/// The connection is closed when the manager is dropped. As messages are handed to each
+/// receiver in turn, a receiver which isn't read eventually holds up the others.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() {
+/// use kick_client::manager::ChatManager;
+/// use kick_client::reconnect::ReconnectingClient;
+/// use kick_client::DEFAULT_WEBSOCKET_URL;
+///
+/// let manager = ChatManager::spawn(ReconnectingClient::new(DEFAULT_WEBSOCKET_URL, vec![]));
+/// let mut xqc = manager.join(668);
+/// while let Some(message) = xqc.recv().await {
+///     println!("{}", message);
+/// }
+/// manager.leave(668);
+/// # }
+/// ```
+pub struct ChatManager {
+    commands: mpsc::UnboundedSender<Command>,
+    /// The task running the connection, stopped when the manager is dropped.
+    task: JoinHandle<()>,
+}
+
+impl ChatManager {
+    /// Starts running `client` in a task. Chatrooms `client` was created with stay
+    /// subscribed, but their messages are only received once they are joined.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called outside of a Tokio runtime.
+    pub fn spawn(client: ReconnectingClient) -> Self {
+        let (commands, received) = mpsc::unbounded_channel();
+        let task = crate::trace::spawn("kick_client::manager", run(client, received));
+        Self { commands, task }
+    }
+
+    /// Joins a chatroom, subscribing to it unless it already is, and returns a receiver
+    /// for its messages. A `MessageData::PossibleGap` marker is received after a long
+    /// disconnection.
+    pub fn join(&self, chatroom_id: u64) -> mpsc::Receiver<KickChatMessage> {
+        let (sender, receiver) = mpsc::channel(MESSAGE_BUFFER);
+        // The task only stops when the manager is dropped.
+        let _ = self.commands.send(Command::Join(chatroom_id, sender));
+        receiver
+    }
+
+    /// Leaves a chatroom, closing its receivers and unsubscribing from it. Chatrooms are
+    /// also left once all their receivers are dropped.
+    pub fn leave(&self, chatroom_id: u64) {
+        let _ = self.commands.send(Command::Leave(chatroom_id));
+    }
+}
+
+impl Drop for ChatManager {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn run(mut client: ReconnectingClient, mut commands: mpsc::UnboundedReceiver<Command>) {
+    let mut joined: HashMap<u64, Vec<mpsc::Sender<KickChatMessage>>> = HashMap::new();
+    loop {
+        tokio::select! {
+            command = commands.recv() => match command {
+                Some(Command::Join(chatroom_id, sender)) => {
+                    client.subscribe(chatroom_id).await;
+                    joined.entry(chatroom_id).or_default().push(sender);
+                }
+                Some(Command::Leave(chatroom_id)) => {
+                    joined.remove(&chatroom_id);
+                    client.unsubscribe(chatroom_id).await;
+                }
+                None => return,
+            },
+            message = client.read_message() => {
+                let Ok(Some(message)) = message else {
+                    return;
+                };
+                for chatroom_id in recipients(&message, joined.keys()) {
+                    let Some(senders) = joined.get_mut(&chatroom_id) else {
+                        continue;
+                    };
+                    let mut open = Vec::with_capacity(senders.len());
+                    for sender in senders.drain(..) {
+                        let send = sender.send(message.clone());
+                        let send = send.instrument(span!("dispatch", chatroom_id));
+                        if send.await.is_ok() {
+                            open.push(sender);
+                        }
+                    }
+                    if open.is_empty() {
+                        joined.remove(&chatroom_id);
+                        client.unsubscribe(chatroom_id).await;
+                    } else {
+                        *senders = open;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the chatrooms a message is handed to: its own, or every chatroom for a
+/// `PossibleGap` marker.
+fn recipients<'a>(
+    message: &KickChatMessage,
+    chatroom_ids: impl Iterator<Item = &'a u64>,
+) -> Vec<u64> {
+    match (&message.data, message.chatroom_id()) {
+        (MessageData::PossibleGap(_), _) => chatroom_ids.copied().collect(),
+        (_, Some(chatroom_id)) => vec![chatroom_id.into()],
+        (_, None) => Vec::new(),
+    }
+}
+
+/// Reads every message of `source` in a task, returning a receiver per chatroom of
+/// `chatroom_ids` fed the messages of that chatroom. A `MessageData::PossibleGap` marker
+/// is received by every receiver, and messages of other chatrooms are dropped.
+///
+/// The task stops once `source` ends or every receiver is dropped.
+///
+/// # Panics
+///
+/// This function panics if called outside of a Tokio runtime.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() {
+/// use kick_client::manager;
+/// use kick_client::reconnect::ReconnectingClient;
+/// use kick_client::DEFAULT_WEBSOCKET_URL;
+///
+/// let client = ReconnectingClient::new(DEFAULT_WEBSOCKET_URL, vec![668, 1234]);
+/// let mut channels = manager::split_by_channel(client, [668, 1234]);
+/// let mut xqc = channels.remove(&668).unwrap();
+/// while let Some(message) = xqc.recv().await {
+///     println!("{}", message);
+/// }
+/// # }
+/// ```
+pub fn split_by_channel<S>(
+    mut source: S,
+    chatroom_ids: impl IntoIterator<Item = u64>,
+) -> HashMap<u64, mpsc::Receiver<KickChatMessage>>
+where
+    S: MessageSource + Send + 'static,
+{
+    let mut senders = HashMap::new();
+    let mut receivers = HashMap::new();
+    for chatroom_id in chatroom_ids {
+        let (sender, receiver) = mpsc::channel(MESSAGE_BUFFER);
+        senders.insert(chatroom_id, sender);
+        receivers.insert(chatroom_id, receiver);
+    }
+    crate::trace::spawn("kick_client::manager::split_by_channel", async move {
+        while let Ok(Some(message)) = source.read_message().await {
+            for chatroom_id in recipients(&message, senders.keys()) {
+                if let Some(sender) = senders.get(&chatroom_id) {
+                    let send = sender.send(message.clone());
+                    let send = send.instrument(span!("dispatch", chatroom_id));
+                    if send.await.is_err() {
+                        senders.remove(&chatroom_id);
+                    }
+                }
+            }
+            if senders.is_empty() {
+                return;
+            }
+        }
+    });
+    receivers
+}
+
+impl<T: Transport + 'static> KickClient<T> {
+    /// Returns a receiver per subscribed chatroom, fed its messages by a task reading the
+    /// client. See `manager::split_by_channel`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called outside of a Tokio runtime.
+    pub fn split_by_channel(self) -> HashMap<u64, mpsc::Receiver<KickChatMessage>> {
+        let chatroom_ids = self.channel_ids().to_vec();
+        split_by_channel(self, chatroom_ids)
+    }
+}
+
+impl ReconnectingClient {
+    /// Returns a receiver per subscribed chatroom, fed its messages by a task reading the
+    /// client. See `manager::split_by_channel`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called outside of a Tokio runtime.
+    pub fn split_by_channel(self) -> HashMap<u64, mpsc::Receiver<KickChatMessage>> {
+        let chatroom_ids = self.channel_ids().to_vec();
+        split_by_channel(self, chatroom_ids)
+    }
+}
@@ -0,0 +1,203 @@
+#[cfg(feature = "statsd")]
+use crate::statsd::StatsdEmitter;
+use crate::{KickChatMessage, MessageData};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The window latency percentiles are computed over by default.
+const DEFAULT_LATENCY_WINDOW: Duration = Duration::from_secs(60);
+/// The most latency samples kept within the window.
+const MAX_LATENCY_SAMPLES: usize = 10_000;
+
+/// Percentiles of the latencies recorded within the latency window.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct LatencyPercentiles {
+    /// The number of samples the percentiles were computed from.
+    pub samples: usize,
+    pub p50: Duration,
+    pub p90: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+/// A point-in-time view of the counters and gauges of `Metrics`.
+#[derive(Serialize, Debug, Clone)]
+pub struct MetricsSnapshot {
+    /// The number of messages received.
+    pub messages_received: u64,
+    /// The number of received messages that couldn't be parsed.
+    pub unsupported_messages: u64,
+    /// The number of messages or frames dropped because the buffer holding them was full.
+    pub dropped: u64,
+    /// The latency of chat messages, measured from their `created_at` to their receipt,
+    /// or `None` if none were recorded within the window.
+    pub latency: Option<LatencyPercentiles>,
+}
+
+/// Counters and latency measurements about the messages a client receives.
+///
+/// Chat message latency is recorded automatically from `created_at` when the `chrono`
+/// feature is enabled. Cloning a `Metrics` is cheap and all clones share the same state.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::metrics::Metrics;
+///
+/// let metrics = Metrics::new();
+/// while let Some(message) = client.read_message().await? {
+///     metrics.observe(&message);
+/// }
+/// println!("{:?}", metrics.snapshot().latency);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct Metrics {
+    state: Arc<Mutex<MetricsState>>,
+}
+
+#[derive(Default)]
+struct MetricsState {
+    messages_received: u64,
+    unsupported_messages: u64,
+    dropped: u64,
+    latency: LatencyWindow,
+    /// The emitter sending every update to a statsd agent, if any.
+    #[cfg(feature = "statsd")]
+    statsd: Option<StatsdEmitter>,
+}
+
+/// The latencies recorded within a sliding window.
+struct LatencyWindow {
+    window: Duration,
+    /// When each sample was recorded, with its latency, oldest first.
+    samples: VecDeque<(Instant, Duration)>,
+}
+
+impl Default for LatencyWindow {
+    fn default() -> Self {
+        Self {
+            window: DEFAULT_LATENCY_WINDOW,
+            samples: VecDeque::new(),
+        }
+    }
+}
+
+impl LatencyWindow {
+    fn expire(&mut self, now: Instant) {
+        while self.samples.len() > MAX_LATENCY_SAMPLES
+            || self
+                .samples
+                .front()
+                .is_some_and(|(recorded_at, _)| now.duration_since(*recorded_at) > self.window)
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    fn percentiles(&mut self) -> Option<LatencyPercentiles> {
+        self.expire(Instant::now());
+        let mut latencies: Vec<Duration> =
+            self.samples.iter().map(|(_, latency)| *latency).collect();
+        if latencies.is_empty() {
+            return None;
+        }
+        latencies.sort_unstable();
+        let percentile = |p: f64| {
+            let rank = (p * (latencies.len() - 1) as f64).round() as usize;
+            latencies[rank]
+        };
+        Some(LatencyPercentiles {
+            samples: latencies.len(),
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+            max: latencies[latencies.len() - 1],
+        })
+    }
+}
+
+impl Metrics {
+    /// Creates a new instance of `Metrics` computing latency percentiles over a minute.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the window latency percentiles are computed over.
+    pub fn with_latency_window(self, window: Duration) -> Self {
+        self.lock().latency.window = window;
+        self
+    }
+
+    /// Sends every update of the metrics to a statsd agent through `emitter`.
+    #[cfg(feature = "statsd")]
+    pub fn with_statsd(self, emitter: StatsdEmitter) -> Self {
+        self.lock().statsd = Some(emitter);
+        self
+    }
+
+    /// Updates the metrics from a received message.
+    pub fn observe(&self, message: &KickChatMessage) {
+        let mut state = self.lock();
+        state.messages_received += 1;
+        #[cfg(feature = "statsd")]
+        if let Some(statsd) = &state.statsd {
+            statsd.count("messages", 1, &[("kind", message.data.kind())]);
+            if let MessageData::Unsupported(..) = &message.data {
+                statsd.count("unsupported_messages", 1, &[]);
+            }
+        }
+        match &message.data {
+            MessageData::Unsupported(..) => state.unsupported_messages += 1,
+            #[cfg(feature = "chrono")]
+            MessageData::ChatMessage(data) => {
+                if let Some(latency) = data.latency() {
+                    drop(state);
+                    self.record_latency(latency);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Records that `count` messages or frames were dropped because a buffer was full.
+    pub fn record_dropped(&self, count: u64) {
+        let mut state = self.lock();
+        state.dropped += count;
+        #[cfg(feature = "statsd")]
+        if let Some(statsd) = &state.statsd {
+            statsd.count("dropped", count, &[]);
+        }
+    }
+
+    /// Records the latency of a received message.
+    pub fn record_latency(&self, latency: Duration) {
+        let now = Instant::now();
+        let mut state = self.lock();
+        #[cfg(feature = "statsd")]
+        if let Some(statsd) = &state.statsd {
+            statsd.timing("latency", latency, &[]);
+        }
+        state.latency.samples.push_back((now, latency));
+        state.latency.expire(now);
+    }
+
+    /// Returns the current values of the metrics.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let mut state = self.lock();
+        MetricsSnapshot {
+            messages_received: state.messages_received,
+            unsupported_messages: state.unsupported_messages,
+            dropped: state.dropped,
+            latency: state.latency.percentiles(),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MetricsState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{parse_frame, ChatSender, KickChatMessage, KickError, MessageSource};
+use futures_util::future::BoxFuture;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// A chat message sent through a `MockKickClient`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentMessage {
+    /// The ID of the chatroom the message was sent to.
+    pub chatroom_id: u32,
+    /// The content of the message.
+    pub content: String,
+}
+
+/// A test double standing in for both a `KickClient` and a `ChatSender`, so handlers can be
+/// tested without any network.
+///
+/// Pushed messages are returned by `read_message` in order; once none are left, it returns
+/// `None`, ending loops such as `Commands::run`. Messages sent through it are recorded
+/// instead. Cloning a `MockKickClient` is cheap and all clones share the same state.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(message: kick_client::KickChatMessage) -> Result<(), kick_client::KickError> {
+/// use kick_client::commands::Commands;
+/// use kick_client::mock::MockKickClient;
+///
+/// let mut mock = MockKickClient::new();
+/// let mut commands = Commands::new("!").with_sender(mock.clone());
+/// commands.command("ping", |ctx| async move { ctx.reply("pong").await });
+///
+/// mock.push(message);
+/// commands.run(&mut mock).await?;
+/// assert_eq!(mock.sent()[0].content, "pong");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct MockKickClient {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    incoming: VecDeque<Result<KickChatMessage, KickError>>,
+    sent: Vec<SentMessage>,
+    /// The error every sent message fails with, if set.
+    send_error: Option<String>,
+}
+
+impl MockKickClient {
+    /// Creates a new instance of `MockKickClient` with no messages to read.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a message to be returned by `read_message`.
+    pub fn push(&self, message: KickChatMessage) -> &Self {
+        self.lock().incoming.push_back(Ok(message));
+        self
+    }
+
+    /// Queues a raw Pusher frame, parsed exactly as `KickClient` parses it.
+    pub fn push_frame(&self, frame: &str) -> &Self {
+        self.push(parse_frame(frame.as_bytes()))
+    }
+
+    /// Queues an error to be returned by `read_message`.
+    pub fn push_error(&self, error: KickError) -> &Self {
+        self.lock().incoming.push_back(Err(error));
+        self
+    }
+
+    /// Makes every message sent from now on fail with a `KickError::ApiError` carrying
+    /// `body`, or succeed again if `None`.
+    pub fn fail_sends(&self, body: Option<&str>) {
+        self.lock().send_error = body.map(str::to_string);
+    }
+
+    /// Returns the number of queued messages not read yet.
+    pub fn pending(&self) -> usize {
+        self.lock().incoming.len()
+    }
+
+    /// Returns the messages sent so far, oldest first.
+    pub fn sent(&self) -> Vec<SentMessage> {
+        self.lock().sent.clone()
+    }
+
+    /// Returns and forgets the messages sent so far, oldest first.
+    pub fn take_sent(&self) -> Vec<SentMessage> {
+        std::mem::take(&mut self.lock().sent)
+    }
+
+    /// Returns the next queued message, or `None` once none are left.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the next queued error, if it comes before any message.
+    pub async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        self.lock().incoming.pop_front().transpose()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl MessageSource for MockKickClient {
+    async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        MockKickClient::read_message(self).await
+    }
+}
+
+impl ChatSender for MockKickClient {
+    fn send_message<'a>(
+        &'a self,
+        chatroom_id: u32,
+        content: &'a str,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        Box::pin(async move {
+            let mut state = self.lock();
+            if let Some(body) = &state.send_error {
+                return Err(KickError::ApiError {
+                    status: 500,
+                    body: body.clone(),
+                });
+            }
+            state.sent.push(SentMessage {
+                chatroom_id,
+                content: content.to_string(),
+            });
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,325 @@
+use crate::{KickChatMessage, KickError};
+use futures_util::future::{select, Either};
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, Notify};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+/// A local WebSocket server speaking enough of the Pusher protocol to exercise the full
+/// client in integration tests.
+///
+/// Every connection is greeted with `pusher:connection_established`, every subscription is
+/// confirmed with `pusher_internal:subscription_succeeded` and pings are answered with
+/// pongs. Events are only sent when the test asks for them. The server shuts down when
+/// dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::mock_server::MockPusherServer;
+/// use kick_client::KickClient;
+///
+/// let server = MockPusherServer::start().await?;
+/// let mut client = KickClient::new(&server.url(), vec![1234]).await.unwrap();
+/// server.wait_for_subscription("chatrooms.1234.v2").await;
+/// server.broadcast("chatrooms.1234.v2", "App\\Events\\ChatroomClearEvent", &serde_json::json!({ "id": "1" }));
+/// println!("{:?}", client.read_message().await?);
+/// # Ok(())
+/// # }
+/// ```
+pub struct MockPusherServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<ServerState>>,
+    /// Notified whenever a client connects, subscribes or disconnects.
+    changed: Arc<Notify>,
+    accept: JoinHandle<()>,
+}
+
+#[derive(Default)]
+struct ServerState {
+    clients: Vec<Connection>,
+    /// The number of connections accepted so far.
+    accepted: usize,
+}
+
+struct Connection {
+    id: usize,
+    subscriptions: HashSet<String>,
+    outgoing: mpsc::UnboundedSender<Outgoing>,
+}
+
+enum Outgoing {
+    Frame(String),
+    /// Closes the connection with a closing handshake.
+    Close,
+    /// Drops the connection without a closing handshake.
+    Abort,
+}
+
+impl MockPusherServer {
+    /// Starts a server on a free local port.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the listener cannot be bound.
+    pub async fn start() -> Result<Self, KickError> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(ServerState::default()));
+        let changed = Arc::new(Notify::new());
+        let accept = crate::trace::spawn("kick_client::mock_server::accept", accept_loop(listener, state.clone(), changed.clone()));
+        Ok(Self {
+            addr,
+            state,
+            changed,
+            accept,
+        })
+    }
+
+    /// Returns the address the server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the WebSocket URL to connect to the server with.
+    pub fn url(&self) -> String {
+        format!(
+            "ws://{}/app/mock?protocol=7&client=js&version=8.4.0-rc2&flash=false",
+            self.addr
+        )
+    }
+
+    /// Returns the number of connections accepted so far, including closed ones.
+    pub fn accepted(&self) -> usize {
+        self.lock().accepted
+    }
+
+    /// Returns the number of open connections.
+    pub fn connected(&self) -> usize {
+        self.lock().clients.len()
+    }
+
+    /// Returns `true` if an open connection is subscribed to a channel.
+    pub fn is_subscribed(&self, channel: &str) -> bool {
+        self.lock()
+            .clients
+            .iter()
+            .any(|client| client.subscriptions.contains(channel))
+    }
+
+    /// Waits until an open connection is subscribed to a channel, so events broadcast to
+    /// it are received.
+    pub async fn wait_for_subscription(&self, channel: &str) {
+        loop {
+            let changed = self.changed.notified();
+            if self.is_subscribed(channel) {
+                return;
+            }
+            changed.await;
+        }
+    }
+
+    /// Waits until `count` connections were accepted, e.g. to observe a reconnection.
+    pub async fn wait_for_accepted(&self, count: usize) {
+        loop {
+            let changed = self.changed.notified();
+            if self.accepted() >= count {
+                return;
+            }
+            changed.await;
+        }
+    }
+
+    /// Sends an event to every connection subscribed to `channel`, with `data` encoded as
+    /// a JSON string the way Pusher does. Returns the number of connections it was sent to.
+    pub fn broadcast(&self, channel: &str, event: &str, data: &impl Serialize) -> usize {
+        let frame = serde_json::json!({
+            "event": event,
+            "data": serde_json::to_string(data).unwrap_or_default(),
+            "channel": channel,
+        });
+        let frame = frame.to_string();
+        self.lock()
+            .clients
+            .iter()
+            .filter(|client| client.subscriptions.contains(channel))
+            .filter(|client| client.outgoing.send(Outgoing::Frame(frame.clone())).is_ok())
+            .count()
+    }
+
+    /// Sends a message to every connection subscribed to its channel, or to every open
+    /// connection if it has none. Returns the number of connections it was sent to.
+    pub fn send(&self, message: &KickChatMessage) -> usize {
+        let frame = serde_json::to_string(message).unwrap_or_default();
+        self.lock()
+            .clients
+            .iter()
+            .filter(|client| {
+                message
+                    .channel
+                    .as_ref()
+                    .is_none_or(|channel| client.subscriptions.contains(channel))
+            })
+            .filter(|client| client.outgoing.send(Outgoing::Frame(frame.clone())).is_ok())
+            .count()
+    }
+
+    /// Sends a raw frame to every open connection, whatever it is subscribed to.
+    pub fn send_raw(&self, frame: &str) {
+        for client in &self.lock().clients {
+            let _ = client.outgoing.send(Outgoing::Frame(frame.to_string()));
+        }
+    }
+
+    /// Closes every open connection, e.g. to exercise reconnections.
+    pub fn disconnect_all(&self) {
+        self.close_all(|| Outgoing::Close);
+    }
+
+    /// Drops every open connection without a closing handshake, as when the network fails.
+    pub fn abort_all(&self) {
+        self.close_all(|| Outgoing::Abort);
+    }
+
+    fn close_all(&self, message: impl Fn() -> Outgoing) {
+        for client in self.lock().clients.drain(..) {
+            let _ = client.outgoing.send(message());
+        }
+        self.changed.notify_waiters();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ServerState> {
+        lock(&self.state)
+    }
+}
+
+impl Drop for MockPusherServer {
+    fn drop(&mut self) {
+        self.accept.abort();
+        self.disconnect_all();
+    }
+}
+
+fn lock(state: &Mutex<ServerState>) -> std::sync::MutexGuard<'_, ServerState> {
+    state.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+async fn accept_loop(listener: TcpListener, state: Arc<Mutex<ServerState>>, changed: Arc<Notify>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        crate::trace::spawn("kick_client::mock_server::connection", serve(stream, state.clone(), changed.clone()));
+    }
+}
+
+/// Serves a single connection until either side closes it.
+async fn serve(stream: TcpStream, state: Arc<Mutex<ServerState>>, changed: Arc<Notify>) {
+    let Ok(websocket) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+    let (mut write, mut read) = websocket.split();
+    let (outgoing, mut queue) = mpsc::unbounded_channel();
+
+    let id = {
+        let mut state = lock(&state);
+        state.accepted += 1;
+        let id = state.accepted;
+        state.clients.push(Connection {
+            id,
+            subscriptions: HashSet::new(),
+            outgoing: outgoing.clone(),
+        });
+        id
+    };
+    changed.notify_waiters();
+    let _ = outgoing.send(Outgoing::Frame(pusher_frame(
+        "pusher:connection_established",
+        serde_json::json!({ "socket_id": format!("{}.{}", id, id), "activity_timeout": 120 }),
+        None,
+    )));
+
+    // Resolves to `true` if the connection is to be dropped without a closing handshake.
+    let writer = async move {
+        while let Some(message) = queue.recv().await {
+            match message {
+                Outgoing::Frame(frame) => {
+                    if write.send(Message::Text(frame.into())).await.is_err() {
+                        return false;
+                    }
+                }
+                Outgoing::Close => {
+                    let _ = write.send(Message::Close(None)).await;
+                    return false;
+                }
+                Outgoing::Abort => return true,
+            }
+        }
+        false
+    };
+    let reader = async {
+        while let Some(Ok(message)) = read.next().await {
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let Ok(frame) = serde_json::from_str::<serde_json::Value>(&text) else {
+                continue;
+            };
+            match frame["event"].as_str() {
+                Some("pusher:subscribe") => {
+                    let Some(channel) = frame["data"]["channel"].as_str() else {
+                        continue;
+                    };
+                    if let Some(client) = lock(&state).clients.iter_mut().find(|c| c.id == id) {
+                        client.subscriptions.insert(channel.to_string());
+                    }
+                    let _ = outgoing.send(Outgoing::Frame(pusher_frame(
+                        "pusher_internal:subscription_succeeded",
+                        serde_json::json!({}),
+                        Some(channel),
+                    )));
+                    changed.notify_waiters();
+                }
+                Some("pusher:unsubscribe") => {
+                    let Some(channel) = frame["data"]["channel"].as_str() else {
+                        continue;
+                    };
+                    if let Some(client) = lock(&state).clients.iter_mut().find(|c| c.id == id) {
+                        client.subscriptions.remove(channel);
+                    }
+                    changed.notify_waiters();
+                }
+                Some("pusher:ping") => {
+                    let _ = outgoing.send(Outgoing::Frame(pusher_frame(
+                        "pusher:pong",
+                        serde_json::json!({}),
+                        None,
+                    )));
+                }
+                _ => {}
+            }
+        }
+    };
+
+    match select(pin!(writer), pin!(reader)).await {
+        Either::Left((true, _)) | Either::Right(((), _)) => {}
+        // Keep reading until the client answers the closing handshake.
+        Either::Left((false, reader)) => reader.await,
+    }
+
+    lock(&state).clients.retain(|client| client.id != id);
+    changed.notify_waiters();
+}
+
+/// Builds a Pusher frame, with `data` encoded as a JSON string.
+fn pusher_frame(event: &str, data: serde_json::Value, channel: Option<&str>) -> String {
+    let mut frame = serde_json::json!({ "event": event, "data": data.to_string() });
+    if let Some(channel) = channel {
+        frame["channel"] = channel.into();
+    }
+    frame.to_string()
+}
@@ -0,0 +1,616 @@
+//! Support for Kick's official public API, authenticated with OAuth 2.1 app tokens.
+//!
+//! `OAuthClient` implements the client-credentials and authorization-code (with PKCE)
+//! flows, and `OfficialApi` wraps the official endpoints. Both can be used next to the
+//! unofficial `KickApi`, and all of them authenticate through a shared `TokenManager`.
+
+use crate::api::{HttpRequest, HttpTransport, RestClient};
+use crate::auth::{Credentials, TokenManager};
+use crate::KickError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Base URL of Kick's OAuth server.
+const DEFAULT_AUTH_URL: &str = "https://id.kick.com";
+/// Base URL of Kick's official public API.
+const DEFAULT_API_URL: &str = "https://api.kick.com/public/v1";
+
+/// A registered Kick app performing the OAuth flows.
+pub struct OAuthClient<T = reqwest::Client> {
+    /// The transport requests are sent through.
+    http: Arc<T>,
+    /// The base URL of the OAuth server.
+    auth_url: String,
+    /// The ID of the app.
+    client_id: String,
+    /// The secret of the app.
+    client_secret: String,
+    /// Where users are sent back to after authorizing the app.
+    redirect_uri: Option<String>,
+}
+
+/// A pending authorization-code flow.
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    /// The URL to send the user to.
+    pub url: String,
+    /// The random value Kick echoes back in the redirect; compare it before exchanging
+    /// the code.
+    pub state: String,
+    /// The PKCE verifier to pass to `OAuthClient::exchange_code`.
+    pub code_verifier: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+impl From<TokenResponse> for Credentials {
+    fn from(token: TokenResponse) -> Self {
+        let mut credentials = Credentials::new(token.access_token);
+        credentials.refresh_token = token.refresh_token;
+        if let Some(expires_in) = token.expires_in {
+            credentials = credentials.expires_in(Duration::from_secs(expires_in));
+        }
+        credentials
+    }
+}
+
+impl<T> Clone for OAuthClient<T> {
+    fn clone(&self) -> Self {
+        Self {
+            http: self.http.clone(),
+            auth_url: self.auth_url.clone(),
+            client_id: self.client_id.clone(),
+            client_secret: self.client_secret.clone(),
+            redirect_uri: self.redirect_uri.clone(),
+        }
+    }
+}
+
+impl OAuthClient {
+    /// Creates a new instance of `OAuthClient` for the app with the given ID and secret.
+    pub fn new(client_id: impl Into<String>, client_secret: impl Into<String>) -> Self {
+        Self {
+            http: Arc::new(reqwest::Client::new()),
+            auth_url: DEFAULT_AUTH_URL.to_string(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            redirect_uri: None,
+        }
+    }
+}
+
+impl<T: HttpTransport> OAuthClient<T> {
+    /// Sends requests through the given transport instead of the default `reqwest::Client`.
+    pub fn with_transport<U: HttpTransport>(self, transport: U) -> OAuthClient<U> {
+        OAuthClient {
+            http: Arc::new(transport),
+            auth_url: self.auth_url,
+            client_id: self.client_id,
+            client_secret: self.client_secret,
+            redirect_uri: self.redirect_uri,
+        }
+    }
+
+    /// Sets the redirect URI registered for the app, required by the authorization-code flow.
+    pub fn with_redirect_uri(mut self, redirect_uri: impl Into<String>) -> Self {
+        self.redirect_uri = Some(redirect_uri.into());
+        self
+    }
+
+    /// Overrides the base URL of the OAuth server.
+    pub fn with_auth_url(mut self, auth_url: impl Into<String>) -> Self {
+        self.auth_url = auth_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Obtains an app access token through the client-credentials flow.
+    ///
+    /// App tokens can read public data but cannot act on behalf of a user.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn client_credentials(&self) -> Result<Credentials, KickError> {
+        self.request_token(&[
+            ("grant_type", "client_credentials"),
+            ("client_id", &self.client_id),
+            ("client_secret", &self.client_secret),
+        ])
+        .await
+    }
+
+    /// Starts the authorization-code flow, generating the state and PKCE verifier.
+    ///
+    /// # Arguments
+    ///
+    /// * `scopes` - The scopes to request, e.g. `["user:read", "chat:write"]`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run(code: &str) -> Result<(), kick_client::KickError> {
+    /// use kick_client::official::OAuthClient;
+    ///
+    /// let oauth = OAuthClient::new("id", "secret").with_redirect_uri("http://localhost:3000/callback");
+    /// let request = oauth.authorization_url(&["user:read", "chat:write"]);
+    /// println!("Open {}", request.url);
+    /// // ...receive `code` on the redirect URI...
+    /// let credentials = oauth.exchange_code(code, &request.code_verifier).await?;
+    /// let tokens = oauth.token_manager(credentials);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn authorization_url(&self, scopes: &[&str]) -> AuthorizationRequest {
+        let state = random_string(32);
+        let code_verifier = random_string(64);
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+        let scope = scopes.join(" ");
+        let mut params = vec![
+            ("response_type", "code"),
+            ("client_id", self.client_id.as_str()),
+            ("scope", scope.as_str()),
+            ("state", state.as_str()),
+            ("code_challenge", code_challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ];
+        if let Some(redirect_uri) = &self.redirect_uri {
+            params.push(("redirect_uri", redirect_uri));
+        }
+        let url =
+            reqwest::Url::parse_with_params(&format!("{}/oauth/authorize", self.auth_url), params)
+                .map(String::from)
+                .unwrap_or_default();
+
+        AuthorizationRequest {
+            url,
+            state,
+            code_verifier,
+        }
+    }
+
+    /// Exchanges the code received on the redirect URI for user credentials.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if no redirect URI is configured, the request
+    /// fails or Kick rejects it.
+    pub async fn exchange_code(
+        &self,
+        code: &str,
+        code_verifier: &str,
+    ) -> Result<Credentials, KickError> {
+        let redirect_uri = self
+            .redirect_uri
+            .as_deref()
+            .ok_or_else(|| KickError::AuthError("no redirect URI configured".to_string()))?;
+        self.request_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("client_id", &self.client_id),
+            ("client_secret", &self.client_secret),
+            ("redirect_uri", redirect_uri),
+            ("code_verifier", code_verifier),
+        ])
+        .await
+    }
+
+    /// Exchanges a refresh token for new user credentials.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<Credentials, KickError> {
+        self.request_token(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", &self.client_id),
+            ("client_secret", &self.client_secret),
+        ])
+        .await
+    }
+
+    /// Creates a `TokenManager` holding the given credentials and refreshing them
+    /// through this app.
+    pub fn token_manager(&self, credentials: Credentials) -> TokenManager {
+        let oauth = self.clone();
+        TokenManager::new(credentials).with_refresher(move |refresh_token: String| {
+            let oauth = oauth.clone();
+            async move { oauth.refresh(&refresh_token).await }
+        })
+    }
+
+    async fn request_token(&self, form: &[(&str, &str)]) -> Result<Credentials, KickError> {
+        let body =
+            serde_urlencoded::to_string(form).map_err(|e| KickError::HttpError(Box::new(e)))?;
+        let response = self
+            .http
+            .send(HttpRequest {
+                method: Method::POST,
+                url: format!("{}/oauth/token", self.auth_url),
+                headers: vec![(
+                    "Content-Type".to_string(),
+                    "application/x-www-form-urlencoded".to_string(),
+                )],
+                body: Some(body.into_bytes()),
+            })
+            .await?;
+
+        if !response.is_success() {
+            return Err(KickError::ApiError {
+                status: response.status,
+                body: String::from_utf8_lossy(&response.body).into_owned(),
+            });
+        }
+        let token: TokenResponse = response.json()?;
+        Ok(token.into())
+    }
+}
+
+/// An authenticated client for Kick's official public API.
+pub struct OfficialApi<T = reqwest::Client> {
+    /// The client requests are sent through.
+    rest: RestClient<T>,
+    /// The credentials requests are authenticated with.
+    tokens: TokenManager,
+}
+
+impl<T> Clone for OfficialApi<T> {
+    fn clone(&self) -> Self {
+        Self {
+            rest: self.rest.clone(),
+            tokens: self.tokens.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct OfficialResponse<T> {
+    data: T,
+}
+
+/// A user as returned by the official API.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OfficialUser {
+    pub user_id: u64,
+    pub name: String,
+    pub email: Option<String>,
+    pub profile_picture: Option<String>,
+}
+
+/// A channel as returned by the official API.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OfficialChannel {
+    pub broadcaster_user_id: u64,
+    pub slug: String,
+    pub channel_description: Option<String>,
+    pub banner_picture: Option<String>,
+    pub stream_title: Option<String>,
+    pub category: Option<OfficialCategory>,
+    pub stream: Option<OfficialStream>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OfficialCategory {
+    pub id: u64,
+    pub name: String,
+    pub thumbnail: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OfficialStream {
+    pub is_live: bool,
+    pub is_mature: Option<bool>,
+    pub language: Option<String>,
+    pub start_time: Option<String>,
+    pub viewer_count: Option<u64>,
+}
+
+/// The result of sending a chat message through the official API.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SentChatMessage {
+    pub is_sent: bool,
+    pub message_id: String,
+}
+
+/// An event type and version that can be delivered to the app's webhook.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventType {
+    /// The name of the event, e.g. `chat.message.sent`.
+    pub name: String,
+    /// The version of the event's payload.
+    pub version: u32,
+}
+
+impl EventType {
+    /// Creates a new instance of `EventType`.
+    pub fn new(name: impl Into<String>, version: u32) -> Self {
+        Self {
+            name: name.into(),
+            version,
+        }
+    }
+}
+
+/// An existing subscription delivering an event type to the app's webhook.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EventSubscription {
+    pub id: String,
+    pub app_id: Option<String>,
+    pub broadcaster_user_id: u64,
+    pub event: String,
+    pub version: u32,
+    pub method: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+/// The outcome of subscribing to a single event type.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatedEventSubscription {
+    pub name: String,
+    pub version: u32,
+    pub subscription_id: Option<String>,
+    pub error: Option<String>,
+}
+
+/// The changes made by `OfficialApi::reconcile_event_subscriptions`.
+#[derive(Debug, Default)]
+pub struct ReconciledEventSubscriptions {
+    /// The subscriptions that were missing and have been requested.
+    pub created: Vec<CreatedEventSubscription>,
+    /// The IDs of the subscriptions that were not desired and have been deleted.
+    pub deleted: Vec<String>,
+}
+
+impl OfficialApi {
+    /// Creates a new instance of `OfficialApi` authenticated through the given token manager.
+    pub fn new(tokens: TokenManager) -> Self {
+        Self {
+            rest: RestClient::new(DEFAULT_API_URL, tokens.clone().into()),
+            tokens,
+        }
+    }
+}
+
+impl<T: HttpTransport> OfficialApi<T> {
+    /// Sends requests through the given transport instead of the default `reqwest::Client`.
+    pub fn with_transport<U: HttpTransport>(self, transport: U) -> OfficialApi<U> {
+        OfficialApi {
+            rest: self.rest.with_transport(transport),
+            tokens: self.tokens,
+        }
+    }
+
+    /// Overrides the base URL endpoints are resolved against.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.rest = self.rest.with_base_url(base_url);
+        self
+    }
+
+    /// Returns the token manager used to authenticate requests.
+    pub fn tokens(&self) -> &TokenManager {
+        &self.tokens
+    }
+
+    /// Fetches users by their IDs, or the authenticated user if `ids` is empty.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn users(&self, ids: &[u64]) -> Result<Vec<OfficialUser>, KickError> {
+        let query: Vec<_> = ids.iter().map(|id| ("id", id.to_string())).collect();
+        self.get("/users", &query).await
+    }
+
+    /// Fetches channels by their broadcasters' user IDs, or the authenticated user's
+    /// channel if `broadcaster_user_ids` is empty.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn channels(
+        &self,
+        broadcaster_user_ids: &[u64],
+    ) -> Result<Vec<OfficialChannel>, KickError> {
+        let query: Vec<_> = broadcaster_user_ids
+            .iter()
+            .map(|id| ("broadcaster_user_id", id.to_string()))
+            .collect();
+        self.get("/channels", &query).await
+    }
+
+    /// Fetches channels by their slugs.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn channels_by_slug(
+        &self,
+        slugs: &[&str],
+    ) -> Result<Vec<OfficialChannel>, KickError> {
+        let query: Vec<_> = slugs
+            .iter()
+            .map(|slug| ("slug", slug.to_string()))
+            .collect();
+        self.get("/channels", &query).await
+    }
+
+    /// Sends a chat message to a channel's chatroom as the authenticated user.
+    ///
+    /// # Arguments
+    ///
+    /// * `broadcaster_user_id` - The user ID of the channel's broadcaster.
+    /// * `content` - The text of the message.
+    /// * `reply_to_message_id` - The ID of the message to reply to, if any.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn send_chat_message(
+        &self,
+        broadcaster_user_id: u64,
+        content: &str,
+        reply_to_message_id: Option<&str>,
+    ) -> Result<SentChatMessage, KickError> {
+        let mut body = serde_json::json!({
+            "broadcaster_user_id": broadcaster_user_id,
+            "content": content,
+            "type": "user",
+        });
+        if let Some(reply_to_message_id) = reply_to_message_id {
+            body["reply_to_message_id"] = reply_to_message_id.into();
+        }
+
+        let response = self
+            .rest
+            .execute(Method::POST, "/chat", &[], Some(&body))
+            .await?;
+        let sent: OfficialResponse<SentChatMessage> = response.json()?;
+        Ok(sent.data)
+    }
+
+    /// Lists the event subscriptions of the app.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn event_subscriptions(&self) -> Result<Vec<EventSubscription>, KickError> {
+        self.get("/events/subscriptions", &[]).await
+    }
+
+    /// Subscribes the app's webhook to the given event types of a broadcaster.
+    ///
+    /// # Arguments
+    ///
+    /// * `broadcaster_user_id` - The broadcaster whose events to receive, or `None` for the
+    ///   authenticated user.
+    /// * `events` - The event types to subscribe to.
+    ///
+    /// # Returns
+    ///
+    /// The outcome for each event type; Kick may reject individual types while accepting
+    /// the others.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn create_event_subscriptions(
+        &self,
+        broadcaster_user_id: Option<u64>,
+        events: &[EventType],
+    ) -> Result<Vec<CreatedEventSubscription>, KickError> {
+        let mut body = serde_json::json!({
+            "events": events,
+            "method": "webhook",
+        });
+        if let Some(broadcaster_user_id) = broadcaster_user_id {
+            body["broadcaster_user_id"] = broadcaster_user_id.into();
+        }
+
+        let response = self
+            .rest
+            .execute(Method::POST, "/events/subscriptions", &[], Some(&body))
+            .await?;
+        let created: OfficialResponse<Vec<CreatedEventSubscription>> = response.json()?;
+        Ok(created.data)
+    }
+
+    /// Deletes event subscriptions by their IDs.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn delete_event_subscriptions(&self, ids: &[&str]) -> Result<(), KickError> {
+        let query: Vec<_> = ids.iter().map(|id| ("id", id.to_string())).collect();
+        self.rest
+            .execute::<()>(Method::DELETE, "/events/subscriptions", &query, None)
+            .await
+            .map(|_| ())
+    }
+
+    /// Makes the app's event subscriptions for a broadcaster match `desired`, creating
+    /// the missing ones and deleting the ones no longer wanted.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if any of the requests fails or Kick rejects it.
+    pub async fn reconcile_event_subscriptions(
+        &self,
+        broadcaster_user_id: u64,
+        desired: &[EventType],
+    ) -> Result<ReconciledEventSubscriptions, KickError> {
+        let existing: Vec<_> = self
+            .event_subscriptions()
+            .await?
+            .into_iter()
+            .filter(|subscription| subscription.broadcaster_user_id == broadcaster_user_id)
+            .collect();
+
+        let deleted: Vec<String> = existing
+            .iter()
+            .filter(|subscription| {
+                !desired.iter().any(|event| {
+                    event.name == subscription.event && event.version == subscription.version
+                })
+            })
+            .map(|subscription| subscription.id.clone())
+            .collect();
+        let missing: Vec<EventType> = desired
+            .iter()
+            .filter(|event| {
+                !existing.iter().any(|subscription| {
+                    event.name == subscription.event && event.version == subscription.version
+                })
+            })
+            .cloned()
+            .collect();
+
+        let mut reconciled = ReconciledEventSubscriptions::default();
+        if !deleted.is_empty() {
+            let ids: Vec<&str> = deleted.iter().map(String::as_str).collect();
+            self.delete_event_subscriptions(&ids).await?;
+            reconciled.deleted = deleted;
+        }
+        if !missing.is_empty() {
+            reconciled.created = self
+                .create_event_subscriptions(Some(broadcaster_user_id), &missing)
+                .await?;
+        }
+        Ok(reconciled)
+    }
+
+    async fn get<R: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<R, KickError> {
+        let response = self
+            .rest
+            .execute::<()>(Method::GET, path, query, None)
+            .await?;
+        let parsed: OfficialResponse<R> = response.json()?;
+        Ok(parsed.data)
+    }
+}
+
+fn random_string(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,163 @@
+//! Exporting traces and metrics of the client through OpenTelemetry.
+//!
+//! `ConnectOptions::with_telemetry` makes every connection attempt, including those of a
+//! `ReconnectingClient`, run in a `kick_client.connect` span and count towards
+//! `kick_client.connect.attempts`. The connected client then records how long Pusher took
+//! to confirm each subscription in `kick_client.subscribe.latency`, and counts the read
+//! messages by kind in `kick_client.messages`:
+//!
+//! ```no_run
+//! # async fn run() -> Result<(), kick_client::KickError> {
+//! use kick_client::otel::Telemetry;
+//! use kick_client::transport::ConnectOptions;
+//! use kick_client::KickClient;
+//!
+//! let options = ConnectOptions::new().with_telemetry(Telemetry::new());
+//! let mut client = KickClient::connect_with(kick_client::DEFAULT_WEBSOCKET_URL, vec![668], &options).await?;
+//! while let Some(message) = client.read_message().await? {
+//!     println!("{}", message);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{KickChatMessage, KickError, MessageData};
+use opentelemetry::global::{self, BoxedTracer, ObjectSafeTracerProvider};
+use opentelemetry::metrics::{Counter, Histogram, MeterProvider};
+use opentelemetry::trace::{Span, Status, Tracer};
+use opentelemetry::{InstrumentationScope, KeyValue};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// The name of the instrumentation scope spans and metrics are recorded under.
+const SCOPE: &str = "kick_client";
+
+/// The spans and instruments a client records to. Cloning a `Telemetry` is cheap and all
+/// clones share the same instruments.
+#[derive(Clone)]
+pub struct Telemetry {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    tracer: BoxedTracer,
+    connect_attempts: Counter<u64>,
+    subscribe_latency: Histogram<f64>,
+    messages: Counter<u64>,
+    /// When the subscription to each chatroom not confirmed yet was requested.
+    subscribing: Mutex<HashMap<u32, Instant>>,
+}
+
+impl Telemetry {
+    /// Creates a new instance of `Telemetry` recording to the global tracer and meter
+    /// providers, as installed by the application.
+    pub fn new() -> Self {
+        Self::from_providers(&*global::meter_provider(), &global::tracer_provider())
+    }
+
+    /// Creates a new instance of `Telemetry` recording to the given providers.
+    pub fn from_providers(
+        meter_provider: &(impl MeterProvider + ?Sized),
+        tracer_provider: &impl ObjectSafeTracerProvider,
+    ) -> Self {
+        let meter = meter_provider.meter(SCOPE);
+        let scope = InstrumentationScope::builder(SCOPE).build();
+        Self {
+            inner: Arc::new(Inner {
+                tracer: BoxedTracer::new(tracer_provider.boxed_tracer(scope)),
+                connect_attempts: meter
+                    .u64_counter("kick_client.connect.attempts")
+                    .with_description("Connection attempts, by outcome.")
+                    .build(),
+                subscribe_latency: meter
+                    .f64_histogram("kick_client.subscribe.latency")
+                    .with_description("How long Pusher took to confirm a subscription.")
+                    .with_unit("s")
+                    .build(),
+                messages: meter
+                    .u64_counter("kick_client.messages")
+                    .with_description("Messages read, by kind.")
+                    .build(),
+                subscribing: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// Runs a connection attempt in a `kick_client.connect` span, counting its outcome.
+    pub(crate) async fn connect<T>(
+        &self,
+        url: &str,
+        chatrooms: usize,
+        connect: impl Future<Output = Result<T, KickError>>,
+    ) -> Result<T, KickError> {
+        let mut span = self.inner.tracer.start("kick_client.connect");
+        span.set_attribute(KeyValue::new("url", url.to_string()));
+        span.set_attribute(KeyValue::new("chatrooms", chatrooms as i64));
+        let result = connect.await;
+        let outcome = match &result {
+            Ok(_) => "success",
+            Err(e) => {
+                span.set_status(Status::error(e.to_string()));
+                "failure"
+            }
+        };
+        span.end();
+        self.inner
+            .connect_attempts
+            .add(1, &[KeyValue::new("outcome", outcome)]);
+        result
+    }
+
+    /// Notes that the subscription to a chatroom was requested.
+    pub(crate) fn subscribing(&self, chatroom_id: u64) {
+        if let Ok(chatroom_id) = u32::try_from(chatroom_id) {
+            self.pending().insert(chatroom_id, Instant::now());
+        }
+    }
+
+    /// Records the latency of a subscription confirmed by `message`, if it is one which was
+    /// waited for.
+    pub(crate) fn confirmed(&self, message: &KickChatMessage) {
+        let (MessageData::PusherSubscriptionSucceeded(_), Some(chatroom_id)) =
+            (&message.data, message.chatroom_id())
+        else {
+            return;
+        };
+        if let Some(requested_at) = self.pending().remove(&chatroom_id) {
+            self.inner.subscribe_latency.record(
+                requested_at.elapsed().as_secs_f64(),
+                &[KeyValue::new("chatroom_id", i64::from(chatroom_id))],
+            );
+        }
+    }
+
+    /// Counts a read message.
+    pub(crate) fn observe(&self, message: &KickChatMessage) {
+        self.confirmed(message);
+        self.inner
+            .messages
+            .add(1, &[KeyValue::new("kind", message.data.kind())]);
+    }
+
+    fn pending(&self) -> std::sync::MutexGuard<'_, HashMap<u32, Instant>> {
+        self.inner
+            .subscribing
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for Telemetry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Telemetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Telemetry").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,212 @@
+#[cfg(feature = "api")]
+use crate::api::{HttpTransport, KickApi};
+use crate::commands::{Command, Permission, Username};
+use crate::content::links;
+#[cfg(feature = "api")]
+use crate::KickError;
+use crate::{ChatMessageEventData, KickChatMessage, MessageData};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a permit lasts when `!permit` is given no duration.
+const DEFAULT_PERMIT_DURATION: Duration = Duration::from_secs(60);
+
+/// A message containing links its sender wasn't permitted to post.
+#[derive(Debug, Clone)]
+pub struct LinkViolation {
+    /// The message containing the links.
+    pub message: ChatMessageEventData,
+    /// The offending links, as written.
+    pub links: Vec<String>,
+}
+
+#[cfg(feature = "api")]
+impl LinkViolation {
+    /// Deletes the message through the moderation API.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the request fails or Kick rejects it.
+    pub async fn delete<T: HttpTransport>(&self, api: &KickApi<T>) -> Result<(), KickError> {
+        api.delete_message(self.message.chatroom_id, &self.message.id)
+            .await
+    }
+}
+
+/// Tracks which users may post links, granted by moderators with `!permit user 60s`.
+///
+/// Senders at or above the exempt permission level may always post links, and links to
+/// allowed hosts are never violations. Cloning a `LinkPermits` is cheap and all clones
+/// share the same permits.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(
+/// #     mut client: kick_client::KickClient,
+/// #     api: impl kick_client::ChatSender,
+/// # ) -> Result<(), kick_client::KickError> {
+/// use kick_client::commands::Commands;
+/// use kick_client::permit::LinkPermits;
+///
+/// let permits = LinkPermits::new().allow_host("clips.kick.com");
+/// let mut commands = Commands::new("!").with_sender(api);
+/// commands.register(permits.command());
+///
+/// while let Some(message) = client.read_message().await? {
+///     if let Some(violation) = permits.check(&message) {
+///         println!("{} posted {:?}", violation.message.sender.username, violation.links);
+///     }
+///     commands.dispatch(&message).await;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct LinkPermits {
+    state: Arc<Mutex<PermitState>>,
+}
+
+struct PermitState {
+    /// When the permit of each user in each chatroom expires, keyed by lowercase username,
+    /// or `None` if it doesn't.
+    permits: HashMap<(u32, String), Option<Instant>>,
+    /// Senders at or above this level may always post links.
+    exempt: Permission,
+    /// Hosts anyone may link to, without `www.`.
+    allowed_hosts: HashSet<String>,
+}
+
+impl LinkPermits {
+    /// Creates a new instance of `LinkPermits` without any permits. VIPs and above may
+    /// always post links.
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(PermitState {
+                permits: HashMap::new(),
+                exempt: Permission::Vip,
+                allowed_hosts: HashSet::new(),
+            })),
+        }
+    }
+
+    /// Sets the permission level at or above which senders may always post links.
+    pub fn with_exempt(self, exempt: Permission) -> Self {
+        self.lock().exempt = exempt;
+        self
+    }
+
+    /// Lets anyone link to a host and its subdomains, e.g. `kick.com`.
+    pub fn allow_host(self, host: impl Into<String>) -> Self {
+        let host = host.into().to_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+        self.lock().allowed_hosts.insert(host);
+        self
+    }
+
+    /// Lets a user post links in a chatroom for `duration`. A duration too long to
+    /// represent lasts until the permit is revoked.
+    pub fn permit(&self, chatroom_id: u32, username: &str, duration: Duration) {
+        self.lock().permits.insert(
+            (chatroom_id, username.to_lowercase()),
+            Instant::now().checked_add(duration),
+        );
+    }
+
+    /// Revokes the permit of a user in a chatroom.
+    pub fn revoke(&self, chatroom_id: u32, username: &str) {
+        self.lock()
+            .permits
+            .remove(&(chatroom_id, username.to_lowercase()));
+    }
+
+    /// Returns `true` if a user holds an unexpired permit in a chatroom.
+    pub fn is_permitted(&self, chatroom_id: u32, username: &str) -> bool {
+        self.lock()
+            .permits
+            .get(&(chatroom_id, username.to_lowercase()))
+            .is_some_and(|expires_at| expires_at.is_none_or(|at| at > Instant::now()))
+    }
+
+    /// Checks a received message, returning a violation if it contains links its sender
+    /// may not post.
+    pub fn check(&self, message: &KickChatMessage) -> Option<LinkViolation> {
+        match &message.data {
+            MessageData::ChatMessage(data) => self.check_chat_message(data),
+            _ => None,
+        }
+    }
+
+    /// Checks a chat message, returning a violation if it contains links its sender may
+    /// not post.
+    pub fn check_chat_message(&self, message: &ChatMessageEventData) -> Option<LinkViolation> {
+        let content = message.content.as_deref().unwrap_or_default();
+        let state = self.lock();
+        if Permission::of(&message.sender) >= state.exempt {
+            return None;
+        }
+        let links: Vec<String> = links(content)
+            .into_iter()
+            .filter(|link| {
+                let host = link.host();
+                !state
+                    .allowed_hosts
+                    .iter()
+                    .any(|allowed| host == *allowed || host.ends_with(&format!(".{}", allowed)))
+            })
+            .map(|link| link.text.to_string())
+            .collect();
+        drop(state);
+
+        if links.is_empty() || self.is_permitted(message.chatroom_id, &message.sender.username) {
+            return None;
+        }
+        Some(LinkViolation {
+            message: message.clone(),
+            links,
+        })
+    }
+
+    /// Forgets expired permits.
+    pub fn purge_expired(&self) {
+        let now = Instant::now();
+        self.lock()
+            .permits
+            .retain(|_, expires_at| expires_at.is_none_or(|at| at > now));
+    }
+
+    /// Returns the moderator-only `!permit <user> [duration]` command granting permits,
+    /// for 60 seconds unless a duration is given.
+    pub fn command(&self) -> Command {
+        let permits = self.clone();
+        Command::with_args(
+            "permit",
+            move |ctx, (user, duration): (Username, Option<Duration>)| {
+                let permits = permits.clone();
+                async move {
+                    let duration = duration.unwrap_or(DEFAULT_PERMIT_DURATION);
+                    permits.permit(ctx.invocation.chatroom_id(), &user.0, duration);
+                    ctx.reply(&format!(
+                        "@{} may post links for {}s",
+                        user,
+                        duration.as_secs()
+                    ))
+                    .await
+                }
+            },
+        )
+        .with_usage("<user> [duration]")
+        .with_permission(Permission::Moderator)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, PermitState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for LinkPermits {
+    fn default() -> Self {
+        Self::new()
+    }
+}
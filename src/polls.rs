@@ -0,0 +1,144 @@
+use crate::{KickChatMessage, MessageData, Poll};
+use std::collections::HashMap;
+
+/// How the votes for a poll option changed between two updates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VoteDelta {
+    /// The ID of the option.
+    pub option_id: u32,
+    /// The label of the option.
+    pub label: String,
+    /// The votes for the option after the update.
+    pub votes: u32,
+    /// How many votes the option gained since the previous update.
+    pub delta: i64,
+}
+
+/// A change in the poll of a chatroom, derived from `PollUpdate` and `PollDelete` messages.
+#[derive(Debug, Clone)]
+pub enum PollEvent {
+    /// A poll started.
+    Started { chatroom_id: u32, poll: Poll },
+    /// Votes were cast in a running poll.
+    Updated {
+        chatroom_id: u32,
+        poll: Poll,
+        deltas: Vec<VoteDelta>,
+    },
+    /// A poll stopped accepting votes. `poll` holds the final results.
+    Finished { chatroom_id: u32, poll: Poll },
+    /// A poll was removed from the chatroom.
+    Deleted { chatroom_id: u32 },
+}
+
+/// Keeps the current poll of each chatroom and reports how it changes.
+///
+/// Poll messages don't name their chatroom, so it is taken from the channel the message
+/// was received on.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::polls::{PollEvent, PollTracker};
+///
+/// let mut polls = PollTracker::new();
+/// while let Some(message) = client.read_message().await? {
+///     if let Some(PollEvent::Finished { poll, .. }) = polls.observe(&message) {
+///         println!("{} is over: {:?}", poll.title, poll.options);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct PollTracker {
+    /// The latest state of the poll of each chatroom, and whether it finished.
+    polls: HashMap<u32, (Poll, bool)>,
+}
+
+impl PollTracker {
+    /// Creates a new instance of `PollTracker` without any known polls.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the latest state of the poll of a chatroom, if it has one.
+    pub fn poll(&self, chatroom_id: u32) -> Option<&Poll> {
+        self.polls.get(&chatroom_id).map(|(poll, _)| poll)
+    }
+
+    /// Updates the tracked polls from a received message, returning what changed.
+    pub fn observe(&mut self, message: &KickChatMessage) -> Option<PollEvent> {
+        match &message.data {
+            MessageData::PollUpdate(data) => self.update(message.chatroom_id()?, &data.poll),
+            MessageData::PollDelete(_) => {
+                let chatroom_id = message.chatroom_id()?;
+                self.polls
+                    .remove(&chatroom_id)
+                    .map(|_| PollEvent::Deleted { chatroom_id })
+            }
+            _ => None,
+        }
+    }
+
+    /// Applies an update of the poll of a chatroom, returning what changed.
+    pub fn update(&mut self, chatroom_id: u32, poll: &Poll) -> Option<PollEvent> {
+        let finished = poll.remaining == 0;
+        let previous = self.polls.insert(chatroom_id, (poll.clone(), finished));
+
+        let poll = poll.clone();
+        match previous {
+            // Updates keep coming while the results are displayed.
+            Some((_, true)) if finished => None,
+            Some((previous, false)) if is_same_poll(&previous, &poll) => {
+                if finished {
+                    Some(PollEvent::Finished { chatroom_id, poll })
+                } else {
+                    let deltas = vote_deltas(&previous, &poll);
+                    Some(PollEvent::Updated {
+                        chatroom_id,
+                        poll,
+                        deltas,
+                    })
+                }
+            }
+            _ if finished => Some(PollEvent::Finished { chatroom_id, poll }),
+            _ => Some(PollEvent::Started { chatroom_id, poll }),
+        }
+    }
+}
+
+/// Returns `true` if two poll states are likely to belong to the same poll.
+pub(crate) fn is_same_poll(previous: &Poll, current: &Poll) -> bool {
+    // Updates sent once a poll ended lack its duration.
+    let ended = current.remaining == 0 && current.duration == 0;
+    previous.title == current.title
+        && (ended || previous.duration == current.duration)
+        && previous.remaining >= current.remaining
+        && previous
+            .options
+            .iter()
+            .map(|option| option.id)
+            .eq(current.options.iter().map(|option| option.id))
+}
+
+fn vote_deltas(previous: &Poll, current: &Poll) -> Vec<VoteDelta> {
+    current
+        .options
+        .iter()
+        .map(|option| {
+            let before = previous
+                .options
+                .iter()
+                .find(|previous| previous.id == option.id)
+                .map_or(0, |previous| previous.votes);
+            VoteDelta {
+                option_id: option.id,
+                label: option.label.clone(),
+                votes: option.votes,
+                delta: i64::from(option.votes) - i64::from(before),
+            }
+        })
+        .collect()
+}
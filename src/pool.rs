@@ -0,0 +1,161 @@
+//! Sharding many chatrooms across several WebSocket connections.
+//!
+//! A single connection subscribed to hundreds of chatrooms is slow to resubscribe after a
+//! disconnection, and loses every chatroom at once when it drops. `KickClientPool` spreads
+//! the chatrooms over a number of `ReconnectingClient`s, each reconnecting on its own,
+//! and merges their messages into one stream labeled with the connection they came from.
+
+use crate::reconnect::ReconnectingClient;
+use crate::trace::Instrument;
+use crate::{KickChatMessage, KickError, MessageSource};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// How many chatrooms each connection is subscribed to at most by default.
+const DEFAULT_CHATROOMS_PER_CONNECTION: usize = 100;
+/// How many messages are buffered before the connections wait for them to be read.
+const MESSAGE_BUFFER: usize = 1024;
+
+/// A message received by a `KickClientPool`, with the connection it was received on.
+#[derive(Debug, Clone)]
+pub struct PooledMessage {
+    /// The index of the connection, in the order of `KickClientPool::shards`.
+    pub connection: usize,
+    /// The message.
+    pub message: KickChatMessage,
+}
+
+type Configure = Arc<dyn Fn(ReconnectingClient) -> ReconnectingClient + Send + Sync>;
+
+/// Chatrooms spread across several reconnecting connections, read as one stream.
+///
+/// Chatrooms are assigned to connections round-robin, so the connections carry similar
+/// numbers of chatrooms. Connections are established on the first read, and closed when
+/// the pool is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::pool::KickClientPool;
+/// use kick_client::DEFAULT_WEBSOCKET_URL;
+///
+/// let chatroom_ids: Vec<u64> = (1..=500).collect();
+/// let mut pool = KickClientPool::new(DEFAULT_WEBSOCKET_URL, chatroom_ids).with_connections(5);
+/// while let Some(pooled) = pool.recv().await {
+///     println!("[{}] {}", pooled.connection, pooled.message);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct KickClientPool {
+    url: String,
+    chatroom_ids: Vec<u64>,
+    /// The number of connections, or `None` to derive it from the number of chatrooms.
+    connections: Option<usize>,
+    configure: Configure,
+    messages: Option<mpsc::Receiver<PooledMessage>>,
+    /// The tasks reading each connection, stopped when the pool is dropped.
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl KickClientPool {
+    /// Creates a new instance of `KickClientPool`, with one connection for every 100
+    /// chatrooms.
+    pub fn new(url: impl Into<String>, chatroom_ids: Vec<u64>) -> Self {
+        Self {
+            url: url.into(),
+            chatroom_ids,
+            connections: None,
+            configure: Arc::new(|client| client),
+            messages: None,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Spreads the chatrooms across `count` connections, or fewer if there are fewer
+    /// chatrooms.
+    pub fn with_connections(mut self, count: usize) -> Self {
+        self.connections = Some(count.max(1));
+        self
+    }
+
+    /// Configures the `ReconnectingClient` of every connection, e.g. its backoff or
+    /// connect options.
+    pub fn with_client_config(
+        mut self,
+        configure: impl Fn(ReconnectingClient) -> ReconnectingClient + Send + Sync + 'static,
+    ) -> Self {
+        self.configure = Arc::new(configure);
+        self
+    }
+
+    /// Returns the chatroom IDs each connection is subscribed to, by connection.
+    pub fn shards(&self) -> Vec<Vec<u64>> {
+        let count = self
+            .connections
+            .unwrap_or_else(|| {
+                self.chatroom_ids
+                    .len()
+                    .div_ceil(DEFAULT_CHATROOMS_PER_CONNECTION)
+            })
+            .min(self.chatroom_ids.len())
+            .max(1);
+        let mut shards = vec![Vec::new(); count];
+        for (index, chatroom_id) in self.chatroom_ids.iter().enumerate() {
+            shards[index % count].push(*chatroom_id);
+        }
+        shards
+    }
+
+    /// Receives the next message from any connection, connecting on the first call.
+    /// Returns `None` only if every connection stopped.
+    pub async fn recv(&mut self) -> Option<PooledMessage> {
+        if self.messages.is_none() {
+            self.messages = Some(self.start());
+        }
+        self.messages.as_mut()?.recv().await
+    }
+
+    /// Spawns a task for each connection, returning the receiver of their messages.
+    fn start(&mut self) -> mpsc::Receiver<PooledMessage> {
+        let (sender, receiver) = mpsc::channel(MESSAGE_BUFFER);
+        for (connection, chatroom_ids) in self.shards().into_iter().enumerate() {
+            let client = (self.configure)(ReconnectingClient::new(&self.url, chatroom_ids));
+            let sender = sender.clone();
+            let task = async move {
+                let mut client = client;
+                while let Ok(Some(message)) = client.read_message().await {
+                    let pooled = PooledMessage {
+                        connection,
+                        message,
+                    };
+                    let send = sender
+                        .send(pooled)
+                        .instrument(span!("dispatch", connection));
+                    if send.await.is_err() {
+                        return;
+                    }
+                }
+            };
+            self.tasks
+                .push(crate::trace::spawn("kick_client::pool::connection", task));
+        }
+        receiver
+    }
+}
+
+impl Drop for KickClientPool {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl MessageSource for KickClientPool {
+    async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        Ok(self.recv().await.map(|pooled| pooled.message))
+    }
+}
@@ -0,0 +1,196 @@
+// The protobuf messages generated from `proto/kick_client.proto` at build time.
+include!(concat!(env!("OUT_DIR"), "/kick_client.rs"));
+
+use crate::{ChatMessageEventData, ChatMessageSenderBadge, KickChatMessage, MessageData};
+use event::Data;
+
+/// Returns the `.proto` schema the messages of this module are generated from, for teams
+/// generating bindings in other languages.
+pub fn schema() -> &'static str {
+    include_str!("../proto/kick_client.proto")
+}
+
+impl From<&KickChatMessage> for Event {
+    fn from(message: &KickChatMessage) -> Self {
+        Self {
+            channel: message.channel.clone(),
+            data: Some(Data::from(&message.data)),
+        }
+    }
+}
+
+impl From<&MessageData> for Data {
+    fn from(data: &MessageData) -> Self {
+        match data {
+            MessageData::ChatMessage(data) => Data::ChatMessage(data.into()),
+            MessageData::DeletedMessage(data) => Data::MessageDeleted(MessageDeleted {
+                id: data.id.clone(),
+                message_id: data.message.id.clone(),
+                ai_moderated: data.ai_moderated,
+                violated_rules: data.violated_rules.clone().unwrap_or_default(),
+                original: data.original.as_ref().map(ChatMessage::from),
+            }),
+            MessageData::UserBanned(data) => Data::UserBanned(UserBanned {
+                id: data.id.clone(),
+                user: Some((&data.user).into()),
+                banned_by: Some((&data.banned_by).into()),
+                permanent: data.permanent,
+                duration: data.duration,
+                expires_at: data.expires_at.clone(),
+            }),
+            MessageData::UserUnbanned(data) => Data::UserUnbanned(UserUnbanned {
+                id: data.id.clone(),
+                user: Some((&data.user).into()),
+                unbanned_by: Some((&data.unbanned_by).into()),
+                permanent: data.permanent,
+            }),
+            MessageData::ChatroomUpdated(data) => Data::ChatroomUpdated(ChatroomUpdated {
+                id: data.id,
+                slow_mode: data.slow_mode.enabled,
+                slow_mode_interval: data.slow_mode.message_interval,
+                subscribers_mode: data.subscribers_mode.enabled,
+                followers_mode: data.followers_mode.enabled,
+                followers_mode_min_duration: data.followers_mode.min_duration,
+                emotes_mode: data.emotes_mode.enabled,
+                advanced_bot_protection: data.advanced_bot_protection.enabled,
+                advanced_bot_protection_remaining_time: data.advanced_bot_protection.remaining_time,
+            }),
+            MessageData::ChatroomClear(data) => Data::ChatroomClear(ChatroomClear {
+                id: data.id.clone(),
+            }),
+            MessageData::PollUpdate(data) => Data::PollUpdate(PollUpdate {
+                title: data.poll.title.clone(),
+                options: data
+                    .poll
+                    .options
+                    .iter()
+                    .map(|option| PollOption {
+                        id: option.id,
+                        label: option.label.clone(),
+                        votes: option.votes,
+                    })
+                    .collect(),
+                duration: data.poll.duration,
+                remaining: data.poll.remaining,
+                result_display_duration: data.poll.result_display_duration,
+                has_voted: data.poll.has_voted,
+                voted_option_id: data.poll.voted_option_id.clone(),
+            }),
+            MessageData::PollDelete(_) => Data::PollDelete(PollDelete {}),
+            MessageData::PusherConnectionEstablished(data) => {
+                Data::ConnectionEstablished(ConnectionEstablished {
+                    socket_id: data.socket_id.clone(),
+                    activity_timeout: data.activity_timeout,
+                })
+            }
+            MessageData::PusherSubscriptionSucceeded(_) => {
+                Data::SubscriptionSucceeded(SubscriptionSucceeded {})
+            }
+            MessageData::PusherPong(_) => Data::Pong(Pong {}),
+            MessageData::SubscriptionEvent(data) => Data::Subscription(Subscription {
+                chatroom_id: data.chatroom_id,
+                username: data.username.clone(),
+                months: data.months,
+            }),
+            MessageData::PinnedMessageDeletedEvent(_) => {
+                Data::PinnedMessageDeleted(PinnedMessageDeleted {})
+            }
+            MessageData::PinnedMessageCreatedEvent(data) => {
+                Data::PinnedMessageCreated(PinnedMessageCreated {
+                    message: Some((&data.message).into()),
+                    duration: data.duration.clone(),
+                    pinned_by: Some((&data.pinned_by).into()),
+                })
+            }
+            MessageData::GiftedSubscriptions(data) => {
+                Data::GiftedSubscriptions(GiftedSubscriptions {
+                    chatroom_id: data.chatroom_id,
+                    gifted_usernames: data.gifted_usernames.clone(),
+                    gifter_username: data.gifter_username.clone(),
+                })
+            }
+            MessageData::ChannelFollowed(data) => Data::ChannelFollowed(ChannelFollowed {
+                broadcaster_user_id: data.broadcaster_user_id,
+                follower_user_id: data.follower_user_id,
+                follower_username: data.follower_username.clone(),
+            }),
+            MessageData::LivestreamStatusUpdated(data) => {
+                Data::LivestreamStatusUpdated(LivestreamStatusUpdated {
+                    broadcaster_user_id: data.broadcaster_user_id,
+                    broadcaster_username: data.broadcaster_username.clone(),
+                    is_live: data.is_live,
+                    title: data.title.clone(),
+                    started_at: data.started_at.clone(),
+                    ended_at: data.ended_at.clone(),
+                })
+            }
+            MessageData::PossibleGap(data) => Data::PossibleGap(PossibleGap {
+                downtime_ms: data.downtime_ms,
+            }),
+            MessageData::Unknown(unknown) => Data::Unknown(Unknown {
+                event: unknown.as_ref().map(|unknown| unknown.event.clone()),
+                data: unknown.as_ref().map(|unknown| unknown.data.to_string()),
+            }),
+            MessageData::Unsupported(frame, error) => Data::Unsupported(Unsupported {
+                frame: frame.clone(),
+                error: error.clone(),
+            }),
+        }
+    }
+}
+
+impl From<&ChatMessageEventData> for ChatMessage {
+    fn from(data: &ChatMessageEventData) -> Self {
+        Self {
+            id: data.id.clone(),
+            chatroom_id: data.chatroom_id,
+            content: data.content.clone(),
+            r#type: data.r#type.clone(),
+            created_at: data.created_at.clone(),
+            sender: Some((&data.sender).into()),
+        }
+    }
+}
+
+impl From<&crate::ChatMessageSender> for Sender {
+    fn from(sender: &crate::ChatMessageSender) -> Self {
+        Self {
+            id: sender.id,
+            username: sender.username.clone(),
+            slug: sender.slug.clone(),
+            color: sender.identity.color.clone(),
+            badges: sender.identity.badges.iter().map(Badge::from).collect(),
+        }
+    }
+}
+
+impl From<&ChatMessageSenderBadge> for Badge {
+    fn from(badge: &ChatMessageSenderBadge) -> Self {
+        match badge {
+            ChatMessageSenderBadge::FullBadge {
+                r#type,
+                text,
+                count,
+            } => Self {
+                r#type: r#type.clone(),
+                text: text.clone(),
+                count: *count,
+            },
+            ChatMessageSenderBadge::SimpleBadge { r#type, text } => Self {
+                r#type: r#type.clone(),
+                text: text.clone(),
+                count: None,
+            },
+        }
+    }
+}
+
+impl From<&crate::User> for User {
+    fn from(user: &crate::User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username.clone(),
+            slug: user.slug.clone(),
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::api::{HttpTransport, KickApi};
+use crate::metrics::Metrics;
+use crate::{ChatSender, KickError};
+use futures_util::future::BoxFuture;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// How many messages may be queued by default.
+const DEFAULT_LIMIT: usize = 10_000;
+
+/// How urgently an outgoing message should be sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Periodic announcements and other messages nobody is waiting for.
+    Announcement,
+    /// Replies to regular chatters.
+    Chatter,
+    /// Moderation replies, sent before anything else.
+    Moderation,
+}
+
+impl Priority {
+    const ALL: [Priority; 3] = [
+        Priority::Moderation,
+        Priority::Chatter,
+        Priority::Announcement,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// A chat message waiting in a `MessageQueue`.
+#[derive(Debug, Clone)]
+pub struct OutgoingMessage {
+    /// The ID of the chatroom to send the message to.
+    pub chatroom_id: u32,
+    /// The text of the message.
+    pub content: String,
+    /// How urgently the message should be sent.
+    pub priority: Priority,
+    /// Messages with the same key for the same chatroom replace each other while queued,
+    /// so only the latest one is sent.
+    pub coalesce_key: Option<String>,
+}
+
+impl OutgoingMessage {
+    /// Creates a new instance of `OutgoingMessage` that is never coalesced.
+    pub fn new(chatroom_id: u32, content: impl Into<String>, priority: Priority) -> Self {
+        Self {
+            chatroom_id,
+            content: content.into(),
+            priority,
+            coalesce_key: None,
+        }
+    }
+
+    /// Sets the key used to coalesce this message with other queued ones.
+    pub fn coalesce(mut self, key: impl Into<String>) -> Self {
+        self.coalesce_key = Some(key.into());
+        self
+    }
+}
+
+/// A queue of outgoing chat messages, sent in order of priority.
+///
+/// Messages accumulate while the rate limiter of the sending `KickApi` is saturated. During
+/// that time, a message enqueued with the same coalesce key and chatroom as a queued one
+/// replaces it instead of waiting behind it. Messages to a chatroom in slow mode don't hold
+/// up less urgent messages to other chatrooms. Once the queue holds as many messages as its
+/// limit, the oldest of the least urgent messages is dropped for each new one. Cloning a
+/// `MessageQueue` is cheap and all clones share the same queue.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() {
+/// use kick_client::api::KickApi;
+/// use kick_client::queue::{MessageQueue, OutgoingMessage, Priority};
+/// use kick_client::ratelimit::RateLimiter;
+///
+/// let api = KickApi::new("token").with_rate_limiter(RateLimiter::default());
+/// let queue = MessageQueue::new();
+/// tokio::spawn(queue.clone().run(api));
+///
+/// queue.enqueue(OutgoingMessage::new(1234, "Viewers: 42", Priority::Announcement).coalesce("viewers"));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct MessageQueue {
+    state: Arc<Mutex<QueueState>>,
+    notify: Arc<Notify>,
+}
+
+/// What `MessageQueue::poll_next` found.
+enum Next {
+    Ready(OutgoingMessage),
+    Wait(Duration),
+    Empty,
+    Closed,
+}
+
+struct QueueState {
+    /// The queued messages of each priority, oldest first.
+    queues: [VecDeque<OutgoingMessage>; 3],
+    /// Whether the queue stops once it is drained.
+    closed: bool,
+    /// How many messages may be queued.
+    limit: usize,
+    /// The number of messages dropped because the queue was full.
+    dropped: u64,
+    /// The metrics counting dropped messages too, if any.
+    metrics: Option<Metrics>,
+}
+
+impl Default for MessageQueue {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(QueueState {
+                queues: Default::default(),
+                closed: false,
+                limit: DEFAULT_LIMIT,
+                dropped: 0,
+                metrics: None,
+            })),
+            notify: Arc::default(),
+        }
+    }
+}
+
+impl MessageQueue {
+    /// Creates a new, empty instance of `MessageQueue` holding up to 10,000 messages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many messages may be queued before the least urgent ones are dropped.
+    pub fn with_limit(self, limit: usize) -> Self {
+        self.lock().limit = limit;
+        self
+    }
+
+    /// Counts the messages dropped because the queue was full in `metrics`.
+    pub fn with_metrics(self, metrics: Metrics) -> Self {
+        self.lock().metrics = Some(metrics);
+        self
+    }
+
+    /// Returns the number of messages dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.lock().dropped
+    }
+
+    /// Adds a message to the queue, or replaces the queued message it coalesces with.
+    pub fn enqueue(&self, message: OutgoingMessage) {
+        let mut state = self.lock();
+        if let Some(key) = &message.coalesce_key {
+            let queued = state.queues.iter_mut().flatten().find(|queued| {
+                queued.chatroom_id == message.chatroom_id
+                    && queued.coalesce_key.as_ref() == Some(key)
+            });
+            if let Some(queued) = queued {
+                if queued.priority == message.priority {
+                    *queued = message;
+                    return;
+                }
+                // The replacement has a different priority, so it moves to another queue.
+                let priority = queued.priority;
+                let chatroom_id = queued.chatroom_id;
+                state.queues[priority.index()].retain(|queued| {
+                    queued.chatroom_id != chatroom_id || queued.coalesce_key.as_ref() != Some(key)
+                });
+            }
+        }
+        state.queues[message.priority.index()].push_back(message);
+        state.trim();
+        drop(state);
+        self.notify.notify_one();
+    }
+
+    /// Removes and returns the most urgent queued message.
+    pub fn pop(&self) -> Option<OutgoingMessage> {
+        let mut state = self.lock();
+        Priority::ALL
+            .iter()
+            .find_map(|priority| state.queues[priority.index()].pop_front())
+    }
+
+    /// Returns the number of queued messages.
+    pub fn len(&self) -> usize {
+        self.lock().queues.iter().map(VecDeque::len).sum()
+    }
+
+    /// Returns `true` if no messages are queued.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Makes `run` return once the queued messages have been sent.
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.notify.notify_one();
+    }
+
+    /// Sends queued messages through `api` until the queue is closed and drained,
+    /// skipping messages that fail to send.
+    pub async fn run<T: HttpTransport>(self, api: KickApi<T>) {
+        self.run_with(api, |_, _| {}).await
+    }
+
+    /// Sends queued messages through `api` until the queue is closed and drained,
+    /// passing messages that fail to send to `on_error`.
+    pub async fn run_with<T: HttpTransport>(
+        self,
+        api: KickApi<T>,
+        mut on_error: impl FnMut(OutgoingMessage, KickError),
+    ) {
+        loop {
+            let Some(message) = self.next_ready(&api).await else {
+                return;
+            };
+            if let Err(e) = api
+                .send_message_now(message.chatroom_id, &message.content)
+                .await
+            {
+                on_error(message, e);
+            }
+        }
+    }
+
+    /// Waits for the most urgent message the rate limiter allows to be sent, leaving it
+    /// queued while waiting so it can still be coalesced.
+    async fn next_ready<T: HttpTransport>(&self, api: &KickApi<T>) -> Option<OutgoingMessage> {
+        loop {
+            let notified = self.notify.notified();
+            match self.poll_next(api) {
+                Next::Ready(message) => return Some(message),
+                Next::Closed => return None,
+                Next::Wait(wait) => {
+                    let _ = tokio::time::timeout(wait, notified).await;
+                }
+                Next::Empty => notified.await,
+            }
+        }
+    }
+
+    fn poll_next<T: HttpTransport>(&self, api: &KickApi<T>) -> Next {
+        let mut state = self.lock();
+        if state.queues.iter().all(VecDeque::is_empty) {
+            return if state.closed {
+                Next::Closed
+            } else {
+                Next::Empty
+            };
+        }
+        let Some(limiter) = api.rate_limiter() else {
+            return Priority::ALL
+                .iter()
+                .find_map(|priority| state.queues[priority.index()].pop_front())
+                .map_or(Next::Empty, Next::Ready);
+        };
+
+        // A chatroom in slow mode doesn't hold up the messages to other chatrooms, so the
+        // first message to a chatroom the limiter allows is sent.
+        let mut waiting = HashSet::new();
+        let mut wait = Duration::MAX;
+        for priority in Priority::ALL {
+            let queue = &mut state.queues[priority.index()];
+            for i in 0..queue.len() {
+                let chatroom_id = queue[i].chatroom_id;
+                if waiting.contains(&chatroom_id) {
+                    continue;
+                }
+                match limiter.try_acquire(chatroom_id) {
+                    Ok(()) => return queue.remove(i).map_or(Next::Empty, Next::Ready),
+                    Err(chatroom_wait) => {
+                        wait = wait.min(chatroom_wait);
+                        waiting.insert(chatroom_id);
+                    }
+                }
+            }
+        }
+        Next::Wait(wait)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl QueueState {
+    /// Drops the oldest of the least urgent messages until the queue is within its limit.
+    fn trim(&mut self) {
+        let queued: usize = self.queues.iter().map(VecDeque::len).sum();
+        let mut excess = queued.saturating_sub(self.limit);
+        if excess == 0 {
+            return;
+        }
+        self.dropped += excess as u64;
+        if let Some(metrics) = &self.metrics {
+            metrics.record_dropped(excess as u64);
+        }
+        for priority in Priority::ALL.iter().rev() {
+            let queue = &mut self.queues[priority.index()];
+            let count = excess.min(queue.len());
+            queue.drain(..count);
+            excess -= count;
+        }
+    }
+}
+
+/// Enqueues messages with `Priority::Chatter`, so command replies sent through a queue
+/// wait behind moderation replies.
+impl ChatSender for MessageQueue {
+    fn send_message<'a>(
+        &'a self,
+        chatroom_id: u32,
+        content: &'a str,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        self.enqueue(OutgoingMessage::new(
+            chatroom_id,
+            content,
+            Priority::Chatter,
+        ));
+        Box::pin(async { Ok(()) })
+    }
+}
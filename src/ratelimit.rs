@@ -0,0 +1,173 @@
+use crate::{ChatroomUpdatedEventData, KickChatMessage, MessageData};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How many messages may be sent in a burst by default.
+const DEFAULT_CAPACITY: u32 = 5;
+/// How long it takes by default to be allowed a full burst again.
+const DEFAULT_PERIOD: Duration = Duration::from_secs(10);
+
+/// A token-bucket limiter for outgoing chat messages.
+///
+/// Applies a per-user limit across all chatrooms, plus the slow mode of each chatroom.
+/// Slow mode intervals are updated automatically from the `ChatroomUpdated` messages passed
+/// to `observe`. Cloning a `RateLimiter` is cheap and all clones share the same state.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::ratelimit::RateLimiter;
+///
+/// let limiter = RateLimiter::default();
+/// while let Some(message) = client.read_message().await? {
+///     limiter.observe(&message);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct RateLimiter {
+    state: Arc<Mutex<LimiterState>>,
+}
+
+struct LimiterState {
+    /// The maximum number of tokens in the bucket.
+    capacity: f64,
+    /// How many tokens are added to the bucket per second.
+    refill_rate: f64,
+    /// The tokens currently in the bucket.
+    tokens: f64,
+    /// When tokens were last added to the bucket.
+    last_refill: Instant,
+    /// Whether slow mode applies to the sending account.
+    honor_slow_mode: bool,
+    /// The slow mode interval of each chatroom that has it enabled.
+    slow_mode: HashMap<u32, Duration>,
+    /// When a message was last sent to each chatroom.
+    last_sent: HashMap<u32, Instant>,
+}
+
+impl LimiterState {
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Returns how long to wait before a message may be sent to the chatroom.
+    fn wait_time(&mut self, chatroom_id: u32, now: Instant) -> Duration {
+        self.refill(now);
+        let bucket_wait = if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.refill_rate)
+        };
+
+        let slow_mode_wait = match (
+            self.honor_slow_mode,
+            self.slow_mode.get(&chatroom_id),
+            self.last_sent.get(&chatroom_id),
+        ) {
+            (true, Some(interval), Some(last_sent)) => {
+                (*last_sent + *interval).saturating_duration_since(now)
+            }
+            _ => Duration::ZERO,
+        };
+
+        bucket_wait.max(slow_mode_wait)
+    }
+}
+
+impl RateLimiter {
+    /// Creates a new instance of `RateLimiter` allowing bursts of `capacity` messages,
+    /// refilled over `period`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or `period` is zero.
+    pub fn new(capacity: u32, period: Duration) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        assert!(!period.is_zero(), "period must be positive");
+        Self {
+            state: Arc::new(Mutex::new(LimiterState {
+                capacity: capacity.into(),
+                refill_rate: f64::from(capacity) / period.as_secs_f64(),
+                tokens: capacity.into(),
+                last_refill: Instant::now(),
+                honor_slow_mode: true,
+                slow_mode: HashMap::new(),
+                last_sent: HashMap::new(),
+            })),
+        }
+    }
+
+    /// Sets whether slow mode applies, e.g. `false` for moderator accounts which Kick
+    /// exempts from it.
+    pub fn with_slow_mode(self, honor_slow_mode: bool) -> Self {
+        self.lock().honor_slow_mode = honor_slow_mode;
+        self
+    }
+
+    /// Sets the slow mode interval of a chatroom, or `None` if slow mode is disabled.
+    pub fn set_slow_mode(&self, chatroom_id: u32, interval: Option<Duration>) {
+        let mut state = self.lock();
+        match interval {
+            Some(interval) => state.slow_mode.insert(chatroom_id, interval),
+            None => state.slow_mode.remove(&chatroom_id),
+        };
+    }
+
+    /// Applies the slow mode settings of a chatroom update.
+    pub fn apply_chatroom_update(&self, update: &ChatroomUpdatedEventData) {
+        let interval = update
+            .slow_mode
+            .enabled
+            .then(|| Duration::from_secs(update.slow_mode.message_interval));
+        self.set_slow_mode(update.id, interval);
+    }
+
+    /// Updates the limiter from a received message, keeping slow mode intervals current.
+    pub fn observe(&self, message: &KickChatMessage) {
+        if let MessageData::ChatroomUpdated(update) = &message.data {
+            self.apply_chatroom_update(update);
+        }
+    }
+
+    /// Takes a token for sending to the chatroom if one is available right away.
+    ///
+    /// # Errors
+    ///
+    /// Returns how long to wait before trying again if no message may be sent yet.
+    pub fn try_acquire(&self, chatroom_id: u32) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut state = self.lock();
+        let wait = state.wait_time(chatroom_id, now);
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+        state.tokens -= 1.0;
+        state.last_sent.insert(chatroom_id, now);
+        Ok(())
+    }
+
+    /// Waits until a message may be sent to the chatroom and takes a token for it.
+    pub async fn acquire(&self, chatroom_id: u32) {
+        while let Err(wait) = self.try_acquire(chatroom_id) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LimiterState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for RateLimiter {
+    /// Creates a conservative limiter allowing 5 messages per 10 seconds.
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY, DEFAULT_PERIOD)
+    }
+}
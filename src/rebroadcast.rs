@@ -0,0 +1,339 @@
+//! A local WebSocket server re-serving one upstream connection to many clients.
+//!
+//! Tools running on the same machine connect to the server instead of Kick, so a single
+//! upstream connection serves all of them. The server speaks enough of the Pusher protocol
+//! for `KickClient` and other Pusher clients to connect to it unchanged: connections are
+//! greeted with `pusher:connection_established`, subscriptions to `chatrooms.{id}.v2` are
+//! confirmed and pings are answered. Messages are sent as Kick sends them.
+//!
+//! Each client chooses what it receives. Subscribing to a chatroom's channel, or listing
+//! chatrooms in the `chatrooms` query parameter of the URL, e.g. `?chatrooms=668,669`,
+//! selects chatrooms, and the `kinds` query parameter, e.g. `?kinds=chat,ban`, restricts
+//! the messages received to those `MessageData::kind`s. The server never subscribes to
+//! chatrooms upstream, so clients only receive chatrooms the upstream connection joined.
+//!
+//! Messages sent to a client too slow to keep up are dropped once too many frames are
+//! waiting to be written to it, and counted in `RebroadcastServer::dropped`.
+
+use crate::{channel_chatroom_id, KickChatMessage, KickError, MessageSource};
+use futures_util::future::select;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::pin::pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::net::{TcpListener, TcpStream, ToSocketAddrs};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+/// How many frames may wait to be written to a client before messages are dropped.
+const CLIENT_BUFFER: usize = 1024;
+
+/// A local WebSocket server rebroadcasting messages to the clients connected to it.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::rebroadcast::RebroadcastServer;
+/// use kick_client::KickClient;
+///
+/// let upstream = KickClient::new(kick_client::DEFAULT_WEBSOCKET_URL, vec![668, 669]).await.unwrap();
+/// let server = RebroadcastServer::bind("127.0.0.1:8765").await?;
+/// // Local tools connect to `server.url()` as they would to Kick, optionally appending
+/// // e.g. `&kinds=chat` to only receive chat messages.
+/// println!("{}", server.url());
+/// server.forward(upstream).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RebroadcastServer {
+    addr: SocketAddr,
+    clients: Arc<Mutex<Vec<Client>>>,
+    accept: JoinHandle<()>,
+    /// The number of messages dropped because a client had too many frames waiting.
+    dropped: AtomicU64,
+}
+
+struct Client {
+    id: usize,
+    filter: ClientFilter,
+    outgoing: Queue,
+}
+
+/// The frames waiting to be written to a client.
+#[derive(Clone)]
+struct Queue {
+    frames: mpsc::UnboundedSender<Outgoing>,
+    /// How many frames were queued and not written yet.
+    queued: Arc<AtomicUsize>,
+}
+
+impl Queue {
+    /// Queues a frame, however many are waiting, returning `false` if the client is gone.
+    fn send(&self, outgoing: Outgoing) -> bool {
+        self.queued.fetch_add(1, Ordering::Relaxed);
+        let sent = self.frames.send(outgoing).is_ok();
+        if !sent {
+            self.queued.fetch_sub(1, Ordering::Relaxed);
+        }
+        sent
+    }
+
+    /// Returns `true` if so many frames are waiting that messages are dropped.
+    fn is_full(&self) -> bool {
+        self.queued.load(Ordering::Relaxed) >= CLIENT_BUFFER
+    }
+}
+
+enum Outgoing {
+    Frame(String),
+    Close,
+}
+
+/// What a client receives.
+#[derive(Default)]
+struct ClientFilter {
+    chatrooms: HashSet<u32>,
+    /// The kinds of messages received, or `None` for every kind.
+    kinds: Option<HashSet<String>>,
+}
+
+impl ClientFilter {
+    /// Parses the filter from the query string of the URL a client connected to.
+    fn from_query(query: &str) -> Self {
+        let mut filter = Self::default();
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            let values = value
+                .split([',', ' '])
+                .flat_map(|value| value.split("%2C"))
+                .flat_map(|value| value.split("%2c"))
+                .filter(|value| !value.is_empty());
+            match key {
+                "chatrooms" => filter
+                    .chatrooms
+                    .extend(values.filter_map(|value| value.parse::<u32>().ok())),
+                "kinds" => filter
+                    .kinds
+                    .get_or_insert_with(HashSet::new)
+                    .extend(values.map(str::to_string)),
+                _ => {}
+            }
+        }
+        filter
+    }
+
+    fn accepts(&self, chatroom_id: u32, kind: &str) -> bool {
+        self.chatrooms.contains(&chatroom_id)
+            && self.kinds.as_ref().is_none_or(|kinds| kinds.contains(kind))
+    }
+}
+
+impl RebroadcastServer {
+    /// Starts a server accepting WebSocket clients on the given address.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the address cannot be bound.
+    pub async fn bind(addr: impl ToSocketAddrs) -> Result<Self, KickError> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let clients = Arc::new(Mutex::new(Vec::new()));
+        let accept = crate::trace::spawn("kick_client::rebroadcast::accept", accept_loop(listener, clients.clone()));
+        Ok(Self {
+            addr,
+            clients,
+            accept,
+            dropped: AtomicU64::new(0),
+        })
+    }
+
+    /// Returns the address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the WebSocket URL to connect to the server with, in the shape of Kick's.
+    pub fn url(&self) -> String {
+        format!(
+            "ws://{}/app/local?protocol=7&client=js&version=8.4.0-rc2&flash=false",
+            self.addr
+        )
+    }
+
+    /// Returns the number of connected clients.
+    pub fn connected(&self) -> usize {
+        lock(&self.clients).len()
+    }
+
+    /// Returns the number of connected clients receiving a chatroom, whatever kinds of
+    /// messages they receive.
+    pub fn subscribers(&self, chatroom_id: u32) -> usize {
+        lock(&self.clients)
+            .iter()
+            .filter(|client| client.filter.chatrooms.contains(&chatroom_id))
+            .count()
+    }
+
+    /// Returns the number of messages dropped because a client had too many frames waiting.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Sends a message to every client whose filter accepts it, returning how many clients
+    /// it was queued for. Messages received on no chatroom, such as Pusher's own events, are
+    /// sent to no one, as the server answers these itself.
+    pub fn send(&self, message: &KickChatMessage) -> usize {
+        let Some(chatroom_id) = message.chatroom_id() else {
+            return 0;
+        };
+        let kind = message.data.kind();
+        let Ok(frame) = serde_json::to_string(message) else {
+            return 0;
+        };
+        lock(&self.clients)
+            .iter()
+            .filter(|client| client.filter.accepts(chatroom_id, kind))
+            .filter(|client| {
+                if client.outgoing.is_full() {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return false;
+                }
+                client.outgoing.send(Outgoing::Frame(frame.clone()))
+            })
+            .count()
+    }
+
+    /// Sends every message read from `source` to the clients, until the source ends.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if reading from the source fails.
+    pub async fn forward<S: MessageSource>(&self, mut source: S) -> Result<(), KickError> {
+        while let Some(message) = source.read_message().await? {
+            self.send(&message);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RebroadcastServer {
+    fn drop(&mut self) {
+        self.accept.abort();
+        for client in lock(&self.clients).drain(..) {
+            let _ = client.outgoing.send(Outgoing::Close);
+        }
+    }
+}
+
+fn lock(clients: &Mutex<Vec<Client>>) -> std::sync::MutexGuard<'_, Vec<Client>> {
+    clients.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+async fn accept_loop(listener: TcpListener, clients: Arc<Mutex<Vec<Client>>>) {
+    let mut next_id = 0;
+    while let Ok((stream, _)) = listener.accept().await {
+        next_id += 1;
+        crate::trace::spawn("kick_client::rebroadcast::connection", serve(stream, next_id, clients.clone()));
+    }
+}
+
+/// Serves a single client until either side closes the connection.
+async fn serve(stream: TcpStream, id: usize, clients: Arc<Mutex<Vec<Client>>>) {
+    let mut query = String::new();
+    let callback = |request: &Request, response: Response| {
+        query = request.uri().query().unwrap_or_default().to_string();
+        Ok(response)
+    };
+    let Ok(websocket) = tokio_tungstenite::accept_hdr_async(stream, callback).await else {
+        return;
+    };
+    let (mut write, mut read) = websocket.split();
+    let (frames, mut queue) = mpsc::unbounded_channel();
+    let outgoing = Queue {
+        frames,
+        queued: Arc::new(AtomicUsize::new(0)),
+    };
+
+    lock(&clients).push(Client {
+        id,
+        filter: ClientFilter::from_query(&query),
+        outgoing: outgoing.clone(),
+    });
+    let _ = outgoing.send(Outgoing::Frame(pusher_frame(
+        "pusher:connection_established",
+        serde_json::json!({ "socket_id": format!("{}.{}", id, id), "activity_timeout": 120 }),
+        None,
+    )));
+
+    let queued = outgoing.queued.clone();
+    let writer = async move {
+        while let Some(message) = queue.recv().await {
+            queued.fetch_sub(1, Ordering::Relaxed);
+            match message {
+                Outgoing::Frame(frame) => {
+                    if write.send(Message::Text(frame.into())).await.is_err() {
+                        return;
+                    }
+                }
+                Outgoing::Close => {
+                    let _ = write.send(Message::Close(None)).await;
+                    return;
+                }
+            }
+        }
+    };
+    let reader = async {
+        while let Some(Ok(message)) = read.next().await {
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let Ok(frame) = serde_json::from_str::<serde_json::Value>(&text) else {
+                continue;
+            };
+            let channel = frame["data"]["channel"].as_str();
+            let chatroom_id = channel.and_then(channel_chatroom_id);
+            match (frame["event"].as_str(), channel, chatroom_id) {
+                (Some("pusher:subscribe"), Some(channel), Some(chatroom_id)) => {
+                    if let Some(client) = lock(&clients).iter_mut().find(|c| c.id == id) {
+                        client.filter.chatrooms.insert(chatroom_id);
+                    }
+                    let _ = outgoing.send(Outgoing::Frame(pusher_frame(
+                        "pusher_internal:subscription_succeeded",
+                        serde_json::json!({}),
+                        Some(channel),
+                    )));
+                }
+                (Some("pusher:unsubscribe"), _, Some(chatroom_id)) => {
+                    if let Some(client) = lock(&clients).iter_mut().find(|c| c.id == id) {
+                        client.filter.chatrooms.remove(&chatroom_id);
+                    }
+                }
+                (Some("pusher:ping"), _, _) => {
+                    let _ = outgoing.send(Outgoing::Frame(pusher_frame(
+                        "pusher:pong",
+                        serde_json::json!({}),
+                        None,
+                    )));
+                }
+                _ => {}
+            }
+        }
+    };
+
+    // Stops once the client disconnects, or the server drops the client's queue.
+    let _ = select(pin!(writer), pin!(reader)).await;
+    lock(&clients).retain(|client| client.id != id);
+}
+
+/// Builds a Pusher frame, with `data` encoded as a JSON string.
+fn pusher_frame(event: &str, data: serde_json::Value, channel: Option<&str>) -> String {
+    let mut frame = serde_json::json!({ "event": event, "data": data.to_string() });
+    if let Some(channel) = channel {
+        frame["channel"] = channel.into();
+    }
+    frame.to_string()
+}
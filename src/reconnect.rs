@@ -0,0 +1,419 @@
+#[cfg(feature = "health")]
+use crate::health::Health;
+use crate::trace::Instrument;
+use crate::transport::ConnectOptions;
+use crate::{KickChatMessage, KickClient, KickError, MessageData, MessageSource, PossibleGapData};
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+/// How long to wait before the first reconnection attempt by default.
+const DEFAULT_INITIAL_DELAY: Duration = Duration::from_secs(1);
+/// The longest time to wait between reconnection attempts by default.
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(60);
+/// How long a disconnection may last by default before a `PossibleGap` is reported.
+const DEFAULT_GAP_THRESHOLD: Duration = Duration::from_secs(5);
+/// How many message IDs are remembered by default to drop duplicates.
+const DEFAULT_DEDUPE_WINDOW: usize = 1000;
+
+/// Remembers the IDs of recent messages to recognize duplicates.
+#[derive(Debug)]
+pub struct Deduplicator {
+    window: usize,
+    seen: HashSet<String>,
+    /// The remembered IDs, oldest first.
+    order: VecDeque<String>,
+}
+
+impl Deduplicator {
+    /// Creates a new instance of `Deduplicator` remembering the last `window` IDs.
+    pub fn new(window: usize) -> Self {
+        Self {
+            window,
+            seen: HashSet::with_capacity(window),
+            order: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// Records a message, returning `true` if a message with the same ID was recorded
+    /// before. Messages without an ID are never duplicates.
+    pub fn is_duplicate(&mut self, message: &KickChatMessage) -> bool {
+        let Some(id) = message_id(message) else {
+            return false;
+        };
+        if self.seen.contains(id) {
+            return true;
+        }
+        if self.window == 0 {
+            return false;
+        }
+        if self.order.len() >= self.window {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        self.seen.insert(id.to_string());
+        self.order.push_back(id.to_string());
+        false
+    }
+}
+
+/// Returns the ID Kick assigned to a message, if it has one.
+fn message_id(message: &KickChatMessage) -> Option<&str> {
+    match &message.data {
+        MessageData::ChatMessage(data) => Some(&data.id),
+        MessageData::DeletedMessage(data) => Some(&data.id),
+        MessageData::UserBanned(data) => Some(&data.id),
+        MessageData::UserUnbanned(data) => Some(&data.id),
+        _ => None,
+    }
+}
+
+/// The health of an endpoint a `ReconnectingClient` connects to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointHealth {
+    /// The WebSocket URL of the endpoint.
+    pub url: String,
+    /// The number of failed connection attempts since the last successful one.
+    pub consecutive_failures: u32,
+    /// When connecting last failed, if it ever did.
+    pub last_failure: Option<Instant>,
+    /// When a connection was last established, if one ever was.
+    pub last_connected: Option<Instant>,
+}
+
+impl EndpointHealth {
+    fn new(url: String) -> Self {
+        Self {
+            url,
+            consecutive_failures: 0,
+            last_failure: None,
+            last_connected: None,
+        }
+    }
+}
+
+/// A `KickClient` that reconnects with exponential backoff whenever the connection fails
+/// or drops.
+///
+/// Fallback endpoints, such as other Pusher clusters, are tried in order when connecting
+/// fails. Each endpoint backs off on its own, so the first endpoint which isn't backing off
+/// is always preferred, and the client returns to the primary endpoint once it recovers.
+///
+/// With an idle timeout, a connection on which nothing, not even a pong, was received for
+/// that long is considered dead and replaced, catching half-open TCP connections which
+/// would otherwise never fail.
+///
+/// Messages received twice around a reconnection are dropped. After a disconnection
+/// longer than the gap threshold, a `MessageData::PossibleGap` marker is returned before
+/// the first message of the new connection, so consumers know messages may be missing.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::reconnect::ReconnectingClient;
+/// use kick_client::DEFAULT_WEBSOCKET_URL;
+///
+/// let mut client = ReconnectingClient::new(DEFAULT_WEBSOCKET_URL, vec![1234]);
+/// while let Some(message) = client.read_message().await? {
+///     println!("{}", message);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReconnectingClient {
+    /// The primary endpoint followed by the fallbacks, in order of preference.
+    endpoints: Vec<EndpointHealth>,
+    /// The index of the endpoint of the current connection.
+    current: Option<usize>,
+    channel_ids: Vec<u64>,
+    client: Option<KickClient>,
+    initial_delay: Duration,
+    max_delay: Duration,
+    gap_threshold: Duration,
+    deduplicator: Deduplicator,
+    options: ConnectOptions,
+    /// How long a connection may stay silent before it is replaced, if limited.
+    idle_timeout: Option<Duration>,
+    /// When the last frame was received on the current connection.
+    last_frame_at: Option<Instant>,
+    /// When the connection was lost, if it hasn't been restored yet.
+    disconnected_at: Option<Instant>,
+    /// The health reported to the health endpoints, if any.
+    #[cfg(feature = "health")]
+    health: Option<Health>,
+}
+
+impl ReconnectingClient {
+    /// Creates a new instance of `ReconnectingClient`. The connection is established on
+    /// the first read.
+    pub fn new(url: impl Into<String>, channel_ids: Vec<u64>) -> Self {
+        Self {
+            endpoints: vec![EndpointHealth::new(url.into())],
+            current: None,
+            channel_ids,
+            client: None,
+            initial_delay: DEFAULT_INITIAL_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            gap_threshold: DEFAULT_GAP_THRESHOLD,
+            deduplicator: Deduplicator::new(DEFAULT_DEDUPE_WINDOW),
+            options: ConnectOptions::default(),
+            idle_timeout: None,
+            last_frame_at: None,
+            disconnected_at: None,
+            #[cfg(feature = "health")]
+            health: None,
+        }
+    }
+
+    /// Sets the delay before the first reconnection attempt, doubled after each failed
+    /// attempt up to `max`.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_delay = initial;
+        self.max_delay = max;
+        self
+    }
+
+    /// Adds endpoints to connect to, in order, when connecting to the primary endpoint and
+    /// the fallbacks added before fails.
+    pub fn with_fallback_urls<I, U>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = U>,
+        U: Into<String>,
+    {
+        self.endpoints
+            .extend(urls.into_iter().map(|url| EndpointHealth::new(url.into())));
+        self
+    }
+
+    /// Sets how long a disconnection may last before a `PossibleGap` is reported.
+    pub fn with_gap_threshold(mut self, threshold: Duration) -> Self {
+        self.gap_threshold = threshold;
+        self
+    }
+
+    /// Sets how many message IDs are remembered to drop duplicates.
+    pub fn with_dedupe_window(mut self, window: usize) -> Self {
+        self.deduplicator = Deduplicator::new(window);
+        self
+    }
+
+    /// Establishes every connection as configured by `options`.
+    pub fn with_connect_options(mut self, options: ConnectOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Reconnects whenever nothing is received for `timeout`. Pusher expects clients to
+    /// ping it during quiet periods, so the timeout should be longer than the ping
+    /// interval for quiet chatrooms to stay connected.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Reports the connection state, received messages and reconnections to `health`.
+    #[cfg(feature = "health")]
+    pub fn with_health(mut self, health: Health) -> Self {
+        self.health = Some(health);
+        self
+    }
+
+    /// Returns when the last frame was received on the current connection, or `None` while
+    /// disconnected.
+    pub fn last_frame_at(&self) -> Option<Instant> {
+        self.client.as_ref().and(self.last_frame_at)
+    }
+
+    /// Returns the health of every endpoint, the primary one first.
+    pub fn endpoints(&self) -> &[EndpointHealth] {
+        &self.endpoints
+    }
+
+    /// Returns the URL of the endpoint the client is connected to, if connected.
+    pub fn current_url(&self) -> Option<&str> {
+        let index = self.current.filter(|_| self.client.is_some())?;
+        Some(&self.endpoints[index].url)
+    }
+
+    /// Returns the ID Pusher gave the current connection, if connected and already known.
+    pub fn socket_id(&self) -> Option<&str> {
+        self.client.as_ref()?.socket_id()
+    }
+
+    /// Subscribes to another chatroom, now if connected and on every reconnection. If the
+    /// subscription cannot be sent, the client reconnects on the next read.
+    pub async fn subscribe(&mut self, channel_id: u64) {
+        if self.channel_ids.contains(&channel_id) {
+            return;
+        }
+        self.channel_ids.push(channel_id);
+        if let Some(client) = &mut self.client {
+            if client.subscribe(channel_id).await.is_err() {
+                self.disconnect();
+            }
+        }
+    }
+
+    /// Unsubscribes from a chatroom. If the request cannot be sent, the client reconnects
+    /// on the next read.
+    pub async fn unsubscribe(&mut self, channel_id: u64) {
+        self.channel_ids.retain(|id| *id != channel_id);
+        if let Some(client) = &mut self.client {
+            if client.unsubscribe(channel_id).await.is_err() {
+                self.disconnect();
+            }
+        }
+    }
+
+    /// Returns the IDs of the subscribed chatrooms.
+    pub fn channel_ids(&self) -> &[u64] {
+        &self.channel_ids
+    }
+
+    /// Closes the current connection, if any. The client connects again on the next read.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the close request cannot be sent.
+    pub async fn close(&mut self) -> Result<(), KickError> {
+        let Some(mut client) = self.client.take() else {
+            return Ok(());
+        };
+        self.disconnect();
+        client.close().await
+    }
+
+    /// Returns `true` if the client is currently connected.
+    pub fn is_connected(&self) -> bool {
+        self.client.is_some()
+    }
+
+    /// Reads the next message, reconnecting as often as needed. Never returns `None`.
+    ///
+    /// # Errors
+    ///
+    /// Connection errors are retried rather than returned, so this function never returns
+    /// an error; the `Result` matches `MessageSource`.
+    pub async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        loop {
+            let Some(client) = &mut self.client else {
+                if let Some(gap) = self.connect().await {
+                    return Ok(Some(gap));
+                }
+                continue;
+            };
+            let last_frame_at = *self.last_frame_at.get_or_insert_with(Instant::now);
+            let read = match self.idle_timeout {
+                Some(idle) => {
+                    let remaining = idle.saturating_sub(last_frame_at.elapsed());
+                    tokio::time::timeout(remaining, client.read_message()).await
+                }
+                None => Ok(client.read_message().await),
+            };
+            match read {
+                Ok(Ok(Some(message))) => {
+                    self.last_frame_at = Some(Instant::now());
+                    #[cfg(feature = "health")]
+                    if let Some(health) = &self.health {
+                        health.observe(&message);
+                    }
+                    if !self.deduplicator.is_duplicate(&message) {
+                        return Ok(Some(message));
+                    }
+                }
+                Ok(Ok(None) | Err(_)) => self.disconnect(),
+                // Nothing was heard since the last frame, so that is when the connection
+                // was last known to be alive.
+                Err(_) => {
+                    self.disconnect();
+                    self.disconnected_at = Some(last_frame_at);
+                }
+            }
+        }
+    }
+
+    /// Connects, retrying with backoff, and returns a `PossibleGap` marker if the client
+    /// was disconnected for too long.
+    async fn connect(&mut self) -> Option<KickChatMessage> {
+        loop {
+            let (index, retry_at) = self.next_endpoint();
+            let endpoint = &mut self.endpoints[index];
+            if let Some(retry_at) = retry_at {
+                tokio::time::sleep_until(retry_at.into())
+                    .instrument(span!(
+                        "backoff",
+                        url = %endpoint.url,
+                        failures = endpoint.consecutive_failures
+                    ))
+                    .await;
+            }
+            // The error isn't `Send`, so it must not be held across the sleep above.
+            let client =
+                KickClient::connect_with(&endpoint.url, self.channel_ids.clone(), &self.options)
+                    .await
+                    .ok();
+            let now = Instant::now();
+            if let Some(client) = client {
+                endpoint.consecutive_failures = 0;
+                endpoint.last_connected = Some(now);
+                self.current = Some(index);
+                self.client = Some(client);
+                self.last_frame_at = Some(now);
+                #[cfg(feature = "health")]
+                if let Some(health) = &self.health {
+                    health.connected();
+                }
+                break;
+            }
+            endpoint.consecutive_failures = endpoint.consecutive_failures.saturating_add(1);
+            endpoint.last_failure = Some(now);
+        }
+
+        let downtime = self.disconnected_at.take()?.elapsed();
+        (downtime > self.gap_threshold).then(|| KickChatMessage {
+            data: MessageData::PossibleGap(PossibleGapData {
+                downtime_ms: downtime.as_millis().try_into().unwrap_or(u64::MAX),
+            }),
+            channel: None,
+        })
+    }
+}
+
+impl ReconnectingClient {
+    fn disconnect(&mut self) {
+        self.client = None;
+        self.last_frame_at = None;
+        self.disconnected_at = Some(Instant::now());
+        #[cfg(feature = "health")]
+        if let Some(health) = &self.health {
+            health.disconnected();
+        }
+    }
+
+    /// Returns the index of the endpoint to connect to next, with when it may be tried if
+    /// it is still backing off: the first endpoint which isn't, or else the one which will
+    /// be ready the soonest.
+    fn next_endpoint(&self) -> (usize, Option<Instant>) {
+        let now = Instant::now();
+        let (initial, max) = (self.initial_delay, self.max_delay);
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let failures = endpoint.consecutive_failures.checked_sub(1)?;
+                let backoff = initial
+                    .saturating_mul(2u32.saturating_pow(failures))
+                    .min(max);
+                Some(endpoint.last_failure? + backoff).filter(|retry_at| *retry_at > now)
+            })
+            .enumerate()
+            .min_by_key(|(index, retry_at)| (*retry_at, *index))
+            .unwrap_or((0, None))
+    }
+}
+
+impl MessageSource for ReconnectingClient {
+    async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        ReconnectingClient::read_message(self).await
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{parse_frame, KickChatMessage, KickError, MessageSource};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// A raw frame received from Kick, as stored in a recording, one per line.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordedFrame {
+    /// When the frame was received, in milliseconds since the Unix epoch.
+    pub received_at: u64,
+    /// The Pusher channel the frame was received on, if any.
+    pub channel: Option<String>,
+    /// The frame, exactly as received.
+    pub frame: String,
+}
+
+impl RecordedFrame {
+    /// Creates a new instance of `RecordedFrame` for a frame received now.
+    pub fn new(frame: impl Into<String>) -> Self {
+        let frame = frame.into();
+        Self {
+            received_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| {
+                    elapsed.as_millis().try_into().unwrap_or(u64::MAX)
+                }),
+            channel: frame_channel(&frame),
+            frame,
+        }
+    }
+}
+
+/// Returns the `channel` field of a raw Pusher frame.
+fn frame_channel(frame: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Envelope {
+        channel: Option<String>,
+    }
+
+    serde_json::from_str::<Envelope>(frame).ok()?.channel
+}
+
+/// Writes every raw frame a client receives to a JSONL file.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::recording::Recorder;
+///
+/// let mut client = client.with_recorder(Recorder::create("session.jsonl")?);
+/// while let Some(message) = client.read_message().await? {
+///     println!("{}", message);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Recorder {
+    writer: Box<dyn Write + Send>,
+}
+
+impl Recorder {
+    /// Creates a new instance of `Recorder` writing to `writer`.
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Box::new(writer),
+        }
+    }
+
+    /// Creates a new instance of `Recorder` writing to a file, truncating it if it exists.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, KickError> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    /// Creates a new instance of `Recorder` appending to a file, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be opened.
+    pub fn append(path: impl AsRef<Path>) -> Result<Self, KickError> {
+        let file = File::options().create(true).append(true).open(path)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+
+    /// Records a raw frame received now.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if writing the frame fails.
+    pub fn record(&mut self, frame: &str) -> Result<(), KickError> {
+        self.write(&RecordedFrame::new(frame))
+    }
+
+    /// Writes a recorded frame as a line, flushing it so recordings survive crashes.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if writing the frame fails.
+    pub fn write(&mut self, frame: &RecordedFrame) -> Result<(), KickError> {
+        serde_json::to_writer(&mut self.writer, frame)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// How fast a `ReplayClient` replays a recording.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pacing {
+    /// Return frames as fast as they are read.
+    Instant,
+    /// Wait between frames as long as between their recording.
+    RealTime,
+    /// Wait between frames as long as between their recording, divided by the factor.
+    Accelerated(f64),
+}
+
+/// A message source replaying a recording made by a `Recorder`.
+///
+/// Frames are parsed exactly as `KickClient` parses them, so code written against a
+/// `MessageSource` behaves the same on a recording as on a live connection.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::recording::{Pacing, ReplayClient};
+///
+/// let mut replay = ReplayClient::open("session.jsonl")?.with_pacing(Pacing::Accelerated(10.0));
+/// while let Ok(Some(message)) = replay.read_message().await {
+///     println!("{}", message);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct ReplayClient {
+    lines: Lines<Box<dyn BufRead + Send>>,
+    pacing: Pacing,
+    /// When the first frame was recorded and replayed.
+    started: Option<(u64, Instant)>,
+}
+
+impl ReplayClient {
+    /// Creates a new instance of `ReplayClient` replaying a recording read from `reader`.
+    pub fn new(reader: impl BufRead + Send + 'static) -> Self {
+        let reader: Box<dyn BufRead + Send> = Box::new(reader);
+        Self {
+            lines: reader.lines(),
+            pacing: Pacing::Instant,
+            started: None,
+        }
+    }
+
+    /// Creates a new instance of `ReplayClient` replaying a recording file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be opened.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, KickError> {
+        Ok(Self::new(BufReader::new(File::open(path)?)))
+    }
+
+    /// Sets how fast the recording is replayed.
+    pub fn with_pacing(mut self, pacing: Pacing) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    /// Reads the next recorded frame, waiting as the pacing requires.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `KickError::StreamEnded` once the recording is exhausted,
+    /// or another error if reading a frame from it fails.
+    pub async fn read_frame(&mut self) -> Result<RecordedFrame, KickError> {
+        let frame = loop {
+            match self.lines.next() {
+                Some(line) => {
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        break serde_json::from_str::<RecordedFrame>(&line)?;
+                    }
+                }
+                None => return Err(KickError::StreamEnded),
+            }
+        };
+
+        let speed = match self.pacing {
+            Pacing::Instant => return Ok(frame),
+            Pacing::RealTime => 1.0,
+            Pacing::Accelerated(speed) => speed,
+        };
+        let (first_recorded, first_replayed) = *self
+            .started
+            .get_or_insert((frame.received_at, Instant::now()));
+        let offset = Duration::from_millis(frame.received_at.saturating_sub(first_recorded));
+        let target = first_replayed + offset.div_f64(speed);
+        tokio::time::sleep_until(target.into()).await;
+        Ok(frame)
+    }
+
+    /// Reads and parses the next recorded frame, waiting as the pacing requires.
+    ///
+    /// # Errors
+    ///
+    /// This function will return `KickError::StreamEnded` once the recording is exhausted,
+    /// or another error if reading a frame from it fails.
+    pub async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        let frame = self.read_frame().await?;
+        Ok(Some(parse_frame(frame.frame.as_bytes())))
+    }
+}
+
+impl MessageSource for ReplayClient {
+    async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        ReplayClient::read_message(self).await
+    }
+}
@@ -0,0 +1,191 @@
+use crate::content::emotes;
+use crate::{ChatMessageEventData, KickChatMessage, MessageData};
+use std::fmt::Write;
+
+/// Where Kick serves emote images from.
+pub const EMOTE_CDN_URL: &str = "https://files.kick.com/emotes";
+
+/// Renders messages as sanitized HTML for browser-source overlays.
+///
+/// Chat messages are rendered as a `div` with the `chat-message` class, containing the
+/// sender's badges, their username in their color and the content, with emotes as `img`
+/// tags and mentions in `span`s with the `mention` class. Other events are rendered as a
+/// `div` with the `system-event` class. Everything taken from a message is escaped.
+///
+/// # Examples
+///
+/// ```
+/// use kick_client::render::html::HtmlRenderer;
+///
+/// let renderer = HtmlRenderer::new().with_highlighted_user("Streamer");
+/// assert_eq!(
+///     renderer.render_content("<b>hi</b> @streamer [emote:37226:KEKW]"),
+///     "&lt;b&gt;hi&lt;/b&gt; <span class=\"mention highlighted\">@streamer</span> \
+///      <img class=\"emote\" src=\"https://files.kick.com/emotes/37226/fullsize\" \
+///      alt=\"KEKW\" title=\"KEKW\">"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct HtmlRenderer {
+    emote_url: String,
+    highlighted: Vec<String>,
+}
+
+impl Default for HtmlRenderer {
+    fn default() -> Self {
+        Self {
+            emote_url: EMOTE_CDN_URL.to_string(),
+            highlighted: Vec::new(),
+        }
+    }
+}
+
+impl HtmlRenderer {
+    /// Creates a new instance of `HtmlRenderer` loading emotes from Kick's CDN.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the base URL emote images are loaded from, as `{url}/{id}/fullsize`, e.g. to
+    /// serve them from a local cache.
+    pub fn with_emote_url(mut self, url: impl Into<String>) -> Self {
+        self.emote_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Adds a user whose mentions get the `highlighted` class, and whose messages mentioning
+    /// them get it too. Usernames are compared case-insensitively.
+    pub fn with_highlighted_user(mut self, username: impl Into<String>) -> Self {
+        self.highlighted.push(username.into().to_lowercase());
+        self
+    }
+
+    /// Renders a message as a single HTML element.
+    pub fn render(&self, message: &KickChatMessage) -> String {
+        match &message.data {
+            MessageData::ChatMessage(data) => self.render_chat_message(data),
+            _ => format!(
+                "<div class=\"system-event\">{}</div>",
+                escape(&message.to_string())
+            ),
+        }
+    }
+
+    /// Renders a chat message as a single HTML element.
+    pub fn render_chat_message(&self, message: &ChatMessageEventData) -> String {
+        let content = message.content.as_deref().unwrap_or_default();
+        let mut html = String::from("<div class=\"chat-message");
+        if mentions(content).any(|mention| self.is_highlighted(mention)) {
+            html.push_str(" highlighted");
+        }
+        let _ = write!(html, "\" data-id=\"{}\">", escape(&message.id));
+        html.push_str("<span class=\"badges\">");
+        for badge in &message.sender.identity.badges {
+            let _ = write!(
+                html,
+                "<span class=\"badge badge-{}\" title=\"{}\"></span>",
+                class_name(badge.badge_type()),
+                escape(badge.text())
+            );
+        }
+        html.push_str("</span><span class=\"username\"");
+        if let Some(color) = message.sender.identity.color.as_deref() {
+            if is_hex_color(color) {
+                let _ = write!(html, " style=\"color: {}\"", color);
+            }
+        }
+        let _ = write!(
+            html,
+            ">{}</span>: <span class=\"content\">{}</span></div>",
+            escape(&message.sender.username),
+            self.render_content(content)
+        );
+        html
+    }
+
+    /// Renders the content of a chat message, with emotes as `img` tags and mentions
+    /// highlighted.
+    pub fn render_content(&self, content: &str) -> String {
+        let mut html = String::with_capacity(content.len());
+        let mut offset = 0;
+        for emote in emotes(content) {
+            self.render_text(&content[offset..emote.range.start], &mut html);
+            let name = escape(emote.name);
+            let _ = write!(
+                html,
+                "<img class=\"emote\" src=\"{}/{}/fullsize\" alt=\"{name}\" title=\"{name}\">",
+                escape(&self.emote_url),
+                emote.id
+            );
+            offset = emote.range.end;
+        }
+        self.render_text(&content[offset..], &mut html);
+        html
+    }
+
+    /// Renders text without emotes, highlighting its mentions.
+    fn render_text(&self, text: &str, html: &mut String) {
+        let mut offset = 0;
+        for mention in mentions(text) {
+            // Mentions are subslices of `text`.
+            let start = mention.as_ptr() as usize - text.as_ptr() as usize;
+            html.push_str(&escape(&text[offset..start]));
+            let class = if self.is_highlighted(mention) {
+                "mention highlighted"
+            } else {
+                "mention"
+            };
+            let _ = write!(html, "<span class=\"{class}\">{}</span>", escape(mention));
+            offset = start + mention.len();
+        }
+        html.push_str(&escape(&text[offset..]));
+    }
+
+    fn is_highlighted(&self, mention: &str) -> bool {
+        let username = mention.trim_start_matches('@').to_lowercase();
+        self.highlighted.contains(&username)
+    }
+}
+
+/// Escapes text for use in HTML content and quoted attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns the mentions in text, such as `@SomeViewer`, including the `@`.
+fn mentions(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace().filter_map(|word| {
+        let name = word.strip_prefix('@')?;
+        let length = name
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(name.len());
+        (length > 0).then(|| &word[..=length])
+    })
+}
+
+/// Returns `true` if a color is written as `#RRGGBB`, so it's safe in a `style` attribute.
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Returns a badge type with anything but ASCII letters, digits, `-` and `_` removed, so
+/// it's safe in a class name.
+fn class_name(badge_type: &str) -> String {
+    badge_type
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
@@ -0,0 +1,156 @@
+use crate::{ChatMessageEventData, KickChatMessage, MessageData};
+use std::fmt::Write;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+
+/// Formats messages for terminals, with the sender's color, badge glyphs and dimmed
+/// system events.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::render::terminal::TerminalFormatter;
+///
+/// let formatter = TerminalFormatter::from_env();
+/// while let Some(message) = client.read_message().await? {
+///     println!("{}", formatter.format(&message));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TerminalFormatter {
+    colors: bool,
+    glyphs: bool,
+    timestamps: bool,
+}
+
+impl Default for TerminalFormatter {
+    fn default() -> Self {
+        Self {
+            colors: true,
+            glyphs: true,
+            timestamps: true,
+        }
+    }
+}
+
+impl TerminalFormatter {
+    /// Creates a new instance of `TerminalFormatter` with colors, badge glyphs and
+    /// timestamps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new instance of `TerminalFormatter` without colors if the `NO_COLOR`
+    /// environment variable is set.
+    pub fn from_env() -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Self::new().with_colors(!no_color)
+    }
+
+    /// Sets whether ANSI colors and styles are used.
+    pub fn with_colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Sets whether badges are shown as glyphs before usernames.
+    pub fn with_glyphs(mut self, glyphs: bool) -> Self {
+        self.glyphs = glyphs;
+        self
+    }
+
+    /// Sets whether chat messages start with the time they were sent.
+    pub fn with_timestamps(mut self, timestamps: bool) -> Self {
+        self.timestamps = timestamps;
+        self
+    }
+
+    /// Formats a message as a single line, without a trailing newline.
+    pub fn format(&self, message: &KickChatMessage) -> String {
+        match &message.data {
+            MessageData::ChatMessage(data) => self.format_chat_message(data),
+            _ => self.style(DIM, &format!("* {}", message)),
+        }
+    }
+
+    /// Formats a chat message as a single line, without a trailing newline.
+    pub fn format_chat_message(&self, message: &ChatMessageEventData) -> String {
+        let mut line = String::new();
+        if self.timestamps {
+            if let Some(time) = message
+                .created_at
+                .as_deref()
+                .and_then(|created_at| created_at.split_once('T'))
+                .and_then(|(_, time)| time.get(..5))
+            {
+                line.push_str(&self.style(DIM, &format!("[{}]", time)));
+                line.push(' ');
+            }
+        }
+        if self.glyphs {
+            for badge in &message.sender.identity.badges {
+                if let Some(glyph) = badge_glyph(badge.badge_type()) {
+                    line.push_str(glyph);
+                }
+            }
+            if !message.sender.identity.badges.is_empty() {
+                line.push(' ');
+            }
+        }
+        let username = &message.sender.username;
+        match message
+            .sender
+            .identity
+            .color
+            .as_deref()
+            .and_then(parse_hex_color)
+        {
+            Some((r, g, b)) if self.colors => {
+                let _ = write!(line, "{BOLD}\x1b[38;2;{r};{g};{b}m{username}{RESET}");
+            }
+            _ => line.push_str(&self.style(BOLD, username)),
+        }
+        line.push_str(": ");
+        line.push_str(message.content.as_deref().unwrap_or_default());
+        line
+    }
+
+    fn style(&self, style: &str, text: &str) -> String {
+        if self.colors {
+            format!("{style}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Returns the glyph shown for a badge type, if it has one.
+pub fn badge_glyph(badge_type: &str) -> Option<&'static str> {
+    Some(match badge_type {
+        "broadcaster" => "🎥",
+        "moderator" => "🗡",
+        "vip" => "💎",
+        "og" => "🏅",
+        "founder" => "✦",
+        "subscriber" => "★",
+        "sub_gifter" => "🎁",
+        "verified" => "✔",
+        "staff" => "⚙",
+        _ => return None,
+    })
+}
+
+/// Parses a `#RRGGBB` color.
+pub(crate) fn parse_hex_color(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
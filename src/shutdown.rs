@@ -0,0 +1,267 @@
+//! Tearing down every part of a bot together, within a deadline.
+//!
+//! A `Shutdown` keeps track of the tasks spawned through it and of the hooks to run when
+//! shutting down. Shutting down stops the spawned tasks from accepting new work: sources
+//! wrapped with `ShutdownToken::until` end, so `sinks::pipe` flushes its sink, and tasks
+//! waiting on `ShutdownToken::triggered` wake up. The hooks then run in order, e.g. to
+//! close an outbound `MessageQueue` so it drains, and every spawned task is awaited until
+//! the deadline, after which the remaining ones are aborted.
+//!
+//! ```no_run
+//! # async fn run(sink: impl kick_client::sinks::Sink + 'static) -> Result<(), kick_client::KickError> {
+//! use kick_client::shutdown::Shutdown;
+//! use kick_client::KickClient;
+//!
+//! let shutdown = Shutdown::new();
+//! let client: KickClient = KickClient::connect(kick_client::DEFAULT_WEBSOCKET_URL, vec![668]).await?;
+//! let mut source = shutdown.token().until(client);
+//! shutdown.spawn("archive", async move {
+//!     let _ = kick_client::sinks::pipe(&mut source, sink).await;
+//!     let _ = source.into_inner().close().await;
+//! });
+//!
+//! let report = shutdown.on_ctrl_c().await;
+//! println!("{} tasks aborted", report.aborted.len());
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "api")]
+use crate::queue::MessageQueue;
+use crate::{KickChatMessage, KickError, MessageSource};
+use futures_util::future::{self, BoxFuture, Either};
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// How long shutting down may take by default before the remaining tasks are aborted.
+const DEFAULT_DEADLINE: Duration = Duration::from_secs(10);
+
+/// A teardown step run when shutting down.
+type Hook = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+/// What happened to the spawned tasks when shutting down.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// The number of tasks which finished before the deadline.
+    pub finished: usize,
+    /// The names of the tasks aborted at the deadline.
+    pub aborted: Vec<&'static str>,
+    /// Whether a hook was still running at the deadline, and the remaining ones were
+    /// skipped.
+    pub hooks_timed_out: bool,
+}
+
+/// Coordinates shutting down spawned tasks and teardown hooks. Cloning a `Shutdown` is
+/// cheap and all clones share the same tasks and hooks.
+#[derive(Clone)]
+pub struct Shutdown {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    signal: watch::Sender<bool>,
+    deadline: Mutex<Duration>,
+    tasks: Mutex<Vec<(&'static str, JoinHandle<()>)>>,
+    hooks: Mutex<Vec<Hook>>,
+}
+
+impl Shutdown {
+    /// Creates a new instance of `Shutdown` giving tasks ten seconds to finish.
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                signal: watch::Sender::new(false),
+                deadline: Mutex::new(DEFAULT_DEADLINE),
+                tasks: Mutex::new(Vec::new()),
+                hooks: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Sets how long shutting down may take, hooks included, before the remaining tasks
+    /// are aborted.
+    pub fn with_deadline(self, deadline: Duration) -> Self {
+        *lock(&self.inner.deadline) = deadline;
+        self
+    }
+
+    /// Returns a token telling when shutting down began.
+    pub fn token(&self) -> ShutdownToken {
+        ShutdownToken {
+            signal: self.inner.signal.subscribe(),
+        }
+    }
+
+    /// Returns `true` once shutting down began.
+    pub fn is_triggered(&self) -> bool {
+        *self.inner.signal.borrow()
+    }
+
+    /// Spawns a task named `name`, awaited when shutting down. Returns `false`, without
+    /// spawning the task, once shutting down began.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called outside of a Tokio runtime.
+    pub fn spawn<F>(&self, name: &'static str, future: F) -> bool
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let mut tasks = lock(&self.inner.tasks);
+        if self.is_triggered() {
+            return false;
+        }
+        tasks.retain(|(_, task)| !task.is_finished());
+        tasks.push((name, crate::trace::spawn(name, future)));
+        true
+    }
+
+    /// Runs `hook` when shutting down, after the hooks added before it, or right away if
+    /// shutting down already began.
+    pub fn on_shutdown<F, Fut>(&self, hook: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let hook: Hook = Box::new(move || Box::pin(hook()));
+        let mut hooks = lock(&self.inner.hooks);
+        if self.is_triggered() {
+            drop(hooks);
+            crate::trace::spawn("kick_client::shutdown::hook", hook());
+            return;
+        }
+        hooks.push(hook);
+    }
+
+    /// Closes `queue` when shutting down, so a task running it and spawned through this
+    /// `Shutdown` sends the queued messages before finishing.
+    #[cfg(feature = "api")]
+    pub fn drain_queue(&self, queue: &MessageQueue) {
+        let queue = queue.clone();
+        self.on_shutdown(move || async move { queue.close() });
+    }
+
+    /// Begins shutting down without waiting for anything, e.g. from a signal handler.
+    pub fn trigger(&self) {
+        self.inner.signal.send_replace(true);
+    }
+
+    /// Shuts down: signals the tasks, runs the hooks and awaits the tasks until the
+    /// deadline, then aborts the remaining ones.
+    pub async fn shutdown(&self) -> ShutdownReport {
+        let deadline = Instant::now() + *lock(&self.inner.deadline);
+        self.trigger();
+        let mut report = ShutdownReport::default();
+
+        let hooks = std::mem::take(&mut *lock(&self.inner.hooks));
+        let run_hooks = async {
+            for hook in hooks {
+                hook().await;
+            }
+        };
+        report.hooks_timed_out = tokio::time::timeout_at(deadline, run_hooks).await.is_err();
+
+        let tasks = std::mem::take(&mut *lock(&self.inner.tasks));
+        for (name, mut task) in tasks {
+            if tokio::time::timeout_at(deadline, &mut task).await.is_ok() {
+                report.finished += 1;
+            } else {
+                task.abort();
+                report.aborted.push(name);
+            }
+        }
+        report
+    }
+
+    /// Shuts down once the process receives Ctrl-C, or once shutting down was triggered
+    /// otherwise.
+    pub async fn on_ctrl_c(&self) -> ShutdownReport {
+        let mut token = self.token();
+        let ctrl_c = std::pin::pin!(tokio::signal::ctrl_c());
+        let triggered = std::pin::pin!(token.triggered());
+        future::select(ctrl_c, triggered).await;
+        self.shutdown().await
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Shutdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Shutdown")
+            .field("triggered", &self.is_triggered())
+            .field("tasks", &lock(&self.inner.tasks).len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Tells a task when shutting down began.
+#[derive(Debug, Clone)]
+pub struct ShutdownToken {
+    signal: watch::Receiver<bool>,
+}
+
+impl ShutdownToken {
+    /// Returns `true` once shutting down began.
+    pub fn is_triggered(&self) -> bool {
+        *self.signal.borrow()
+    }
+
+    /// Waits until shutting down begins. Never returns if the `Shutdown` is dropped
+    /// without shutting down.
+    pub async fn triggered(&mut self) {
+        if self.signal.wait_for(|triggered| *triggered).await.is_err() {
+            future::pending::<()>().await;
+        }
+    }
+
+    /// Wraps `source` so it ends once shutting down begins, dropping any message being
+    /// read at that point.
+    pub fn until<S: MessageSource>(self, source: S) -> UntilShutdown<S> {
+        UntilShutdown {
+            token: self,
+            source,
+        }
+    }
+}
+
+/// A `MessageSource` which ends once shutting down begins. See `ShutdownToken::until`.
+#[derive(Debug)]
+pub struct UntilShutdown<S> {
+    token: ShutdownToken,
+    source: S,
+}
+
+impl<S> UntilShutdown<S> {
+    /// Returns the wrapped source, e.g. to close its connection.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+}
+
+impl<S: MessageSource + Send> MessageSource for UntilShutdown<S> {
+    async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        if self.token.is_triggered() {
+            return Ok(None);
+        }
+        let read = std::pin::pin!(self.source.read_message());
+        let triggered = std::pin::pin!(self.token.triggered());
+        match future::select(read, triggered).await {
+            Either::Left((read, _)) => read,
+            Either::Right(_) => Ok(None),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
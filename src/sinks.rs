@@ -0,0 +1,133 @@
+use crate::{KickChatMessage, KickError, MessageSource};
+use futures_util::future::BoxFuture;
+
+#[cfg(feature = "discord")]
+pub mod discord;
+pub mod file;
+#[cfg(feature = "http-sink")]
+pub mod http;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+#[cfg(feature = "redis")]
+pub mod redis;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+/// Somewhere messages are stored or forwarded to, such as a database or a message broker.
+///
+/// Sinks may buffer messages, so `flush` must be called once done writing to make sure
+/// everything was stored.
+pub trait Sink: Send {
+    /// Writes a message to the sink.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message cannot be stored or forwarded.
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>>;
+
+    /// Stores or forwards the messages buffered by the sink, if any.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the buffered messages cannot be stored or
+    /// forwarded.
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), KickError>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for Box<S> {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        (**self).write(message)
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), KickError>> {
+        (**self).flush()
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for &mut S {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        (**self).write(message)
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), KickError>> {
+        (**self).flush()
+    }
+}
+
+/// How sinks publishing to message brokers serialize messages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayloadFormat {
+    /// JSON, as Kick sends messages.
+    #[default]
+    Json,
+    /// MessagePack, as `encoding::to_msgpack` encodes messages.
+    #[cfg(feature = "msgpack")]
+    MessagePack,
+}
+
+impl PayloadFormat {
+    /// Serializes a message in this format.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message cannot be serialized.
+    pub fn encode(self, message: &KickChatMessage) -> Result<Vec<u8>, KickError> {
+        match self {
+            PayloadFormat::Json => Ok(serde_json::to_vec(message)?),
+            #[cfg(feature = "msgpack")]
+            PayloadFormat::MessagePack => crate::encoding::to_msgpack(message),
+        }
+    }
+
+    /// Returns the MIME type of messages serialized in this format.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            PayloadFormat::Json => "application/json",
+            #[cfg(feature = "msgpack")]
+            PayloadFormat::MessagePack => "application/msgpack",
+        }
+    }
+}
+
+/// Writes every message read from `source` to `sink` until the source is exhausted, then
+/// flushes the sink.
+///
+/// # Errors
+///
+/// This function will return an error if reading from the source fails, in which case the
+/// sink is flushed first, or if writing to the sink fails.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(client: kick_client::KickClient, sink: impl kick_client::sinks::Sink) -> Result<(), kick_client::KickError> {
+/// kick_client::sinks::pipe(client, sink).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn pipe<S: MessageSource, K: Sink>(mut source: S, mut sink: K) -> Result<(), KickError> {
+    loop {
+        match source.read_message().await {
+            Ok(Some(message)) => sink.write(&message).await?,
+            Ok(None) => break,
+            Err(e) => {
+                sink.flush().await?;
+                return Err(e);
+            }
+        }
+    }
+    sink.flush().await
+}
@@ -0,0 +1,255 @@
+use super::http::send_with_retries;
+use super::Sink;
+use crate::api::{HttpRequest, HttpTransport};
+use crate::{KickChatMessage, KickError, MessageData};
+use futures_util::future::BoxFuture;
+use reqwest::Method;
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+
+/// The kinds of events forwarded by default: subscriptions, gifted subscriptions, bans and
+/// the stream going live or offline.
+pub const DEFAULT_KINDS: &[&str] = &["subscription", "gifted_subscriptions", "ban", "livestream"];
+
+/// Kick's brand green, the color of most embeds.
+const KICK_GREEN: u32 = 0x53FC18;
+const BAN_RED: u32 = 0xE53E3E;
+const OFFLINE_GREY: u32 = 0x747F8D;
+
+/// A sink posting selected events to a Discord webhook, formatted as embeds.
+///
+/// Events are selected by `MessageData::kind`, defaulting to `DEFAULT_KINDS`. Stream status
+/// events are only delivered through webhooks, so forwarding them requires reading events
+/// from a `WebhookServer`. Other events are dropped. Requests are retried with exponential
+/// backoff if Discord rate limits them or fails to respond.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::sinks::{discord::DiscordSink, pipe};
+///
+/// let sink = DiscordSink::new("https://discord.com/api/webhooks/123/token")
+///     .with_kinds(["subscription", "gifted_subscriptions"])
+///     .with_username("Kick");
+/// pipe(client, sink).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct DiscordSink<T = reqwest::Client> {
+    transport: T,
+    url: String,
+    kinds: Vec<String>,
+    username: Option<String>,
+    avatar_url: Option<String>,
+    attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+}
+
+impl DiscordSink {
+    /// Creates a new instance of `DiscordSink` posting to the Discord webhook at `url`.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            transport: reqwest::Client::new(),
+            url: url.into(),
+            kinds: DEFAULT_KINDS.iter().map(|kind| kind.to_string()).collect(),
+            username: None,
+            avatar_url: None,
+            attempts: 5,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+}
+
+impl<T: HttpTransport> DiscordSink<T> {
+    /// Sends requests through the given transport instead of the default `reqwest::Client`.
+    pub fn with_transport<U: HttpTransport>(self, transport: U) -> DiscordSink<U> {
+        DiscordSink {
+            transport,
+            url: self.url,
+            kinds: self.kinds,
+            username: self.username,
+            avatar_url: self.avatar_url,
+            attempts: self.attempts,
+            initial_delay: self.initial_delay,
+            max_delay: self.max_delay,
+        }
+    }
+
+    /// Sets the kinds of events forwarded, as returned by `MessageData::kind`.
+    pub fn with_kinds<I, S>(mut self, kinds: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.kinds = kinds.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Overrides the name the webhook posts as.
+    pub fn with_username(mut self, username: impl Into<String>) -> Self {
+        self.username = Some(username.into());
+        self
+    }
+
+    /// Overrides the avatar the webhook posts with.
+    pub fn with_avatar_url(mut self, avatar_url: impl Into<String>) -> Self {
+        self.avatar_url = Some(avatar_url.into());
+        self
+    }
+
+    /// Sets how many times a request is tried before giving up.
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry, doubled after each failed attempt up to
+    /// `max`.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_delay = initial;
+        self.max_delay = max;
+        self
+    }
+
+    /// Returns `true` if the sink forwards this kind of event.
+    pub fn forwards(&self, message: &KickChatMessage) -> bool {
+        let kind = message.data.kind();
+        self.kinds.iter().any(|k| k == kind)
+    }
+
+    /// Posts the embed of a message.
+    async fn post(&self, message: &KickChatMessage) -> Result<(), KickError> {
+        let mut body = Map::new();
+        if let Some(username) = &self.username {
+            body.insert("username".to_string(), json!(username));
+        }
+        if let Some(avatar_url) = &self.avatar_url {
+            body.insert("avatar_url".to_string(), json!(avatar_url));
+        }
+        body.insert("embeds".to_string(), json!([embed(message)]));
+        let request = HttpRequest {
+            method: Method::POST,
+            url: self.url.clone(),
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: Some(serde_json::to_vec(&body)?),
+        };
+        send_with_retries(
+            &self.transport,
+            request,
+            self.attempts,
+            self.initial_delay,
+            self.max_delay,
+        )
+        .await
+    }
+}
+
+impl<T: HttpTransport> Sink for DiscordSink<T> {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        Box::pin(async move {
+            if !self.forwards(message) {
+                return Ok(());
+            }
+            self.post(message).await
+        })
+    }
+}
+
+/// Returns a message as a Discord embed.
+///
+/// Subscriptions, bans and stream status events have a title of their own, and other events
+/// are described as displayed.
+pub fn embed(message: &KickChatMessage) -> Value {
+    match &message.data {
+        MessageData::SubscriptionEvent(data) => json!({
+            "title": "New subscriber",
+            "description": format!(
+                "**{}** subscribed for {}",
+                escape_markdown(&data.username),
+                plural(data.months, "month")
+            ),
+            "color": KICK_GREEN,
+        }),
+        MessageData::GiftedSubscriptions(data) => json!({
+            "title": "Gifted subscriptions",
+            "description": format!(
+                "**{}** gifted {}",
+                escape_markdown(data.gifter_username.as_deref().unwrap_or("Anonymous")),
+                plural(data.gifted_usernames.len() as u32, "subscription")
+            ),
+            "color": KICK_GREEN,
+        }),
+        MessageData::UserBanned(data) => {
+            let (title, duration) = match data.duration {
+                Some(minutes) if !data.permanent => (
+                    "User timed out",
+                    format!(" for {}", plural(minutes as u32, "minute")),
+                ),
+                _ => ("User banned", String::new()),
+            };
+            json!({
+                "title": title,
+                "description": format!(
+                    "**{}** was banned by **{}**{}",
+                    escape_markdown(&data.user.username),
+                    escape_markdown(&data.banned_by.username),
+                    duration
+                ),
+                "color": BAN_RED,
+            })
+        }
+        MessageData::LivestreamStatusUpdated(data) if data.is_live => {
+            let mut embed = json!({
+                "title": data.title,
+                "description": format!("**{}** is live", escape_markdown(&data.broadcaster_username)),
+                "color": KICK_GREEN,
+            });
+            if let Some(slug) = &message.channel {
+                embed["url"] = json!(format!("https://kick.com/{}", slug));
+            }
+            if let Some(started_at) = &data.started_at {
+                embed["timestamp"] = json!(started_at);
+            }
+            embed
+        }
+        MessageData::LivestreamStatusUpdated(data) => json!({
+            "title": "Stream ended",
+            "description": format!("**{}** went offline", escape_markdown(&data.broadcaster_username)),
+            "color": OFFLINE_GREY,
+        }),
+        _ => json!({
+            "description": escape_markdown(&message.to_string()),
+            "color": KICK_GREEN,
+        }),
+    }
+}
+
+fn plural(count: u32, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {}", noun)
+    } else {
+        format!("{} {}s", count, noun)
+    }
+}
+
+/// Escapes the characters Discord treats as Markdown, so usernames like `__init__` are
+/// displayed as is.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '*' | '_' | '~' | '`' | '|' | '>' | '#' | '[' | ']'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,264 @@
+use super::Sink;
+use crate::{KickChatMessage, KickError, MessageData};
+use futures_util::future::BoxFuture;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The columns of CSV files written by a `FileSink`.
+const CSV_HEADER: &str = "received_at,channel,chatroom_id,kind,username,content,text\n";
+
+/// How a `FileSink` writes messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// One message per line, serialized as Kick sends it.
+    Jsonl,
+    /// One message per row, with the columns `received_at` (milliseconds since the Unix
+    /// epoch), `channel`, `chatroom_id`, `kind`, `username` and `content` for chat
+    /// messages, and `text`, the message as displayed.
+    Csv,
+}
+
+/// A sink appending messages to a file as JSON lines or CSV, rotating it once it grows too
+/// large or old.
+///
+/// Rotated files are renamed after the time they were rotated at, e.g. `chat.jsonl` becomes
+/// `chat.1714566896.jsonl`, and compressed to `chat.1714566896.jsonl.gz` if compression is
+/// enabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::sinks::file::{FileFormat, FileSink};
+/// use kick_client::sinks::pipe;
+/// use std::time::Duration;
+///
+/// let sink = FileSink::open("logs/chat.jsonl", FileFormat::Jsonl)?
+///     .with_max_size(64 * 1024 * 1024)
+///     .with_max_age(Duration::from_secs(24 * 60 * 60));
+/// pipe(client, sink).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct FileSink {
+    path: PathBuf,
+    format: FileFormat,
+    writer: BufWriter<File>,
+    size: u64,
+    opened: Instant,
+    max_size: Option<u64>,
+    max_age: Option<Duration>,
+    compress: bool,
+}
+
+impl FileSink {
+    /// Creates a new instance of `FileSink` appending to the file at `path`, creating it if
+    /// needed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be opened.
+    pub fn open(path: impl AsRef<Path>, format: FileFormat) -> Result<Self, KickError> {
+        let path = path.as_ref().to_path_buf();
+        let (writer, size) = open(&path, format)?;
+        Ok(Self {
+            path,
+            format,
+            writer,
+            size,
+            opened: Instant::now(),
+            max_size: None,
+            max_age: None,
+            compress: false,
+        })
+    }
+
+    /// Rotates the file once it reaches `max_size` bytes.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Rotates the file once it has been written to for `max_age`.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sets whether rotated files are compressed with gzip.
+    #[cfg(feature = "gzip")]
+    pub fn with_compression(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Rotates the file now, returning the path it was moved to.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file cannot be renamed, compressed or
+    /// opened again.
+    pub fn rotate(&mut self) -> Result<PathBuf, KickError> {
+        self.writer.flush()?;
+        let rotated = rotated_path(&self.path);
+        std::fs::rename(&self.path, &rotated)?;
+        let (writer, size) = open(&self.path, self.format)?;
+        self.writer = writer;
+        self.size = size;
+        self.opened = Instant::now();
+        if self.compress {
+            return compress(rotated);
+        }
+        Ok(rotated)
+    }
+
+    fn should_rotate(&self) -> bool {
+        let header = match self.format {
+            FileFormat::Jsonl => 0,
+            FileFormat::Csv => CSV_HEADER.len() as u64,
+        };
+        self.size > header
+            && (self.max_size.is_some_and(|max_size| self.size >= max_size)
+                || self
+                    .max_age
+                    .is_some_and(|max_age| self.opened.elapsed() >= max_age))
+    }
+
+    fn write_line(&mut self, message: &KickChatMessage) -> Result<(), KickError> {
+        if self.should_rotate() {
+            self.rotate()?;
+        }
+        let line = match self.format {
+            FileFormat::Jsonl => {
+                let mut line = serde_json::to_string(message)?;
+                line.push('\n');
+                line
+            }
+            FileFormat::Csv => csv_row(message),
+        };
+        self.writer.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+impl Sink for FileSink {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        Box::pin(async move { self.write_line(message) })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), KickError>> {
+        Box::pin(async move { Ok(self.writer.flush()?) })
+    }
+}
+
+/// Opens a file for appending, writing the CSV header if it's new, and returns its size.
+fn open(path: &Path, format: FileFormat) -> Result<(BufWriter<File>, u64), KickError> {
+    let file = File::options().create(true).append(true).open(path)?;
+    let mut size = file.metadata()?.len();
+    let mut writer = BufWriter::new(file);
+    if size == 0 && format == FileFormat::Csv {
+        writer.write_all(CSV_HEADER.as_bytes())?;
+        size = CSV_HEADER.len() as u64;
+    }
+    Ok((writer, size))
+}
+
+/// Returns an unused path to rotate a file to, e.g. `chat.1714566896.jsonl` for
+/// `chat.jsonl`.
+fn rotated_path(path: &Path) -> PathBuf {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    (0..)
+        .map(|n| {
+            let suffix = if n == 0 {
+                secs.to_string()
+            } else {
+                format!("{}-{}", secs, n)
+            };
+            path.with_file_name(format!("{}.{}{}", stem, suffix, extension))
+        })
+        .find(|candidate| !candidate.exists() && !gz_path(candidate).exists())
+        .expect("an unused path")
+}
+
+fn gz_path(path: &Path) -> PathBuf {
+    let mut gz = path.as_os_str().to_owned();
+    gz.push(".gz");
+    PathBuf::from(gz)
+}
+
+/// Compresses a rotated file with gzip, removing the original.
+#[cfg(feature = "gzip")]
+fn compress(path: PathBuf) -> Result<PathBuf, KickError> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let gz = gz_path(&path);
+    let mut encoder = GzEncoder::new(BufWriter::new(File::create(&gz)?), Compression::default());
+    std::io::copy(&mut File::open(&path)?, &mut encoder)?;
+    encoder.finish()?.flush()?;
+    std::fs::remove_file(&path)?;
+    Ok(gz)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn compress(path: PathBuf) -> Result<PathBuf, KickError> {
+    Ok(path)
+}
+
+/// Returns a message as a CSV row, including the line break.
+fn csv_row(message: &KickChatMessage) -> String {
+    let received_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis());
+    let (username, content) = match &message.data {
+        MessageData::ChatMessage(data) => (
+            data.sender.username.as_str(),
+            data.content.as_deref().unwrap_or_default(),
+        ),
+        _ => ("", ""),
+    };
+    let fields = [
+        received_at.to_string(),
+        message.channel.clone().unwrap_or_default(),
+        message
+            .chatroom_id()
+            .map(|id| id.to_string())
+            .unwrap_or_default(),
+        message.data.kind().to_string(),
+        username.to_string(),
+        content.to_string(),
+        message.to_string(),
+    ];
+    let mut row = fields
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",");
+    row.push('\n');
+    row
+}
+
+/// Quotes a CSV field if it contains a comma, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
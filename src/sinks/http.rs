@@ -0,0 +1,268 @@
+use super::Sink;
+use crate::api::{HttpRequest, HttpTransport};
+use crate::encoding::to_json_value;
+use crate::metrics::Metrics;
+use crate::{KickChatMessage, KickError};
+use futures_util::future::BoxFuture;
+use hmac::{Hmac, Mac};
+use reqwest::Method;
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// The header holding the signature of a request body.
+pub const SIGNATURE_HEADER: &str = "X-Kick-Client-Signature-256";
+
+/// How many messages may be buffered by default.
+const DEFAULT_BUFFER_LIMIT: usize = 10_000;
+
+/// A sink POSTing messages as JSON to a URL, such as a no-code automation's webhook.
+///
+/// Messages are posted as JSON objects like Kick's, except that `data` is a nested object
+/// rather than a JSON string, which `KickChatMessage` still deserializes from. With a batch
+/// size above 1, each request holds a JSON array of up to that many messages instead.
+///
+/// Messages whose request failed stay buffered and are posted again with the next ones;
+/// while the URL is unreachable, the oldest messages are dropped beyond the buffer limit.
+///
+/// Requests failing without a response, or with a 429 or 5xx status, are retried with
+/// exponential backoff. With a secret set, each request has a `X-Kick-Client-Signature-256`
+/// header holding `sha256=` followed by the hex-encoded HMAC-SHA256 of the body, as GitHub
+/// signs its webhooks.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::sinks::{http::HttpSink, pipe};
+///
+/// let sink = HttpSink::new("https://hooks.example.com/kick")
+///     .with_secret("shared secret")
+///     .with_batch_size(20);
+/// pipe(client, sink).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct HttpSink<T = reqwest::Client> {
+    transport: T,
+    url: String,
+    headers: Vec<(String, String)>,
+    secret: Option<Vec<u8>>,
+    batch: VecDeque<Value>,
+    batch_size: usize,
+    /// How many messages may be buffered.
+    limit: usize,
+    /// The number of messages dropped because the buffer was full.
+    dropped: u64,
+    /// The metrics counting dropped messages too, if any.
+    metrics: Option<Metrics>,
+    attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+}
+
+impl HttpSink {
+    /// Creates a new instance of `HttpSink` posting each message to `url`, trying each
+    /// request up to 5 times.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            transport: reqwest::Client::new(),
+            url: url.into(),
+            headers: Vec::new(),
+            secret: None,
+            batch: VecDeque::new(),
+            batch_size: 1,
+            limit: DEFAULT_BUFFER_LIMIT,
+            dropped: 0,
+            metrics: None,
+            attempts: 5,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl<T: HttpTransport> HttpSink<T> {
+    /// Sends requests through the given transport instead of the default `reqwest::Client`.
+    pub fn with_transport<U: HttpTransport>(self, transport: U) -> HttpSink<U> {
+        HttpSink {
+            transport,
+            url: self.url,
+            headers: self.headers,
+            secret: self.secret,
+            batch: self.batch,
+            batch_size: self.batch_size,
+            limit: self.limit,
+            dropped: self.dropped,
+            metrics: self.metrics,
+            attempts: self.attempts,
+            initial_delay: self.initial_delay,
+            max_delay: self.max_delay,
+        }
+    }
+
+    /// Adds a header to every request, e.g. for authentication.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Signs every request with the given secret.
+    pub fn with_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.secret = Some(secret.as_ref().to_vec());
+        self
+    }
+
+    /// Sets how many messages are posted together, as a JSON array. Defaults to 1, posting
+    /// each message on its own as a JSON object.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets how many messages may stay buffered while requests fail before the oldest ones
+    /// are dropped. Defaults to 10,000.
+    pub fn with_buffer_limit(mut self, limit: usize) -> Self {
+        self.limit = limit.max(1);
+        self
+    }
+
+    /// Counts the messages dropped because the buffer was full in `metrics`.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Returns the number of messages dropped because the buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Sets how many times a request is tried before giving up.
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry, doubled after each failed attempt up to
+    /// `max`.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_delay = initial;
+        self.max_delay = max;
+        self
+    }
+
+    /// Posts the buffered messages, up to a batch per request.
+    async fn post_batch(&mut self) -> Result<(), KickError> {
+        while !self.batch.is_empty() {
+            let count = self.batch.len().min(self.batch_size);
+            self.post(count).await?;
+            self.batch.drain(..count);
+        }
+        Ok(())
+    }
+
+    /// Posts the first `count` buffered messages.
+    async fn post(&self, count: usize) -> Result<(), KickError> {
+        let body = if self.batch_size == 1 {
+            serde_json::to_vec(&self.batch[0])?
+        } else {
+            serde_json::to_vec(&self.batch.range(..count).collect::<Vec<_>>())?
+        };
+        let mut headers = self.headers.clone();
+        headers.push(("Content-Type".to_string(), "application/json".to_string()));
+        if let Some(secret) = &self.secret {
+            headers.push((SIGNATURE_HEADER.to_string(), sign(secret, &body)));
+        }
+        let request = HttpRequest {
+            method: Method::POST,
+            url: self.url.clone(),
+            headers,
+            body: Some(body),
+        };
+
+        send_with_retries(
+            &self.transport,
+            request,
+            self.attempts,
+            self.initial_delay,
+            self.max_delay,
+        )
+        .await
+    }
+}
+
+impl<T: HttpTransport> Sink for HttpSink<T> {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        Box::pin(async move {
+            let value = to_json_value(message)?;
+            if self.batch.len() >= self.limit {
+                self.batch.pop_front();
+                self.dropped += 1;
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_dropped(1);
+                }
+            }
+            self.batch.push_back(value);
+            if self.batch.len() >= self.batch_size {
+                self.post_batch().await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), KickError>> {
+        Box::pin(self.post_batch())
+    }
+}
+
+/// Sends a request, retrying it with exponential backoff if it fails without a response or
+/// with a 429 or 5xx status.
+pub(crate) async fn send_with_retries<T: HttpTransport>(
+    transport: &T,
+    request: HttpRequest,
+    attempts: u32,
+    initial_delay: Duration,
+    max_delay: Duration,
+) -> Result<(), KickError> {
+    let mut delay = initial_delay;
+    let mut attempt = 1;
+    loop {
+        let error = match transport.send(request.clone()).await {
+            Ok(response) if response.is_success() => return Ok(()),
+            Ok(response) => {
+                let error = KickError::ApiError {
+                    status: response.status,
+                    body: String::from_utf8_lossy(&response.body).into_owned(),
+                };
+                if response.status != 429 && response.status < 500 {
+                    return Err(error);
+                }
+                error
+            }
+            Err(e) => e,
+        };
+        if attempt >= attempts {
+            return Err(error);
+        }
+        attempt += 1;
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(max_delay);
+    }
+}
+
+/// Returns the value of the signature header for a body.
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    let mut signature = String::from("sha256=");
+    for byte in mac.finalize().into_bytes() {
+        let _ = write!(signature, "{:02x}", byte);
+    }
+    signature
+}
@@ -0,0 +1,133 @@
+use super::{PayloadFormat, Sink};
+use crate::{KickChatMessage, KickError};
+use futures_util::future::BoxFuture;
+use rdkafka::error::{KafkaError, RDKafkaErrorCode};
+use rdkafka::message::{Header, OwnedHeaders};
+use rdkafka::producer::{DeliveryFuture, FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+
+/// How many messages may be awaiting acknowledgement before writing waits for them.
+const MAX_PENDING: usize = 10_000;
+
+/// A sink publishing messages to a Kafka topic, keyed by chatroom ID so the messages of a
+/// chatroom stay ordered within a partition.
+///
+/// Messages are queued by the producer and sent in the background; `flush` waits until every
+/// queued message was acknowledged. Each record has a `kind` header holding
+/// `MessageData::kind` and a `content-type` header holding the MIME type of the payload.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::sinks::{kafka::KafkaSink, pipe};
+///
+/// pipe(client, KafkaSink::new("localhost:9092", "kick-chat")?).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct KafkaSink {
+    producer: FutureProducer,
+    topic: String,
+    format: PayloadFormat,
+    pending: Vec<DeliveryFuture>,
+}
+
+impl KafkaSink {
+    /// Creates a new instance of `KafkaSink` publishing to `topic` on the given
+    /// comma-separated brokers.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the producer cannot be created.
+    pub fn new(brokers: &str, topic: impl Into<String>) -> Result<Self, KickError> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .create()
+            .map_err(sink_error)?;
+        Ok(Self::from_producer(producer, topic))
+    }
+
+    /// Creates a new instance of `KafkaSink` publishing to `topic` through a configured
+    /// producer, e.g. one with authentication or compression.
+    pub fn from_producer(producer: FutureProducer, topic: impl Into<String>) -> Self {
+        Self {
+            producer,
+            topic: topic.into(),
+            format: PayloadFormat::Json,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Sets how messages are serialized. Defaults to JSON.
+    pub fn with_format(mut self, format: PayloadFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Queues a message, waiting for queued messages to be sent if the queue is full.
+    async fn queue(&mut self, message: &KickChatMessage) -> Result<(), KickError> {
+        if self.pending.len() >= MAX_PENDING {
+            wait_for(&mut self.pending).await?;
+        }
+        let payload = self.format.encode(message)?;
+        let key = message.chatroom_id().map(|id| id.to_string());
+        let headers = OwnedHeaders::new()
+            .insert(Header {
+                key: "kind",
+                value: Some(message.data.kind()),
+            })
+            .insert(Header {
+                key: "content-type",
+                value: Some(self.format.content_type()),
+            });
+        let mut record = FutureRecord::to(&self.topic)
+            .payload(&payload)
+            .headers(headers);
+        if let Some(key) = &key {
+            record = record.key(key);
+        }
+        match self.producer.send_result(record) {
+            Ok(delivery) => self.pending.push(delivery),
+            Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), record)) => {
+                wait_for(&mut self.pending).await?;
+                let delivery = self
+                    .producer
+                    .send_result(record)
+                    .map_err(|(e, _)| sink_error(e))?;
+                self.pending.push(delivery);
+            }
+            Err((e, _)) => return Err(sink_error(e)),
+        }
+        Ok(())
+    }
+}
+
+impl Sink for KafkaSink {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        Box::pin(self.queue(message))
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), KickError>> {
+        Box::pin(wait_for(&mut self.pending))
+    }
+}
+
+/// Waits until every queued message was acknowledged.
+async fn wait_for(pending: &mut Vec<DeliveryFuture>) -> Result<(), KickError> {
+    for delivery in std::mem::take(pending) {
+        match delivery.await {
+            Ok(Ok(_)) => {}
+            Ok(Err((e, _))) => return Err(sink_error(e)),
+            Err(_) => return Err(sink_error(KafkaError::Canceled)),
+        }
+    }
+    Ok(())
+}
+
+fn sink_error(err: KafkaError) -> KickError {
+    KickError::SinkError(Box::new(err))
+}
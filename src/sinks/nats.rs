@@ -0,0 +1,100 @@
+use super::{PayloadFormat, Sink};
+use crate::{KickChatMessage, KickError};
+use async_nats::{Client, HeaderMap};
+use futures_util::future::BoxFuture;
+
+/// A sink publishing messages to NATS, on a subject per chatroom and kind of event so
+/// services can subscribe to just what they need.
+///
+/// Messages are published to `{prefix}.{chatroom}.{kind}`, e.g. `kick.668.chat` or
+/// `kick.668.ban`, where `kind` is `MessageData::kind`. Messages received on no chatroom,
+/// such as `pusher:connection_established`, are published to `{prefix}.{kind}`. Each
+/// message has a `Content-Type` header holding the MIME type of the payload.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::sinks::{nats::NatsSink, pipe};
+///
+/// // Consumers subscribe to e.g. `kick.*.chat` for every chat message, or `kick.668.>` for
+/// // everything happening in chatroom 668.
+/// pipe(client, NatsSink::connect("nats://localhost:4222").await?).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct NatsSink {
+    client: Client,
+    prefix: String,
+    format: PayloadFormat,
+}
+
+impl NatsSink {
+    /// Creates a new instance of `NatsSink` connected to the NATS server at `url`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the server cannot be connected to.
+    pub async fn connect(url: &str) -> Result<Self, KickError> {
+        let client = async_nats::connect(url)
+            .await
+            .map_err(|e| KickError::SinkError(Box::new(e)))?;
+        Ok(Self::from_client(client))
+    }
+
+    /// Creates a new instance of `NatsSink` publishing through a connected client, e.g. one
+    /// with credentials or TLS.
+    pub fn from_client(client: Client) -> Self {
+        Self {
+            client,
+            prefix: "kick".to_string(),
+            format: PayloadFormat::Json,
+        }
+    }
+
+    /// Sets the first token of the subjects messages are published to. Defaults to `kick`.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Sets how messages are serialized. Defaults to JSON.
+    pub fn with_format(mut self, format: PayloadFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Returns the subject a message is published to.
+    pub fn subject(&self, message: &KickChatMessage) -> String {
+        match message.chatroom_id() {
+            Some(chatroom_id) => format!("{}.{}.{}", self.prefix, chatroom_id, message.data.kind()),
+            None => format!("{}.{}", self.prefix, message.data.kind()),
+        }
+    }
+}
+
+impl Sink for NatsSink {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        Box::pin(async move {
+            let payload = self.format.encode(message)?;
+            let mut headers = HeaderMap::new();
+            headers.insert("Content-Type", self.format.content_type());
+            self.client
+                .publish_with_headers(self.subject(message), headers, bytes::Bytes::from(payload))
+                .await
+                .map_err(|e| KickError::SinkError(Box::new(e)))
+        })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), KickError>> {
+        Box::pin(async move {
+            self.client
+                .flush()
+                .await
+                .map_err(|e| KickError::SinkError(Box::new(e)))
+        })
+    }
+}
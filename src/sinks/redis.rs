@@ -0,0 +1,174 @@
+use super::{PayloadFormat, Sink};
+use crate::{KickChatMessage, KickError};
+use futures_util::future::BoxFuture;
+use redis::aio::MultiplexedConnection;
+
+/// How a `RedisSink` hands messages to Redis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedisTarget {
+    /// `PUBLISH` each message on a channel, for subscribers connected at the time.
+    Publish,
+    /// `XADD` each message to a stream, letting Redis assign IDs so consumer groups read
+    /// messages in the order they were received. Streams are trimmed to about `max_len`
+    /// entries, if set.
+    Stream {
+        /// About how many entries the stream is trimmed to.
+        max_len: Option<usize>,
+    },
+}
+
+/// A sink handing messages to Redis, through pub/sub or streams.
+///
+/// Channels and stream keys are built from a template, where `{chatroom}` is replaced with
+/// the chatroom ID, or `global` for messages received on no chatroom, and `{kind}` with
+/// `MessageData::kind`. The template defaults to `kick:{chatroom}:{kind}` for pub/sub, so
+/// subscribers can `PSUBSCRIBE kick:*:chat`, and to `kick:{chatroom}` for streams.
+///
+/// Stream entries have the fields `kind`, `chatroom_id` (empty for messages received on no
+/// chatroom) and `payload`, holding the serialized message.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::sinks::pipe;
+/// use kick_client::sinks::redis::{RedisSink, RedisTarget};
+///
+/// let mut sink = RedisSink::connect("redis://localhost", RedisTarget::Stream { max_len: Some(100_000) })
+///     .await?
+///     .with_key("kick:events");
+/// sink.create_group("archiver").await?;
+/// pipe(client, sink).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RedisSink {
+    connection: MultiplexedConnection,
+    target: RedisTarget,
+    key: String,
+    format: PayloadFormat,
+}
+
+impl RedisSink {
+    /// Creates a new instance of `RedisSink` connected to the Redis server at `url`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the URL is invalid or the server cannot be
+    /// connected to.
+    pub async fn connect(url: &str, target: RedisTarget) -> Result<Self, KickError> {
+        let client = redis::Client::open(url).map_err(sink_error)?;
+        let connection = client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(sink_error)?;
+        Ok(Self::from_connection(connection, target))
+    }
+
+    /// Creates a new instance of `RedisSink` using an open connection.
+    pub fn from_connection(connection: MultiplexedConnection, target: RedisTarget) -> Self {
+        let key = match target {
+            RedisTarget::Publish => "kick:{chatroom}:{kind}",
+            RedisTarget::Stream { .. } => "kick:{chatroom}",
+        };
+        Self {
+            connection,
+            target,
+            key: key.to_string(),
+            format: PayloadFormat::Json,
+        }
+    }
+
+    /// Sets the template of the channels or stream keys messages are written to.
+    pub fn with_key(mut self, template: impl Into<String>) -> Self {
+        self.key = template.into();
+        self
+    }
+
+    /// Sets how messages are serialized. Defaults to JSON.
+    pub fn with_format(mut self, format: PayloadFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Returns the channel or stream key a message is written to.
+    pub fn key(&self, message: &KickChatMessage) -> String {
+        let chatroom = message
+            .chatroom_id()
+            .map_or_else(|| "global".to_string(), |id| id.to_string());
+        self.key
+            .replace("{chatroom}", &chatroom)
+            .replace("{kind}", message.data.kind())
+    }
+
+    /// Creates a consumer group reading a stream from its start, and the stream if needed.
+    /// Creating a group that already exists does nothing. Only useful when writing every
+    /// message to the same stream, as the group is created on the key of the template with
+    /// `{chatroom}` and `{kind}` left as is.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the group cannot be created.
+    pub async fn create_group(&mut self, group: &str) -> Result<(), KickError> {
+        let result = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(&self.key)
+            .arg(group)
+            .arg("0")
+            .arg("MKSTREAM")
+            .query_async::<()>(&mut self.connection)
+            .await;
+        match result {
+            Err(e) if e.code() == Some("BUSYGROUP") => Ok(()),
+            result => result.map_err(sink_error),
+        }
+    }
+}
+
+impl Sink for RedisSink {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        Box::pin(async move {
+            let key = self.key(message);
+            let payload = self.format.encode(message)?;
+            let command = match self.target {
+                RedisTarget::Publish => {
+                    let mut command = redis::cmd("PUBLISH");
+                    command.arg(key).arg(payload);
+                    command
+                }
+                RedisTarget::Stream { max_len } => {
+                    let mut command = redis::cmd("XADD");
+                    command.arg(key);
+                    if let Some(max_len) = max_len {
+                        command.arg("MAXLEN").arg("~").arg(max_len);
+                    }
+                    command
+                        .arg("*")
+                        .arg("kind")
+                        .arg(message.data.kind())
+                        .arg("chatroom_id")
+                        .arg(
+                            message
+                                .chatroom_id()
+                                .map(|id| id.to_string())
+                                .unwrap_or_default(),
+                        )
+                        .arg("payload")
+                        .arg(payload);
+                    command
+                }
+            };
+            command
+                .query_async::<()>(&mut self.connection)
+                .await
+                .map_err(sink_error)
+        })
+    }
+}
+
+fn sink_error(err: redis::RedisError) -> KickError {
+    KickError::SinkError(Box::new(err))
+}
@@ -0,0 +1,389 @@
+use super::Sink;
+use crate::metrics::Metrics;
+use crate::polls::is_same_poll;
+use crate::{ChatMessageSender, KickChatMessage, KickError, MessageData, Poll, PollOption, User};
+use futures_util::future::BoxFuture;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How many messages may be buffered by default.
+const DEFAULT_BUFFER_LIMIT: usize = 10_000;
+
+/// The tables messages are written to. Every event is kept as received in `events`, and
+/// chat messages, users, bans and polls are also written to their own tables.
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    received_at INTEGER NOT NULL,
+    channel TEXT,
+    chatroom_id INTEGER,
+    kind TEXT NOT NULL,
+    frame TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS events_by_chatroom ON events (chatroom_id, kind);
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY,
+    username TEXT NOT NULL,
+    slug TEXT,
+    color TEXT
+);
+CREATE TABLE IF NOT EXISTS messages (
+    id TEXT PRIMARY KEY,
+    chatroom_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    content TEXT,
+    type TEXT,
+    created_at TEXT,
+    deleted INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS messages_by_chatroom ON messages (chatroom_id, created_at);
+CREATE INDEX IF NOT EXISTS messages_by_user ON messages (user_id);
+CREATE TABLE IF NOT EXISTS bans (
+    id TEXT PRIMARY KEY,
+    chatroom_id INTEGER,
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    banned_by INTEGER NOT NULL REFERENCES users (id),
+    permanent INTEGER NOT NULL,
+    duration INTEGER,
+    expires_at TEXT,
+    unbanned_by INTEGER REFERENCES users (id)
+);
+CREATE INDEX IF NOT EXISTS bans_by_user ON bans (chatroom_id, user_id);
+CREATE TABLE IF NOT EXISTS polls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    chatroom_id INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    duration INTEGER NOT NULL,
+    remaining INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS polls_by_chatroom ON polls (chatroom_id, title);
+CREATE TABLE IF NOT EXISTS poll_options (
+    poll_id INTEGER NOT NULL REFERENCES polls (id),
+    option_id INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    votes INTEGER NOT NULL,
+    PRIMARY KEY (poll_id, option_id)
+);
+";
+
+/// A sink archiving messages into a SQLite database.
+///
+/// Messages are written in batches, in a single transaction, once `batch_size` messages
+/// are buffered or `flush_interval` passed since the last batch. A failed batch stays
+/// buffered and is retried with the next one; while the database is unavailable, the
+/// oldest messages are dropped beyond the buffer limit. Every event is kept in
+/// the `events` table as its JSON frame, and the following tables are kept up to date:
+///
+/// - `users`, every user seen sending a message or banning, being banned or unbanned;
+/// - `messages`, chat messages, with `deleted` set once a moderator deletes them;
+/// - `bans`, with `unbanned_by` set once the user is unbanned;
+/// - `polls` and `poll_options`, with the latest votes. A poll started again with the same
+///   title gets a new row, keeping the results of the earlier one.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::sinks::{pipe, sqlite::SqliteSink};
+///
+/// pipe(client, SqliteSink::open("chat.db")?).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SqliteSink {
+    connection: Connection,
+    buffer: VecDeque<(i64, KickChatMessage)>,
+    batch_size: usize,
+    /// How many messages may be buffered.
+    limit: usize,
+    /// The number of messages dropped because the buffer was full.
+    dropped: u64,
+    /// The metrics counting dropped messages too, if any.
+    metrics: Option<Metrics>,
+    flush_interval: Duration,
+    last_flush: Instant,
+}
+
+impl SqliteSink {
+    /// Creates a new instance of `SqliteSink` writing to the database at `path`, creating it
+    /// and its tables if needed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the database cannot be opened or its tables
+    /// cannot be created.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, KickError> {
+        Self::from_connection(Connection::open(path).map_err(sink_error)?)
+    }
+
+    /// Creates a new instance of `SqliteSink` writing to an open database, creating its
+    /// tables if needed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the tables cannot be created.
+    pub fn from_connection(connection: Connection) -> Result<Self, KickError> {
+        connection.execute_batch(SCHEMA).map_err(sink_error)?;
+        Ok(Self {
+            connection,
+            buffer: VecDeque::new(),
+            batch_size: 100,
+            limit: DEFAULT_BUFFER_LIMIT,
+            dropped: 0,
+            metrics: None,
+            flush_interval: Duration::from_secs(1),
+            last_flush: Instant::now(),
+        })
+    }
+
+    /// Sets how many messages are buffered before they are written. Defaults to 100.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets how long messages may stay buffered before they are written, checked whenever a
+    /// message is written. Defaults to a second.
+    pub fn with_flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Sets how many messages may stay buffered while batches fail, e.g. because the
+    /// database is locked, before the oldest ones are dropped. Defaults to 10,000.
+    pub fn with_buffer_limit(mut self, limit: usize) -> Self {
+        self.limit = limit.max(1);
+        self
+    }
+
+    /// Counts the messages dropped because the buffer was full in `metrics`.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Returns the number of messages dropped because the buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Returns the database connection, e.g. to query the archived messages.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Writes the buffered messages in a single transaction.
+    fn write_batch(&mut self) -> Result<(), KickError> {
+        self.last_flush = Instant::now();
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let transaction = self.connection.transaction().map_err(sink_error)?;
+        for (received_at, message) in &self.buffer {
+            insert(&transaction, *received_at, message).map_err(sink_error)?;
+        }
+        transaction.commit().map_err(sink_error)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl Sink for SqliteSink {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        Box::pin(async move {
+            let received_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as i64);
+            if self.buffer.len() >= self.limit {
+                self.buffer.pop_front();
+                self.dropped += 1;
+                if let Some(metrics) = &self.metrics {
+                    metrics.record_dropped(1);
+                }
+            }
+            self.buffer.push_back((received_at, message.clone()));
+            if self.buffer.len() >= self.batch_size
+                || self.last_flush.elapsed() >= self.flush_interval
+            {
+                self.write_batch()?;
+            }
+            Ok(())
+        })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), KickError>> {
+        Box::pin(async move { self.write_batch() })
+    }
+}
+
+impl Drop for SqliteSink {
+    fn drop(&mut self) {
+        let _ = self.write_batch();
+    }
+}
+
+/// Writes a message to every table it belongs in.
+fn insert(
+    transaction: &Transaction<'_>,
+    received_at: i64,
+    message: &KickChatMessage,
+) -> rusqlite::Result<()> {
+    let chatroom_id = message.chatroom_id();
+    let frame = serde_json::to_string(message).unwrap_or_default();
+    transaction.execute(
+        "INSERT INTO events (received_at, channel, chatroom_id, kind, frame) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![received_at, message.channel, chatroom_id, message.data.kind(), frame],
+    )?;
+    match &message.data {
+        MessageData::ChatMessage(data) => {
+            insert_sender(transaction, &data.sender)?;
+            transaction.execute(
+                "INSERT OR IGNORE INTO messages (id, chatroom_id, user_id, content, type, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![data.id, data.chatroom_id, data.sender.id, data.content, data.r#type, data.created_at],
+            )?;
+        }
+        MessageData::DeletedMessage(data) => {
+            transaction.execute(
+                "UPDATE messages SET deleted = 1 WHERE id = ?1",
+                params![data.message.id],
+            )?;
+        }
+        MessageData::UserBanned(data) => {
+            insert_user(transaction, &data.user)?;
+            insert_user(transaction, &data.banned_by)?;
+            transaction.execute(
+                "INSERT OR IGNORE INTO bans (id, chatroom_id, user_id, banned_by, permanent, duration, expires_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    data.id,
+                    chatroom_id,
+                    data.user.id,
+                    data.banned_by.id,
+                    data.permanent,
+                    data.duration.map(|duration| duration as i64),
+                    data.expires_at
+                ],
+            )?;
+        }
+        MessageData::UserUnbanned(data) => {
+            insert_user(transaction, &data.user)?;
+            insert_user(transaction, &data.unbanned_by)?;
+            transaction.execute(
+                "UPDATE bans SET unbanned_by = ?1
+                 WHERE chatroom_id IS ?2 AND user_id = ?3 AND unbanned_by IS NULL",
+                params![data.unbanned_by.id, chatroom_id, data.user.id],
+            )?;
+        }
+        MessageData::PollUpdate(data) => {
+            let chatroom_id = chatroom_id.unwrap_or_default();
+            let poll_id = match latest_poll(transaction, chatroom_id)? {
+                Some((poll_id, previous)) if is_same_poll(&previous, &data.poll) => {
+                    transaction.execute(
+                        "UPDATE polls SET remaining = ?1 WHERE id = ?2",
+                        params![data.poll.remaining, poll_id],
+                    )?;
+                    poll_id
+                }
+                _ => transaction.query_row(
+                    "INSERT INTO polls (chatroom_id, title, duration, remaining) VALUES (?1, ?2, ?3, ?4)
+                     RETURNING id",
+                    params![chatroom_id, data.poll.title, data.poll.duration, data.poll.remaining],
+                    |row| row.get(0),
+                )?,
+            };
+            for option in &data.poll.options {
+                transaction.execute(
+                    "INSERT INTO poll_options (poll_id, option_id, label, votes) VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT (poll_id, option_id)
+                     DO UPDATE SET label = excluded.label, votes = excluded.votes",
+                    params![poll_id, option.id, option.label, option.votes],
+                )?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Returns the ID and the stored state of the latest poll of a chatroom, if it had one.
+fn latest_poll(
+    transaction: &Transaction<'_>,
+    chatroom_id: u32,
+) -> rusqlite::Result<Option<(i64, Poll)>> {
+    let poll = transaction
+        .query_row(
+            "SELECT id, title, duration, remaining FROM polls
+             WHERE chatroom_id = ?1 ORDER BY id DESC LIMIT 1",
+            params![chatroom_id],
+            |row| {
+                let poll = Poll {
+                    title: row.get(1)?,
+                    options: Vec::new(),
+                    duration: row.get(2)?,
+                    remaining: row.get(3)?,
+                    result_display_duration: 0,
+                    has_voted: None,
+                    voted_option_id: None,
+                };
+                Ok((row.get(0)?, poll))
+            },
+        )
+        .optional()?;
+    let Some((poll_id, mut poll)) = poll else {
+        return Ok(None);
+    };
+    let mut options = transaction.prepare(
+        "SELECT option_id, label, votes FROM poll_options WHERE poll_id = ?1 ORDER BY rowid",
+    )?;
+    poll.options = options
+        .query_map(params![poll_id], |row| {
+            Ok(PollOption {
+                id: row.get(0)?,
+                label: row.get(1)?,
+                votes: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(Some((poll_id, poll)))
+}
+
+/// Inserts a chat message sender into `users`, or updates them if they were seen before.
+fn insert_sender(
+    transaction: &Transaction<'_>,
+    sender: &ChatMessageSender,
+) -> rusqlite::Result<()> {
+    transaction.execute(
+        "INSERT INTO users (id, username, slug, color) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (id) DO UPDATE SET
+             username = excluded.username,
+             slug = COALESCE(excluded.slug, users.slug),
+             color = COALESCE(excluded.color, users.color)",
+        params![
+            sender.id,
+            sender.username,
+            sender.slug,
+            sender.identity.color
+        ],
+    )?;
+    Ok(())
+}
+
+/// Inserts a user into `users`, or updates them if they were seen before.
+fn insert_user(transaction: &Transaction<'_>, user: &User) -> rusqlite::Result<()> {
+    transaction.execute(
+        "INSERT INTO users (id, username, slug) VALUES (?1, ?2, ?3)
+         ON CONFLICT (id) DO UPDATE SET username = excluded.username, slug = excluded.slug",
+        params![user.id, user.username, user.slug],
+    )?;
+    Ok(())
+}
+
+fn sink_error(err: rusqlite::Error) -> KickError {
+    KickError::SinkError(Box::new(err))
+}
@@ -0,0 +1,203 @@
+//! An HTTP server rebroadcasting messages as Server-Sent Events.
+//!
+//! Browsers subscribe with `new EventSource("http://localhost:3000/events/668")`, without
+//! any WebSocket or Pusher code on their side, e.g. in a streaming software's browser
+//! source. `/events/{chatroom}` streams the messages of a chatroom, and `/events` every
+//! message. Each event is named after `MessageData::kind`, so overlays can listen for just
+//! `chat` or `ban` events, and holds the message as JSON, with `data` as a nested object.
+//!
+//! Responses allow any origin, so overlays loaded from local files can subscribe too.
+
+use crate::encoding::to_json_value;
+use crate::{KickChatMessage, KickError, MessageSource};
+use axum::extract::{Path, State};
+use axum::http::header::ACCESS_CONTROL_ALLOW_ORIGIN;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::Router;
+use futures_util::Stream;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// How many messages are buffered for a slow subscriber before it misses some.
+const EVENT_BUFFER: usize = 1024;
+
+/// A message serialized once for every subscriber.
+struct Encoded {
+    chatroom_id: Option<u32>,
+    kind: &'static str,
+    json: String,
+}
+
+/// The sending end of the SSE endpoints, handing messages to every subscriber.
+#[derive(Clone)]
+pub struct SseBroadcaster {
+    events: broadcast::Sender<Arc<Encoded>>,
+}
+
+impl SseBroadcaster {
+    /// Sends a message to the subscribers of its chatroom and of every message, returning
+    /// how many subscribers are connected.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message cannot be serialized.
+    pub fn send(&self, message: &KickChatMessage) -> Result<usize, KickError> {
+        let encoded = Encoded {
+            chatroom_id: message.chatroom_id(),
+            kind: message.data.kind(),
+            json: to_json_value(message)?.to_string(),
+        };
+        Ok(self.events.send(Arc::new(encoded)).unwrap_or(0))
+    }
+
+    /// Returns the number of connected subscribers.
+    pub fn subscribers(&self) -> usize {
+        self.events.receiver_count()
+    }
+
+    /// Sends every message read from `source` to the subscribers, until the source ends.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if reading from the source fails.
+    pub async fn forward<S: MessageSource>(&self, mut source: S) -> Result<(), KickError> {
+        while let Some(message) = source.read_message().await? {
+            self.send(&message)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the events of a chatroom, or of every chatroom, as a stream of SSE events.
+    fn subscribe(&self, chatroom_id: Option<u32>) -> impl Stream<Item = Result<Event, Infallible>> {
+        let events = self.events.subscribe();
+        futures_util::stream::unfold(events, move |mut events| async move {
+            loop {
+                match events.recv().await {
+                    Ok(encoded) if chatroom_id.is_none() || encoded.chatroom_id == chatroom_id => {
+                        let event = Event::default().event(encoded.kind).data(&encoded.json);
+                        return Some((Ok(event), events));
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+}
+
+/// Returns a router serving the SSE endpoints, to be mounted in an existing application,
+/// and the broadcaster feeding them.
+pub fn router() -> (Router, SseBroadcaster) {
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+    let broadcaster = SseBroadcaster { events };
+    let router = Router::new()
+        .route("/events", get(all_events))
+        .route("/events/{chatroom}", get(chatroom_events))
+        .with_state(broadcaster.clone());
+    (router, broadcaster)
+}
+
+async fn all_events(State(broadcaster): State<SseBroadcaster>) -> impl IntoResponse {
+    respond(&broadcaster, None)
+}
+
+async fn chatroom_events(
+    State(broadcaster): State<SseBroadcaster>,
+    Path(chatroom_id): Path<u32>,
+) -> impl IntoResponse {
+    respond(&broadcaster, Some(chatroom_id))
+}
+
+fn respond(broadcaster: &SseBroadcaster, chatroom_id: Option<u32>) -> impl IntoResponse {
+    let stream = broadcaster.subscribe(chatroom_id);
+    (
+        [(ACCESS_CONTROL_ALLOW_ORIGIN, "*")],
+        Sse::new(stream).keep_alive(KeepAlive::default()),
+    )
+}
+
+/// A standalone HTTP server serving the SSE endpoints.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::sse::SseServer;
+/// use kick_client::KickClient;
+///
+/// let client = KickClient::new(kick_client::DEFAULT_WEBSOCKET_URL, vec![668]).await.unwrap();
+/// let server = SseServer::bind("127.0.0.1:3000").await?;
+/// // Overlays subscribe to http://127.0.0.1:3000/events/668.
+/// server.forward(client).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SseServer {
+    broadcaster: SseBroadcaster,
+    /// The address the server is listening on.
+    local_addr: SocketAddr,
+    /// The task running the server, stopped when the server is dropped.
+    server: JoinHandle<()>,
+}
+
+impl SseServer {
+    /// Starts a server on the given address.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the address cannot be bound.
+    pub async fn bind(addr: impl ToSocketAddrs) -> Result<Self, KickError> {
+        let (router, broadcaster) = router();
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let server = crate::trace::spawn("kick_client::sse::server", async move {
+            let _ = axum::serve(listener, router).await;
+        });
+
+        Ok(Self {
+            broadcaster,
+            local_addr,
+            server,
+        })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Returns the broadcaster feeding the server, e.g. to send messages from another task.
+    pub fn broadcaster(&self) -> SseBroadcaster {
+        self.broadcaster.clone()
+    }
+
+    /// Sends a message to its subscribers. See `SseBroadcaster::send`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the message cannot be serialized.
+    pub fn send(&self, message: &KickChatMessage) -> Result<usize, KickError> {
+        self.broadcaster.send(message)
+    }
+
+    /// Sends every message read from `source` to the subscribers, until the source ends.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if reading from the source fails.
+    pub async fn forward<S: MessageSource>(&self, source: S) -> Result<(), KickError> {
+        self.broadcaster.forward(source).await
+    }
+}
+
+impl Drop for SseServer {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
@@ -0,0 +1,103 @@
+use crate::ChatroomUpdatedEventData;
+use serde::Serialize;
+#[cfg(feature = "client-core")]
+use {
+    crate::{KickChatMessage, MessageData},
+    std::{collections::HashMap, sync::Mutex},
+    tokio::sync::watch,
+};
+
+/// The chat mode configuration of a chatroom.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ChatroomState {
+    /// The minimum number of seconds between a user's messages, if slow mode is enabled.
+    pub slow_mode: Option<u64>,
+    /// How many minutes a user must have followed the channel before chatting, if
+    /// followers-only mode is enabled.
+    pub followers_mode: Option<u64>,
+    /// Whether only subscribers may chat.
+    pub subscribers_mode: bool,
+    /// Whether messages may only contain emotes.
+    pub emotes_mode: bool,
+    /// How many seconds advanced bot protection remains active for, if it is enabled.
+    pub advanced_bot_protection: Option<u64>,
+}
+
+impl From<&ChatroomUpdatedEventData> for ChatroomState {
+    fn from(update: &ChatroomUpdatedEventData) -> Self {
+        Self {
+            slow_mode: update
+                .slow_mode
+                .enabled
+                .then_some(update.slow_mode.message_interval),
+            followers_mode: update
+                .followers_mode
+                .enabled
+                .then_some(update.followers_mode.min_duration),
+            subscribers_mode: update.subscribers_mode.enabled,
+            emotes_mode: update.emotes_mode.enabled,
+            advanced_bot_protection: update
+                .advanced_bot_protection
+                .enabled
+                .then_some(update.advanced_bot_protection.remaining_time),
+        }
+    }
+}
+
+/// Keeps the current `ChatroomState` of each chatroom from `ChatroomUpdated` messages.
+///
+/// The state of a chatroom is unknown, `None`, until its first update is received.
+#[cfg(feature = "client-core")]
+#[derive(Debug, Default)]
+pub struct ChatroomStateTracker {
+    states: Mutex<HashMap<u32, watch::Sender<Option<ChatroomState>>>>,
+}
+
+#[cfg(feature = "client-core")]
+impl ChatroomStateTracker {
+    /// Creates a new instance of `ChatroomStateTracker` without any known states.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the tracked states from a received message.
+    pub fn observe(&self, message: &KickChatMessage) {
+        if let MessageData::ChatroomUpdated(update) = &message.data {
+            self.apply(update);
+        }
+    }
+
+    /// Applies a chatroom update, notifying the watchers of the chatroom if its state
+    /// changed.
+    pub fn apply(&self, update: &ChatroomUpdatedEventData) {
+        let state = Some(ChatroomState::from(update));
+        self.with_sender(update.id, |sender| {
+            sender.send_if_modified(|current| {
+                let modified = *current != state;
+                *current = state;
+                modified
+            })
+        });
+    }
+
+    /// Returns the current state of a chatroom, if known.
+    pub fn get(&self, chatroom_id: u32) -> Option<ChatroomState> {
+        self.with_sender(chatroom_id, |sender| *sender.borrow())
+    }
+
+    /// Returns a receiver notified whenever the state of a chatroom changes.
+    pub fn watch(&self, chatroom_id: u32) -> watch::Receiver<Option<ChatroomState>> {
+        self.with_sender(chatroom_id, watch::Sender::subscribe)
+    }
+
+    fn with_sender<R>(
+        &self,
+        chatroom_id: u32,
+        f: impl FnOnce(&watch::Sender<Option<ChatroomState>>) -> R,
+    ) -> R {
+        let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        f(states
+            .entry(chatroom_id)
+            .or_insert_with(|| watch::channel(None).0))
+    }
+}
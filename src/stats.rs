@@ -0,0 +1,196 @@
+use crate::content::emotes;
+use crate::{KickChatMessage, MessageData};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// How many entries the top lists of a snapshot hold by default.
+const DEFAULT_TOP: usize = 10;
+/// The window messages per second are averaged over by default.
+const DEFAULT_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// A point-in-time view of the statistics gathered by `ChatStats`.
+#[derive(Serialize, Debug, Clone)]
+pub struct StatsSnapshot {
+    /// How long the statistics have been gathered for.
+    pub elapsed: Duration,
+    /// The average number of chat messages per second over the rate window.
+    pub messages_per_second: f64,
+    /// The number of chat messages received.
+    pub total_messages: u64,
+    /// The number of distinct users who sent a chat message.
+    pub unique_chatters: usize,
+    /// The usernames of the most active chatters with their message counts, most active first.
+    pub top_chatters: Vec<(String, u64)>,
+    /// The names of the most used emotes with their use counts, most used first.
+    pub top_emotes: Vec<(String, u64)>,
+    /// The number of permanent bans.
+    pub bans: u64,
+    /// The number of timeouts.
+    pub timeouts: u64,
+    /// The number of deleted messages.
+    pub deleted_messages: u64,
+}
+
+/// Aggregates statistics about the messages of a stream, e.g. for overlays and
+/// end-of-stream reports.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+/// use kick_client::stats::ChatStats;
+///
+/// let mut stats = ChatStats::new();
+/// while let Some(message) = client.read_message().await? {
+///     stats.observe(&message);
+/// }
+/// println!("{:#?}", stats.snapshot());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ChatStats {
+    started_at: Instant,
+    rate_window: Duration,
+    top: usize,
+    /// When each chat message within the rate window was received, oldest first.
+    recent: VecDeque<Instant>,
+    total_messages: u64,
+    /// The username and message count of each chatter.
+    chatters: HashMap<u32, (String, u64)>,
+    /// The use count of each emote, by name.
+    emotes: HashMap<String, u64>,
+    bans: u64,
+    timeouts: u64,
+    deleted_messages: u64,
+}
+
+impl ChatStats {
+    /// Creates a new instance of `ChatStats` averaging messages per second over a minute
+    /// and keeping the top 10 chatters and emotes.
+    pub fn new() -> Self {
+        Self {
+            started_at: Instant::now(),
+            rate_window: DEFAULT_RATE_WINDOW,
+            top: DEFAULT_TOP,
+            recent: VecDeque::new(),
+            total_messages: 0,
+            chatters: HashMap::new(),
+            emotes: HashMap::new(),
+            bans: 0,
+            timeouts: 0,
+            deleted_messages: 0,
+        }
+    }
+
+    /// Sets the window messages per second are averaged over.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero.
+    pub fn with_rate_window(mut self, window: Duration) -> Self {
+        assert!(!window.is_zero(), "window must be positive");
+        self.rate_window = window;
+        self
+    }
+
+    /// Sets how many entries the top lists of a snapshot hold.
+    pub fn with_top(mut self, top: usize) -> Self {
+        self.top = top;
+        self
+    }
+
+    /// Updates the statistics from a received message.
+    pub fn observe(&mut self, message: &KickChatMessage) {
+        match &message.data {
+            MessageData::ChatMessage(data) => {
+                let now = Instant::now();
+                self.recent.push_back(now);
+                self.expire(now);
+                self.total_messages += 1;
+
+                let chatter = self
+                    .chatters
+                    .entry(data.sender.id)
+                    .or_insert_with(|| (data.sender.username.clone(), 0));
+                chatter.1 += 1;
+
+                for emote in emotes(data.content.as_deref().unwrap_or_default()) {
+                    *self.emotes.entry(emote.name.to_string()).or_default() += 1;
+                }
+            }
+            MessageData::UserBanned(data) if data.permanent => self.bans += 1,
+            MessageData::UserBanned(_) => self.timeouts += 1,
+            MessageData::DeletedMessage(_) => self.deleted_messages += 1,
+            _ => {}
+        }
+    }
+
+    /// Returns the current statistics.
+    pub fn snapshot(&self) -> StatsSnapshot {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.started_at);
+        let recent = self
+            .recent
+            .iter()
+            .filter(|received_at| now.duration_since(**received_at) <= self.rate_window)
+            .count();
+        // Until a full window has passed, average over the time elapsed so far.
+        let window = self.rate_window.min(elapsed).max(Duration::from_secs(1));
+
+        StatsSnapshot {
+            elapsed,
+            messages_per_second: recent as f64 / window.as_secs_f64(),
+            total_messages: self.total_messages,
+            unique_chatters: self.chatters.len(),
+            top_chatters: top(
+                self.chatters.values().map(|(name, count)| (name, *count)),
+                self.top,
+            ),
+            top_emotes: top(
+                self.emotes.iter().map(|(name, count)| (name, *count)),
+                self.top,
+            ),
+            bans: self.bans,
+            timeouts: self.timeouts,
+            deleted_messages: self.deleted_messages,
+        }
+    }
+
+    /// Clears the statistics, e.g. when a new stream starts.
+    pub fn reset(&mut self) {
+        *self = Self {
+            rate_window: self.rate_window,
+            top: self.top,
+            ..Self::new()
+        };
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while self
+            .recent
+            .front()
+            .is_some_and(|received_at| now.duration_since(*received_at) > self.rate_window)
+        {
+            self.recent.pop_front();
+        }
+    }
+}
+
+impl Default for ChatStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the `n` entries with the highest counts, highest first, ties broken by name.
+fn top<'a>(entries: impl Iterator<Item = (&'a String, u64)>, n: usize) -> Vec<(String, u64)> {
+    let mut entries: Vec<_> = entries.collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    entries
+        .into_iter()
+        .take(n)
+        .map(|(name, count)| (name.clone(), count))
+        .collect()
+}
@@ -0,0 +1,159 @@
+//! Emitting client metrics to a statsd or DogStatsD agent over UDP.
+//!
+//! A `Metrics` given an emitter with `Metrics::with_statsd` sends every observed message to
+//! the agent as it is counted, with no scrape target to run:
+//!
+//! - `kick_client.messages`, a counter of read messages by kind.
+//! - `kick_client.unsupported_messages`, a counter of messages that couldn't be parsed.
+//! - `kick_client.dropped`, a counter of messages dropped because a buffer was full.
+//! - `kick_client.latency`, a timer of chat message latency in milliseconds.
+//!
+//! DogStatsD agents receive the kind of a message as a `kind` tag. Plain statsd has no
+//! tags, so the kind is appended to the metric name instead, e.g. `kick_client.messages.chat`.
+//!
+//! ```no_run
+//! # async fn run(mut client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+//! use kick_client::metrics::Metrics;
+//! use kick_client::statsd::StatsdEmitter;
+//!
+//! let emitter = StatsdEmitter::dogstatsd("127.0.0.1:8125")?.with_tag("env", "prod");
+//! let metrics = Metrics::new().with_statsd(emitter);
+//! while let Some(message) = client.read_message().await? {
+//!     metrics.observe(&message);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fmt::{self, Write};
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The prefix of metric names by default.
+const DEFAULT_PREFIX: &str = "kick_client";
+
+/// Sends metrics to a statsd or DogStatsD agent. Cloning a `StatsdEmitter` is cheap and all
+/// clones share the same socket.
+///
+/// Metrics are sent as single datagrams from a non-blocking socket, and failures to send
+/// them are ignored, as statsd is meant to be lossy.
+#[derive(Clone)]
+pub struct StatsdEmitter {
+    socket: Arc<UdpSocket>,
+    prefix: String,
+    /// Whether the agent understands DogStatsD tags.
+    dogstatsd: bool,
+    /// The tags sent with every metric, already formatted as `key:value`.
+    tags: Vec<String>,
+}
+
+impl StatsdEmitter {
+    /// Creates a new instance of `StatsdEmitter` sending to a plain statsd agent at `addr`.
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::open(addr, false)
+    }
+
+    /// Creates a new instance of `StatsdEmitter` sending to a DogStatsD agent at `addr`.
+    pub fn dogstatsd(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::open(addr, true)
+    }
+
+    fn open(addr: impl ToSocketAddrs, dogstatsd: bool) -> io::Result<Self> {
+        let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no address to send metrics to")
+        })?;
+        let local: SocketAddr = match addr {
+            SocketAddr::V4(_) => ([0, 0, 0, 0], 0).into(),
+            SocketAddr::V6(_) => ([0u16; 8], 0).into(),
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(addr)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket: Arc::new(socket),
+            prefix: DEFAULT_PREFIX.to_string(),
+            dogstatsd,
+            tags: Vec::new(),
+        })
+    }
+
+    /// Sets the prefix of metric names, `kick_client` by default. An empty prefix sends the
+    /// names as they are.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Adds a tag sent with every metric. Tags are only sent to DogStatsD agents.
+    pub fn with_tag(mut self, key: impl fmt::Display, value: impl fmt::Display) -> Self {
+        self.tags.push(format!("{}:{}", key, value));
+        self
+    }
+
+    /// Adds `value` to the counter `name`.
+    pub fn count(&self, name: &str, value: u64, tags: &[(&str, &str)]) {
+        self.send(name, value, "c", tags);
+    }
+
+    /// Sets the gauge `name` to `value`.
+    pub fn gauge(&self, name: &str, value: u64, tags: &[(&str, &str)]) {
+        self.send(name, value, "g", tags);
+    }
+
+    /// Records `duration` in the timer `name`, in milliseconds.
+    pub fn timing(&self, name: &str, duration: Duration, tags: &[(&str, &str)]) {
+        self.send(name, duration.as_millis(), "ms", tags);
+    }
+
+    fn send(&self, name: &str, value: impl fmt::Display, kind: &str, tags: &[(&str, &str)]) {
+        let _ = self
+            .socket
+            .send(self.format(name, value, kind, tags).as_bytes());
+    }
+
+    /// Formats a metric as a statsd line, e.g. `kick_client.messages:1|c|#kind:chat`.
+    fn format(
+        &self,
+        name: &str,
+        value: impl fmt::Display,
+        kind: &str,
+        tags: &[(&str, &str)],
+    ) -> String {
+        let mut line = String::new();
+        if !self.prefix.is_empty() {
+            line.push_str(&self.prefix);
+            line.push('.');
+        }
+        line.push_str(name);
+        if !self.dogstatsd {
+            for (_, value) in tags {
+                line.push('.');
+                line.push_str(value);
+            }
+        }
+        let _ = write!(line, ":{}|{}", value, kind);
+        if self.dogstatsd && !(self.tags.is_empty() && tags.is_empty()) {
+            let tags = self
+                .tags
+                .iter()
+                .cloned()
+                .chain(tags.iter().map(|(key, value)| format!("{}:{}", key, value)));
+            line.push_str("|#");
+            line.push_str(&tags.collect::<Vec<_>>().join(","));
+        }
+        line
+    }
+}
+
+impl fmt::Debug for StatsdEmitter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatsdEmitter")
+            .field("peer", &self.socket.peer_addr().ok())
+            .field("prefix", &self.prefix)
+            .field("dogstatsd", &self.dogstatsd)
+            .field("tags", &self.tags)
+            .finish()
+    }
+}
@@ -0,0 +1,107 @@
+//! Strict parsing, reporting the fields of frames which the typed events don't have.
+//!
+//! Kick adds fields to its payloads without notice, and parsing ignores them so clients
+//! keep working. Strict parsing reports them instead, to notice schema changes early.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn run(client: kick_client::KickClient) -> Result<(), kick_client::KickError> {
+//! use kick_client::MessageData;
+//!
+//! let mut client = client.with_strict_parsing(true);
+//! while let Some(message) = client.read_message().await? {
+//!     if let MessageData::Unsupported(_, err) = &message.data {
+//!         eprintln!("{}", err);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{KickChatMessage, MessageData};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Returns the fields of `frame` which are missing from `message`, parsed from it, as
+/// sorted and deduplicated paths such as `data.metadata` or `data.sender.identity.badges.kind`.
+/// The JSON string in `data` is decoded on both sides, and array items are compared
+/// index by index.
+///
+/// Frames which couldn't be parsed into a typed event have no unknown fields.
+pub fn unknown_fields(message: &KickChatMessage, frame: &[u8]) -> Vec<String> {
+    if matches!(
+        message.data,
+        MessageData::Unknown(_) | MessageData::Unsupported(..)
+    ) {
+        return Vec::new();
+    }
+    let (Ok(received), Ok(parsed)) = (
+        serde_json::from_slice::<Value>(frame),
+        serde_json::to_value(message),
+    ) else {
+        return Vec::new();
+    };
+    let mut unknown = BTreeSet::new();
+    diff(
+        &decode_data(received),
+        &decode_data(parsed),
+        "",
+        &mut unknown,
+    );
+    unknown.into_iter().collect()
+}
+
+/// Turns `message` into `MessageData::Unsupported` if `frame` has unknown fields, listing
+/// them in its error.
+#[cfg(any(feature = "client", feature = "async-std", feature = "wasm"))]
+pub(crate) fn check(message: KickChatMessage, frame: &[u8]) -> KickChatMessage {
+    let unknown = unknown_fields(&message, frame);
+    if unknown.is_empty() {
+        return message;
+    }
+    KickChatMessage {
+        data: MessageData::Unsupported(
+            Some(String::from_utf8_lossy(frame).into_owned()),
+            format!("unknown fields: {}", unknown.join(", ")),
+        ),
+        channel: message.channel,
+    }
+}
+
+/// Decodes the `data` of a frame when it is a JSON string.
+fn decode_data(mut frame: Value) -> Value {
+    if let Some(data) = frame.get_mut("data") {
+        if let Some(decoded) = data.as_str().and_then(|s| serde_json::from_str(s).ok()) {
+            *data = decoded;
+        }
+    }
+    frame
+}
+
+/// Collects the paths of the object fields of `received` which `parsed` doesn't have.
+fn diff(received: &Value, parsed: &Value, path: &str, unknown: &mut BTreeSet<String>) {
+    match (received, parsed) {
+        (Value::Object(received), Value::Object(parsed)) => {
+            for (key, value) in received {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                match parsed.get(key) {
+                    Some(parsed) => diff(value, parsed, &path, unknown),
+                    None => {
+                        unknown.insert(path);
+                    }
+                }
+            }
+        }
+        (Value::Array(received), Value::Array(parsed)) => {
+            for (received, parsed) in received.iter().zip(parsed) {
+                diff(received, parsed, path, unknown);
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,100 @@
+//! Spans and task names for debugging with `tracing` and `tokio-console`.
+//!
+//! With the `tracing` feature, connecting, reading frames and dispatching messages run in
+//! `kick_client` spans, and spawned tasks are named after the part of the client they run,
+//! when built with `--cfg tokio_unstable` as `tokio-console` requires. Without it the
+//! spans compile to nothing.
+
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::Instrument;
+
+/// Creates a debug-level span, or a placeholder without the `tracing` feature.
+macro_rules! span {
+    ($($args:tt)*) => {{
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(target: "kick_client", $($args)*);
+        #[cfg(not(feature = "tracing"))]
+        let span = $crate::trace::Span;
+        span
+    }};
+}
+
+/// A span which records nothing, without the `tracing` feature.
+#[cfg(not(feature = "tracing"))]
+#[derive(Debug, Clone)]
+pub(crate) struct Span;
+
+// Spans are only entered by the client, not by the servers spawning tasks.
+#[cfg(all(
+    not(feature = "tracing"),
+    any(feature = "client", feature = "async-std", feature = "wasm")
+))]
+impl Span {
+    pub(crate) fn enter(&self) -> Entered {
+        Entered
+    }
+}
+
+/// The guard of an entered placeholder span.
+#[cfg(all(
+    not(feature = "tracing"),
+    any(feature = "client", feature = "async-std", feature = "wasm")
+))]
+pub(crate) struct Entered;
+
+/// Runs a future in a span, or as is without the `tracing` feature.
+#[cfg(not(feature = "tracing"))]
+pub(crate) trait Instrument: Sized {
+    fn instrument(self, _span: Span) -> Self {
+        self
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+impl<T: std::future::Future> Instrument for T {}
+
+/// Spawns a task named `name` running in a span of the same name, on the current runtime.
+///
+/// # Panics
+///
+/// This function panics if called outside of a Tokio runtime.
+#[cfg(any(
+    all(
+        feature = "tokio-handling",
+        any(feature = "client", feature = "async-std", feature = "wasm")
+    ),
+    feature = "api",
+    feature = "webhook",
+    feature = "grpc",
+    feature = "irc",
+    feature = "sse",
+    feature = "rebroadcast",
+    feature = "mock-server",
+    feature = "pool",
+    feature = "manager",
+    feature = "handle",
+    feature = "ffi",
+    feature = "health",
+    feature = "shutdown",
+))]
+pub(crate) fn spawn<F>(
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))] name: &'static str,
+    future: F,
+) -> tokio::task::JoinHandle<F::Output>
+where
+    F: std::future::Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let future = future.instrument(span!("task", name));
+    #[cfg(all(tokio_unstable, feature = "tracing"))]
+    {
+        tokio::task::Builder::new()
+            .name(name)
+            .spawn(future)
+            .expect("failed to spawn a task")
+    }
+    #[cfg(not(all(tokio_unstable, feature = "tracing")))]
+    {
+        tokio::spawn(future)
+    }
+}
@@ -0,0 +1,90 @@
+use crate::KickError;
+use bytes::Bytes;
+use std::future::Future;
+
+#[cfg(feature = "async-std")]
+mod async_std;
+#[cfg(feature = "wasm")]
+mod browser;
+#[cfg(feature = "deflate")]
+mod deflate;
+#[cfg(feature = "client")]
+mod websocket;
+
+#[cfg(feature = "async-std")]
+pub use async_std::AsyncStdTransport;
+#[cfg(feature = "wasm")]
+pub use browser::BrowserTransport;
+#[cfg(feature = "client")]
+pub use websocket::{
+    ConnectOptions, Connector, Resolve, StaticResolver, WebSocketConfig, WebSocketTransport,
+};
+
+/// A frame received through a `Transport`.
+///
+/// Payloads are `Bytes` so transports can hand over the buffer they received a frame in
+/// without copying it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Frame {
+    /// A text frame, carrying a Pusher message encoded as UTF-8.
+    Text(Bytes),
+    /// A binary frame, which Kick doesn't send.
+    Binary(Bytes),
+    /// A protocol-level ping.
+    Ping(Bytes),
+    /// A protocol-level pong.
+    Pong(Bytes),
+    /// The server closed the connection.
+    Close,
+}
+
+/// A bidirectional connection to a Pusher server, carrying text frames.
+///
+/// `KickClient` handles subscribing and parsing on top of a transport, so alternative
+/// connections such as mocks or replays can be plugged in.
+pub trait Transport: Send + Sized {
+    /// Connects to the server at `url`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the connection cannot be established.
+    fn connect(url: &str) -> impl Future<Output = Result<Self, KickError>> + Send;
+
+    /// Sends a text frame.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the frame cannot be sent.
+    fn send(&mut self, text: String) -> impl Future<Output = Result<(), KickError>> + Send;
+
+    /// Receives the next frame, or `None` once the connection is closed.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if receiving fails.
+    fn next_frame(&mut self) -> impl Future<Output = Result<Option<Frame>, KickError>> + Send;
+
+    /// Closes the connection, telling the server when the transport can. Does nothing by
+    /// default.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the close request cannot be sent.
+    fn close(&mut self) -> impl Future<Output = Result<(), KickError>> + Send {
+        async { Ok(()) }
+    }
+}
+
+#[cfg(any(feature = "client", feature = "async-std"))]
+impl From<tungstenite::Message> for Frame {
+    fn from(message: tungstenite::Message) -> Self {
+        match message {
+            tungstenite::Message::Text(text) => Frame::Text(text.into()),
+            tungstenite::Message::Binary(data) => Frame::Binary(data),
+            tungstenite::Message::Ping(data) => Frame::Ping(data),
+            tungstenite::Message::Pong(data) => Frame::Pong(data),
+            tungstenite::Message::Close(_) => Frame::Close,
+            tungstenite::Message::Frame(frame) => Frame::Binary(frame.into_payload()),
+        }
+    }
+}
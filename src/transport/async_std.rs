@@ -0,0 +1,78 @@
+use super::{Frame, Transport};
+use crate::KickError;
+use futures_util::StreamExt;
+use tungstenite::protocol::Message;
+
+/// A WebSocket connection driven by `async-std` instead of Tokio, for programs running on
+/// `async-std` or `smol`, whose executors also drive `async-std`'s I/O.
+///
+/// `wss://` URLs are connected to with the platform's TLS library. Of `ConnectOptions`,
+/// available with the `client` feature, only the headers and the WebSocket configuration
+/// apply; timeouts, proxies, resolvers, local addresses and TLS connectors require
+/// `WebSocketTransport`. As
+/// `KickClient::wait_for_subscriptions` uses Tokio's timer, it cannot be used either.
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::transport::AsyncStdTransport;
+/// use kick_client::KickClient;
+///
+/// let mut client =
+///     KickClient::<AsyncStdTransport>::connect(kick_client::DEFAULT_WEBSOCKET_URL, vec![668]).await?;
+/// while let Some(message) = client.read_message().await? {
+///     println!("{}", message);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncStdTransport {
+    stream: async_tungstenite::WebSocketStream<async_tungstenite::async_std::ConnectStream>,
+}
+
+#[cfg(feature = "client")]
+impl AsyncStdTransport {
+    /// Connects to the server at `url` with the headers and WebSocket configuration of
+    /// `options`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the connection cannot be established.
+    pub async fn connect_with(
+        url: &str,
+        options: &super::ConnectOptions,
+    ) -> Result<Self, KickError> {
+        let request = options.request(url)?;
+        let config = options.websocket_config();
+        let (stream, _) =
+            async_tungstenite::async_std::connect_async_with_config(request, config).await?;
+        Ok(Self { stream })
+    }
+}
+
+impl Transport for AsyncStdTransport {
+    async fn connect(url: &str) -> Result<Self, KickError> {
+        let (stream, _) = async_tungstenite::async_std::connect_async(url).await?;
+        Ok(Self { stream })
+    }
+
+    async fn send(&mut self, text: String) -> Result<(), KickError> {
+        self.stream.send(Message::Text(text.into())).await?;
+        Ok(())
+    }
+
+    async fn next_frame(&mut self) -> Result<Option<Frame>, KickError> {
+        let Some(message) = self.stream.next().await else {
+            return Ok(None);
+        };
+        Ok(Some(Frame::from(message?)))
+    }
+
+    async fn close(&mut self) -> Result<(), KickError> {
+        match self.stream.close(None).await {
+            Ok(()) | Err(tungstenite::Error::AlreadyClosed | tungstenite::Error::ConnectionClosed) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use super::{Frame, Transport};
+use crate::KickError;
+use std::io;
+
+/// The events of a browser WebSocket, forwarded from its callbacks.
+enum BrowserEvent {
+    Open,
+    Frame(Frame),
+    Error,
+}
+
+/// A WebSocket connection through the browser's `WebSocket`, for web dashboards compiled
+/// to WebAssembly, e.g. with Yew or Leptos.
+///
+/// The browser handles TLS and pings, and doesn't allow setting headers, so
+/// `ConnectOptions` don't apply. The client must be read on the browser's event loop, e.g.
+/// in a task spawned with `wasm_bindgen_futures::spawn_local`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # fn show(_: &kick_client::KickChatMessage) {}
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::transport::BrowserTransport;
+/// use kick_client::KickClient;
+///
+/// let mut client =
+///     KickClient::<BrowserTransport>::connect(kick_client::DEFAULT_WEBSOCKET_URL, vec![668]).await?;
+/// while let Some(message) = client.read_message().await? {
+///     show(&message);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct BrowserTransport {
+    socket: web_sys::WebSocket,
+    events: tokio::sync::mpsc::UnboundedReceiver<BrowserEvent>,
+    /// The callbacks registered on the socket, unregistered when the transport is dropped.
+    _callbacks: [wasm_bindgen::closure::Closure<dyn FnMut(web_sys::Event)>; 4],
+    /// Whether the server closed the connection.
+    closed: bool,
+}
+
+// SAFETY: JavaScript values cannot leave the thread they were created on, but without the
+// `atomics` target feature, WebAssembly programs run on a single thread.
+#[cfg(not(target_feature = "atomics"))]
+unsafe impl Send for BrowserTransport {}
+
+impl BrowserTransport {
+    fn error(action: &str, error: wasm_bindgen::JsValue) -> KickError {
+        KickError::IoError(io::Error::other(format!("{}: {:?}", action, error)))
+    }
+
+    /// Creates the socket, registering callbacks forwarding its events.
+    fn open(url: &str) -> Result<Self, KickError> {
+        use wasm_bindgen::closure::Closure;
+        use wasm_bindgen::JsCast;
+
+        let socket = web_sys::WebSocket::new(url)
+            .map_err(|e| Self::error("creating the WebSocket failed", e))?;
+        socket.set_binary_type(web_sys::BinaryType::Arraybuffer);
+        let (sender, events) = tokio::sync::mpsc::unbounded_channel();
+        let callback = |handle: fn(web_sys::Event) -> BrowserEvent| {
+            let sender = sender.clone();
+            Closure::<dyn FnMut(web_sys::Event)>::new(move |event| {
+                let _ = sender.send(handle(event));
+            })
+        };
+        let callbacks = [
+            callback(|_| BrowserEvent::Open),
+            callback(|event| {
+                let data = event.unchecked_into::<web_sys::MessageEvent>().data();
+                BrowserEvent::Frame(match data.as_string() {
+                    Some(text) => Frame::Text(text.into()),
+                    None => Frame::Binary(js_sys::Uint8Array::new(&data).to_vec().into()),
+                })
+            }),
+            callback(|_| BrowserEvent::Frame(Frame::Close)),
+            callback(|_| BrowserEvent::Error),
+        ];
+        socket.set_onopen(Some(callbacks[0].as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(callbacks[1].as_ref().unchecked_ref()));
+        socket.set_onclose(Some(callbacks[2].as_ref().unchecked_ref()));
+        socket.set_onerror(Some(callbacks[3].as_ref().unchecked_ref()));
+
+        Ok(Self {
+            socket,
+            events,
+            _callbacks: callbacks,
+            closed: false,
+        })
+    }
+}
+
+impl Transport for BrowserTransport {
+    async fn connect(url: &str) -> Result<Self, KickError> {
+        let mut transport = Self::open(url)?;
+        match transport.events.recv().await {
+            Some(BrowserEvent::Open) => Ok(transport),
+            // The browser doesn't tell why connecting failed.
+            _ => Err(KickError::StreamEnded),
+        }
+    }
+
+    async fn send(&mut self, text: String) -> Result<(), KickError> {
+        self.socket
+            .send_with_str(&text)
+            .map_err(|e| Self::error("sending failed", e))
+    }
+
+    async fn next_frame(&mut self) -> Result<Option<Frame>, KickError> {
+        if self.closed {
+            return Ok(None);
+        }
+        match self.events.recv().await {
+            Some(BrowserEvent::Frame(frame)) => {
+                self.closed = frame == Frame::Close;
+                Ok(Some(frame))
+            }
+            Some(BrowserEvent::Error) => {
+                Err(KickError::IoError(io::Error::other("the WebSocket failed")))
+            }
+            Some(BrowserEvent::Open) | None => Ok(None),
+        }
+    }
+}
+
+impl Drop for BrowserTransport {
+    fn drop(&mut self) {
+        self.socket.set_onopen(None);
+        self.socket.set_onmessage(None);
+        self.socket.set_onclose(None);
+        self.socket.set_onerror(None);
+        let _ = self.socket.close();
+    }
+}
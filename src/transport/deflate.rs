@@ -0,0 +1,386 @@
+//! The `permessage-deflate` WebSocket extension (RFC 7692), which tungstenite doesn't
+//! implement.
+//!
+//! `Inflate` sits between the TLS stream and tungstenite: it reads the server's handshake
+//! response to learn whether compression was accepted, then decompresses every compressed
+//! message into a plain frame before tungstenite parses it. Messages sent by the client
+//! aren't compressed, which the extension allows.
+
+use crate::KickError;
+use flate2::{Decompress, FlushDecompress, Status};
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{Connector, MaybeTlsStream};
+use tungstenite::error::UrlError;
+use tungstenite::handshake::client::Request;
+
+/// The value of the `Sec-WebSocket-Extensions` header offering compression.
+pub(crate) const OFFER: &str = "permessage-deflate";
+
+/// The trailer RFC 7692 strips from compressed messages, restored before inflating them.
+const TRAILER: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+/// The largest decompressed message by default, tungstenite's default message limit.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 << 20;
+
+/// A stream decompressing the messages of a connection which negotiated
+/// `permessage-deflate`, and passing everything else through unchanged.
+pub(crate) struct Inflate<S> {
+    inner: S,
+    /// Whether the handshake response was read yet.
+    handshaken: bool,
+    /// Whether the server accepted compression.
+    negotiated: bool,
+    /// Whether the server resets its compression context after every message.
+    no_context_takeover: bool,
+    decompress: Decompress,
+    /// The largest frame or decompressed message accepted.
+    max_message_size: usize,
+    /// Bytes read from `inner`, not processed yet.
+    input: Vec<u8>,
+    /// Processed bytes, not read yet.
+    output: Vec<u8>,
+    read: usize,
+    /// The opcode and compressed payload of a message whose frames are still arriving.
+    message: Option<(u8, Vec<u8>)>,
+    eof: bool,
+}
+
+impl<S> Inflate<S> {
+    pub(crate) fn new(inner: S, max_message_size: Option<usize>) -> Self {
+        Self {
+            inner,
+            handshaken: false,
+            negotiated: false,
+            no_context_takeover: false,
+            decompress: Decompress::new(false),
+            max_message_size: max_message_size.unwrap_or(DEFAULT_MAX_MESSAGE_SIZE),
+            input: Vec::new(),
+            output: Vec::new(),
+            read: 0,
+            message: None,
+            eof: false,
+        }
+    }
+
+    /// Returns `true` if the server accepted compression.
+    pub(crate) fn is_negotiated(&self) -> bool {
+        self.negotiated
+    }
+
+    /// Moves as many processed bytes as possible from `input` to `output`.
+    fn process(&mut self) -> io::Result<()> {
+        if !self.handshaken {
+            let Some(end) = self.input.windows(4).position(|w| w == b"\r\n\r\n") else {
+                return Ok(());
+            };
+            let head: Vec<u8> = self.input.drain(..end + 4).collect();
+            self.read_response(&String::from_utf8_lossy(&head));
+            self.output.extend_from_slice(&head);
+            self.handshaken = true;
+        }
+        if !self.negotiated {
+            self.output.append(&mut self.input);
+            return Ok(());
+        }
+        while let Some(len) = self.next_frame_len()? {
+            let frame: Vec<u8> = self.input.drain(..len).collect();
+            self.process_frame(&frame)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the extensions the server accepted from its handshake response.
+    fn read_response(&mut self, head: &str) {
+        let extensions = head.lines().filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim()
+                .eq_ignore_ascii_case("sec-websocket-extensions")
+                .then_some(value)
+        });
+        for extension in extensions.flat_map(|value| value.split(',')) {
+            let mut params = extension.split(';').map(str::trim);
+            if params.next() == Some(OFFER) {
+                self.negotiated = true;
+                self.no_context_takeover = params.any(|p| p == "server_no_context_takeover");
+            }
+        }
+    }
+
+    /// Returns the length of the first frame of `input`, or `None` if it isn't complete.
+    fn next_frame_len(&self) -> io::Result<Option<usize>> {
+        let [_, second, ..] = self.input[..] else {
+            return Ok(None);
+        };
+        let (extended, payload) = match second & 0x7f {
+            126 => (
+                2,
+                self.input
+                    .get(2..4)
+                    .map(|b| u16::from_be_bytes([b[0], b[1]]) as u64),
+            ),
+            127 => (
+                8,
+                self.input
+                    .get(2..10)
+                    .map(|b| u64::from_be_bytes(b.try_into().unwrap())),
+            ),
+            len => (0, Some(len as u64)),
+        };
+        let Some(payload) = payload else {
+            return Ok(None);
+        };
+        if payload > self.max_message_size as u64 {
+            return Err(too_long());
+        }
+        let mask = if second & 0x80 != 0 { 4 } else { 0 };
+        let len = 2 + extended + mask + payload as usize;
+        Ok((self.input.len() >= len).then_some(len))
+    }
+
+    /// Decompresses a frame of a compressed message, or passes any other frame through.
+    fn process_frame(&mut self, frame: &[u8]) -> io::Result<()> {
+        let fin = frame[0] & 0x80 != 0;
+        let compressed = frame[0] & 0x40 != 0;
+        let opcode = frame[0] & 0x0f;
+        let is_control = opcode >= 8;
+        let continues_compressed = opcode == 0 && self.message.is_some();
+        if is_control || !(compressed || continues_compressed) {
+            self.output.extend_from_slice(frame);
+            return Ok(());
+        }
+        let mut payload = payload(frame);
+        let (opcode, message) = self.message.get_or_insert_with(|| (opcode, Vec::new()));
+        let opcode = *opcode;
+        message.append(&mut payload);
+        if message.len() > self.max_message_size {
+            return Err(too_long());
+        }
+        if fin {
+            let (_, mut message) = self.message.take().unwrap_or_default();
+            message.extend_from_slice(&TRAILER);
+            let inflated = self.inflate(&message)?;
+            write_frame(&mut self.output, opcode, &inflated);
+        }
+        Ok(())
+    }
+
+    /// Decompresses a message, trailer included.
+    fn inflate(&mut self, message: &[u8]) -> io::Result<Vec<u8>> {
+        let mut inflated = Vec::with_capacity(message.len() * 4);
+        let start = self.decompress.total_in();
+        loop {
+            let consumed = (self.decompress.total_in() - start) as usize;
+            if inflated.len() == inflated.capacity() {
+                inflated.reserve(inflated.len());
+            }
+            let status = self
+                .decompress
+                .decompress_vec(&message[consumed..], &mut inflated, FlushDecompress::Sync)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            if inflated.len() > self.max_message_size {
+                return Err(too_long());
+            }
+            let consumed = (self.decompress.total_in() - start) as usize;
+            let done = consumed == message.len() && inflated.len() < inflated.capacity();
+            if done || status == Status::StreamEnd {
+                break;
+            }
+        }
+        if self.no_context_takeover {
+            self.decompress.reset(false);
+        }
+        Ok(inflated)
+    }
+}
+
+/// Returns the unmasked payload of a complete frame.
+fn payload(frame: &[u8]) -> Vec<u8> {
+    let extended = match frame[1] & 0x7f {
+        126 => 2,
+        127 => 8,
+        _ => 0,
+    };
+    if frame[1] & 0x80 == 0 {
+        return frame[2 + extended..].to_vec();
+    }
+    let key = &frame[2 + extended..6 + extended];
+    frame[6 + extended..]
+        .iter()
+        .zip(key.iter().cycle())
+        .map(|(byte, key)| byte ^ key)
+        .collect()
+}
+
+/// Writes an unmasked, unfragmented frame.
+fn write_frame(output: &mut Vec<u8>, opcode: u8, payload: &[u8]) {
+    output.push(0x80 | opcode);
+    match payload.len() {
+        len @ 0..=125 => output.push(len as u8),
+        len @ 126..=0xffff => {
+            output.push(126);
+            output.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            output.push(127);
+            output.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    output.extend_from_slice(payload);
+}
+
+fn too_long() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "compressed message exceeds the maximum message size",
+    )
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Inflate<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        loop {
+            if this.read < this.output.len() {
+                let n = buf.remaining().min(this.output.len() - this.read);
+                buf.put_slice(&this.output[this.read..this.read + n]);
+                this.read += n;
+                if this.read == this.output.len() {
+                    this.output.clear();
+                    this.read = 0;
+                }
+                return Poll::Ready(Ok(()));
+            }
+            if this.eof {
+                return Poll::Ready(Ok(()));
+            }
+            let mut chunk = [0; 8192];
+            let mut chunk = ReadBuf::new(&mut chunk);
+            ready!(Pin::new(&mut this.inner).poll_read(cx, &mut chunk))?;
+            if chunk.filled().is_empty() {
+                // Leaves what couldn't be processed to tungstenite, which reports the
+                // truncated frame.
+                this.eof = true;
+                this.output.append(&mut this.input);
+                continue;
+            }
+            this.input.extend_from_slice(chunk.filled());
+            this.process()?;
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Inflate<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Encrypts the connection of a `wss://` request with `connector`, or with the default
+/// configuration of the enabled TLS feature, as `tokio-tungstenite` would. A plain
+/// connector refuses `wss://` URLs rather than falling back.
+pub(crate) async fn wrap_tls(
+    tcp: TcpStream,
+    request: &Request,
+    connector: Option<Connector>,
+) -> Result<MaybeTlsStream<TcpStream>, KickError> {
+    if request.uri().scheme_str() != Some("wss") {
+        return Ok(MaybeTlsStream::Plain(tcp));
+    }
+    #[cfg_attr(
+        not(any(
+            feature = "native-tls",
+            feature = "rustls",
+            feature = "rustls-native-roots"
+        )),
+        allow(unused_variables)
+    )]
+    let domain = request
+        .uri()
+        .host()
+        .ok_or(tungstenite::Error::Url(UrlError::NoHostName))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    match connector {
+        #[cfg(feature = "native-tls")]
+        Some(Connector::NativeTls(connector)) => native_tls(tcp, &domain, connector).await,
+        #[cfg(any(feature = "rustls", feature = "rustls-native-roots"))]
+        Some(Connector::Rustls(config)) => rustls(tcp, domain, config).await,
+        #[cfg(feature = "native-tls")]
+        None => {
+            let connector = tokio_native_tls::native_tls::TlsConnector::new()
+                .map_err(|e| tungstenite::Error::Tls(e.into()))?;
+            native_tls(tcp, &domain, connector).await
+        }
+        #[cfg(all(
+            not(feature = "native-tls"),
+            any(feature = "rustls", feature = "rustls-native-roots")
+        ))]
+        None => rustls(tcp, domain, default_rustls_config()?).await,
+        _ => Err(tungstenite::Error::Url(UrlError::TlsFeatureNotEnabled).into()),
+    }
+}
+
+#[cfg(feature = "native-tls")]
+async fn native_tls(
+    tcp: TcpStream,
+    domain: &str,
+    connector: tokio_native_tls::native_tls::TlsConnector,
+) -> Result<MaybeTlsStream<TcpStream>, KickError> {
+    let stream = tokio_native_tls::TlsConnector::from(connector)
+        .connect(domain, tcp)
+        .await
+        .map_err(|e| tungstenite::Error::Tls(e.into()))?;
+    Ok(MaybeTlsStream::NativeTls(stream))
+}
+
+#[cfg(any(feature = "rustls", feature = "rustls-native-roots"))]
+async fn rustls(
+    tcp: TcpStream,
+    domain: String,
+    config: std::sync::Arc<rustls::ClientConfig>,
+) -> Result<MaybeTlsStream<TcpStream>, KickError> {
+    let domain = rustls::pki_types::ServerName::try_from(domain)
+        .map_err(|_| tungstenite::Error::Tls(tungstenite::error::TlsError::InvalidDnsName))?;
+    let stream = tokio_rustls::TlsConnector::from(config)
+        .connect(domain, tcp)
+        .await?;
+    Ok(MaybeTlsStream::Rustls(stream))
+}
+
+/// Returns a rustls configuration trusting the roots of the enabled rustls features.
+#[cfg(all(
+    not(feature = "native-tls"),
+    any(feature = "rustls", feature = "rustls-native-roots")
+))]
+fn default_rustls_config() -> Result<std::sync::Arc<rustls::ClientConfig>, KickError> {
+    #[allow(unused_mut)]
+    let mut roots = rustls::RootCertStore::empty();
+    #[cfg(feature = "rustls-native-roots")]
+    roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+    #[cfg(feature = "rustls")]
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config = rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(std::sync::Arc::new(config))
+}
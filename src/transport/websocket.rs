@@ -0,0 +1,524 @@
+#[cfg(feature = "deflate")]
+use super::deflate::Inflate;
+use super::{Frame, Transport};
+use crate::KickError;
+use futures_util::future::BoxFuture;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpSocket, TcpStream};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::error::UrlError;
+use tungstenite::handshake::client::{Request, Response};
+use tungstenite::http::{HeaderName, HeaderValue};
+use tungstenite::protocol::Message;
+
+pub use tokio_tungstenite::Connector;
+pub use tungstenite::protocol::WebSocketConfig;
+
+/// Resolves host names to the addresses connections are made to, in place of the
+/// system's resolver, e.g. to pin addresses or resolve over HTTPS with `hickory-resolver`.
+pub trait Resolve: Send + Sync + 'static {
+    /// Returns the addresses of `host`, tried in order, with `port`.
+    ///
+    /// # Errors
+    ///
+    /// This function should return an error if the host cannot be resolved.
+    fn resolve<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> BoxFuture<'a, io::Result<Vec<SocketAddr>>>;
+}
+
+/// A resolver answering with fixed addresses for some hosts, and asking the system for the
+/// others.
+///
+/// # Examples
+///
+/// ```
+/// use kick_client::transport::{ConnectOptions, StaticResolver};
+///
+/// let resolver = StaticResolver::new().with_host("ws-us2.pusher.com", ["54.81.71.142".parse().unwrap()]);
+/// let options = ConnectOptions::new().with_resolver(resolver);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StaticResolver {
+    hosts: HashMap<String, Vec<IpAddr>>,
+}
+
+impl StaticResolver {
+    /// Creates a new instance of `StaticResolver` pinning no host.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves `host` to `addrs`, case-insensitively.
+    pub fn with_host(
+        mut self,
+        host: impl Into<String>,
+        addrs: impl IntoIterator<Item = IpAddr>,
+    ) -> Self {
+        self.hosts.insert(
+            host.into().to_ascii_lowercase(),
+            addrs.into_iter().collect(),
+        );
+        self
+    }
+}
+
+impl Resolve for StaticResolver {
+    fn resolve<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> BoxFuture<'a, io::Result<Vec<SocketAddr>>> {
+        Box::pin(async move {
+            match self.hosts.get(&host.to_ascii_lowercase()) {
+                Some(addrs) => Ok(addrs.iter().map(|ip| SocketAddr::new(*ip, port)).collect()),
+                None => Ok(tokio::net::lookup_host((host, port)).await?.collect()),
+            }
+        })
+    }
+}
+
+/// How long establishing a connection may take by default.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How `WebSocketTransport` establishes connections, for `KickClient::connect_with` and
+/// `ReconnectingClient::with_connect_options`.
+#[derive(Clone)]
+pub struct ConnectOptions {
+    /// How long the TCP connection, TLS and WebSocket handshakes may take together.
+    connect_timeout: Duration,
+    /// How long Pusher may take to confirm the subscriptions, or `None` not to wait.
+    subscribe_timeout: Option<Duration>,
+    /// The TLS connector used for `wss://` URLs, or `None` for the crate's default.
+    tls: Option<Connector>,
+    /// The limits and buffer sizes of the connection, or `None` for tungstenite's defaults.
+    websocket: Option<WebSocketConfig>,
+    /// The extra headers of the upgrade request.
+    headers: Vec<(String, String)>,
+    /// Whether `permessage-deflate` is offered to the server.
+    #[cfg(feature = "deflate")]
+    compression: bool,
+    /// The local address connections are made from, if set.
+    local_addr: Option<IpAddr>,
+    /// The resolver host names are resolved with, or `None` for the system's.
+    resolver: Option<Arc<dyn Resolve>>,
+    /// The proxy connections are tunneled through, if any.
+    #[cfg(feature = "proxy")]
+    proxy: Option<crate::proxy::Proxy>,
+    /// The telemetry connections are recorded to, if any.
+    #[cfg(feature = "otel")]
+    telemetry: Option<crate::otel::Telemetry>,
+}
+
+impl Default for ConnectOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            subscribe_timeout: None,
+            tls: None,
+            websocket: None,
+            headers: Vec::new(),
+            #[cfg(feature = "deflate")]
+            compression: true,
+            local_addr: None,
+            resolver: None,
+            #[cfg(feature = "proxy")]
+            proxy: None,
+            #[cfg(feature = "otel")]
+            telemetry: None,
+        }
+    }
+}
+
+impl ConnectOptions {
+    /// Creates a new instance of `ConnectOptions` with the crate's defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how long establishing the connection may take, from opening the TCP connection
+    /// to completing the WebSocket handshake, through the proxy if any. Defaults to 30
+    /// seconds.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Waits after connecting until Pusher confirms the subscription to every chatroom,
+    /// failing if that takes longer than `timeout`. By default, connecting returns as soon
+    /// as the subscriptions are sent. See `KickClient::wait_for_subscriptions`.
+    pub fn with_subscribe_timeout(mut self, timeout: Duration) -> Self {
+        self.subscribe_timeout = Some(timeout);
+        self
+    }
+
+    /// Returns how long Pusher may take to confirm the subscriptions, if connecting waits
+    /// for them.
+    pub(crate) fn subscribe_timeout(&self) -> Option<Duration> {
+        self.subscribe_timeout
+    }
+
+    /// Returns the WebSocket configuration, for transports other than this one.
+    #[cfg(feature = "async-std")]
+    pub(crate) fn websocket_config(&self) -> Option<WebSocketConfig> {
+        self.websocket
+    }
+
+    /// Records connection attempts, subscriptions and messages to `telemetry`.
+    #[cfg(feature = "otel")]
+    pub fn with_telemetry(mut self, telemetry: crate::otel::Telemetry) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    #[cfg(feature = "otel")]
+    pub(crate) fn telemetry(&self) -> Option<&crate::otel::Telemetry> {
+        self.telemetry.as_ref()
+    }
+
+    /// Encrypts connections with a pre-built TLS connector, e.g. one trusting custom root
+    /// certificates or pinning Kick's, instead of the default configuration of the enabled
+    /// TLS feature.
+    ///
+    /// REST clients are configured the same way by building a `reqwest::Client` with
+    /// `use_preconfigured_tls` and passing it to their `with_transport`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # #[cfg(feature = "rustls")]
+    /// # async fn run(roots: rustls::RootCertStore) -> Result<(), kick_client::KickError> {
+    /// use kick_client::transport::{ConnectOptions, Connector};
+    /// use kick_client::KickClient;
+    /// use std::sync::Arc;
+    ///
+    /// let config = rustls::ClientConfig::builder()
+    ///     .with_root_certificates(roots)
+    ///     .with_no_client_auth();
+    /// let options = ConnectOptions::new().with_tls(Connector::Rustls(Arc::new(config)));
+    /// let client = KickClient::connect_with(kick_client::DEFAULT_WEBSOCKET_URL, vec![668], &options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_tls(mut self, connector: Connector) -> Self {
+        self.tls = Some(connector);
+        self
+    }
+
+    /// Sets the limits and buffer sizes of the connection, such as the largest frame and
+    /// message accepted, e.g. to reject oversized frames early or save memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use kick_client::transport::{ConnectOptions, WebSocketConfig};
+    ///
+    /// let config = WebSocketConfig::default()
+    ///     .max_frame_size(Some(64 << 10))
+    ///     .max_message_size(Some(256 << 10))
+    ///     .max_write_buffer_size(1 << 20);
+    /// let options = ConnectOptions::new().with_websocket_config(config);
+    /// ```
+    pub fn with_websocket_config(mut self, config: WebSocketConfig) -> Self {
+        self.websocket = Some(config);
+        self
+    }
+
+    /// Sets a header of the WebSocket upgrade request, such as `Origin` or `Cookie`,
+    /// replacing any value set before. Invalid names or values fail the connection.
+    ///
+    /// `Sec-WebSocket-Extensions` is refused: the transport reads no extension but
+    /// `permessage-deflate`, which `with_compression` offers with the `deflate` feature.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        let name = name.into();
+        self.headers
+            .retain(|(set, _)| !set.eq_ignore_ascii_case(&name));
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Offers `permessage-deflate` to the server, which compresses the messages it sends
+    /// if it accepts, saving bandwidth on busy chatrooms for some CPU. Enabled by default.
+    /// See `WebSocketTransport::is_compressed`.
+    #[cfg(feature = "deflate")]
+    pub fn with_compression(mut self, compression: bool) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Sets the `User-Agent` header of the WebSocket upgrade request.
+    pub fn with_user_agent(self, user_agent: impl Into<String>) -> Self {
+        self.with_header("User-Agent", user_agent)
+    }
+
+    /// Builds the upgrade request for `url`, with the extra headers.
+    pub(crate) fn request(&self, url: &str) -> Result<Request, KickError> {
+        let mut request = url.into_client_request()?;
+        for (name, value) in &self.headers {
+            let invalid = |e: tungstenite::http::Error| tungstenite::Error::HttpFormat(e);
+            let name = HeaderName::try_from(name.as_str()).map_err(|e| invalid(e.into()))?;
+            if name == tungstenite::http::header::SEC_WEBSOCKET_EXTENSIONS {
+                return Err(KickError::ConfigError(
+                    "WebSocket extensions can't be set as headers, permessage-deflate is offered with `with_compression`".into(),
+                ));
+            }
+            let value = HeaderValue::try_from(value.as_str()).map_err(|e| invalid(e.into()))?;
+            request.headers_mut().insert(name, value);
+        }
+        #[cfg(feature = "deflate")]
+        if self.compression {
+            request.headers_mut().insert(
+                tungstenite::http::header::SEC_WEBSOCKET_EXTENSIONS,
+                HeaderValue::from_static(super::deflate::OFFER),
+            );
+        }
+        Ok(request)
+    }
+
+    /// Makes connections from a local address, e.g. to egress through one of several
+    /// network interfaces or IP addresses of the machine. Only addresses of the same
+    /// family are connected to. Connections to a proxy are made from it too.
+    /// REST clients are configured the same way with `reqwest::ClientBuilder::local_address`.
+    pub fn with_local_addr(mut self, addr: IpAddr) -> Self {
+        self.local_addr = Some(addr);
+        self
+    }
+
+    /// Resolves host names, including the proxy's, with `resolver` instead of the system's
+    /// resolver.
+    pub fn with_resolver(mut self, resolver: impl Resolve) -> Self {
+        self.resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Tunnels connections through an HTTP or SOCKS5 proxy.
+    #[cfg(feature = "proxy")]
+    pub fn with_proxy(mut self, proxy: crate::proxy::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Opens the TCP connection a WebSocket handshake is made on, through the proxy if
+    /// there is one.
+    async fn tcp_stream(&self, request: &Request) -> Result<TcpStream, KickError> {
+        let uri = request.uri();
+        let host = uri
+            .host()
+            .ok_or(tungstenite::Error::Url(UrlError::NoHostName))?;
+        // IPv6 addresses are bracketed in URLs.
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let port = match uri.port_u16() {
+            Some(port) => port,
+            None if uri.scheme_str() == Some("wss") => 443,
+            None => 80,
+        };
+        #[cfg(feature = "proxy")]
+        if let Some(proxy) = &self.proxy {
+            let (proxy_host, proxy_port) = proxy
+                .addr()
+                .rsplit_once(':')
+                .and_then(|(host, port)| Some((host, port.parse::<u16>().ok()?)))
+                .ok_or_else(|| {
+                    KickError::ConfigError(format!("proxy {:?} has no port", proxy.addr()))
+                })?;
+            let proxy_host = proxy_host.trim_start_matches('[').trim_end_matches(']');
+            let mut stream = self.open(proxy_host, proxy_port).await?;
+            proxy.tunnel(&mut stream, host, port).await?;
+            return Ok(stream);
+        }
+        self.open(host, port).await
+    }
+
+    /// Opens a TCP connection to the first reachable address of `host`, from the local
+    /// address if one is set.
+    async fn open(&self, host: &str, port: u16) -> Result<TcpStream, KickError> {
+        let addrs = match (host.parse::<IpAddr>(), &self.resolver) {
+            (Ok(ip), _) => vec![SocketAddr::new(ip, port)],
+            (Err(_), Some(resolver)) => resolver.resolve(host, port).await?,
+            (Err(_), None) => tokio::net::lookup_host((host, port)).await?.collect(),
+        };
+        let mut last_error = None;
+        for addr in addrs {
+            let connected = match self.local_addr {
+                Some(local) if local.is_ipv4() != addr.is_ipv4() => continue,
+                Some(local) => {
+                    let socket = match addr {
+                        SocketAddr::V4(_) => TcpSocket::new_v4()?,
+                        SocketAddr::V6(_) => TcpSocket::new_v6()?,
+                    };
+                    socket.bind(SocketAddr::new(local, 0))?;
+                    socket.connect(addr).await
+                }
+                None => TcpStream::connect(addr).await,
+            };
+            match connected {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error
+            .unwrap_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::AddrNotAvailable,
+                    format!("{} has no address reachable from the local address", host),
+                )
+            })
+            .into())
+    }
+}
+
+/// The WebSocket stream of a `WebSocketTransport`.
+enum Stream {
+    Plain(WebSocketStream<MaybeTlsStream<TcpStream>>),
+    /// A stream connected by the transport, which may have negotiated compression.
+    #[cfg(feature = "deflate")]
+    Inflated(WebSocketStream<Inflate<MaybeTlsStream<TcpStream>>>),
+}
+
+/// The default transport: a WebSocket connection through `tokio-tungstenite`.
+pub struct WebSocketTransport {
+    stream: Stream,
+    /// The HTTP response to the WebSocket upgrade, if connected by the transport.
+    response: Option<Response>,
+}
+
+impl WebSocketTransport {
+    /// Creates a new instance of `WebSocketTransport` from an established WebSocket stream.
+    pub fn from_stream(stream: WebSocketStream<MaybeTlsStream<TcpStream>>) -> Self {
+        Self {
+            stream: Stream::Plain(stream),
+            response: None,
+        }
+    }
+
+    /// Returns `true` if the server accepted `permessage-deflate` and compresses the
+    /// messages it sends.
+    #[cfg(feature = "deflate")]
+    pub fn is_compressed(&self) -> bool {
+        match &self.stream {
+            Stream::Inflated(stream) => stream.get_ref().is_negotiated(),
+            Stream::Plain(_) => false,
+        }
+    }
+
+    /// Returns the HTTP response of the server to the WebSocket upgrade, e.g. to read the
+    /// `cf-ray` header Cloudflare adds, or `None` for transports created from a stream.
+    pub fn handshake_response(&self) -> Option<&Response> {
+        self.response.as_ref()
+    }
+
+    /// Connects to the server at `url` as configured by `options`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the connection cannot be established.
+    pub async fn connect_with(url: &str, options: &ConnectOptions) -> Result<Self, KickError> {
+        let request = options.request(url)?;
+        tokio::time::timeout(options.connect_timeout, Self::handshake(request, options))
+            .await
+            .map_err(|_| {
+                KickError::TimeoutError(format!(
+                    "connecting took longer than {:?}",
+                    options.connect_timeout
+                ))
+            })?
+    }
+
+    /// Opens the connection and performs the TLS and WebSocket handshakes, inflating the
+    /// messages the server compresses if it accepts `permessage-deflate`.
+    #[cfg(feature = "deflate")]
+    async fn handshake(request: Request, options: &ConnectOptions) -> Result<Self, KickError> {
+        let tcp = options.tcp_stream(&request).await?;
+        let tls = super::deflate::wrap_tls(tcp, &request, options.tls.clone()).await?;
+        let max_message_size = options.websocket.and_then(|c| c.max_message_size);
+        let stream = Inflate::new(tls, max_message_size);
+        let (stream, response) =
+            tokio_tungstenite::client_async_with_config(request, stream, options.websocket).await?;
+        Ok(Self {
+            stream: Stream::Inflated(stream),
+            response: Some(response),
+        })
+    }
+
+    /// Opens the connection and performs the TLS and WebSocket handshakes.
+    #[cfg(not(feature = "deflate"))]
+    async fn handshake(request: Request, options: &ConnectOptions) -> Result<Self, KickError> {
+        let tcp = options.tcp_stream(&request).await?;
+        #[cfg(any(
+            feature = "native-tls",
+            feature = "rustls",
+            feature = "rustls-native-roots"
+        ))]
+        let (stream, response) = tokio_tungstenite::client_async_tls_with_config(
+            request,
+            tcp,
+            options.websocket,
+            options.tls.clone(),
+        )
+        .await?;
+        #[cfg(not(any(
+            feature = "native-tls",
+            feature = "rustls",
+            feature = "rustls-native-roots"
+        )))]
+        let (stream, response) = {
+            if request.uri().scheme_str() == Some("wss") {
+                return Err(tungstenite::Error::Url(UrlError::TlsFeatureNotEnabled).into());
+            }
+            let tcp = MaybeTlsStream::Plain(tcp);
+            tokio_tungstenite::client_async_with_config(request, tcp, options.websocket).await?
+        };
+        Ok(Self {
+            stream: Stream::Plain(stream),
+            response: Some(response),
+        })
+    }
+}
+
+impl Transport for WebSocketTransport {
+    async fn connect(url: &str) -> Result<Self, KickError> {
+        Self::connect_with(url, &ConnectOptions::default()).await
+    }
+
+    async fn send(&mut self, text: String) -> Result<(), KickError> {
+        let message = Message::Text(text.into());
+        match &mut self.stream {
+            Stream::Plain(stream) => stream.send(message).await?,
+            #[cfg(feature = "deflate")]
+            Stream::Inflated(stream) => stream.send(message).await?,
+        }
+        Ok(())
+    }
+
+    async fn next_frame(&mut self) -> Result<Option<Frame>, KickError> {
+        let message = match &mut self.stream {
+            Stream::Plain(stream) => stream.next().await,
+            #[cfg(feature = "deflate")]
+            Stream::Inflated(stream) => stream.next().await,
+        };
+        let Some(message) = message else {
+            return Ok(None);
+        };
+        Ok(Some(Frame::from(message?)))
+    }
+
+    async fn close(&mut self) -> Result<(), KickError> {
+        let closed = match &mut self.stream {
+            Stream::Plain(stream) => stream.close(None).await,
+            #[cfg(feature = "deflate")]
+            Stream::Inflated(stream) => stream.close(None).await,
+        };
+        match closed {
+            Ok(()) | Err(tungstenite::Error::AlreadyClosed | tungstenite::Error::ConnectionClosed) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use crate::render::terminal::{badge_glyph, parse_hex_color};
+use crate::{KickChatMessage, KickError, MessageData, MessageSource};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use futures_util::StreamExt;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Tabs};
+use ratatui::Frame;
+use std::collections::VecDeque;
+
+/// How many lines a page up or down scrolls.
+const PAGE: usize = 10;
+
+/// A message in the scrollback, numbered in the order it was received.
+#[derive(Debug, Clone)]
+struct Entry {
+    number: u64,
+    message: KickChatMessage,
+}
+
+/// An interactive terminal chat viewer, with scrollback, a tab per chatroom, pausing and
+/// search.
+///
+/// | Key | Action |
+/// | --- | --- |
+/// | `q`, `Ctrl+C` | Quit |
+/// | `Tab`, `→` / `Shift+Tab`, `←` | Next / previous tab |
+/// | `↑`, `k` / `↓`, `j` | Scroll up / down |
+/// | `PgUp` / `PgDn`, `Home` / `End` | Scroll a page, to the top / bottom |
+/// | `p`, `Space` | Pause / resume |
+/// | `/` | Search, until `Enter`; `Esc` clears the search |
+///
+/// # Examples
+///
+/// ```no_run
+/// # async fn run() -> Result<(), kick_client::KickError> {
+/// use kick_client::tui::ChatViewer;
+/// use kick_client::KickClient;
+///
+/// let client = KickClient::new("wss://ws-us2.pusher.com/app/...", vec![668]).await.unwrap();
+/// ChatViewer::new().run(client).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChatViewer {
+    entries: VecDeque<Entry>,
+    scrollback: usize,
+    received: u64,
+    chatrooms: Vec<u32>,
+    selected: usize,
+    scroll: usize,
+    paused_at: Option<u64>,
+    search: String,
+    searching: bool,
+    status: Option<String>,
+    quit: bool,
+}
+
+impl Default for ChatViewer {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            scrollback: 10_000,
+            received: 0,
+            chatrooms: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            paused_at: None,
+            search: String::new(),
+            searching: false,
+            status: None,
+            quit: false,
+        }
+    }
+}
+
+impl ChatViewer {
+    /// Creates a new instance of `ChatViewer` keeping the last 10,000 messages.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how many messages are kept in the scrollback, across every tab.
+    pub fn with_scrollback(mut self, scrollback: usize) -> Self {
+        self.scrollback = scrollback.max(1);
+        self
+    }
+
+    /// Takes over the terminal and shows messages read from `source` until the user quits.
+    /// Once the source ends or fails, the viewer stays open with the reason in its status.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the terminal cannot be drawn to or read from.
+    pub async fn run<S: MessageSource>(mut self, mut source: S) -> Result<(), KickError> {
+        let mut terminal = ratatui::init();
+        let mut events = EventStream::new();
+        let mut reading = true;
+        let result = loop {
+            if self.quit {
+                break Ok(());
+            }
+            if let Err(e) = terminal.draw(|frame| self.draw(frame)) {
+                break Err(KickError::IoError(e));
+            }
+            tokio::select! {
+                message = source.read_message(), if reading => match message {
+                    Ok(Some(message)) => self.push(message),
+                    Ok(None) => {
+                        reading = false;
+                        self.status = Some("No more messages".to_string());
+                    }
+                    Err(e) => {
+                        reading = false;
+                        self.status = Some(e.to_string());
+                    }
+                },
+                event = events.next() => match event {
+                    Some(Ok(Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                        self.handle_key(key)
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => break Err(KickError::IoError(e)),
+                    None => break Ok(()),
+                },
+            }
+        };
+        ratatui::restore();
+        result
+    }
+
+    /// Adds a message to the scrollback, opening a tab for its chatroom if it's the first
+    /// message from it.
+    pub fn push(&mut self, message: KickChatMessage) {
+        if let Some(chatroom_id) = message.chatroom_id() {
+            if !self.chatrooms.contains(&chatroom_id) {
+                self.chatrooms.push(chatroom_id);
+            }
+        }
+        self.received += 1;
+        self.entries.push_back(Entry {
+            number: self.received,
+            message,
+        });
+        while self.entries.len() > self.scrollback {
+            self.entries.pop_front();
+        }
+        if self.scroll > 0 && self.paused_at.is_none() {
+            // Keep the same messages in view while scrolled up.
+            self.scroll += 1;
+        }
+    }
+
+    /// Handles a key press.
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return;
+        }
+        if self.searching {
+            match key.code {
+                KeyCode::Enter => self.searching = false,
+                KeyCode::Esc => self.clear_search(),
+                KeyCode::Backspace => {
+                    self.search.pop();
+                }
+                KeyCode::Char(c) => self.search.push(c),
+                _ => {}
+            }
+            self.scroll = 0;
+            return;
+        }
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Tab | KeyCode::Right => self.select((self.selected + 1) % self.tab_count()),
+            KeyCode::BackTab | KeyCode::Left => {
+                self.select((self.selected + self.tab_count() - 1) % self.tab_count())
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_add(PAGE),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_sub(PAGE),
+            KeyCode::Home => self.scroll = usize::MAX,
+            KeyCode::End => self.scroll = 0,
+            KeyCode::Char('p') | KeyCode::Char(' ') => self.toggle_pause(),
+            KeyCode::Char('/') => {
+                self.searching = true;
+                self.search.clear();
+            }
+            KeyCode::Esc => self.clear_search(),
+            _ => {}
+        }
+    }
+
+    /// Returns `true` once the user asked to quit.
+    pub fn is_done(&self) -> bool {
+        self.quit
+    }
+
+    /// Returns `true` if the view is paused. Messages received while paused are kept, and
+    /// shown once resumed.
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Returns the chatroom of the selected tab, or `None` for the tab showing every
+    /// message.
+    pub fn selected_chatroom(&self) -> Option<u32> {
+        self.selected
+            .checked_sub(1)
+            .map(|index| self.chatrooms[index])
+    }
+
+    /// Returns the messages the selected tab shows, with the pause and search applied,
+    /// oldest first.
+    pub fn visible(&self) -> impl Iterator<Item = &KickChatMessage> {
+        let chatroom = self.selected_chatroom();
+        let search = self.search.to_lowercase();
+        self.entries
+            .iter()
+            .filter(move |entry| self.paused_at.is_none_or(|last| entry.number <= last))
+            .map(|entry| &entry.message)
+            .filter(move |message| chatroom.is_none_or(|id| message.chatroom_id() == Some(id)))
+            .filter(move |message| search.is_empty() || matches_search(message, &search))
+    }
+
+    /// Draws the viewer to a frame.
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, messages_area, help_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(3),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let titles = std::iter::once("All".to_string())
+            .chain(self.chatrooms.iter().map(|id| format!("#{}", id)))
+            .collect::<Vec<_>>();
+        frame.render_widget(
+            Tabs::new(titles)
+                .select(self.selected)
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+                .block(Block::bordered().title(" Kick chat ")),
+            tabs_area,
+        );
+
+        let visible = self.visible().collect::<Vec<_>>();
+        let height = usize::from(messages_area.height.saturating_sub(2));
+        let scroll = self.scroll.min(visible.len().saturating_sub(height));
+        let end = visible.len() - scroll;
+        let lines = visible[end.saturating_sub(height)..end]
+            .iter()
+            .map(|message| line(message))
+            .collect::<Vec<_>>();
+        self.scroll = scroll;
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(self.title())),
+            messages_area,
+        );
+
+        let help = if self.searching {
+            format!(" Search: {}_  (Enter to keep, Esc to clear)", self.search)
+        } else {
+            " q quit  ←/→ tabs  ↑/↓ PgUp/PgDn scroll  p pause  / search".to_string()
+        };
+        frame.render_widget(
+            Paragraph::new(help).style(Style::new().add_modifier(Modifier::DIM)),
+            help_area,
+        );
+    }
+
+    fn title(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(last) = self.paused_at {
+            parts.push(format!("paused, {} new", self.received - last));
+        }
+        if self.scroll > 0 {
+            parts.push(format!("scrolled up {}", self.scroll));
+        }
+        if !self.search.is_empty() {
+            parts.push(format!("search: {}", self.search));
+        }
+        if let Some(status) = &self.status {
+            parts.push(status.clone());
+        }
+        if parts.is_empty() {
+            String::new()
+        } else {
+            format!(" {} ", parts.join(" | "))
+        }
+    }
+
+    fn tab_count(&self) -> usize {
+        self.chatrooms.len() + 1
+    }
+
+    fn select(&mut self, tab: usize) {
+        self.selected = tab;
+        self.scroll = 0;
+    }
+
+    fn toggle_pause(&mut self) {
+        self.paused_at = match self.paused_at {
+            Some(_) => None,
+            None => Some(self.received),
+        };
+        self.scroll = 0;
+    }
+
+    fn clear_search(&mut self) {
+        self.searching = false;
+        self.search.clear();
+        self.scroll = 0;
+    }
+}
+
+/// Returns `true` if a message contains the lowercase search text, in its content or
+/// sender for chat messages.
+fn matches_search(message: &KickChatMessage, search: &str) -> bool {
+    match &message.data {
+        MessageData::ChatMessage(data) => {
+            data.sender.username.to_lowercase().contains(search)
+                || data
+                    .content
+                    .as_deref()
+                    .is_some_and(|content| content.to_lowercase().contains(search))
+        }
+        _ => message.to_string().to_lowercase().contains(search),
+    }
+}
+
+/// Returns the line showing a message, like `render::terminal` formats it.
+fn line(message: &KickChatMessage) -> Line<'static> {
+    let dim = Style::new().add_modifier(Modifier::DIM);
+    let MessageData::ChatMessage(data) = &message.data else {
+        return Line::styled(format!("* {}", message), dim.add_modifier(Modifier::ITALIC));
+    };
+    let mut spans = Vec::new();
+    if let Some(time) = data
+        .created_at
+        .as_deref()
+        .and_then(|created_at| created_at.split_once('T'))
+        .and_then(|(_, time)| time.get(..5))
+    {
+        spans.push(Span::styled(format!("[{}] ", time), dim));
+    }
+    let glyphs = data
+        .sender
+        .identity
+        .badges
+        .iter()
+        .filter_map(|badge| badge_glyph(badge.badge_type()))
+        .collect::<String>();
+    if !glyphs.is_empty() {
+        spans.push(Span::raw(glyphs + " "));
+    }
+    let mut username = Style::new().add_modifier(Modifier::BOLD);
+    if let Some((r, g, b)) = data
+        .sender
+        .identity
+        .color
+        .as_deref()
+        .and_then(parse_hex_color)
+    {
+        username = username.fg(Color::Rgb(r, g, b));
+    }
+    spans.push(Span::styled(data.sender.username.clone(), username));
+    spans.push(Span::raw(": "));
+    spans.push(Span::raw(data.content.clone().unwrap_or_default()));
+    Line::from(spans)
+}
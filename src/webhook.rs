@@ -0,0 +1,466 @@
+//! A receiver for the webhook events of Kick's official API.
+//!
+//! Incoming events are converted into the same `KickChatMessage`s the WebSocket client
+//! produces and delivered through `MessageSource`, so handlers written against
+//! `KickClient` work unchanged. Webhooks don't carry chatroom IDs, so the `chatroom_id`
+//! fields of converted events hold the broadcaster's user ID, and `channel` holds the
+//! broadcaster's channel slug.
+//!
+//! Every event is signed by Kick. `WebhookVerifier` checks these signatures, either inside
+//! the listener or standalone for users running their own HTTP server.
+
+use crate::{
+    ChannelFollowedEventData, ChatMessageEventData, ChatMessageSender, ChatMessageSenderBadge,
+    ChatMessageSenderIdentity, GiftedSubscriptionsEventData, KickChatMessage, KickError,
+    LivestreamStatusEventData, MessageData, MessageSource, SubscriptionEventData, UnknownEventData,
+};
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rsa::pkcs1v15::{Signature, VerifyingKey};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::signature::Verifier;
+use rsa::RsaPublicKey;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::net::SocketAddr;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// The header carrying the type of a webhook event, e.g. `chat.message.sent`.
+pub const EVENT_TYPE_HEADER: &str = "Kick-Event-Type";
+/// The header carrying the unique ID of a webhook event.
+pub const MESSAGE_ID_HEADER: &str = "Kick-Event-Message-Id";
+/// The header carrying the time a webhook event was sent at.
+pub const TIMESTAMP_HEADER: &str = "Kick-Event-Message-Timestamp";
+/// The header carrying the base64-encoded signature of a webhook event.
+pub const SIGNATURE_HEADER: &str = "Kick-Event-Signature";
+
+/// Where Kick publishes the key webhook events are signed with.
+const PUBLIC_KEY_URL: &str = "https://api.kick.com/public/v1/public-key";
+
+/// How many parsed events are buffered before the endpoint stops accepting new ones.
+const EVENT_BUFFER: usize = 1024;
+
+/// The receiving end of a webhook endpoint.
+pub struct WebhookEvents {
+    events: mpsc::Receiver<KickChatMessage>,
+}
+
+impl MessageSource for WebhookEvents {
+    async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        match self.events.recv().await {
+            Some(message) => Ok(Some(message)),
+            None => Err(KickError::StreamEnded),
+        }
+    }
+}
+
+/// A standalone HTTP server receiving Kick's webhook events.
+pub struct WebhookListener {
+    /// The events received by the server.
+    events: WebhookEvents,
+    /// The address the server is listening on.
+    local_addr: SocketAddr,
+    /// The task running the server, stopped when the listener is dropped.
+    server: JoinHandle<()>,
+}
+
+impl WebhookListener {
+    /// Starts a server accepting webhook events on the given address and path.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - The address to listen on, e.g. `0.0.0.0:3000`.
+    /// * `path` - The path of the endpoint registered as the webhook URL, e.g. `/kick`.
+    /// * `verifier` - The verifier used to reject events not signed by Kick. Passing `None`
+    ///   accepts any request and should only be done in tests.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the address cannot be bound.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn run() -> Result<(), kick_client::KickError> {
+    /// use kick_client::webhook::{WebhookListener, WebhookVerifier};
+    /// use kick_client::MessageSource;
+    ///
+    /// let verifier = WebhookVerifier::fetch().await?;
+    /// let mut listener = WebhookListener::bind("0.0.0.0:3000", "/kick", Some(verifier)).await?;
+    /// while let Some(message) = listener.read_message().await? {
+    ///     println!("{}", message);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn bind(
+        addr: impl ToSocketAddrs,
+        path: &str,
+        verifier: Option<WebhookVerifier>,
+    ) -> Result<Self, KickError> {
+        let (router, events) = router(path, verifier);
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let server = crate::trace::spawn("kick_client::webhook::server", async move {
+            let _ = axum::serve(listener, router).await;
+        });
+
+        Ok(Self {
+            events,
+            local_addr,
+            server,
+        })
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl MessageSource for WebhookListener {
+    async fn read_message(&mut self) -> Result<Option<KickChatMessage>, KickError> {
+        self.events.read_message().await
+    }
+}
+
+impl Drop for WebhookListener {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+/// Creates a router serving the webhook endpoint on `path`, for merging into an existing
+/// axum application, together with the events it receives.
+///
+/// Requests failing verification are answered with `401 Unauthorized` when a verifier is
+/// given.
+pub fn router(path: &str, verifier: Option<WebhookVerifier>) -> (Router, WebhookEvents) {
+    let (sender, events) = mpsc::channel(EVENT_BUFFER);
+    let router = Router::new()
+        .route(path, post(handle_event))
+        .with_state(WebhookState { sender, verifier });
+    (router, WebhookEvents { events })
+}
+
+#[derive(Clone)]
+struct WebhookState {
+    sender: mpsc::Sender<KickChatMessage>,
+    verifier: Option<WebhookVerifier>,
+}
+
+async fn handle_event(
+    State(state): State<WebhookState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    if let Some(verifier) = &state.verifier {
+        if verifier.verify_headers(&headers, &body).is_err() {
+            return StatusCode::UNAUTHORIZED;
+        }
+    }
+
+    let Some(event_type) = headers
+        .get(EVENT_TYPE_HEADER)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return StatusCode::BAD_REQUEST;
+    };
+
+    let message = match parse_event(event_type, &body) {
+        Ok(message) => message,
+        Err(e) => KickChatMessage {
+            data: MessageData::Unsupported(
+                Some(String::from_utf8_lossy(&body).into_owned()),
+                e.to_string(),
+            ),
+            channel: None,
+        },
+    };
+    match state.sender.send(message).await {
+        Ok(()) => StatusCode::OK,
+        Err(_) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+/// Verifies that webhook events were signed by Kick.
+#[derive(Clone)]
+pub struct WebhookVerifier {
+    key: VerifyingKey<Sha256>,
+}
+
+#[derive(Deserialize)]
+struct PublicKeyResponse {
+    data: PublicKeyData,
+}
+
+#[derive(Deserialize)]
+struct PublicKeyData {
+    public_key: String,
+}
+
+impl WebhookVerifier {
+    /// Creates a verifier from a PEM-encoded public key.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the key cannot be parsed.
+    pub fn from_pem(pem: &str) -> Result<Self, KickError> {
+        let key = RsaPublicKey::from_public_key_pem(pem)
+            .map_err(|e| KickError::SignatureError(e.to_string()))?;
+        Ok(Self {
+            key: VerifyingKey::new(key),
+        })
+    }
+
+    /// Creates a verifier using the public key currently published by Kick.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the key cannot be fetched or parsed.
+    pub async fn fetch() -> Result<Self, KickError> {
+        Self::fetch_from(PUBLIC_KEY_URL).await
+    }
+
+    /// Creates a verifier using the public key published at `url`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the key cannot be fetched or parsed.
+    pub async fn fetch_from(url: &str) -> Result<Self, KickError> {
+        let response = reqwest::get(url).await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(KickError::ApiError {
+                status: status.as_u16(),
+                body: response.text().await.unwrap_or_default(),
+            });
+        }
+        let key: PublicKeyResponse = response.json().await?;
+        Self::from_pem(&key.data.public_key)
+    }
+
+    /// Verifies the signature of a webhook event.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_id` - The value of the `Kick-Event-Message-Id` header.
+    /// * `timestamp` - The value of the `Kick-Event-Message-Timestamp` header.
+    /// * `body` - The raw request body.
+    /// * `signature` - The value of the `Kick-Event-Signature` header.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the signature is malformed or doesn't match.
+    pub fn verify(
+        &self,
+        message_id: &str,
+        timestamp: &str,
+        body: &[u8],
+        signature: &str,
+    ) -> Result<(), KickError> {
+        let signature = STANDARD
+            .decode(signature)
+            .map_err(|e| KickError::SignatureError(e.to_string()))?;
+        let signature = Signature::try_from(signature.as_slice())
+            .map_err(|e| KickError::SignatureError(e.to_string()))?;
+
+        let mut signed = Vec::with_capacity(message_id.len() + timestamp.len() + body.len() + 2);
+        signed.extend_from_slice(message_id.as_bytes());
+        signed.push(b'.');
+        signed.extend_from_slice(timestamp.as_bytes());
+        signed.push(b'.');
+        signed.extend_from_slice(body);
+
+        self.key
+            .verify(&signed, &signature)
+            .map_err(|e| KickError::SignatureError(e.to_string()))
+    }
+
+    /// Verifies the signature of a webhook event using the headers of its request.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a header is missing, or the signature is
+    /// malformed or doesn't match.
+    pub fn verify_headers(&self, headers: &HeaderMap, body: &[u8]) -> Result<(), KickError> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .ok_or_else(|| KickError::SignatureError(format!("missing {} header", name)))
+        };
+        self.verify(
+            header(MESSAGE_ID_HEADER)?,
+            header(TIMESTAMP_HEADER)?,
+            body,
+            header(SIGNATURE_HEADER)?,
+        )
+    }
+}
+
+/// Parses the body of a webhook event into a `KickChatMessage`.
+///
+/// Event types without a counterpart in `MessageData` are returned as `MessageData::Unknown`
+/// holding the event type and the parsed body, or the raw body if it isn't JSON.
+///
+/// # Arguments
+///
+/// * `event_type` - The value of the `Kick-Event-Type` header.
+/// * `body` - The raw request body.
+///
+/// # Errors
+///
+/// This function will return an error if the body doesn't match the event type.
+pub fn parse_event(event_type: &str, body: &[u8]) -> Result<KickChatMessage, KickError> {
+    let (data, broadcaster) = match event_type {
+        "chat.message.sent" => {
+            let event: ChatMessageSentPayload = serde_json::from_slice(body)?;
+            let data = MessageData::ChatMessage(ChatMessageEventData {
+                id: event.message_id,
+                chatroom_id: event.broadcaster.user_id,
+                content: Some(event.content),
+                r#type: Some("message".to_string()),
+                created_at: event.created_at,
+                sender: event.sender.into(),
+            });
+            (data, event.broadcaster)
+        }
+        "channel.followed" => {
+            let event: ChannelFollowedPayload = serde_json::from_slice(body)?;
+            let data = MessageData::ChannelFollowed(ChannelFollowedEventData {
+                broadcaster_user_id: event.broadcaster.user_id.into(),
+                follower_user_id: event.follower.user_id.into(),
+                follower_username: event.follower.username,
+            });
+            (data, event.broadcaster)
+        }
+        "livestream.status.updated" => {
+            let event: LivestreamStatusPayload = serde_json::from_slice(body)?;
+            let data = MessageData::LivestreamStatusUpdated(LivestreamStatusEventData {
+                broadcaster_user_id: event.broadcaster.user_id.into(),
+                broadcaster_username: event.broadcaster.username.clone(),
+                is_live: event.is_live,
+                title: event.title,
+                started_at: event.started_at,
+                ended_at: event.ended_at,
+            });
+            (data, event.broadcaster)
+        }
+        "channel.subscription.new" | "channel.subscription.renewal" => {
+            let event: SubscriptionPayload = serde_json::from_slice(body)?;
+            let data = MessageData::SubscriptionEvent(SubscriptionEventData {
+                chatroom_id: event.broadcaster.user_id,
+                username: event.subscriber.username,
+                months: event.duration,
+            });
+            (data, event.broadcaster)
+        }
+        "channel.subscription.gifts" => {
+            let event: SubscriptionGiftsPayload = serde_json::from_slice(body)?;
+            let data = MessageData::GiftedSubscriptions(GiftedSubscriptionsEventData {
+                chatroom_id: event.broadcaster.user_id,
+                gifted_usernames: event.giftees.into_iter().map(|u| u.username).collect(),
+                gifter_username: event.gifter.map(|u| u.username),
+            });
+            (data, event.broadcaster)
+        }
+        _ => {
+            return Ok(KickChatMessage {
+                data: MessageData::Unknown(Some(UnknownEventData {
+                    event: event_type.to_string(),
+                    data: serde_json::from_slice(body).unwrap_or_else(|_| {
+                        serde_json::Value::String(String::from_utf8_lossy(body).into_owned())
+                    }),
+                })),
+                channel: None,
+            })
+        }
+    };
+
+    Ok(KickChatMessage {
+        data,
+        channel: broadcaster.channel_slug,
+    })
+}
+
+#[derive(Deserialize)]
+struct WebhookUser {
+    user_id: u32,
+    username: String,
+    channel_slug: Option<String>,
+    identity: Option<WebhookIdentity>,
+}
+
+#[derive(Deserialize)]
+struct WebhookIdentity {
+    username_color: Option<String>,
+    #[serde(default)]
+    badges: Vec<ChatMessageSenderBadge>,
+}
+
+impl From<WebhookUser> for ChatMessageSender {
+    fn from(user: WebhookUser) -> Self {
+        let identity = user.identity.map_or(
+            ChatMessageSenderIdentity {
+                color: None,
+                badges: Vec::new(),
+            },
+            |identity| ChatMessageSenderIdentity {
+                color: identity.username_color,
+                badges: identity.badges,
+            },
+        );
+        ChatMessageSender {
+            id: user.user_id,
+            username: user.username,
+            slug: user.channel_slug,
+            identity,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ChatMessageSentPayload {
+    message_id: String,
+    broadcaster: WebhookUser,
+    sender: WebhookUser,
+    content: String,
+    created_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChannelFollowedPayload {
+    broadcaster: WebhookUser,
+    follower: WebhookUser,
+}
+
+#[derive(Deserialize)]
+struct LivestreamStatusPayload {
+    broadcaster: WebhookUser,
+    is_live: bool,
+    title: String,
+    started_at: Option<String>,
+    ended_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionPayload {
+    broadcaster: WebhookUser,
+    subscriber: WebhookUser,
+    duration: u32,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionGiftsPayload {
+    broadcaster: WebhookUser,
+    gifter: Option<WebhookUser>,
+    giftees: Vec<WebhookUser>,
+}
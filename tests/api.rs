@@ -0,0 +1,274 @@
+use kick_client::api::{HttpRequest, HttpResponse, HttpTransport, KickApi};
+use kick_client::{KickChatMessage, KickError, MessageData};
+use reqwest::Method;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+/// Accepts every request, keeping them.
+#[derive(Clone, Default)]
+struct Recording {
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl HttpTransport for Recording {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, KickError> {
+        self.requests.lock().unwrap().push(request);
+        Ok(HttpResponse {
+            status: 200,
+            headers: Vec::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
+impl Recording {
+    /// Returns the method, path and JSON body of each request.
+    fn sent(&self) -> Vec<(Method, String, Option<Value>)> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| {
+                let path = request.url.strip_prefix("https://kick.com").unwrap();
+                let body = request
+                    .body
+                    .as_ref()
+                    .map(|body| serde_json::from_slice(body).unwrap());
+                (request.method.clone(), path.to_string(), body)
+            })
+            .collect()
+    }
+}
+
+fn api() -> (KickApi<Recording>, Recording) {
+    let transport = Recording::default();
+    let api = KickApi::new("token").with_transport(transport.clone());
+    (api, transport)
+}
+
+#[tokio::test]
+async fn chats_are_cleared_by_chatroom_id() {
+    let transport = Recording::default();
+    let api = KickApi::new("token")
+        .with_transport(transport.clone())
+        .with_channel(1234, "xqc");
+
+    api.clear_chat(1234).await.unwrap();
+    let error = api.clear_chat(5678).await.unwrap_err();
+    assert!(matches!(error, KickError::ConfigError(_)), "{error:?}");
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].url,
+        "https://kick.com/api/v2/channels/xqc/chat-commands"
+    );
+    let body: serde_json::Value =
+        serde_json::from_slice(requests[0].body.as_ref().unwrap()).unwrap();
+    assert_eq!(body, serde_json::json!({ "command": "clear" }));
+}
+
+#[tokio::test]
+async fn chatroom_ids_are_looked_up_on_the_given_website() {
+    /// Answers with a channel whose chatroom ID is 1234, keeping the URLs requested.
+    #[derive(Clone, Default)]
+    struct Channels {
+        urls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl HttpTransport for Channels {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, KickError> {
+            self.urls.lock().unwrap().push(request.url);
+            Ok(HttpResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: br#"{"id":668,"slug":"xqc","chatroom":{"id":1234}}"#.to_vec(),
+            })
+        }
+    }
+
+    let transport = Channels::default();
+    let chatroom_id = kick_client::api::chatroom_id(&transport, "xqc")
+        .await
+        .unwrap();
+    assert_eq!(chatroom_id, 1234);
+    let chatroom_id =
+        kick_client::api::chatroom_id_from(&transport, "http://127.0.0.1:8080/", "xqc")
+            .await
+            .unwrap();
+    assert_eq!(chatroom_id, 1234);
+
+    assert_eq!(
+        *transport.urls.lock().unwrap(),
+        [
+            "https://kick.com/api/v2/channels/xqc",
+            "http://127.0.0.1:8080/api/v2/channels/xqc",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn messages_are_pinned_and_unpinned() {
+    let (api, transport) = api();
+    let message =
+        serde_json::from_str::<KickChatMessage>(include_str!("fixtures/chat_message.json"))
+            .unwrap();
+    let MessageData::ChatMessage(message) = message.data else {
+        panic!("expected a chat message");
+    };
+
+    api.pin_message("xqc", &message, 120).await.unwrap();
+    api.unpin_message("xqc").await.unwrap();
+
+    let sent = transport.sent();
+    let (method, path, body) = &sent[0];
+    assert_eq!(*method, Method::POST);
+    assert_eq!(path, "/api/v2/channels/xqc/pinned-message");
+    let body = body.as_ref().unwrap();
+    assert_eq!(body["duration"], 120);
+    assert_eq!(
+        body["message"]["id"],
+        "9c6e5425-d3c7-4f0a-9e41-4d5a1c6b1a2f"
+    );
+    assert_eq!(body["message"]["content"], "hello chat [emote:37226:KEKW]");
+    assert_eq!(body["message"]["sender"]["username"], "SomeViewer");
+    assert_eq!(
+        sent[1],
+        (
+            Method::DELETE,
+            "/api/v2/channels/xqc/pinned-message".to_string(),
+            None
+        )
+    );
+}
+
+#[tokio::test]
+async fn chat_modes_update_the_chatroom() {
+    let (api, transport) = api();
+
+    api.set_slow_mode("xqc", Some(5)).await.unwrap();
+    api.set_slow_mode("xqc", None).await.unwrap();
+    api.set_followers_mode("xqc", Some(10)).await.unwrap();
+    api.set_followers_mode("xqc", None).await.unwrap();
+    api.set_subscribers_mode("xqc", true).await.unwrap();
+    api.set_emotes_mode("xqc", false).await.unwrap();
+
+    let sent = transport.sent();
+    for (method, path, _) in &sent {
+        assert_eq!(*method, Method::PUT);
+        assert_eq!(path, "/api/v2/channels/xqc/chatroom");
+    }
+    let bodies: Vec<_> = sent.into_iter().map(|(_, _, body)| body.unwrap()).collect();
+    assert_eq!(
+        bodies,
+        [
+            json!({ "slow_mode": true, "message_interval": 5 }),
+            json!({ "slow_mode": false }),
+            json!({ "followers_mode": true, "following_min_duration": 10 }),
+            json!({ "followers_mode": false }),
+            json!({ "subscribers_mode": true }),
+            json!({ "emotes_mode": false }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn polls_are_created_voted_on_and_deleted() {
+    let (api, transport) = api();
+
+    api.create_poll("xqc", "Best map?", &["Dust 2", "Mirage"], 60, 15)
+        .await
+        .unwrap();
+    api.vote("xqc", 1).await.unwrap();
+    api.delete_poll("xqc").await.unwrap();
+
+    assert_eq!(
+        transport.sent(),
+        [
+            (
+                Method::POST,
+                "/api/v2/channels/xqc/polls".to_string(),
+                Some(json!({
+                    "title": "Best map?",
+                    "options": ["Dust 2", "Mirage"],
+                    "duration": 60,
+                    "result_display_duration": 15,
+                }))
+            ),
+            (
+                Method::POST,
+                "/api/v2/channels/xqc/polls/vote".to_string(),
+                Some(json!({ "id": 1 }))
+            ),
+            (
+                Method::DELETE,
+                "/api/v2/channels/xqc/polls".to_string(),
+                None
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn messages_are_deleted_from_their_chatroom() {
+    let (api, transport) = api();
+
+    api.delete_message(668, "9c6e5425-d3c7-4f0a-9e41-4d5a1c6b1a2f")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        transport.sent(),
+        [(
+            Method::DELETE,
+            "/api/v2/chatrooms/668/messages/9c6e5425-d3c7-4f0a-9e41-4d5a1c6b1a2f".to_string(),
+            None
+        )]
+    );
+    let requests = transport.requests.lock().unwrap();
+    assert!(requests[0]
+        .headers
+        .contains(&("Authorization".to_string(), "Bearer token".to_string())));
+}
+
+#[tokio::test]
+async fn users_are_timed_out_banned_and_unbanned() {
+    let (api, transport) = api();
+
+    // Timeouts are given in minutes, as Kick expects them.
+    api.timeout_user("xqc", "SomeViewer", 10, Some("Spamming"))
+        .await
+        .unwrap();
+    api.ban_user("xqc", "SomeViewer", None).await.unwrap();
+    api.unban_user("xqc", "SomeViewer").await.unwrap();
+
+    assert_eq!(
+        transport.sent(),
+        [
+            (
+                Method::POST,
+                "/api/v2/channels/xqc/bans".to_string(),
+                Some(json!({
+                    "banned_username": "SomeViewer",
+                    "duration": 10,
+                    "permanent": false,
+                    "reason": "Spamming",
+                }))
+            ),
+            (
+                Method::POST,
+                "/api/v2/channels/xqc/bans".to_string(),
+                Some(json!({
+                    "banned_username": "SomeViewer",
+                    "permanent": true,
+                    "reason": null,
+                }))
+            ),
+            (
+                Method::DELETE,
+                "/api/v2/channels/xqc/bans/SomeViewer".to_string(),
+                None
+            ),
+        ]
+    );
+}
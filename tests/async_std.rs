@@ -0,0 +1,35 @@
+use kick_client::mock_server::MockPusherServer;
+use kick_client::transport::AsyncStdTransport;
+use kick_client::{KickClient, MessageData};
+
+#[test]
+fn clients_run_on_async_std() {
+    // The mock server runs on Tokio, the client on async-std only.
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockPusherServer::start()).unwrap();
+
+    async_std::task::block_on(async {
+        let mut client = KickClient::<AsyncStdTransport>::connect(&server.url(), vec![1234])
+            .await
+            .unwrap();
+        let established = client.read_message().await.unwrap().unwrap();
+        assert!(matches!(
+            established.data,
+            MessageData::PusherConnectionEstablished(_)
+        ));
+        let succeeded = client.read_message().await.unwrap().unwrap();
+        assert!(matches!(
+            succeeded.data,
+            MessageData::PusherSubscriptionSucceeded(_)
+        ));
+
+        server.broadcast(
+            "chatrooms.1234.v2",
+            "App\\Events\\ChatroomClearEvent",
+            &serde_json::json!({ "id": "1" }),
+        );
+        let cleared = client.read_message().await.unwrap().unwrap();
+        assert!(matches!(cleared.data, MessageData::ChatroomClear(_)));
+        assert_eq!(cleared.chatroom_id(), Some(1234));
+    });
+}
@@ -0,0 +1,126 @@
+use kick_client::auth::{Credentials, Session, TokenManager};
+use kick_client::KickError;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Returns a manager refreshing to `access {n}` tokens valid for `expires_in`, with the
+/// number of refreshes.
+fn manager(credentials: Credentials, expires_in: Duration) -> (TokenManager, Arc<AtomicUsize>) {
+    let refreshes = Arc::new(AtomicUsize::new(0));
+    let count = refreshes.clone();
+    let tokens = TokenManager::new(credentials).with_refresher(move |_: String| {
+        let n = count.fetch_add(1, Ordering::SeqCst) + 1;
+        async move { Ok(Credentials::new(format!("access {n}")).expires_in(expires_in)) }
+    });
+    (tokens, refreshes)
+}
+
+#[tokio::test]
+async fn expiring_tokens_are_refreshed_on_access() {
+    let credentials = Credentials::new("access")
+        .with_refresh_token("refresh")
+        .expires_in(Duration::from_secs(30));
+    let (tokens, refreshes) = manager(credentials, Duration::from_secs(3600));
+
+    assert_eq!(tokens.access_token().await.unwrap(), "access 1");
+    assert_eq!(tokens.access_token().await.unwrap(), "access 1");
+    assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    // The refresh token is kept when the issuer doesn't rotate it.
+    assert_eq!(
+        tokens.credentials().await.refresh_token.as_deref(),
+        Some("refresh")
+    );
+}
+
+#[tokio::test]
+async fn clones_share_their_configuration() {
+    let credentials = Credentials::new("access")
+        .with_refresh_token("refresh")
+        .expires_in(Duration::from_secs(30));
+    let (tokens, refreshes) = manager(credentials, Duration::from_secs(3600));
+    let clone = tokens.clone();
+
+    let tokens = tokens.with_refresh_margin(Duration::from_secs(10));
+    assert_eq!(clone.access_token().await.unwrap(), "access");
+    let _tokens = tokens.with_refresh_margin(Duration::from_secs(60));
+    assert_eq!(clone.access_token().await.unwrap(), "access 1");
+    assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn tokens_are_refreshed_ahead_of_expiry() {
+    let credentials = Credentials::new("access")
+        .with_refresh_token("refresh")
+        .expires_in(Duration::from_millis(150));
+    let (tokens, refreshes) = manager(credentials, Duration::from_millis(250));
+    let tokens = tokens.with_refresh_margin(Duration::from_millis(100));
+    let task = tokens.refresh_in_background();
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(refreshes.load(Ordering::SeqCst), 1);
+    assert_eq!(tokens.credentials().await.access_token, "access 1");
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(refreshes.load(Ordering::SeqCst), 2);
+
+    // The task stops with the manager.
+    drop(tokens);
+    tokio::time::timeout(Duration::from_secs(1), task)
+        .await
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn tokens_without_a_refresher_are_used_until_they_expire() {
+    let tokens = TokenManager::new(
+        Credentials::new("access")
+            .with_refresh_token("refresh")
+            .expires_in(Duration::from_secs(30)),
+    );
+    assert!(!tokens.can_refresh().await);
+    let error = tokens.access_token().await.unwrap_err();
+    assert!(matches!(error, KickError::AuthError(_)), "{error:?}");
+    // Nothing to refresh, so the background task stops right away.
+    tokens.refresh_in_background().await.unwrap();
+
+    let tokens = TokenManager::new(Credentials::new("forever"));
+    assert_eq!(tokens.access_token().await.unwrap(), "forever");
+}
+
+#[test]
+fn sessions_are_imported_from_json_exports() {
+    let export = r#"[
+        {"domain": ".kick.com", "name": "session_token", "value": "123%7Cbearer", "httpOnly": true},
+        {"domain": ".kick.com", "name": "XSRF-TOKEN", "value": "xsrf%3D%3D"},
+        {"domain": ".kick.com", "name": "kick_session", "value": "abc"}
+    ]"#;
+    let session = Session::from_cookie_export(export).unwrap();
+    assert_eq!(session.bearer_token, "123|bearer");
+    assert_eq!(session.xsrf_token, "xsrf==");
+    assert_eq!(
+        session.cookie_header(),
+        "session_token=123%7Cbearer; XSRF-TOKEN=xsrf%3D%3D; kick_session=abc"
+    );
+}
+
+#[test]
+fn sessions_are_imported_from_netscape_exports() {
+    let export = "# Netscape HTTP Cookie File\n\
+        # This is a generated file! Do not edit.\n\
+        \n\
+        #HttpOnly_.kick.com\tTRUE\t/\tTRUE\t1900000000\tsession_token\t123%7Cbearer\n\
+        .kick.com\tTRUE\t/\tTRUE\t1900000000\tXSRF-TOKEN\txsrf\r\n\
+        # .kick.com\tTRUE\t/\tTRUE\t1900000000\tcommented\tout\n";
+    let session = Session::from_cookie_export(export).unwrap();
+    assert_eq!(session.bearer_token, "123|bearer");
+    assert_eq!(session.xsrf_token, "xsrf");
+    assert_eq!(session.cookies.len(), 2);
+}
+
+#[test]
+fn exports_without_a_session_are_rejected() {
+    let export = ".kick.com\tTRUE\t/\tTRUE\t1900000000\tXSRF-TOKEN\txsrf\n";
+    let error = Session::from_cookie_export(export).unwrap_err();
+    assert!(matches!(error, KickError::AuthError(_)), "{error:?}");
+}
@@ -0,0 +1,135 @@
+use kick_client::automod::{SpamDetector, SpamReason};
+use kick_client::commands::Permission;
+use kick_client::{ChatMessageEventData, KickChatMessage};
+use std::time::Duration;
+
+fn message(user_id: u32, content: &str) -> KickChatMessage {
+    ChatMessageEventData::builder(content)
+        .with_sender(user_id, format!("user{user_id}"))
+        .into_message()
+}
+
+#[test]
+fn floods_are_flagged_per_user() {
+    let mut detector = SpamDetector::new().with_flood_limit(3, Duration::from_secs(10));
+    for i in 0..3 {
+        assert!(detector
+            .check(&message(1, &format!("message {i}")))
+            .is_none());
+        assert!(detector.check(&message(2, &format!("other {i}"))).is_none());
+    }
+    let spam = detector.check(&message(1, "one more")).unwrap();
+    assert_eq!(spam.message.sender.id, 1);
+    assert_eq!(
+        spam.reason,
+        SpamReason::Flood {
+            count: 4,
+            window: Duration::from_secs(10)
+        }
+    );
+    // The history of a flagged user starts over.
+    assert!(detector.check(&message(1, "again")).is_none());
+}
+
+#[test]
+fn near_identical_messages_are_flagged() {
+    let mut detector = SpamDetector::new().with_repeat_limit(2, Duration::from_secs(60), 0.9);
+    assert!(detector
+        .check(&message(1, "Buy followers at spam.example"))
+        .is_none());
+    assert!(detector
+        .check(&message(1, "something else entirely"))
+        .is_none());
+    assert!(detector
+        .check(&message(1, "buy   FOLLOWERS at spam.example"))
+        .is_none());
+    let spam = detector
+        .check(&message(1, "Buy followers at spam.example!"))
+        .unwrap();
+    assert!(matches!(spam.reason, SpamReason::Repeated { count: 3, .. }));
+}
+
+#[test]
+fn privileged_senders_are_exempt() {
+    let mut detector = SpamDetector::new().with_flood_limit(1, Duration::from_secs(10));
+    let moderator = |content: &str| {
+        ChatMessageEventData::builder(content)
+            .with_sender(3, "moderator")
+            .with_badge("moderator")
+            .into_message()
+    };
+    for _ in 0..5 {
+        assert!(detector.check(&moderator("!rules")).is_none());
+    }
+
+    let mut detector = detector.with_exempt(Permission::Broadcaster);
+    assert!(detector.check(&moderator("!rules")).is_none());
+    assert!(detector.check(&moderator("!rules")).is_some());
+}
+
+#[test]
+fn messages_leave_the_window() {
+    let window = Duration::from_millis(50);
+    let mut detector = SpamDetector::new()
+        .with_flood_limit(1, window)
+        .with_repeat_limit(1, window, 0.9);
+    assert!(detector.check(&message(1, "hello")).is_none());
+    std::thread::sleep(window + Duration::from_millis(10));
+    assert!(detector.check(&message(1, "hello")).is_none());
+    assert!(detector.check(&message(1, "hello")).is_some());
+}
+
+#[cfg(feature = "api")]
+#[tokio::test]
+async fn spammers_are_timed_out_of_known_channels() {
+    use kick_client::api::{HttpRequest, HttpResponse, HttpTransport, KickApi};
+    use kick_client::automod::AutoTimeout;
+    use kick_client::KickError;
+    use std::sync::{Arc, Mutex};
+
+    /// Accepts every request, keeping them.
+    #[derive(Clone, Default)]
+    struct Recording {
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+    }
+
+    impl HttpTransport for Recording {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, KickError> {
+            self.requests.lock().unwrap().push(request);
+            Ok(HttpResponse {
+                status: 200,
+                headers: Vec::new(),
+                body: b"{}".to_vec(),
+            })
+        }
+    }
+
+    let transport = Recording::default();
+    let api = KickApi::new("token")
+        .with_transport(transport.clone())
+        .with_channel(1234, "xqc");
+    let timeout = AutoTimeout::new(api, 10);
+    let mut detector = SpamDetector::new().with_flood_limit(1, Duration::from_secs(10));
+    let spam = |chatroom_id: u32| {
+        ChatMessageEventData::builder("spam")
+            .with_chatroom_id(chatroom_id)
+            .with_sender(1, "spammer")
+            .into_message()
+    };
+
+    detector.check(&spam(1234));
+    let flagged = detector.check(&spam(1234)).unwrap();
+    assert!(timeout.apply(&flagged).await.unwrap());
+    detector.check(&spam(5678));
+    let flagged = detector.check(&spam(5678)).unwrap();
+    assert!(!timeout.apply(&flagged).await.unwrap());
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].url, "https://kick.com/api/v2/channels/xqc/bans");
+    let body: serde_json::Value =
+        serde_json::from_slice(requests[0].body.as_ref().unwrap()).unwrap();
+    assert_eq!(body["banned_username"], "spammer");
+    assert_eq!(body["duration"], 10);
+    assert_eq!(body["reason"], "Flooding the chat");
+}
@@ -0,0 +1,72 @@
+use kick_client::bans::BanList;
+use kick_client::fake;
+use std::time::Duration;
+
+#[test]
+fn bans_and_timeouts_are_tracked_per_chatroom() {
+    let mut bans = BanList::new();
+    let spammer = fake::user("spammer").id;
+    let rude = fake::user("rude").id;
+    bans.observe(&fake::user_banned(1, "spammer", "mod", None));
+    bans.observe(&fake::user_banned(1, "rude", "mod", Some(10)));
+    bans.observe(&fake::user_banned(2, "rude", "mod", None));
+    bans.observe(&fake::chat_message(1, "viewer", "hi"));
+
+    let ban = bans.get(1, spammer).unwrap();
+    assert!(ban.is_permanent());
+    assert_eq!(ban.banned_by.username, "mod");
+    assert_eq!(ban.remaining(), None);
+
+    let timeout = bans.get(1, rude).unwrap();
+    assert!(!timeout.is_permanent());
+    let remaining = timeout.remaining().unwrap();
+    assert!(remaining > Duration::from_secs(9 * 60) && remaining <= Duration::from_secs(600));
+
+    assert_eq!(bans.bans(1).count(), 2);
+    assert_eq!(bans.bans_of(rude).count(), 2);
+    assert!(!bans.is_banned(2, spammer));
+    assert!(!bans.is_banned(1, fake::user("viewer").id));
+}
+
+#[test]
+fn unbans_lift_bans() {
+    let mut bans = BanList::new();
+    let rude = fake::user("rude").id;
+    bans.observe(&fake::user_banned(1, "rude", "mod", None));
+    bans.observe(&fake::user_banned(2, "rude", "mod", None));
+    bans.observe(&fake::user_unbanned(1, "rude", "mod"));
+
+    assert!(!bans.is_banned(1, rude));
+    assert!(bans.is_banned(2, rude));
+    // A later ban replaces the previous one.
+    bans.observe(&fake::user_banned(2, "rude", "mod", Some(5)));
+    assert!(!bans.get(2, rude).unwrap().is_permanent());
+}
+
+#[test]
+fn timeouts_expire() {
+    let mut bans = BanList::new();
+    let rude = fake::user("rude").id;
+    bans.observe(&fake::user_banned(1, "rude", "mod", Some(0)));
+    bans.observe(&fake::user_banned(1, "spammer", "mod", None));
+
+    assert!(!bans.is_banned(1, rude));
+    assert_eq!(bans.bans(1).count(), 1);
+    assert_eq!(bans.bans_of(rude).count(), 0);
+
+    bans.purge_expired();
+    assert_eq!(bans.bans(1).count(), 1);
+    assert!(bans.is_banned(1, fake::user("spammer").id));
+}
+
+#[test]
+fn huge_timeouts_do_not_expire() {
+    let mut bans = BanList::new();
+    bans.observe(&fake::user_banned(1, "rude", "mod", Some(u64::MAX)));
+    bans.observe(&fake::user_banned(1, "spammer", "mod", Some(u64::MAX / 60)));
+
+    for username in ["rude", "spammer"] {
+        let ban = bans.get(1, fake::user(username).id).unwrap();
+        assert!(ban.is_permanent());
+    }
+}
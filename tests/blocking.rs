@@ -0,0 +1,60 @@
+use kick_client::blocking::BlockingKickClient;
+use kick_client::mock_server::MockPusherServer;
+use kick_client::reconnect::ReconnectingClient;
+use kick_client::MessageData;
+use std::time::Duration;
+
+#[test]
+fn blocking_clients_receive_without_a_runtime() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockPusherServer::start()).unwrap();
+
+    let mut client = BlockingKickClient::connect(&server.url(), vec![1234]).unwrap();
+    let established = client.recv().unwrap();
+    assert!(matches!(
+        established.data,
+        MessageData::PusherConnectionEstablished(_)
+    ));
+    runtime.block_on(server.wait_for_subscription("chatrooms.1234.v2"));
+    let succeeded = client.recv().unwrap();
+    assert!(matches!(
+        succeeded.data,
+        MessageData::PusherSubscriptionSucceeded(_)
+    ));
+    assert!(client
+        .recv_timeout(Duration::from_millis(50))
+        .unwrap()
+        .is_none());
+
+    server.broadcast(
+        "chatrooms.1234.v2",
+        "App\\Events\\ChatroomClearEvent",
+        &serde_json::json!({ "id": "1" }),
+    );
+    let cleared = client
+        .recv_timeout(Duration::from_secs(5))
+        .unwrap()
+        .unwrap();
+    assert!(matches!(cleared.data, MessageData::ChatroomClear(_)));
+
+    server.disconnect_all();
+    // The iterator ends with the connection, after the close frame.
+    for message in client.by_ref() {
+        assert!(matches!(message.unwrap().data, MessageData::Unknown(None)));
+    }
+}
+
+#[test]
+fn blocking_clients_read_any_source() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockPusherServer::start()).unwrap();
+
+    let client = ReconnectingClient::new(server.url(), vec![1234]);
+    let mut client = BlockingKickClient::reconnecting(client).unwrap();
+    let established = client.recv().unwrap();
+    assert!(matches!(
+        established.data,
+        MessageData::PusherConnectionEstablished(_)
+    ));
+    assert!(client.get_ref().is_connected());
+}
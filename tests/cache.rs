@@ -0,0 +1,121 @@
+use kick_client::cache::{MessageCache, UserCache};
+use kick_client::{fake, ChatMessageEventData, MessageData};
+use std::time::Duration;
+
+fn chat(id: &str, content: &str) -> ChatMessageEventData {
+    ChatMessageEventData::builder(content).with_id(id).build()
+}
+
+#[test]
+fn deleted_messages_are_resolved() {
+    let mut cache = MessageCache::new(10);
+    let mut sent = ChatMessageEventData::builder("something rude")
+        .with_sender(7, "rude")
+        .into_message();
+    let MessageData::ChatMessage(data) = &sent.data else {
+        unreachable!()
+    };
+    let id = data.id.clone();
+    cache.enrich(&mut sent);
+    assert_eq!(cache.len(), 1);
+
+    let mut deleted = fake::message_deleted(1, &id);
+    cache.enrich(&mut deleted);
+    let MessageData::DeletedMessage(data) = &deleted.data else {
+        unreachable!()
+    };
+    let original = data.original.as_ref().unwrap();
+    assert_eq!(original.content.as_deref(), Some("something rude"));
+    assert_eq!(original.sender.username, "rude");
+    // A message is only deleted once.
+    assert!(cache.is_empty());
+
+    let mut unknown = fake::message_deleted(1, fake::id());
+    cache.enrich(&mut unknown);
+    let MessageData::DeletedMessage(data) = &unknown.data else {
+        unreachable!()
+    };
+    assert!(data.original.is_none());
+}
+
+#[test]
+fn the_oldest_messages_are_evicted() {
+    let mut cache = MessageCache::new(2);
+    cache.insert(chat("a", "first"));
+    cache.insert(chat("b", "second"));
+    // Inserting a cached message again updates it in place.
+    cache.insert(chat("a", "edited"));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.get("a").unwrap().content.as_deref(), Some("edited"));
+
+    cache.insert(chat("c", "third"));
+    assert_eq!(cache.len(), 2);
+    assert!(cache.get("a").is_none());
+    assert!(cache.get("b").is_some());
+    assert!(cache.get("c").is_some());
+}
+
+#[test]
+fn removed_messages_free_their_slot() {
+    let mut cache = MessageCache::new(3);
+    for round in 0..20 {
+        let id = format!("removed-{round}");
+        cache.insert(chat(&id, "deleted soon"));
+        assert!(cache.remove(&id).is_some());
+    }
+    cache.insert(chat("a", "first"));
+    cache.insert(chat("b", "second"));
+    cache.insert(chat("c", "third"));
+    assert_eq!(cache.len(), 3);
+    assert!(["a", "b", "c"].iter().all(|id| cache.get(id).is_some()));
+
+    let mut disabled = MessageCache::new(0);
+    disabled.insert(chat("a", "first"));
+    assert!(disabled.is_empty());
+}
+
+#[test]
+fn chatters_are_tracked_per_chatroom() {
+    let mut users = UserCache::new();
+    let first = users
+        .observe(&fake::chat_message(1, "Alice", "hi"))
+        .unwrap()
+        .clone();
+    assert!(first.is_new());
+    assert!(users.observe(&fake::chatroom_clear(1)).is_none());
+
+    let message = ChatMessageEventData::builder("hello again")
+        .with_chatroom_id(1)
+        .with_sender(first.id, "AliceRenamed")
+        .with_badge("vip")
+        .into_message();
+    let chatter = users.observe(&message).unwrap();
+    assert!(!chatter.is_new());
+    assert_eq!(chatter.message_count, 2);
+    assert_eq!(chatter.username, "AliceRenamed");
+    assert_eq!(chatter.badges.len(), 1);
+    assert_eq!(chatter.first_seen, first.first_seen);
+    assert!(chatter.last_seen >= first.last_seen);
+
+    users.observe(&fake::chat_message(2, "Alice", "hi"));
+    assert!(users.get(2, first.id).unwrap().is_new());
+    assert_eq!(users.len(), 2);
+    assert_eq!(users.chatters(1).count(), 1);
+    assert_eq!(
+        users.find_by_username(1, "alicerenamed").unwrap().id,
+        first.id
+    );
+    assert!(users.find_by_username(1, "Alice").is_none());
+}
+
+#[test]
+fn idle_chatters_are_forgotten() {
+    let mut users = UserCache::new();
+    users.observe(&fake::chat_message(1, "idle", "hi"));
+    std::thread::sleep(Duration::from_millis(60));
+    users.observe(&fake::chat_message(1, "active", "hi"));
+
+    users.retain_seen_within(Duration::from_millis(50));
+    assert_eq!(users.len(), 1);
+    assert!(users.find_by_username(1, "active").is_some());
+}
@@ -0,0 +1,153 @@
+use kick_client::fake;
+use kick_client::mock_server::MockPusherServer;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+const CHATROOM: u32 = 1234;
+
+#[tokio::test]
+async fn watch_prints_chat_until_the_connection_closes() {
+    let server = MockPusherServer::start().await.unwrap();
+    let child = Command::new(env!("CARGO_BIN_EXE_kick-client"))
+        .args(["watch", &CHATROOM.to_string(), "--url", &server.url()])
+        .args(["--format", "plain", "--no-timestamps", "--no-reconnect"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    server
+        .wait_for_subscription(&format!("chatrooms.{}.v2", CHATROOM))
+        .await;
+    server.send(&fake::chat_message(CHATROOM, "Alice", "hello"));
+    server.send(&fake::user_banned(CHATROOM, "Bob", "Moderator", None));
+    server.disconnect_all();
+
+    let output = tokio::time::timeout(Duration::from_secs(10), child.wait_with_output())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "Alice: hello\n* Bob was banned by Moderator permanently\n"
+    );
+}
+
+#[tokio::test]
+async fn watch_rejects_unknown_formats() {
+    let output = Command::new(env!("CARGO_BIN_EXE_kick-client"))
+        .args(["watch", "1234", "--format", "yaml"])
+        .output()
+        .await
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid value 'yaml'"));
+}
+
+#[tokio::test]
+async fn watch_prefixes_channels_and_filters_kinds() {
+    let server = MockPusherServer::start().await.unwrap();
+    let child = Command::new(env!("CARGO_BIN_EXE_kick-client"))
+        .args(["watch", "1234", "5678", "--url", &server.url()])
+        .args(["--format", "plain", "--no-timestamps", "--no-reconnect"])
+        .args(["--only", "chat,bans"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    server.wait_for_subscription("chatrooms.1234.v2").await;
+    server.wait_for_subscription("chatrooms.5678.v2").await;
+    server.send(&fake::chat_message(1234, "Alice", "hello"));
+    server.send(&fake::message_deleted(5678, "1"));
+    server.send(&fake::user_banned(5678, "Bob", "Moderator", None));
+    server.disconnect_all();
+
+    let output = tokio::time::timeout(Duration::from_secs(10), child.wait_with_output())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "[1234] Alice: hello\n[5678] * Bob was banned by Moderator permanently\n"
+    );
+}
+
+#[tokio::test]
+async fn watch_rejects_unknown_kinds() {
+    let output = Command::new(env!("CARGO_BIN_EXE_kick-client"))
+        .args(["watch", "1234", "--only", "chat,emotes"])
+        .output()
+        .await
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid value 'emotes'"));
+}
+
+#[tokio::test]
+async fn recordings_replay_as_received() {
+    let path = std::env::temp_dir().join(format!("kick_client_{}.jsonl", fake::id()));
+    let server = MockPusherServer::start().await.unwrap();
+    let child = Command::new(env!("CARGO_BIN_EXE_kick-client"))
+        .args(["record", &CHATROOM.to_string(), "--url", &server.url(), "-o"])
+        .arg(&path)
+        .spawn()
+        .unwrap();
+    server
+        .wait_for_subscription(&format!("chatrooms.{}.v2", CHATROOM))
+        .await;
+    server.send(&fake::chat_message(CHATROOM, "Alice", "hello"));
+    server.disconnect_all();
+    let status = tokio::time::timeout(Duration::from_secs(10), child.wait_with_output())
+        .await
+        .unwrap()
+        .unwrap()
+        .status;
+    assert!(status.success());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_kick-client"))
+        .arg("replay")
+        .arg(&path)
+        .args(["--format", "plain", "--no-timestamps"])
+        .output()
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "Alice: hello\n");
+}
+
+#[tokio::test]
+async fn json_output_has_one_object_per_line() {
+    let server = MockPusherServer::start().await.unwrap();
+    let child = Command::new(env!("CARGO_BIN_EXE_kick-client"))
+        .args(["watch", &CHATROOM.to_string(), "--url", &server.url()])
+        .args(["--format", "json", "--no-reconnect"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    server
+        .wait_for_subscription(&format!("chatrooms.{}.v2", CHATROOM))
+        .await;
+    server.send(&fake::chat_message(CHATROOM, "Alice", "hello"));
+    server.send(&fake::user_banned(CHATROOM, "Bob", "Moderator", None));
+    server.disconnect_all();
+
+    let output = tokio::time::timeout(Duration::from_secs(10), child.wait_with_output())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(output.status.success());
+    let lines: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["kind"], "chat");
+    assert_eq!(lines[0]["chatroom_id"], CHATROOM);
+    assert_eq!(lines[0]["channel"], format!("chatrooms.{}.v2", CHATROOM));
+    assert_eq!(lines[0]["data"]["content"], "hello");
+    assert!(lines[0]["received_at"].as_u64().unwrap() > 0);
+    assert_eq!(lines[1]["kind"], "ban");
+    assert_eq!(lines[1]["data"]["user"]["username"], "Bob");
+}
@@ -0,0 +1,303 @@
+use kick_client::commands::{
+    Args, Command, Commands, DispatchOutcome, FromArgs, Invocation, Permission, Rest, Username,
+};
+use kick_client::cooldown::Cooldowns;
+use kick_client::mock::MockKickClient;
+use kick_client::{ChatMessageEventData, KickChatMessage, KickError};
+use std::time::Duration;
+
+fn chat(content: &str) -> ChatMessageEventData {
+    ChatMessageEventData::builder(content)
+        .with_sender(7, "viewer")
+        .build()
+}
+
+fn message(user_id: u32, badge: Option<&str>, content: &str) -> KickChatMessage {
+    let builder = ChatMessageEventData::builder(content).with_sender(user_id, "viewer");
+    match badge {
+        Some(badge) => builder.with_badge(badge),
+        None => builder,
+    }
+    .into_message()
+}
+
+// The error type is the crate's, as returned by `FromArgs`.
+#[allow(clippy::result_large_err)]
+fn parse<A: FromArgs>(raw_args: &str) -> Result<A, KickError> {
+    A::from_args(&mut Args::new(raw_args))
+}
+
+fn usage_error<T: std::fmt::Debug>(result: Result<T, KickError>) -> String {
+    match result {
+        Err(KickError::UsageError(err)) => err,
+        other => panic!("expected a usage error, got {other:?}"),
+    }
+}
+
+#[test]
+fn invocations_start_with_the_prefix_and_a_name() {
+    let invocation = Invocation::parse("!", &chat("  !Ping  a  b ")).unwrap();
+    assert_eq!(invocation.name, "ping");
+    assert_eq!(invocation.args, ["a", "b"]);
+    assert_eq!(invocation.raw_args, "a  b");
+    assert_eq!(invocation.sender().id, 7);
+
+    let invocation = Invocation::parse("?!", &chat("?!so")).unwrap();
+    assert_eq!(invocation.name, "so");
+    assert!(invocation.args.is_empty());
+
+    for content in ["ping", "!", "! ping", "?ping"] {
+        assert!(
+            Invocation::parse("!", &chat(content)).is_none(),
+            "{content}"
+        );
+    }
+}
+
+#[test]
+fn quoted_args_are_kept_together() {
+    let invocation = Invocation::parse("!", &chat(r#"!poll "Best map?" "Dust 2" Mirage"#)).unwrap();
+    assert_eq!(invocation.args, ["Best map?", "Dust 2", "Mirage"]);
+
+    let (title, option, rest): (String, String, Rest) =
+        parse(r#""Best map?" Mirage "Dust 2" or not"#).unwrap();
+    assert_eq!(title, "Best map?");
+    assert_eq!(option, "Mirage");
+    // The rest is kept as written.
+    assert_eq!(rest.0, r#""Dust 2" or not"#);
+
+    // Quotes that don't enclose a whole argument are part of it.
+    let args: (String, String) = parse(r#"say"hi" "unterminated"#).unwrap();
+    assert_eq!(
+        args,
+        (r#"say"hi""#.to_string(), r#""unterminated"#.to_string())
+    );
+}
+
+#[test]
+fn args_are_parsed_or_explained() {
+    let (user, duration, reason): (Username, Duration, Option<Rest>) =
+        parse("@xqc 1h30m spamming links").unwrap();
+    assert_eq!(user.0, "xqc");
+    assert_eq!(duration, Duration::from_secs(90 * 60));
+    assert_eq!(reason.unwrap().0, "spamming links");
+
+    let (enabled, count, reason): (bool, u32, Option<Rest>) = parse("ON 3").unwrap();
+    assert!(enabled);
+    assert_eq!(count, 3);
+    assert!(reason.is_none());
+
+    assert_eq!(
+        usage_error(parse::<(Username, Duration)>("xqc")),
+        "Missing argument 2: expected a duration such as 30s, 10m or 1h"
+    );
+    assert_eq!(
+        usage_error(parse::<u8>("300")),
+        "Invalid argument 1: expected a number, got \"300\""
+    );
+    assert_eq!(
+        usage_error(parse::<(Username, Rest)>("@xqc")),
+        "Missing argument 2: expected some text"
+    );
+    usage_error(parse::<Username>("@"));
+}
+
+#[test]
+fn durations_accept_units() {
+    let duration = |arg: &str| parse::<Duration>(arg).ok();
+    assert_eq!(duration("90"), Some(Duration::from_secs(90)));
+    assert_eq!(duration("90s"), Some(Duration::from_secs(90)));
+    assert_eq!(duration("5M"), Some(Duration::from_secs(300)));
+    assert_eq!(duration("2h"), Some(Duration::from_secs(7200)));
+    assert_eq!(duration("1d1w"), Some(Duration::from_secs(8 * 86_400)));
+    assert_eq!(duration("1h30m10s"), Some(Duration::from_secs(5410)));
+    for invalid in ["m", "5x", "1h30", "-5s", "99999999999999999999w"] {
+        assert_eq!(duration(invalid), None, "{invalid}");
+    }
+}
+
+#[test]
+fn badges_grant_permissions() {
+    let sender = |badge: Option<&str>| match message(1, badge, "!x").data {
+        kick_client::MessageData::ChatMessage(data) => data.sender,
+        _ => unreachable!(),
+    };
+    assert_eq!(Permission::of(&sender(None)), Permission::Everyone);
+    assert_eq!(
+        Permission::of(&sender(Some("founder"))),
+        Permission::Subscriber
+    );
+    assert_eq!(Permission::of(&sender(Some("vip"))), Permission::Vip);
+    assert_eq!(
+        Permission::of(&sender(Some("moderator"))),
+        Permission::Moderator
+    );
+    assert_eq!(
+        Permission::of(&sender(Some("broadcaster"))),
+        Permission::Broadcaster
+    );
+    assert_eq!(
+        Permission::of(&sender(Some("verified"))),
+        Permission::Everyone
+    );
+
+    let command = Command::new("clear", |_| async { Ok(()) })
+        .with_permission(Permission::Moderator)
+        .allow_user(2);
+    assert!(command.is_permitted(&sender(Some("broadcaster"))));
+    assert!(!command.is_permitted(&sender(Some("vip"))));
+    let mut allowed = sender(None);
+    allowed.id = 2;
+    assert!(command.is_permitted(&allowed));
+}
+
+#[tokio::test]
+async fn dispatch_checks_the_permission() {
+    let mut commands = Commands::new("!");
+    commands.register(
+        Command::new("clear", |_| async { Ok(()) }).with_permission(Permission::Moderator),
+    );
+    assert!(matches!(
+        commands.dispatch(&message(1, None, "!clear")).await,
+        DispatchOutcome::NotPermitted(_)
+    ));
+    assert!(matches!(
+        commands
+            .dispatch(&message(1, Some("moderator"), "!CLEAR"))
+            .await,
+        DispatchOutcome::Handled(Ok(()))
+    ));
+    assert!(matches!(
+        commands.dispatch(&message(1, None, "!unknown")).await,
+        DispatchOutcome::UnknownCommand(_)
+    ));
+    assert!(matches!(
+        commands.dispatch(&message(1, None, "hello")).await,
+        DispatchOutcome::NotACommand
+    ));
+}
+
+#[test]
+fn cooldowns_expire() {
+    let cooldown = Duration::from_millis(50);
+    let mut cooldowns = Cooldowns::new();
+    cooldowns.set_global("clip", cooldown);
+    cooldowns.set_per_user("so", cooldown);
+
+    assert!(cooldowns.try_use(1, "clip").is_ok());
+    let remaining = cooldowns.try_use(2, "clip").unwrap_err();
+    assert!(remaining > Duration::ZERO && remaining <= cooldown);
+    assert!(cooldowns.try_use(1, "so").is_ok());
+    assert!(cooldowns.try_use(1, "so").is_err());
+    assert!(cooldowns.try_use(2, "so").is_ok());
+    // Commands without cooldowns are always available.
+    assert!(cooldowns.try_use(1, "ping").is_ok());
+    assert!(cooldowns.try_use(1, "ping").is_ok());
+
+    std::thread::sleep(cooldown + Duration::from_millis(10));
+    assert!(cooldowns.check(2, "clip").is_ok());
+    assert!(cooldowns.check(1, "so").is_ok());
+}
+
+#[test]
+fn expired_cooldowns_are_purged() {
+    let mut cooldowns = Cooldowns::new();
+    cooldowns.set_global("clip", Duration::from_millis(20));
+    cooldowns.set_per_user("so", Duration::from_millis(20));
+    cooldowns.set_per_user("lurk", Duration::from_secs(60));
+
+    cooldowns.try_use(1, "clip").unwrap();
+    cooldowns.try_use(1, "so").unwrap();
+    cooldowns.try_use(2, "so").unwrap();
+    cooldowns.try_use(1, "lurk").unwrap();
+    // Commands without cooldowns are never tracked.
+    cooldowns.try_use(1, "ping").unwrap();
+    assert_eq!(cooldowns.len(), 4);
+
+    std::thread::sleep(Duration::from_millis(30));
+    cooldowns.purge_expired();
+    assert_eq!(cooldowns.len(), 1);
+    assert!(cooldowns.check(1, "lurk").is_err());
+}
+
+#[tokio::test]
+async fn purging_commands_keeps_running_cooldowns() {
+    let mut commands = Commands::new("!");
+    commands.register(
+        Command::new("ping", |_| async { Ok(()) }).with_user_cooldown(Duration::from_millis(20)),
+    );
+    commands.register(
+        Command::new("lurk", |_| async { Ok(()) }).with_user_cooldown(Duration::from_secs(60)),
+    );
+
+    commands.dispatch(&message(1, None, "!ping")).await;
+    commands.dispatch(&message(1, None, "!lurk")).await;
+    std::thread::sleep(Duration::from_millis(30));
+    commands.purge_expired();
+    assert!(matches!(
+        commands.dispatch(&message(1, None, "!ping")).await,
+        DispatchOutcome::Handled(Ok(()))
+    ));
+    assert!(matches!(
+        commands.dispatch(&message(1, None, "!lurk")).await,
+        DispatchOutcome::OnCooldown(_)
+    ));
+}
+
+#[test]
+fn resetting_ends_only_the_users_cooldowns() {
+    let mut cooldowns = Cooldowns::new();
+    cooldowns.set_global("clip", Duration::from_secs(60));
+    cooldowns.set_per_user("clip", Duration::from_secs(60));
+
+    cooldowns.try_use(1, "clip").unwrap();
+    // Someone else used the command last, so its global cooldown keeps running.
+    cooldowns.reset(2, "clip");
+    assert!(cooldowns.check(2, "clip").is_err());
+
+    cooldowns.reset(1, "clip");
+    assert!(cooldowns.check(1, "clip").is_ok());
+    assert!(cooldowns.check(2, "clip").is_ok());
+}
+
+#[tokio::test]
+async fn usage_errors_reply_and_keep_the_command_available() {
+    let mock = MockKickClient::new();
+    let mut commands = Commands::new("!").with_sender(mock.clone());
+    commands.register(
+        Command::with_args(
+            "timeout",
+            |ctx, (user, duration): (Username, Duration)| async move {
+                ctx.reply(&format!("{} timed out for {}s", user, duration.as_secs()))
+                    .await
+            },
+        )
+        .with_usage("<user> <duration>")
+        .with_user_cooldown(Duration::from_secs(60)),
+    );
+
+    let outcome = commands.dispatch(&message(1, None, "!timeout xqc")).await;
+    assert!(matches!(
+        outcome,
+        DispatchOutcome::Handled(Err(KickError::UsageError(_)))
+    ));
+    let sent = mock.take_sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(
+        sent[0].content,
+        "Missing argument 2: expected a duration such as 30s, 10m or 1h. Usage: !timeout <user> <duration>"
+    );
+
+    // The mistyped invocation didn't start the cooldown.
+    let outcome = commands
+        .dispatch(&message(1, None, "!timeout xqc 10m"))
+        .await;
+    assert!(matches!(outcome, DispatchOutcome::Handled(Ok(()))));
+    assert_eq!(mock.take_sent()[0].content, "xqc timed out for 600s");
+    assert!(matches!(
+        commands
+            .dispatch(&message(1, None, "!timeout xqc 10m"))
+            .await,
+        DispatchOutcome::OnCooldown(_)
+    ));
+}
@@ -0,0 +1,193 @@
+use flate2::{Compress, Compression, FlushCompress};
+use kick_client::transport::{ConnectOptions, Frame, Transport, WebSocketTransport};
+use kick_client::{KickClient, MessageData};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tungstenite::handshake::derive_accept_key;
+
+const CHAT_MESSAGE: &str = include_str!("fixtures/chat_message.json");
+
+/// Accepts one WebSocket connection, answering the upgrade with `extensions` if the client
+/// offered `permessage-deflate`, and returns the stream and the offer.
+async fn accept(listener: &TcpListener, extensions: &str) -> (TcpStream, Option<String>) {
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let mut request = Vec::new();
+    while !request.ends_with(b"\r\n\r\n") {
+        request.push(stream.read_u8().await.unwrap());
+    }
+    let request = String::from_utf8(request).unwrap();
+    let header = |name: &str| {
+        request.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case(name)
+                .then(|| value.trim().to_string())
+        })
+    };
+    let accept = derive_accept_key(header("sec-websocket-key").unwrap().as_bytes());
+    let offer = header("sec-websocket-extensions");
+    let mut response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {accept}\r\n"
+    );
+    if offer.is_some() && !extensions.is_empty() {
+        response += &format!("Sec-WebSocket-Extensions: {extensions}\r\n");
+    }
+    response += "\r\n";
+    stream.write_all(response.as_bytes()).await.unwrap();
+    (stream, offer)
+}
+
+/// Encodes a server frame, setting RSV1 if `compressed`.
+fn frame(fin: bool, compressed: bool, opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut frame = vec![(u8::from(fin) << 7) | (u8::from(compressed) << 6) | opcode];
+    match payload.len() {
+        len @ 0..=125 => frame.push(len as u8),
+        len @ 126..=0xffff => {
+            frame.push(126);
+            frame.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// Compresses a message as `permessage-deflate` does, without the trailer.
+fn compress(compress: &mut Compress, message: &[u8]) -> Vec<u8> {
+    let mut compressed = Vec::with_capacity(message.len() + 64);
+    compress
+        .compress_vec(message, &mut compressed, FlushCompress::Sync)
+        .unwrap();
+    assert!(compressed.ends_with(&[0, 0, 0xff, 0xff]));
+    compressed.truncate(compressed.len() - 4);
+    compressed
+}
+
+fn text(frame: Option<Frame>) -> String {
+    match frame {
+        Some(Frame::Text(text)) => String::from_utf8(text.to_vec()).unwrap(),
+        other => panic!("expected a text frame, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn compressed_messages_are_inflated() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let large = format!(r#"{{"event":"large","data":"{}"}}"#, "kick".repeat(20_000));
+    let expected = large.clone();
+    let server = tokio::spawn(async move {
+        let (mut stream, offer) = accept(&listener, "permessage-deflate").await;
+        // The context is kept across messages, as the server didn't disable it.
+        let mut context = Compress::new(Compression::default(), false);
+        let first = compress(&mut context, CHAT_MESSAGE.as_bytes());
+        let second = compress(&mut context, CHAT_MESSAGE.as_bytes());
+        let (head, tail) = second.split_at(second.len() / 2);
+        let third = compress(&mut context, large.as_bytes());
+        let mut frames = frame(true, true, 1, &first);
+        frames.extend(frame(false, true, 1, head));
+        // Control frames may come between the fragments of a message.
+        frames.extend(frame(true, false, 9, b"ping"));
+        frames.extend(frame(true, false, 0, tail));
+        frames.extend(frame(true, false, 1, br#"{"event":"plain"}"#));
+        frames.extend(frame(true, true, 1, &third));
+        // Splits the frames over several writes, cutting them at arbitrary points.
+        for chunk in frames.chunks(7) {
+            stream.write_all(chunk).await.unwrap();
+        }
+        stream.flush().await.unwrap();
+        let mut rest = Vec::new();
+        let _ = stream.read_to_end(&mut rest).await;
+        offer
+    });
+
+    let mut transport = WebSocketTransport::connect_with(&url, &ConnectOptions::new())
+        .await
+        .unwrap();
+    assert!(transport.is_compressed());
+    assert_eq!(text(transport.next_frame().await.unwrap()), CHAT_MESSAGE);
+    // The ping arrived before the end of the fragmented message.
+    assert!(matches!(
+        transport.next_frame().await.unwrap(),
+        Some(Frame::Ping(_))
+    ));
+    assert_eq!(text(transport.next_frame().await.unwrap()), CHAT_MESSAGE);
+    assert_eq!(
+        text(transport.next_frame().await.unwrap()),
+        r#"{"event":"plain"}"#
+    );
+    assert_eq!(text(transport.next_frame().await.unwrap()), expected);
+    transport.close().await.unwrap();
+    drop(transport);
+
+    assert_eq!(server.await.unwrap().as_deref(), Some("permessage-deflate"));
+}
+
+#[tokio::test]
+async fn compressed_events_are_parsed() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (mut stream, _) = accept(
+            &listener,
+            "permessage-deflate; server_no_context_takeover; client_no_context_takeover",
+        )
+        .await;
+        for _ in 0..2 {
+            // The context is reset after every message, as the server announced.
+            let mut context = Compress::new(Compression::default(), false);
+            let compressed = compress(&mut context, CHAT_MESSAGE.as_bytes());
+            stream
+                .write_all(&frame(true, true, 1, &compressed))
+                .await
+                .unwrap();
+        }
+        let mut rest = Vec::new();
+        let _ = stream.read_to_end(&mut rest).await;
+    });
+
+    let mut client = KickClient::connect_with(&url, vec![], &ConnectOptions::new())
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        let message = client.read_message().await.unwrap().unwrap();
+        assert!(matches!(message.data, MessageData::ChatMessage(_)));
+    }
+}
+
+#[tokio::test]
+async fn servers_without_compression_still_work() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (mut stream, _) = accept(&listener, "").await;
+        stream
+            .write_all(&frame(true, false, 1, CHAT_MESSAGE.as_bytes()))
+            .await
+            .unwrap();
+        let mut rest = Vec::new();
+        let _ = stream.read_to_end(&mut rest).await;
+    });
+
+    let mut transport = WebSocketTransport::connect_with(&url, &ConnectOptions::new())
+        .await
+        .unwrap();
+    assert!(!transport.is_compressed());
+    assert_eq!(text(transport.next_frame().await.unwrap()), CHAT_MESSAGE);
+}
+
+#[tokio::test]
+async fn compression_can_be_disabled() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move { accept(&listener, "permessage-deflate").await.1 });
+
+    let options = ConnectOptions::new().with_compression(false);
+    let transport = WebSocketTransport::connect_with(&url, &options)
+        .await
+        .unwrap();
+    assert!(!transport.is_compressed());
+    assert_eq!(server.await.unwrap(), None);
+}
@@ -0,0 +1,88 @@
+use kick_client::api::{HttpRequest, HttpResponse, HttpTransport};
+use kick_client::discovery::{self, find_pusher_config, PusherConfig};
+use kick_client::KickError;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Serves fixed bodies by URL, keeping the URLs requested.
+#[derive(Clone, Default)]
+struct Site {
+    pages: HashMap<String, String>,
+    requested: Arc<Mutex<Vec<String>>>,
+}
+
+impl HttpTransport for Site {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, KickError> {
+        self.requested.lock().unwrap().push(request.url.clone());
+        let (status, body) = match self.pages.get(&request.url) {
+            Some(body) => (200, body.clone()),
+            None => (404, "Not Found".to_string()),
+        };
+        Ok(HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into_bytes(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn configs_are_found_in_loaded_scripts() {
+    let mut site = Site::default();
+    site.pages.insert(
+        "https://kick.com".to_string(),
+        r#"<html><script src="/_next/missing.js"></script><script src='/_next/app.js' defer></script></html>"#
+            .to_string(),
+    );
+    site.pages.insert(
+        "https://kick.com/_next/app.js".to_string(),
+        r#"let sentry="0123456789abcdef0123";new Pusher("0a1b2c3d4e5f60718293",{cluster:"eu",forceTLS:!0})"#
+            .to_string(),
+    );
+
+    let config = discovery::discover(&site).await.unwrap();
+    assert_eq!(
+        config,
+        PusherConfig {
+            app_key: "0a1b2c3d4e5f60718293".to_string(),
+            cluster: "eu".to_string(),
+        }
+    );
+    assert_eq!(
+        config.websocket_url(),
+        "wss://ws-eu.pusher.com/app/0a1b2c3d4e5f60718293?protocol=7&client=js&version=8.4.0-rc2&flash=false"
+    );
+    assert_eq!(
+        *site.requested.lock().unwrap(),
+        [
+            "https://kick.com",
+            "https://kick.com/_next/missing.js",
+            "https://kick.com/_next/app.js"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn pages_without_configs_fail() {
+    let mut site = Site::default();
+    site.pages
+        .insert("https://kick.com".to_string(), "<html></html>".to_string());
+    assert!(matches!(
+        discovery::discover(&site).await,
+        Err(KickError::ConfigError(_))
+    ));
+}
+
+#[test]
+fn the_default_config_is_the_default_url() {
+    assert_eq!(
+        PusherConfig::default().websocket_url(),
+        kick_client::DEFAULT_WEBSOCKET_URL
+    );
+    let html = r#"<p>Don't miss it</p><div data-config='{"pusher":{"key":"32cbd69e4b950bf97679","cluster":"us2"}}'>"#;
+    assert_eq!(find_pusher_config(html), Some(PusherConfig::default()));
+    assert_eq!(
+        find_pusher_config(r#"{"key":"32cbd69e4b950bf97679"}"#),
+        None
+    );
+}
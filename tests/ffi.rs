@@ -0,0 +1,67 @@
+use kick_client::ffi::{
+    kick_client_free, kick_client_new, kick_client_poll, kick_client_string_free,
+};
+use kick_client::mock_server::MockPusherServer;
+use std::ffi::{CStr, CString};
+
+#[test]
+fn the_checked_in_header_is_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/kick_client.h"));
+    let checked_in = include_str!("../include/kick_client.h");
+    assert_eq!(
+        generated, checked_in,
+        "include/kick_client.h is stale, copy it from the build script's output"
+    );
+}
+
+/// Polls until a message arrives, returning it as JSON.
+fn poll(client: *mut kick_client::ffi::FfiClient) -> serde_json::Value {
+    let event = unsafe { kick_client_poll(client, 5000) };
+    assert!(!event.is_null(), "no message was received");
+    let json = unsafe { CStr::from_ptr(event) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { kick_client_string_free(event) };
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn messages_are_polled_as_json() {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockPusherServer::start()).unwrap();
+
+    let url = CString::new(server.url()).unwrap();
+    let chatroom_ids = [1234u64];
+    let client = unsafe { kick_client_new(url.as_ptr(), chatroom_ids.as_ptr(), 1) };
+    assert!(!client.is_null());
+
+    assert_eq!(poll(client)["event"], "pusher:connection_established");
+    runtime.block_on(server.wait_for_subscription("chatrooms.1234.v2"));
+    assert_eq!(
+        poll(client)["event"],
+        "pusher_internal:subscription_succeeded"
+    );
+    assert!(unsafe { kick_client_poll(client, 0) }.is_null());
+
+    server.broadcast(
+        "chatrooms.1234.v2",
+        "App\\Events\\ChatroomClearEvent",
+        &serde_json::json!({ "id": "1" }),
+    );
+    let cleared = poll(client);
+    assert_eq!(cleared["event"], "App\\Events\\ChatroomClearEvent");
+    assert_eq!(cleared["data"]["id"], "1");
+
+    unsafe { kick_client_free(client) };
+}
+
+#[test]
+fn null_arguments_are_rejected() {
+    assert!(unsafe { kick_client_new(std::ptr::null(), std::ptr::null(), 1) }.is_null());
+    assert!(unsafe { kick_client_poll(std::ptr::null_mut(), 0) }.is_null());
+    unsafe {
+        kick_client_string_free(std::ptr::null_mut());
+        kick_client_free(std::ptr::null_mut());
+    }
+}
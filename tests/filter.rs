@@ -0,0 +1,91 @@
+use kick_client::filter::{FilterAction, FilterConfig, FilterReason, MessageFilter};
+use kick_client::{ChatMessageEventData, KickChatMessage, KickError};
+
+fn message(content: &str) -> KickChatMessage {
+    ChatMessageEventData::builder(content)
+        .with_sender(1, "viewer")
+        .into_message()
+}
+
+fn reason(filter: &MessageFilter, content: &str) -> Option<FilterReason> {
+    filter.check(&message(content)).map(|caught| caught.reason)
+}
+
+#[test]
+fn blocked_words_match_whole_words() {
+    let filter = MessageFilter::new(FilterConfig {
+        blocked_words: vec!["Scam".to_string()],
+        ..FilterConfig::default()
+    })
+    .unwrap();
+    assert_eq!(
+        reason(&filter, "what a SCAM, really"),
+        Some(FilterReason::BlockedWord("scam".to_string()))
+    );
+    assert_eq!(reason(&filter, "scampi for dinner"), None);
+    // Emote names don't count as words.
+    assert_eq!(reason(&filter, "[emote:1:scam] lol"), None);
+}
+
+#[test]
+fn patterns_emotes_and_caps_are_limited() {
+    let filter = MessageFilter::new(FilterConfig {
+        patterns: vec![r"(?i)free\s+followers".to_string()],
+        max_emotes: Some(2),
+        max_caps_ratio: Some(0.7),
+        caps_min_letters: 5,
+        action: FilterAction::Delete,
+        ..FilterConfig::default()
+    })
+    .unwrap();
+    let caught = filter.check(&message("get FREE   followers now")).unwrap();
+    assert_eq!(
+        caught.reason,
+        FilterReason::Pattern(r"(?i)free\s+followers".to_string())
+    );
+    assert_eq!(caught.action, FilterAction::Delete);
+
+    assert_eq!(
+        reason(&filter, "[emote:1:KEKW] [emote:2:LULW] [emote:1:KEKW]"),
+        Some(FilterReason::TooManyEmotes(3))
+    );
+    assert_eq!(reason(&filter, "[emote:1:KEKW] [emote:2:LULW]"), None);
+
+    assert_eq!(
+        reason(&filter, "WHAT A PLAY"),
+        Some(FilterReason::TooManyCaps(1.0))
+    );
+    assert_eq!(reason(&filter, "GG WP"), None, "too short to judge");
+    assert_eq!(reason(&filter, "What A Play"), None);
+}
+
+#[test]
+fn moderators_are_exempt() {
+    let filter = MessageFilter::new(FilterConfig {
+        blocked_words: vec!["scam".to_string()],
+        ..FilterConfig::default()
+    })
+    .unwrap();
+    let moderator = ChatMessageEventData::builder("that's a scam")
+        .with_badge("moderator")
+        .into_message();
+    assert!(filter.check(&moderator).is_none());
+}
+
+#[test]
+fn rules_load_from_json() {
+    let config: FilterConfig =
+        serde_json::from_str(r#"{"blocked_words": ["scam"], "action": "delete"}"#).unwrap();
+    assert_eq!(config.action, FilterAction::Delete);
+    assert_eq!(config.caps_min_letters, 10);
+    assert!(MessageFilter::new(config).is_ok());
+
+    let invalid = FilterConfig {
+        patterns: vec!["(unclosed".to_string()],
+        ..FilterConfig::default()
+    };
+    assert!(matches!(
+        MessageFilter::new(invalid),
+        Err(KickError::ConfigError(_))
+    ));
+}
@@ -0,0 +1,447 @@
+use kick_client::{ChatMessageSenderBadge, KickChatMessage, MessageData};
+use std::collections::HashSet;
+
+/// A fixture file, with a function asserting what it parses into.
+type Fixture = (&'static str, &'static str, fn(&KickChatMessage));
+
+macro_rules! fixture {
+    ($name:literal, $check:expr) => {
+        (
+            $name,
+            include_str!(concat!("fixtures/", $name, ".json")),
+            $check,
+        )
+    };
+}
+
+const FIXTURES: &[Fixture] = &[
+    fixture!("chat_message", |message| {
+        let MessageData::ChatMessage(data) = &message.data else {
+            panic!("expected ChatMessage, got {:?}", message.data);
+        };
+        assert_eq!(data.id, "9c6e5425-d3c7-4f0a-9e41-4d5a1c6b1a2f");
+        assert_eq!(data.chatroom_id, 668);
+        assert_eq!(
+            data.content.as_deref(),
+            Some("hello chat [emote:37226:KEKW]")
+        );
+        assert_eq!(data.r#type.as_deref(), Some("message"));
+        assert_eq!(
+            data.created_at.as_deref(),
+            Some("2024-05-01T12:34:56+00:00")
+        );
+        assert_eq!(data.sender.id, 1447541);
+        assert_eq!(data.sender.username, "SomeViewer");
+        assert_eq!(data.sender.slug.as_deref(), Some("someviewer"));
+        assert_eq!(data.sender.identity.color.as_deref(), Some("#E9113C"));
+        assert!(matches!(
+            &data.sender.identity.badges[..],
+            [
+                ChatMessageSenderBadge::SimpleBadge { r#type: moderator, .. },
+                ChatMessageSenderBadge::FullBadge { r#type: subscriber, count: Some(3), .. },
+            ] if moderator == "moderator" && subscriber == "subscriber"
+        ));
+        assert_eq!(message.chatroom_id(), Some(668));
+    }),
+    fixture!("chat_message_without_badges", |message| {
+        let MessageData::ChatMessage(data) = &message.data else {
+            panic!("expected ChatMessage, got {:?}", message.data);
+        };
+        assert_eq!(data.sender.username, "NewViewer");
+        assert_eq!(data.sender.identity.color.as_deref(), Some("#75FD46"));
+        assert!(data.sender.identity.badges.is_empty());
+    }),
+    fixture!("deleted_message", |message| {
+        let MessageData::DeletedMessage(data) = &message.data else {
+            panic!("expected DeletedMessage, got {:?}", message.data);
+        };
+        assert_eq!(data.id, "4b1c0f84-5f0e-4a53-bb8e-0c4d1a9f6e21");
+        assert_eq!(data.message.id, "9c6e5425-d3c7-4f0a-9e41-4d5a1c6b1a2f");
+        assert!(!data.ai_moderated);
+        assert_eq!(data.violated_rules.as_deref(), Some(&[][..]));
+        assert!(data.original.is_none());
+    }),
+    fixture!("user_banned", |message| {
+        let MessageData::UserBanned(data) = &message.data else {
+            panic!("expected UserBanned, got {:?}", message.data);
+        };
+        assert_eq!(data.user.username, "SomeViewer");
+        assert_eq!(data.banned_by.slug, "streamer");
+        assert!(!data.permanent);
+        assert_eq!(data.duration, Some(10));
+        assert_eq!(
+            data.expires_at.as_deref(),
+            Some("2024-05-01T12:44:56+00:00")
+        );
+    }),
+    fixture!("user_unbanned", |message| {
+        let MessageData::UserUnbanned(data) = &message.data else {
+            panic!("expected UserUnbanned, got {:?}", message.data);
+        };
+        assert_eq!(data.user.id, 1447541);
+        assert_eq!(data.unbanned_by.username, "Streamer");
+        assert!(!data.permanent);
+    }),
+    fixture!("chatroom_updated", |message| {
+        let MessageData::ChatroomUpdated(data) = &message.data else {
+            panic!("expected ChatroomUpdated, got {:?}", message.data);
+        };
+        assert_eq!(data.id, 668);
+        assert!(data.slow_mode.enabled);
+        assert_eq!(data.slow_mode.message_interval, 5);
+        assert!(!data.subscribers_mode.enabled);
+        assert!(data.followers_mode.enabled);
+        assert_eq!(data.followers_mode.min_duration, 10);
+        assert!(!data.emotes_mode.enabled);
+        assert!(!data.advanced_bot_protection.enabled);
+        assert_eq!(message.chatroom_id(), Some(668));
+    }),
+    fixture!("chatroom_updated_partial", |message| {
+        let MessageData::ChatroomUpdated(data) = &message.data else {
+            panic!("expected ChatroomUpdated, got {:?}", message.data);
+        };
+        assert!(data.slow_mode.enabled);
+        assert!(!data.followers_mode.enabled);
+        assert_eq!(data.followers_mode.min_duration, 0);
+        assert!(!data.advanced_bot_protection.enabled);
+        assert_eq!(data.advanced_bot_protection.remaining_time, 0);
+    }),
+    fixture!("chatroom_clear", |message| {
+        let MessageData::ChatroomClear(data) = &message.data else {
+            panic!("expected ChatroomClear, got {:?}", message.data);
+        };
+        assert_eq!(data.id, "c1a2b3d4-e5f6-4a7b-8c9d-0e1f2a3b4c5d");
+    }),
+    fixture!("poll_update", |message| {
+        let MessageData::PollUpdate(data) = &message.data else {
+            panic!("expected PollUpdate, got {:?}", message.data);
+        };
+        let poll = &data.poll;
+        assert_eq!(poll.title, "Best map?");
+        assert_eq!(poll.options.len(), 2);
+        assert_eq!(poll.options[1].label, "Mirage");
+        assert_eq!(poll.options[1].votes, 7);
+        assert_eq!(poll.duration, 120);
+        assert_eq!(poll.remaining, 95);
+        assert_eq!(poll.result_display_duration, 15);
+        assert_eq!(poll.has_voted, Some(false));
+        assert_eq!(poll.voted_option_id, None);
+    }),
+    fixture!("poll_update_ended", |message| {
+        let MessageData::PollUpdate(data) = &message.data else {
+            panic!("expected PollUpdate, got {:?}", message.data);
+        };
+        let poll = &data.poll;
+        assert_eq!(poll.options[0].votes, 12);
+        assert_eq!(poll.options[1].votes, 0);
+        assert_eq!(poll.duration, 0);
+        assert_eq!(poll.remaining, 0);
+        assert_eq!(poll.has_voted, None);
+    }),
+    fixture!("poll_delete", |message| {
+        assert!(matches!(message.data, MessageData::PollDelete(_)));
+        assert_eq!(message.chatroom_id(), Some(668));
+    }),
+    fixture!("connection_established", |message| {
+        let MessageData::PusherConnectionEstablished(data) = &message.data else {
+            panic!(
+                "expected PusherConnectionEstablished, got {:?}",
+                message.data
+            );
+        };
+        assert_eq!(data.socket_id, "352687.1937461");
+        assert_eq!(data.activity_timeout, 120);
+        assert_eq!(message.channel, None);
+    }),
+    fixture!("subscription_succeeded", |message| {
+        assert!(matches!(
+            message.data,
+            MessageData::PusherSubscriptionSucceeded(_)
+        ));
+        assert_eq!(message.channel.as_deref(), Some("chatrooms.668.v2"));
+    }),
+    fixture!("pong", |message| {
+        assert!(matches!(message.data, MessageData::PusherPong(_)));
+    }),
+    fixture!("subscription", |message| {
+        let MessageData::SubscriptionEvent(data) = &message.data else {
+            panic!("expected SubscriptionEvent, got {:?}", message.data);
+        };
+        assert_eq!(data.chatroom_id, 668);
+        assert_eq!(data.username, "SomeViewer");
+        assert_eq!(data.months, 4);
+    }),
+    fixture!("pinned_message_created", |message| {
+        let MessageData::PinnedMessageCreatedEvent(data) = &message.data else {
+            panic!("expected PinnedMessageCreatedEvent, got {:?}", message.data);
+        };
+        assert_eq!(data.message.id, "9c6e5425-d3c7-4f0a-9e41-4d5a1c6b1a2f");
+        assert_eq!(data.duration, "1200");
+        assert_eq!(data.pinned_by.username, "SomeViewer");
+    }),
+    fixture!("pinned_message_deleted", |message| {
+        assert!(matches!(
+            message.data,
+            MessageData::PinnedMessageDeletedEvent(_)
+        ));
+    }),
+    fixture!("gifted_subscriptions", |message| {
+        let MessageData::GiftedSubscriptions(data) = &message.data else {
+            panic!("expected GiftedSubscriptions, got {:?}", message.data);
+        };
+        assert_eq!(data.chatroom_id, 668);
+        assert_eq!(data.gifted_usernames, ["Alpha", "Beta", "Gamma"]);
+        assert_eq!(data.gifter_username.as_deref(), Some("Generous"));
+    }),
+    fixture!("channel_followed", |message| {
+        let MessageData::ChannelFollowed(data) = &message.data else {
+            panic!("expected ChannelFollowed, got {:?}", message.data);
+        };
+        assert_eq!(data.broadcaster_user_id, 668);
+        assert_eq!(data.follower_user_id, 1447541);
+        assert_eq!(data.follower_username, "SomeViewer");
+    }),
+    fixture!("livestream_status_updated", |message| {
+        let MessageData::LivestreamStatusUpdated(data) = &message.data else {
+            panic!("expected LivestreamStatusUpdated, got {:?}", message.data);
+        };
+        assert_eq!(data.broadcaster_user_id, 668);
+        assert_eq!(data.broadcaster_username, "Streamer");
+        assert!(data.is_live);
+        assert_eq!(data.title, "Ranked grind");
+        assert_eq!(data.started_at.as_deref(), Some("2024-05-01T12:00:00Z"));
+        assert!(data.ended_at.is_none());
+        assert_eq!(message.channel.as_deref(), Some("streamer"));
+    }),
+    fixture!("possible_gap", |message| {
+        let MessageData::PossibleGap(data) = &message.data else {
+            panic!("expected PossibleGap, got {:?}", message.data);
+        };
+        assert_eq!(data.downtime_ms, 12500);
+    }),
+    fixture!("stream_host", |message| {
+        let MessageData::Unknown(Some(unknown)) = &message.data else {
+            panic!("expected Unknown, got {:?}", message.data);
+        };
+        assert_eq!(unknown.event, "App\\Events\\StreamHostEvent");
+        assert_eq!(unknown.data["host_username"], "Friend");
+        assert_eq!(unknown.data["number_viewers"], 42);
+        assert_eq!(message.chatroom_id(), Some(668));
+    }),
+];
+
+/// Fixtures of malformed events, which must fail to parse so they are reported as
+/// `MessageData::Unsupported`.
+const UNSUPPORTED: &[&str] = &["unsupported"];
+
+#[test]
+fn fixtures_parse_into_expected_structs() {
+    for (name, frame, check) in FIXTURES {
+        let message = serde_json::from_str::<KickChatMessage>(frame)
+            .unwrap_or_else(|e| panic!("fixture {name} failed to parse: {e}"));
+        check(&message);
+    }
+}
+
+#[test]
+fn fixtures_survive_serialization() {
+    for (name, frame, check) in FIXTURES {
+        let message = serde_json::from_str::<KickChatMessage>(frame).unwrap();
+        let serialized = serde_json::to_string(&message).unwrap();
+        let reparsed = serde_json::from_str::<KickChatMessage>(&serialized)
+            .unwrap_or_else(|e| panic!("fixture {name} failed to parse once serialized: {e}"));
+        check(&reparsed);
+    }
+}
+
+#[test]
+fn fixtures_parse_with_data_before_event() {
+    for (name, frame, check) in FIXTURES {
+        let fields = serde_json::from_str::<serde_json::Value>(frame).unwrap();
+        let reordered = format!(
+            r#"{{"data":{},"channel":{},"event":{}}}"#,
+            fields["data"], fields["channel"], fields["event"]
+        );
+        let message = serde_json::from_str::<KickChatMessage>(&reordered)
+            .unwrap_or_else(|e| panic!("fixture {name} failed to parse reordered: {e}"));
+        check(&message);
+    }
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn fixtures_survive_msgpack() {
+    for (name, frame, check) in FIXTURES {
+        let message = serde_json::from_str::<KickChatMessage>(frame).unwrap();
+        let bytes = kick_client::encoding::to_msgpack(&message).unwrap();
+        let decoded = kick_client::encoding::from_msgpack(&bytes)
+            .unwrap_or_else(|e| panic!("fixture {name} failed to decode from MessagePack: {e}"));
+        check(&decoded);
+    }
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn fixtures_survive_cbor() {
+    for (name, frame, check) in FIXTURES {
+        let message = serde_json::from_str::<KickChatMessage>(frame).unwrap();
+        let bytes = kick_client::encoding::to_cbor(&message).unwrap();
+        let decoded = kick_client::encoding::from_cbor(&bytes)
+            .unwrap_or_else(|e| panic!("fixture {name} failed to decode from CBOR: {e}"));
+        check(&decoded);
+    }
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn schema_describes_every_fixture_event() {
+    let schema = serde_json::to_string(&schemars::schema_for!(KickChatMessage)).unwrap();
+    for (name, frame, _) in FIXTURES {
+        let message = serde_json::from_str::<KickChatMessage>(frame).unwrap();
+        if let MessageData::Unknown(_) = message.data {
+            continue;
+        }
+        let frame: serde_json::Value = serde_json::from_str(frame).unwrap();
+        let event = serde_json::to_string(&frame["event"]).unwrap();
+        assert!(schema.contains(&event), "fixture {name} has no schema");
+    }
+}
+
+#[cfg(feature = "protobuf")]
+#[test]
+fn fixtures_survive_protobuf() {
+    use kick_client::proto::Event;
+    use prost::Message;
+
+    for (name, frame, _) in FIXTURES {
+        let message = serde_json::from_str::<KickChatMessage>(frame).unwrap();
+        let event = Event::from(&message);
+        assert!(event.data.is_some(), "fixture {name} has no protobuf data");
+        let decoded = Event::decode(event.encode_to_vec().as_slice())
+            .unwrap_or_else(|e| panic!("fixture {name} failed to decode from protobuf: {e}"));
+        assert_eq!(decoded, event, "fixture {name} changed through protobuf");
+    }
+}
+
+#[test]
+fn fixtures_display_as_chat_lines() {
+    let lines = [
+        (
+            "chat_message",
+            "[12:34] moderator subscriber SomeViewer: hello chat [emote:37226:KEKW]",
+        ),
+        (
+            "user_banned",
+            "SomeViewer was banned by Streamer for 10 minutes",
+        ),
+        (
+            "chatroom_updated",
+            "Chat modes updated: slow mode (5s), followers only (10m)",
+        ),
+        ("poll_update", "Poll \"Best map?\": Dust 2 (12), Mirage (7)"),
+        ("subscription_succeeded", "Subscribed to chatrooms.668.v2"),
+        ("gifted_subscriptions", "Generous gifted 3 subscriptions"),
+    ];
+    for (name, line) in lines {
+        let (_, frame, _) = FIXTURES.iter().find(|(n, _, _)| *n == name).unwrap();
+        let message = serde_json::from_str::<KickChatMessage>(frame).unwrap();
+        assert_eq!(message.to_string(), line, "fixture {name}");
+    }
+}
+
+#[test]
+fn fixtures_render_for_terminals() {
+    use kick_client::render::terminal::TerminalFormatter;
+
+    let fixture = |name| {
+        let (_, frame, _) = FIXTURES.iter().find(|(n, _, _)| *n == name).unwrap();
+        serde_json::from_str::<KickChatMessage>(frame).unwrap()
+    };
+    let formatter = TerminalFormatter::new();
+    assert_eq!(
+        formatter.format(&fixture("chat_message")),
+        "\x1b[2m[12:34]\x1b[0m 🗡★ \x1b[1m\x1b[38;2;233;17;60mSomeViewer\x1b[0m: hello chat [emote:37226:KEKW]"
+    );
+    assert_eq!(
+        formatter.format(&fixture("chatroom_clear")),
+        format!("\x1b[2m* {}\x1b[0m", fixture("chatroom_clear"))
+    );
+    let plain = formatter.with_colors(false).with_timestamps(false);
+    assert_eq!(
+        plain.format(&fixture("chat_message")),
+        "🗡★ SomeViewer: hello chat [emote:37226:KEKW]"
+    );
+}
+
+#[test]
+fn fixtures_render_as_html() {
+    use kick_client::render::html::HtmlRenderer;
+
+    let fixture = |name| {
+        let (_, frame, _) = FIXTURES.iter().find(|(n, _, _)| *n == name).unwrap();
+        serde_json::from_str::<KickChatMessage>(frame).unwrap()
+    };
+    let renderer = HtmlRenderer::new();
+    assert_eq!(
+        renderer.render(&fixture("chat_message")),
+        "<div class=\"chat-message\" data-id=\"9c6e5425-d3c7-4f0a-9e41-4d5a1c6b1a2f\">\
+         <span class=\"badges\"><span class=\"badge badge-moderator\" title=\"Moderator\"></span>\
+         <span class=\"badge badge-subscriber\" title=\"Subscriber\"></span></span>\
+         <span class=\"username\" style=\"color: #E9113C\">SomeViewer</span>: \
+         <span class=\"content\">hello chat <img class=\"emote\" \
+         src=\"https://files.kick.com/emotes/37226/fullsize\" alt=\"KEKW\" title=\"KEKW\"></span></div>"
+    );
+    assert_eq!(
+        renderer.render(&fixture("poll_update")),
+        "<div class=\"system-event\">Poll &quot;Best map?&quot;: Dust 2 (12), Mirage (7)</div>"
+    );
+}
+
+#[test]
+fn unsupported_fixtures_fail_to_parse() {
+    for name in UNSUPPORTED {
+        let path = format!("{}/tests/fixtures/{name}.json", env!("CARGO_MANIFEST_DIR"));
+        let frame = std::fs::read_to_string(path).unwrap();
+        assert!(
+            serde_json::from_str::<KickChatMessage>(&frame).is_err(),
+            "fixture {name} unexpectedly parsed"
+        );
+    }
+}
+
+#[test]
+fn every_fixture_is_checked() {
+    let checked: HashSet<&str> = FIXTURES
+        .iter()
+        .map(|(name, _, _)| *name)
+        .chain(UNSUPPORTED.iter().copied())
+        .collect();
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        // Subdirectories hold the webhook payloads, which tests/webhook.rs checks.
+        if path.is_dir() {
+            continue;
+        }
+        let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+        assert!(
+            checked.contains(name.as_str()),
+            "fixture {name} has no expectations"
+        );
+    }
+}
+
+#[test]
+fn strict_parsing_lists_unknown_fields() {
+    for (name, frame, _) in FIXTURES {
+        let message = serde_json::from_str::<KickChatMessage>(frame).unwrap();
+        let unknown = kick_client::strict::unknown_fields(&message, frame.as_bytes());
+        let expected: &[&str] = match *name {
+            "chat_message" => &["data.metadata"],
+            "chatroom_updated" => &["data.account_age"],
+            "pinned_message_created" => &["data.message.metadata"],
+            _ => &[],
+        };
+        assert_eq!(unknown, expected, "fixture {name}");
+    }
+}
@@ -0,0 +1,40 @@
+use kick_client::fake;
+use kick_client::grpc::GrpcServer;
+use kick_client::proto::chat_service_client::ChatServiceClient;
+use kick_client::proto::event::Data;
+use kick_client::proto::SubscribeChatRequest;
+use std::time::Duration;
+
+const CHATROOM: u32 = 1234;
+
+#[tokio::test]
+async fn subscribers_receive_the_events_they_select() {
+    let server = GrpcServer::bind("127.0.0.1:0").await.unwrap();
+    let mut client = ChatServiceClient::connect(format!("http://{}", server.local_addr()))
+        .await
+        .unwrap();
+    let mut events = client
+        .subscribe_chat(SubscribeChatRequest {
+            chatroom_ids: vec![CHATROOM],
+            kinds: vec!["chat".to_string()],
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(server.service().subscribers(), 1);
+
+    server.send(&fake::chat_message(4321, "Eve", "elsewhere"));
+    server.send(&fake::user_banned(CHATROOM, "Bob", "Moderator", None));
+    server.send(&fake::chat_message(CHATROOM, "Alice", "hello"));
+
+    let event = tokio::time::timeout(Duration::from_secs(5), events.message())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    let Some(Data::ChatMessage(chat)) = event.data else {
+        panic!("expected a chat message, got {:?}", event.data);
+    };
+    assert_eq!(chat.content.as_deref(), Some("hello"));
+    assert_eq!(chat.chatroom_id, CHATROOM);
+}
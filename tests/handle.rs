@@ -0,0 +1,78 @@
+use kick_client::handle::PausePolicy;
+use kick_client::mock_server::MockPusherServer;
+use kick_client::{KickClient, KickError, MessageData};
+use std::time::Duration;
+
+#[tokio::test]
+async fn handles_control_the_client_from_other_tasks() {
+    let server = MockPusherServer::start().await.unwrap();
+    let client: KickClient = KickClient::connect(&server.url(), vec![1]).await.unwrap();
+    let (handle, mut messages) = client.into_handle();
+
+    let subscriber = handle.clone();
+    tokio::spawn(async move { subscriber.subscribe(2).await })
+        .await
+        .unwrap()
+        .unwrap();
+    server.wait_for_subscription("chatrooms.2.v2").await;
+    assert_eq!(handle.channel_ids().await.unwrap(), [1, 2]);
+    assert_eq!(handle.chatroom_state(2).await.unwrap(), None);
+
+    handle
+        .send(r#"{"event":"pusher:ping","data":{}}"#)
+        .await
+        .unwrap();
+    let pong = loop {
+        let message = messages.recv().await.unwrap();
+        if !matches!(
+            message.data,
+            MessageData::PusherConnectionEstablished(_)
+                | MessageData::PusherSubscriptionSucceeded(_)
+        ) {
+            break message;
+        }
+    };
+    assert!(matches!(pong.data, MessageData::PusherPong(_)), "{pong:?}");
+
+    handle.close().await;
+    assert!(handle.is_closed());
+    while messages.recv().await.is_some() {}
+    assert!(matches!(
+        handle.subscribe(3).await,
+        Err(KickError::StreamEnded)
+    ));
+}
+
+#[tokio::test]
+async fn paused_handles_buffer_messages_until_resumed() {
+    let server = MockPusherServer::start().await.unwrap();
+    let client: KickClient = KickClient::connect(&server.url(), vec![1]).await.unwrap();
+    let (handle, mut messages) = client.into_handle();
+    while !matches!(
+        messages.recv().await.unwrap().data,
+        MessageData::PusherSubscriptionSucceeded(_)
+    ) {}
+    handle.pause(PausePolicy::Buffer(2)).await.unwrap();
+
+    for id in ["1", "2", "3"] {
+        server.broadcast(
+            "chatrooms.1.v2",
+            "App\\Events\\ChatroomClearEvent",
+            &serde_json::json!({ "id": id }),
+        );
+    }
+    // The pong is read after the broadcasts, so once it is buffered, they all were read.
+    handle
+        .send(r#"{"event":"pusher:ping","data":{}}"#)
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(messages.try_recv().is_err());
+
+    assert_eq!(handle.resume().await.unwrap(), 2);
+    let mut kinds = Vec::new();
+    while let Ok(message) = messages.try_recv() {
+        kinds.push(message.data.kind());
+    }
+    assert_eq!(kinds, ["clear", "pong"]);
+}
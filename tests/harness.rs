@@ -0,0 +1,185 @@
+use kick_client::fake;
+use kick_client::mock_server::MockPusherServer;
+use kick_client::reconnect::ReconnectingClient;
+use kick_client::{ChatMessageEventData, KickChatMessage, KickClient, KickError, MessageData};
+use std::time::Duration;
+
+const CHATROOM: u32 = 1234;
+const CHANNEL: &str = "chatrooms.1234.v2";
+
+/// What a script step expects the client to read.
+#[derive(Debug, PartialEq)]
+enum Seen {
+    Established,
+    Subscribed,
+    Chat(String),
+    Deleted(String),
+    Banned(String),
+    Clear,
+    Gap,
+    /// A frame the client couldn't parse.
+    Unsupported,
+    /// An event the client has no type for.
+    Event(String),
+    /// A non-text frame, such as the server's close frame.
+    Unknown,
+    Other,
+}
+
+fn seen(message: &KickChatMessage) -> Seen {
+    match &message.data {
+        MessageData::PusherConnectionEstablished(_) => Seen::Established,
+        MessageData::PusherSubscriptionSucceeded(_) => Seen::Subscribed,
+        MessageData::ChatMessage(data) => Seen::Chat(data.content.clone().unwrap_or_default()),
+        MessageData::DeletedMessage(data) => Seen::Deleted(data.message.id.clone()),
+        MessageData::UserBanned(data) => Seen::Banned(data.user.username.clone()),
+        MessageData::ChatroomClear(_) => Seen::Clear,
+        MessageData::PossibleGap(_) => Seen::Gap,
+        MessageData::Unsupported(..) => Seen::Unsupported,
+        MessageData::Unknown(Some(unknown)) => Seen::Event(unknown.event.clone()),
+        MessageData::Unknown(None) => Seen::Unknown,
+        _ => Seen::Other,
+    }
+}
+
+/// Sends a burst of mixed events, including unknown events and frames the client can't
+/// parse, returning what the client is expected to read from it.
+fn send_burst(server: &MockPusherServer) -> Vec<Seen> {
+    let hello = ChatMessageEventData::builder("hello")
+        .with_id("message-1")
+        .with_chatroom_id(CHATROOM)
+        .with_sender(1, "Alice")
+        .into_message();
+    server.send(&hello);
+    server.send(&fake::chat_message(CHATROOM, "Bob", "hi Alice"));
+    server.send(&fake::message_deleted(CHATROOM, "message-1"));
+    server.send_raw(
+        r#"{"event":"App\\Events\\SomethingNewEvent","data":"{}","channel":"chatrooms.1234.v2"}"#,
+    );
+    server.send_raw("not json at all");
+    server.send(&fake::user_banned(CHATROOM, "Bob", "Moderator", Some(10)));
+    server.send(&fake::chatroom_clear(CHATROOM));
+    vec![
+        Seen::Chat("hello".to_string()),
+        Seen::Chat("hi Alice".to_string()),
+        Seen::Deleted("message-1".to_string()),
+        Seen::Event("App\\Events\\SomethingNewEvent".to_string()),
+        Seen::Unsupported,
+        Seen::Banned("Bob".to_string()),
+        Seen::Clear,
+    ]
+}
+
+async fn read_seen(client: &mut KickClient, count: usize) -> Vec<Seen> {
+    let mut seen_messages = Vec::new();
+    for _ in 0..count {
+        let message = tokio::time::timeout(Duration::from_secs(5), client.read_message())
+            .await
+            .expect("timed out waiting for a message")
+            .expect("reading failed")
+            .expect("no message");
+        seen_messages.push(seen(&message));
+    }
+    seen_messages
+}
+
+async fn connect(server: &MockPusherServer) -> KickClient {
+    let mut client = KickClient::new(&server.url(), vec![CHATROOM.into()])
+        .await
+        .unwrap();
+    assert_eq!(
+        read_seen(&mut client, 2).await,
+        [Seen::Established, Seen::Subscribed]
+    );
+    server.wait_for_subscription(CHANNEL).await;
+    client
+}
+
+#[tokio::test]
+async fn burst_is_read_in_order() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = connect(&server).await;
+
+    let expected = send_burst(&server);
+    assert_eq!(read_seen(&mut client, expected.len()).await, expected);
+}
+
+#[tokio::test]
+async fn abrupt_close_is_a_websocket_error() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = connect(&server).await;
+
+    let expected = send_burst(&server);
+    server.abort_all();
+    assert_eq!(read_seen(&mut client, expected.len()).await, expected);
+    let error = client.read_message().await.unwrap_err();
+    assert!(
+        matches!(error, KickError::WebSocketError(_)),
+        "unexpected {error:?}"
+    );
+}
+
+#[tokio::test]
+async fn graceful_close_is_an_unknown_message_then_the_end_of_the_stream() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = connect(&server).await;
+
+    server.disconnect_all();
+    assert_eq!(read_seen(&mut client, 1).await, [Seen::Unknown]);
+    let error = client.read_message().await.unwrap_err();
+    assert!(
+        matches!(error, KickError::StreamEnded),
+        "unexpected {error:?}"
+    );
+}
+
+#[tokio::test]
+async fn reconnecting_client_flags_gaps_and_drops_duplicates() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = ReconnectingClient::new(server.url(), vec![CHATROOM.into()])
+        .with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+        .with_gap_threshold(Duration::ZERO);
+
+    let first = fake::chat_message(CHATROOM, "Alice", "before the drop");
+    let second = fake::chat_message(CHATROOM, "Alice", "after the drop");
+    let mut seen_messages = Vec::new();
+    for _ in 0..2 {
+        seen_messages.push(seen(&client.read_message().await.unwrap().unwrap()));
+    }
+    server.wait_for_subscription(CHANNEL).await;
+    server.send(&first);
+    seen_messages.push(seen(&client.read_message().await.unwrap().unwrap()));
+
+    server.abort_all();
+    let reader = tokio::spawn(async move {
+        let mut seen_messages = Vec::new();
+        for _ in 0..4 {
+            let message = client.read_message().await.unwrap().unwrap();
+            seen_messages.push(seen(&message));
+        }
+        seen_messages
+    });
+    server.wait_for_accepted(2).await;
+    server.wait_for_subscription(CHANNEL).await;
+    // The first message is sent again, as Kick may do around a reconnection.
+    server.send(&first);
+    server.send(&second);
+
+    let after = tokio::time::timeout(Duration::from_secs(5), reader)
+        .await
+        .expect("timed out waiting for the reconnection")
+        .unwrap();
+    seen_messages.extend(after);
+    assert_eq!(
+        seen_messages,
+        [
+            Seen::Established,
+            Seen::Subscribed,
+            Seen::Chat("before the drop".to_string()),
+            Seen::Gap,
+            Seen::Established,
+            Seen::Subscribed,
+            Seen::Chat("after the drop".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,82 @@
+use kick_client::health::{Health, HealthServer};
+use kick_client::mock_server::MockPusherServer;
+use kick_client::reconnect::ReconnectingClient;
+use kick_client::MessageData;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Requests `path` from the server, returning the whole response.
+async fn get(server: &HealthServer, path: &str) -> String {
+    let mut stream = TcpStream::connect(server.local_addr()).await.unwrap();
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        path
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+        .await
+        .expect("response within 5 seconds")
+        .unwrap();
+    response
+}
+
+#[tokio::test]
+async fn endpoints_report_the_reconnecting_client() {
+    let health = Health::new();
+    let server = HealthServer::bind("127.0.0.1:0", health.clone())
+        .await
+        .unwrap();
+    let response = get(&server, "/readyz").await;
+    assert!(response.starts_with("HTTP/1.1 503"), "{response}");
+    assert!(
+        response.ends_with(r#"{"connected":false,"last_message_age_ms":null,"reconnects":0}"#),
+        "{response}"
+    );
+
+    let pusher = MockPusherServer::start().await.unwrap();
+    let mut client = ReconnectingClient::new(pusher.url(), vec![1234]).with_health(health.clone());
+    client.read_message().await.unwrap();
+    pusher.disconnect_all();
+    loop {
+        let message = client.read_message().await.unwrap().unwrap();
+        if matches!(message.data, MessageData::PusherConnectionEstablished(_))
+            && health.report().reconnects == 1
+        {
+            break;
+        }
+    }
+
+    let response = get(&server, "/readyz").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    assert!(
+        response.contains("content-type: application/json"),
+        "{response}"
+    );
+    assert!(response.contains(r#""connected":true"#), "{response}");
+    assert!(response.contains(r#""reconnects":1"#), "{response}");
+    let response = get(&server, "/healthz").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+}
+
+#[tokio::test]
+async fn liveness_fails_once_messages_stop() {
+    let health = Health::new().with_max_message_age(Duration::from_millis(50));
+    let server = HealthServer::bind("127.0.0.1:0", health.clone())
+        .await
+        .unwrap();
+    health.connected();
+    health.observe(&kick_client::KickChatMessage {
+        data: MessageData::Unknown(None),
+        channel: None,
+    });
+    let response = get(&server, "/healthz").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let response = get(&server, "/healthz").await;
+    assert!(response.starts_with("HTTP/1.1 503"), "{response}");
+    let response = get(&server, "/readyz").await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+}
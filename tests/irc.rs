@@ -0,0 +1,163 @@
+use kick_client::fake;
+use kick_client::irc::{IrcBridge, IrcServer};
+use kick_client::mock::MockKickClient;
+use kick_client::ChatMessageEventData;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+
+const CHATROOM: u32 = 1234;
+
+/// An IRC client connected to the bridge.
+struct TestClient {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    write: OwnedWriteHalf,
+}
+
+impl TestClient {
+    async fn connect(server: &IrcServer) -> Self {
+        let stream = TcpStream::connect(server.local_addr()).await.unwrap();
+        let (read, write) = stream.into_split();
+        Self {
+            lines: BufReader::new(read).lines(),
+            write,
+        }
+    }
+
+    async fn send(&mut self, lines: &str) {
+        self.write.write_all(lines.as_bytes()).await.unwrap();
+    }
+
+    async fn next(&mut self) -> String {
+        tokio::time::timeout(Duration::from_secs(5), self.lines.next_line())
+            .await
+            .expect("a line within 5 seconds")
+            .unwrap()
+            .expect("an open connection")
+    }
+
+    /// Reads lines until one contains `needle`, returning it.
+    async fn until(&mut self, needle: &str) -> String {
+        loop {
+            let line = self.next().await;
+            if line.contains(needle) {
+                return line;
+            }
+        }
+    }
+}
+
+async fn bridge(sender: MockKickClient) -> IrcServer {
+    IrcBridge::new()
+        .with_channel(CHATROOM, "Alice")
+        .with_sender(sender)
+        .bind("127.0.0.1:0")
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn tagged_clients_receive_chat_with_badges_and_colors() {
+    let mock = MockKickClient::new();
+    let server = bridge(mock.clone()).await;
+    let mut client = TestClient::connect(&server).await;
+    client
+        .send("CAP LS 302\r\nNICK viewer\r\nUSER viewer 0 * :Viewer\r\nCAP REQ :message-tags twitch.tv/commands\r\n")
+        .await;
+    assert!(client.until("CAP").await.contains(" LS :message-tags"));
+    assert!(client.until("CAP").await.contains(" ACK :message-tags"));
+    client.send("CAP END\r\nJOIN #alice,#nobody\r\n").await;
+    client.until(" 001 viewer ").await;
+    assert!(client.until(" JOIN ").await.ends_with("JOIN #alice"));
+    client.until(" 366 viewer #alice ").await;
+    client.until(" 403 viewer #nobody ").await;
+
+    let message = ChatMessageEventData::builder("hello; world")
+        .with_chatroom_id(CHATROOM)
+        .with_sender(42, "Bob")
+        .with_badge("moderator")
+        .with_subscriber_badge(3)
+        .with_color("#FF0000")
+        .with_id("abc")
+        .into_message();
+    assert_eq!(server.send(&message), 1);
+    assert_eq!(
+        client.next().await,
+        "@badges=moderator/1,subscriber/3;color=#FF0000;display-name=Bob;id=abc;room-id=1234;user-id=42 \
+         :Bob!Bob@Bob.kick.com PRIVMSG #alice :hello; world"
+    );
+
+    server.send(&fake::user_banned(CHATROOM, "Bob", "Moderator", Some(10)));
+    let ban = client.next().await;
+    assert!(
+        ban.starts_with("@ban-duration=600;target-user-id="),
+        "{ban}"
+    );
+    assert!(ban.ends_with(" CLEARCHAT #alice :Bob"), "{ban}");
+
+    client
+        .send("PRIVMSG #alice :hi from irc\r\nPING :check\r\n")
+        .await;
+    client.until("PONG").await;
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].chatroom_id, CHATROOM);
+    assert_eq!(sent[0].content, "hi from irc");
+}
+
+#[tokio::test]
+async fn untagged_clients_receive_notices() {
+    let server = bridge(MockKickClient::new()).await;
+    let mut client = TestClient::connect(&server).await;
+    client.send("JOIN #alice\r\n").await;
+    client.until(" 451 ").await;
+    client
+        .send("NICK viewer\r\nUSER viewer 0 * :Viewer\r\nJOIN #alice\r\n")
+        .await;
+    client.until(" 366 viewer #alice ").await;
+    assert_eq!(server.connected(), 1);
+
+    server.send(&fake::chat_message(CHATROOM, "Bob", "hello"));
+    assert_eq!(
+        client.next().await,
+        ":Bob!Bob@Bob.kick.com PRIVMSG #alice :hello"
+    );
+    server.send(&fake::user_banned(CHATROOM, "Bob", "Moderator", None));
+    assert_eq!(
+        client.next().await,
+        ":kick.local NOTICE #alice :Bob was banned by Moderator permanently"
+    );
+
+    client.send("PART #alice\r\n").await;
+    client.until(" PART #alice").await;
+    assert_eq!(
+        server.send(&fake::chat_message(CHATROOM, "Bob", "hello")),
+        0
+    );
+}
+
+#[tokio::test]
+async fn messages_to_slow_clients_are_dropped() {
+    let server = IrcBridge::new()
+        .with_channel(CHATROOM, "alice")
+        .with_client_buffer(1)
+        .bind("127.0.0.1:0")
+        .await
+        .unwrap();
+    let mut client = TestClient::connect(&server).await;
+    client
+        .send("NICK viewer\r\nUSER viewer 0 * :Viewer\r\nJOIN #alice\r\n")
+        .await;
+    client.until(" 366 viewer #alice ").await;
+
+    // Nothing is written while the test runs, so only the first message fits.
+    assert_eq!(server.send(&fake::chat_message(CHATROOM, "Bob", "one")), 1);
+    assert_eq!(server.send(&fake::chat_message(CHATROOM, "Bob", "two")), 0);
+    assert_eq!(server.dropped(), 1);
+    assert!(client.next().await.ends_with("PRIVMSG #alice :one"));
+    assert_eq!(
+        server.send(&fake::chat_message(CHATROOM, "Bob", "three")),
+        1
+    );
+}
@@ -0,0 +1,78 @@
+use kick_client::manager::ChatManager;
+use kick_client::mock_server::MockPusherServer;
+use kick_client::reconnect::ReconnectingClient;
+use kick_client::{KickClient, MessageData};
+use std::time::Duration;
+
+const CLEAR: &str = "App\\Events\\ChatroomClearEvent";
+
+fn clear_event() -> serde_json::Value {
+    serde_json::json!({ "id": "1" })
+}
+
+#[tokio::test]
+async fn chatrooms_are_joined_and_left_at_runtime() {
+    let server = MockPusherServer::start().await.unwrap();
+    let manager = ChatManager::spawn(ReconnectingClient::new(server.url(), vec![]));
+
+    let mut first = manager.join(1);
+    let mut second = manager.join(2);
+    server.wait_for_subscription("chatrooms.1.v2").await;
+    server.wait_for_subscription("chatrooms.2.v2").await;
+
+    server.broadcast("chatrooms.2.v2", CLEAR, &clear_event());
+    server.broadcast("chatrooms.1.v2", CLEAR, &clear_event());
+    for (receiver, chatroom_id) in [(&mut first, 1), (&mut second, 2)] {
+        let succeeded = receiver.recv().await.unwrap();
+        assert!(matches!(
+            succeeded.data,
+            MessageData::PusherSubscriptionSucceeded(_)
+        ));
+        let message = receiver.recv().await.unwrap();
+        assert!(matches!(message.data, MessageData::ChatroomClear(_)));
+        assert_eq!(message.chatroom_id(), Some(chatroom_id));
+    }
+
+    manager.leave(1);
+    assert!(first.recv().await.is_none());
+    // Dropping the last receiver of a chatroom leaves it too, once a message for it
+    // arrives.
+    drop(second);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.is_subscribed("chatrooms.1.v2") || server.is_subscribed("chatrooms.2.v2") {
+            server.broadcast("chatrooms.2.v2", CLEAR, &clear_event());
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn merged_streams_are_split_by_channel() {
+    let server = MockPusherServer::start().await.unwrap();
+    let client: KickClient = KickClient::connect(&server.url(), vec![1, 2])
+        .await
+        .unwrap();
+    let mut channels = client.split_by_channel();
+    assert_eq!(channels.len(), 2);
+    server.wait_for_subscription("chatrooms.1.v2").await;
+    server.wait_for_subscription("chatrooms.2.v2").await;
+
+    server.broadcast("chatrooms.2.v2", CLEAR, &clear_event());
+    server.broadcast("chatrooms.1.v2", CLEAR, &clear_event());
+    for chatroom_id in [1, 2] {
+        let receiver = channels.get_mut(&chatroom_id).unwrap();
+        let message = loop {
+            let message = receiver.recv().await.unwrap();
+            if !matches!(message.data, MessageData::PusherSubscriptionSucceeded(_)) {
+                break message;
+            }
+        };
+        assert!(matches!(message.data, MessageData::ChatroomClear(_)));
+        assert_eq!(message.chatroom_id(), Some(chatroom_id as u32));
+    }
+
+    server.disconnect_all();
+    assert!(channels.get_mut(&1).unwrap().recv().await.is_none());
+}
@@ -0,0 +1,77 @@
+use kick_client::metrics::Metrics;
+use kick_client::{fake, KickChatMessage, MessageData};
+use std::time::Duration;
+
+#[test]
+fn percentiles_are_computed_from_the_samples() {
+    let metrics = Metrics::new();
+    assert_eq!(metrics.snapshot().latency, None);
+
+    // Recorded out of order, as messages may be.
+    for ms in (1..=100).rev() {
+        metrics.record_latency(Duration::from_millis(ms));
+    }
+    let latency = metrics.snapshot().latency.unwrap();
+    assert_eq!(latency.samples, 100);
+    // The nearest rank is rounded, so the median of 1..=100 ms is 51 ms.
+    assert_eq!(latency.p50, Duration::from_millis(51));
+    assert_eq!(latency.p90, Duration::from_millis(90));
+    assert_eq!(latency.p99, Duration::from_millis(99));
+    assert_eq!(latency.max, Duration::from_millis(100));
+
+    let single = Metrics::new();
+    single.record_latency(Duration::from_millis(7));
+    let latency = single.snapshot().latency.unwrap();
+    assert_eq!(latency.samples, 1);
+    assert_eq!(latency.p50, Duration::from_millis(7));
+    assert_eq!(latency.p99, Duration::from_millis(7));
+}
+
+#[test]
+fn old_samples_leave_the_window() {
+    let metrics = Metrics::new().with_latency_window(Duration::from_millis(50));
+    metrics.record_latency(Duration::from_secs(10));
+    std::thread::sleep(Duration::from_millis(60));
+    metrics.record_latency(Duration::from_millis(20));
+
+    let latency = metrics.snapshot().latency.unwrap();
+    assert_eq!(latency.samples, 1);
+    assert_eq!(latency.max, Duration::from_millis(20));
+
+    std::thread::sleep(Duration::from_millis(60));
+    assert_eq!(metrics.snapshot().latency, None);
+}
+
+#[test]
+fn messages_are_counted_across_clones() {
+    let metrics = Metrics::new();
+    let clone = metrics.clone();
+    metrics.observe(&fake::chat_message(1, "alice", "hi"));
+    clone.observe(&fake::chatroom_clear(1));
+    clone.observe(&KickChatMessage {
+        data: MessageData::Unsupported(None, "unknown event".to_string()),
+        channel: None,
+    });
+    clone.record_dropped(3);
+
+    let snapshot = metrics.snapshot();
+    assert_eq!(snapshot.messages_received, 3);
+    assert_eq!(snapshot.unsupported_messages, 1);
+    assert_eq!(snapshot.dropped, 3);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chat_message_latency_is_recorded() {
+    use kick_client::ChatMessageEventData;
+
+    let metrics = Metrics::new();
+    let sent = ChatMessageEventData::builder("hi")
+        .with_created_at((chrono::Utc::now() - chrono::Duration::seconds(5)).to_rfc3339())
+        .into_message();
+    metrics.observe(&sent);
+
+    let latency = metrics.snapshot().latency.unwrap();
+    assert_eq!(latency.samples, 1);
+    assert!(latency.max >= Duration::from_secs(5) && latency.max < Duration::from_secs(7));
+}
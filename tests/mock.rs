@@ -0,0 +1,70 @@
+use kick_client::commands::Commands;
+use kick_client::fake;
+use kick_client::mock::{MockKickClient, SentMessage};
+use kick_client::{ChatSender, KickError, MessageData};
+
+fn sent(chatroom_id: u32, content: &str) -> SentMessage {
+    SentMessage {
+        chatroom_id,
+        content: content.to_string(),
+    }
+}
+
+#[tokio::test]
+async fn pushed_messages_are_read_in_order() {
+    let mut mock = MockKickClient::new();
+    mock.push(fake::chat_message(668, "viewer", "hello"))
+        .push_frame(include_str!("fixtures/user_banned.json"))
+        .push_error(KickError::StreamEnded)
+        .push(fake::chatroom_clear(668));
+    assert_eq!(mock.pending(), 4);
+
+    let message = mock.read_message().await.unwrap().unwrap();
+    assert!(
+        matches!(&message.data, MessageData::ChatMessage(data) if data.content.as_deref() == Some("hello"))
+    );
+    // Frames are parsed like the ones a live connection receives.
+    let message = mock.read_message().await.unwrap().unwrap();
+    assert_eq!(message.data.kind(), "ban");
+    assert!(matches!(
+        mock.read_message().await,
+        Err(KickError::StreamEnded)
+    ));
+    let message = mock.read_message().await.unwrap().unwrap();
+    assert_eq!(message.data.kind(), "clear");
+    assert!(mock.read_message().await.unwrap().is_none());
+    assert_eq!(mock.pending(), 0);
+}
+
+#[tokio::test]
+async fn handlers_reply_through_the_mock() {
+    let mut mock = MockKickClient::new();
+    let mut commands = Commands::new("!").with_sender(mock.clone());
+    commands.command("ping", |ctx| async move { ctx.reply("pong").await });
+    mock.push(fake::chat_message(668, "viewer", "!ping"))
+        .push(fake::chat_message(668, "viewer", "hello"))
+        .push(fake::chat_message(1234, "other", "!ping"));
+
+    // `run` ends once every pushed message is read.
+    commands.run(&mut mock).await.unwrap();
+    assert_eq!(mock.sent(), [sent(668, "pong"), sent(1234, "pong")]);
+    assert_eq!(mock.take_sent().len(), 2);
+    assert!(mock.sent().is_empty());
+}
+
+#[tokio::test]
+async fn sends_fail_on_demand() {
+    let mock = MockKickClient::new();
+
+    mock.fail_sends(Some("rate limited"));
+    let error = mock.send_message(668, "hello").await.unwrap_err();
+    assert!(
+        matches!(&error, KickError::ApiError { status: 500, body } if body == "rate limited"),
+        "{error:?}"
+    );
+    assert!(mock.sent().is_empty());
+
+    mock.fail_sends(None);
+    mock.send_message(668, "hello").await.unwrap();
+    assert_eq!(mock.sent(), [sent(668, "hello")]);
+}
@@ -0,0 +1,414 @@
+use kick_client::mock_server::MockPusherServer;
+use kick_client::reconnect::ReconnectingClient;
+use kick_client::transport::{ConnectOptions, Connector, Frame, StaticResolver, WebSocketConfig};
+use kick_client::{KickClient, KickError, MessageData};
+use std::time::Duration;
+
+const CHANNEL: &str = "chatrooms.1234.v2";
+
+fn clear_event() -> serde_json::Value {
+    serde_json::json!({ "id": "1" })
+}
+
+#[test]
+fn text_frames_keep_the_received_buffer() {
+    let payload = bytes::Bytes::from_static(br#"{"event":"pusher:pong","data":"{}"}"#);
+    let text = tungstenite::Utf8Bytes::try_from(payload.clone()).unwrap();
+    let Frame::Text(frame) = Frame::from(tungstenite::Message::Text(text)) else {
+        panic!("expected a text frame");
+    };
+    assert_eq!(frame.as_ptr(), payload.as_ptr());
+}
+
+#[tokio::test]
+async fn client_receives_handshake_and_events() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = KickClient::new(&server.url(), vec![1234]).await.unwrap();
+
+    let established = client.read_message().await.unwrap().unwrap();
+    assert!(matches!(
+        established.data,
+        MessageData::PusherConnectionEstablished(_)
+    ));
+    let subscribed = client.read_message().await.unwrap().unwrap();
+    assert!(matches!(
+        subscribed.data,
+        MessageData::PusherSubscriptionSucceeded(_)
+    ));
+    assert_eq!(subscribed.channel.as_deref(), Some(CHANNEL));
+
+    server.wait_for_subscription(CHANNEL).await;
+    let sent = server.broadcast(CHANNEL, "App\\Events\\ChatroomClearEvent", &clear_event());
+    assert_eq!(sent, 1);
+    let message = client.read_message().await.unwrap().unwrap();
+    assert!(matches!(message.data, MessageData::ChatroomClear(_)));
+    assert_eq!(message.chatroom_id(), Some(1234));
+}
+
+#[tokio::test]
+async fn socket_id_and_handshake_response_are_kept() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = KickClient::new(&server.url(), vec![1234]).await.unwrap();
+    let response = client.handshake_response().unwrap();
+    assert_eq!(response.status(), 101);
+    assert!(response.headers().contains_key("sec-websocket-accept"));
+
+    assert_eq!(client.socket_id(), None);
+    client.read_message().await.unwrap();
+    let socket_id = client.socket_id().unwrap();
+    assert!(socket_id.contains('.'), "{socket_id}");
+}
+
+#[tokio::test]
+async fn idle_connections_are_pinged() {
+    let server = MockPusherServer::start().await.unwrap();
+    let client = KickClient::new(&server.url(), vec![1234]).await.unwrap();
+    assert_eq!(client.activity_timeout(), None);
+    assert_eq!(client.ping_interval(), Duration::from_secs(120));
+    let mut client = client.with_ping_interval(Duration::from_millis(50));
+
+    client.read_message().await.unwrap();
+    assert_eq!(client.activity_timeout(), Some(Duration::from_secs(120)));
+    assert_eq!(client.ping_interval(), Duration::from_millis(50));
+    let pong = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let message = client.read_message().await.unwrap().unwrap();
+            if let MessageData::PusherPong(_) = message.data {
+                break;
+            }
+        }
+    });
+    pong.await.expect("no pong received");
+}
+
+#[tokio::test]
+async fn connect_options_pick_the_tls_connector() {
+    let server = MockPusherServer::start().await.unwrap();
+    let options = ConnectOptions::new().with_tls(Connector::Plain);
+    let mut client = KickClient::connect_with(&server.url(), vec![1234], &options)
+        .await
+        .unwrap();
+    let established = client.read_message().await.unwrap().unwrap();
+    assert!(matches!(
+        established.data,
+        MessageData::PusherConnectionEstablished(_)
+    ));
+
+    // A plain connector refuses to connect to `wss://` URLs rather than falling back.
+    let url = server.url().replacen("ws://", "wss://", 1);
+    let error = KickClient::connect_with(&url, vec![1234], &options)
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        error.to_string().contains("TLS support not compiled in"),
+        "{error}"
+    );
+}
+
+#[tokio::test]
+async fn connect_options_set_handshake_headers() {
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/app/key?protocol=7", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut headers = None;
+        // The error type is tungstenite's, whose size isn't ours to choose.
+        #[allow(clippy::result_large_err)]
+        let callback = |request: &Request, response: Response| {
+            headers = Some(request.headers().clone());
+            Ok(response)
+        };
+        let _websocket = tokio_tungstenite::accept_hdr_async(stream, callback)
+            .await
+            .unwrap();
+        headers.unwrap()
+    });
+
+    let options = ConnectOptions::new()
+        .with_user_agent("my-bot/1.0")
+        .with_header("Origin", "https://example.com")
+        .with_header("origin", "https://kick.com")
+        .with_header("Cookie", "session=abc");
+    let _client = KickClient::connect_with(&url, vec![], &options)
+        .await
+        .unwrap();
+    let headers = server.await.unwrap();
+    assert_eq!(headers["user-agent"], "my-bot/1.0");
+    assert_eq!(headers["origin"], "https://kick.com");
+    assert_eq!(headers["cookie"], "session=abc");
+
+    let options = ConnectOptions::new().with_header("Bad Header", "value");
+    assert!(KickClient::connect_with(&url, vec![], &options)
+        .await
+        .is_err());
+
+    // Extensions are negotiated by the transport, not through headers.
+    let options =
+        ConnectOptions::new().with_header("Sec-WebSocket-Extensions", "permessage-deflate");
+    assert!(matches!(
+        KickClient::connect_with(&url, vec![], &options).await,
+        Err(KickError::ConfigError(_))
+    ));
+}
+
+#[tokio::test]
+async fn raw_events_keep_the_received_frame() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = KickClient::new(&server.url(), vec![1234]).await.unwrap();
+    server.wait_for_subscription(CHANNEL).await;
+    let frame = r#"{"event":"App\\Events\\ChatroomClearEvent","data":"{\"id\": \"1\"}","channel":"chatrooms.1234.v2"}"#;
+    server.send_raw(frame);
+
+    let event = loop {
+        let event = client.read_raw().await.unwrap().unwrap();
+        if let MessageData::ChatroomClear(_) = event.parsed.data {
+            break event;
+        }
+    };
+    assert_eq!(event.raw, frame.as_bytes());
+    assert_eq!(event.parsed.chatroom_id(), Some(1234));
+}
+
+#[tokio::test]
+async fn strict_clients_report_unknown_fields() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = KickClient::new(&server.url(), vec![1234])
+        .await
+        .unwrap()
+        .with_strict_parsing(true);
+    server.wait_for_subscription(CHANNEL).await;
+    server.broadcast(
+        CHANNEL,
+        "App\\Events\\ChatroomClearEvent",
+        &serde_json::json!({ "id": "1", "cleared_by": "Bob" }),
+    );
+
+    let error = loop {
+        match client.read_message().await.unwrap().unwrap().data {
+            MessageData::Unsupported(_, error) => break error,
+            _ => continue,
+        }
+    };
+    assert_eq!(error, "unknown fields: data.cleared_by");
+}
+
+#[tokio::test]
+async fn oversized_messages_are_rejected() {
+    let server = MockPusherServer::start().await.unwrap();
+    let config = WebSocketConfig::default().max_message_size(Some(1024));
+    let options = ConnectOptions::new().with_websocket_config(config);
+    let mut client = KickClient::connect_with(&server.url(), vec![1234], &options)
+        .await
+        .unwrap();
+    server.wait_for_subscription(CHANNEL).await;
+    let sent = server.broadcast(
+        CHANNEL,
+        "App\\Events\\ChatroomClearEvent",
+        &serde_json::json!({ "id": "x".repeat(2048) }),
+    );
+    assert_eq!(sent, 1);
+
+    let error = loop {
+        match client.read_message().await {
+            Ok(_) => continue,
+            Err(error) => break error,
+        }
+    };
+    assert!(error.to_string().contains("Message too long"), "{error}");
+}
+
+#[tokio::test]
+async fn subscriptions_are_awaited_without_losing_messages() {
+    let server = MockPusherServer::start().await.unwrap();
+    let options = ConnectOptions::new().with_subscribe_timeout(Duration::from_secs(5));
+    let mut client = KickClient::connect_with(&server.url(), vec![1234, 5678], &options)
+        .await
+        .unwrap();
+
+    let mut kinds = Vec::new();
+    for _ in 0..3 {
+        kinds.push(client.read_message().await.unwrap().unwrap().data.kind());
+    }
+    assert_eq!(
+        kinds,
+        [
+            "connection_established",
+            "subscription_succeeded",
+            "subscription_succeeded"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn connecting_and_subscribing_time_out() {
+    // Accepts TCP connections without ever answering the WebSocket handshake.
+    let silent = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/app/key?protocol=7", silent.local_addr().unwrap());
+    let options = ConnectOptions::new().with_connect_timeout(Duration::from_millis(100));
+    let error = KickClient::connect_with(&url, vec![1234], &options)
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(error, KickError::TimeoutError(_)), "{error}");
+
+    // Completes the handshake without ever confirming subscriptions.
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/app/key?protocol=7", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let _websocket = tokio_tungstenite::accept_async(stream).await.unwrap();
+        std::future::pending::<()>().await;
+    });
+    let options = ConnectOptions::new().with_subscribe_timeout(Duration::from_millis(100));
+    let error = KickClient::connect_with(&url, vec![1234], &options)
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(error, KickError::TimeoutError(_)), "{error}");
+    drop(silent);
+}
+
+#[tokio::test]
+async fn reconnecting_client_recovers_from_disconnects() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = ReconnectingClient::new(server.url(), vec![1234])
+        .with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+        .with_gap_threshold(Duration::from_secs(60));
+
+    let established = client.read_message().await.unwrap().unwrap();
+    assert!(matches!(
+        established.data,
+        MessageData::PusherConnectionEstablished(_)
+    ));
+    server.wait_for_subscription(CHANNEL).await;
+    server.disconnect_all();
+
+    let reader = tokio::spawn(async move {
+        loop {
+            let message = client.read_message().await.unwrap().unwrap();
+            if let MessageData::ChatroomClear(_) = message.data {
+                return client;
+            }
+        }
+    });
+    server.wait_for_accepted(2).await;
+    server.wait_for_subscription(CHANNEL).await;
+    server.broadcast(CHANNEL, "App\\Events\\ChatroomClearEvent", &clear_event());
+
+    let client = tokio::time::timeout(Duration::from_secs(5), reader)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(client.is_connected());
+    assert_eq!(server.accepted(), 2);
+}
+
+#[tokio::test]
+async fn silent_connections_are_replaced() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = ReconnectingClient::new(server.url(), vec![1234])
+        .with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+        .with_idle_timeout(Duration::from_millis(200))
+        .with_gap_threshold(Duration::from_millis(100));
+
+    let reader = tokio::spawn(async move {
+        loop {
+            let message = client.read_message().await.unwrap().unwrap();
+            if let MessageData::PossibleGap(gap) = message.data {
+                assert!(gap.downtime_ms >= 200, "{}", gap.downtime_ms);
+                return client;
+            }
+        }
+    });
+    // The server stays silent after confirming the subscription.
+    server.wait_for_accepted(2).await;
+
+    let client = tokio::time::timeout(Duration::from_secs(5), reader)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(client.is_connected());
+    assert!(client.last_frame_at().is_some());
+}
+
+#[tokio::test]
+async fn fallback_endpoints_are_tried_in_order() {
+    let server = MockPusherServer::start().await.unwrap();
+    // An address nothing listens on anymore.
+    let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let down = format!("ws://{}/app/key?protocol=7", closed.local_addr().unwrap());
+    drop(closed);
+
+    let mut client = ReconnectingClient::new(&down, vec![1234])
+        .with_fallback_urls([server.url()])
+        .with_backoff(Duration::from_secs(60), Duration::from_secs(60));
+    let established = tokio::time::timeout(Duration::from_secs(5), client.read_message())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        established.data,
+        MessageData::PusherConnectionEstablished(_)
+    ));
+    assert_eq!(client.current_url(), Some(server.url().as_str()));
+
+    let endpoints = client.endpoints();
+    assert_eq!(endpoints[0].url, down);
+    assert_eq!(endpoints[0].consecutive_failures, 1);
+    assert!(endpoints[0].last_connected.is_none());
+    assert_eq!(endpoints[1].consecutive_failures, 0);
+    assert!(endpoints[1].last_connected.is_some());
+}
+
+#[tokio::test]
+async fn connections_are_made_from_the_local_address() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/app/key?protocol=7", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let (stream, peer) = listener.accept().await.unwrap();
+        let _websocket = tokio_tungstenite::accept_async(stream).await.unwrap();
+        peer
+    });
+
+    let local = std::net::IpAddr::from([127, 0, 0, 1]);
+    let options = ConnectOptions::new().with_local_addr(local);
+    let _client = KickClient::connect_with(&url, vec![], &options)
+        .await
+        .unwrap();
+    assert_eq!(server.await.unwrap().ip(), local);
+
+    // An IPv6 local address can't reach an IPv4 server.
+    let options = ConnectOptions::new().with_local_addr(std::net::Ipv6Addr::LOCALHOST.into());
+    assert!(KickClient::connect_with(&url, vec![], &options)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn host_names_are_resolved_with_the_resolver() {
+    let server = MockPusherServer::start().await.unwrap();
+    let url = format!(
+        "ws://ws-test.pusher.invalid:{}/app/key?protocol=7",
+        server.addr().port()
+    );
+    let resolver = StaticResolver::new().with_host("WS-TEST.pusher.invalid", [server.addr().ip()]);
+    let options = ConnectOptions::new().with_resolver(resolver);
+    let mut client = KickClient::connect_with(&url, vec![1234], &options)
+        .await
+        .unwrap();
+    let established = client.read_message().await.unwrap().unwrap();
+    assert!(matches!(
+        established.data,
+        MessageData::PusherConnectionEstablished(_)
+    ));
+
+    // Other hosts are still resolved by the system.
+    let options = ConnectOptions::new().with_resolver(StaticResolver::new());
+    assert!(KickClient::connect_with(&url, vec![], &options)
+        .await
+        .is_err());
+}
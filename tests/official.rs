@@ -0,0 +1,386 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use kick_client::api::{HttpRequest, HttpResponse, HttpTransport};
+use kick_client::auth::{Credentials, TokenManager};
+use kick_client::official::{EventType, OAuthClient, OfficialApi};
+use kick_client::KickError;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Answers requests with fixed bodies by method and URL without its query, keeping the
+/// requests.
+#[derive(Clone, Default)]
+struct Server {
+    routes: Arc<Mutex<HashMap<String, (u16, String)>>>,
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl Server {
+    fn route(&self, method: &str, url: &str, status: u16, body: &str) {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert(format!("{method} {url}"), (status, body.to_string()));
+    }
+
+    fn requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl HttpTransport for Server {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, KickError> {
+        let url = request.url.split('?').next().unwrap();
+        let key = format!("{} {}", request.method, url);
+        let (status, body) = self
+            .routes
+            .lock()
+            .unwrap()
+            .get(&key)
+            .cloned()
+            .unwrap_or((404, "Not Found".to_string()));
+        self.requests.lock().unwrap().push(request);
+        Ok(HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.into_bytes(),
+        })
+    }
+}
+
+/// Returns the form fields of a request body.
+fn form(request: &HttpRequest) -> HashMap<String, String> {
+    serde_urlencoded::from_bytes(request.body.as_deref().unwrap()).unwrap()
+}
+
+fn header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    request
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+const TOKEN_URL: &str = "https://id.kick.com/oauth/token";
+const TOKEN: &str = r#"{"access_token":"access","refresh_token":"refresh","expires_in":3600,"token_type":"Bearer"}"#;
+
+#[test]
+fn authorization_urls_carry_a_pkce_challenge() {
+    let oauth = OAuthClient::new("id", "secret").with_redirect_uri("http://localhost/callback");
+    let request = oauth.authorization_url(&["user:read", "chat:write"]);
+    let url = reqwest::Url::parse(&request.url).unwrap();
+    assert_eq!(url.origin().ascii_serialization(), "https://id.kick.com");
+    assert_eq!(url.path(), "/oauth/authorize");
+    let params: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+    assert_eq!(params["response_type"], "code");
+    assert_eq!(params["client_id"], "id");
+    assert_eq!(params["scope"], "user:read chat:write");
+    assert_eq!(params["redirect_uri"], "http://localhost/callback");
+    assert_eq!(params["state"], request.state);
+    assert_eq!(params["code_challenge_method"], "S256");
+    assert_eq!(
+        params["code_challenge"],
+        URL_SAFE_NO_PAD.encode(Sha256::digest(request.code_verifier.as_bytes()))
+    );
+    // RFC 7636 requires 43 to 128 characters from the unreserved set.
+    assert!((43..=128).contains(&request.code_verifier.len()));
+    assert!(request
+        .code_verifier
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c)));
+
+    let other = oauth.authorization_url(&["user:read"]);
+    assert_ne!(other.state, request.state);
+    assert_ne!(other.code_verifier, request.code_verifier);
+}
+
+#[tokio::test]
+async fn tokens_are_requested_with_forms() {
+    let server = Server::default();
+    server.route("POST", TOKEN_URL, 200, TOKEN);
+    let oauth = OAuthClient::new("id", "secret")
+        .with_redirect_uri("http://localhost/callback")
+        .with_transport(server.clone());
+
+    let credentials = oauth.client_credentials().await.unwrap();
+    assert_eq!(credentials.access_token, "access");
+    assert_eq!(credentials.refresh_token.as_deref(), Some("refresh"));
+    assert!(credentials.expires_within(std::time::Duration::from_secs(3600)));
+    assert!(!credentials.expires_within(std::time::Duration::from_secs(3500)));
+    oauth.exchange_code("code", "verifier").await.unwrap();
+    oauth.refresh("old refresh").await.unwrap();
+
+    let requests = server.requests();
+    assert!(requests
+        .iter()
+        .all(|request| request.method == reqwest::Method::POST && request.url == TOKEN_URL));
+    assert!(requests.iter().all(|request| {
+        header(request, "Content-Type") == Some("application/x-www-form-urlencoded")
+    }));
+    let forms: Vec<_> = requests.iter().map(form).collect();
+    let expected = [
+        vec![
+            ("grant_type", "client_credentials"),
+            ("client_id", "id"),
+            ("client_secret", "secret"),
+        ],
+        vec![
+            ("grant_type", "authorization_code"),
+            ("code", "code"),
+            ("client_id", "id"),
+            ("client_secret", "secret"),
+            ("redirect_uri", "http://localhost/callback"),
+            ("code_verifier", "verifier"),
+        ],
+        vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", "old refresh"),
+            ("client_id", "id"),
+            ("client_secret", "secret"),
+        ],
+    ];
+    for (form, expected) in forms.iter().zip(expected) {
+        let expected: HashMap<String, String> = expected
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        assert_eq!(*form, expected);
+    }
+}
+
+#[tokio::test]
+async fn token_errors_are_reported() {
+    let server = Server::default();
+    server.route("POST", TOKEN_URL, 400, r#"{"error":"invalid_grant"}"#);
+    let oauth = OAuthClient::new("id", "secret").with_transport(server.clone());
+
+    let error = oauth.refresh("expired").await.unwrap_err();
+    assert!(
+        matches!(&error, KickError::ApiError { status: 400, body } if body.contains("invalid_grant")),
+        "{error:?}"
+    );
+    // The authorization-code flow needs the redirect URI.
+    let error = oauth.exchange_code("code", "verifier").await.unwrap_err();
+    assert!(matches!(error, KickError::AuthError(_)), "{error:?}");
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn token_managers_refresh_through_the_app() {
+    let server = Server::default();
+    server.route(
+        "POST",
+        TOKEN_URL,
+        200,
+        r#"{"access_token":"new","expires_in":3600}"#,
+    );
+    let oauth = OAuthClient::new("id", "secret").with_transport(server.clone());
+    let tokens = oauth.token_manager(Credentials::new("old").with_refresh_token("refresh"));
+
+    tokens.refresh().await.unwrap();
+    let credentials = tokens.credentials().await;
+    assert_eq!(credentials.access_token, "new");
+    assert_eq!(credentials.refresh_token.as_deref(), Some("refresh"));
+    assert_eq!(form(&server.requests()[0])["refresh_token"], "refresh");
+}
+
+const SUBSCRIPTIONS_URL: &str = "https://api.kick.com/public/v1/events/subscriptions";
+
+#[tokio::test]
+async fn subscriptions_are_reconciled() {
+    let server = Server::default();
+    server.route(
+        "GET",
+        SUBSCRIPTIONS_URL,
+        200,
+        r#"{"data":[
+            {"id":"keep","broadcaster_user_id":668,"event":"chat.message.sent","version":1,"method":"webhook"},
+            {"id":"outdated","broadcaster_user_id":668,"event":"channel.followed","version":0,"method":"webhook"},
+            {"id":"unwanted","broadcaster_user_id":668,"event":"livestream.status.updated","version":1,"method":"webhook"},
+            {"id":"other","broadcaster_user_id":1,"event":"kicks.gifted","version":1,"method":"webhook"}
+        ],"message":"OK"}"#,
+    );
+    server.route("DELETE", SUBSCRIPTIONS_URL, 204, "");
+    server.route(
+        "POST",
+        SUBSCRIPTIONS_URL,
+        200,
+        r#"{"data":[{"name":"channel.followed","version":1,"subscription_id":"new"}],"message":"OK"}"#,
+    );
+    let api = OfficialApi::new(TokenManager::new(Credentials::new("token")))
+        .with_transport(server.clone());
+
+    let reconciled = api
+        .reconcile_event_subscriptions(
+            668,
+            &[
+                EventType::new("chat.message.sent", 1),
+                EventType::new("channel.followed", 1),
+            ],
+        )
+        .await
+        .unwrap();
+    assert_eq!(reconciled.deleted, ["outdated", "unwanted"]);
+    assert_eq!(reconciled.created.len(), 1);
+    assert_eq!(
+        reconciled.created[0].subscription_id.as_deref(),
+        Some("new")
+    );
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests
+        .iter()
+        .all(|request| header(request, "Authorization") == Some("Bearer token")));
+    let delete = reqwest::Url::parse(&requests[1].url).unwrap();
+    assert_eq!(requests[1].method, reqwest::Method::DELETE);
+    let ids: Vec<_> = delete
+        .query_pairs()
+        .map(|(_, id)| id.into_owned())
+        .collect();
+    assert_eq!(ids, ["outdated", "unwanted"]);
+    assert_eq!(requests[2].method, reqwest::Method::POST);
+    let body: serde_json::Value =
+        serde_json::from_slice(requests[2].body.as_deref().unwrap()).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "broadcaster_user_id": 668,
+            "events": [{ "name": "channel.followed", "version": 1 }],
+            "method": "webhook",
+        })
+    );
+}
+
+#[tokio::test]
+async fn reconciled_subscriptions_are_left_alone() {
+    let server = Server::default();
+    server.route(
+        "GET",
+        SUBSCRIPTIONS_URL,
+        200,
+        r#"{"data":[{"id":"keep","broadcaster_user_id":668,"event":"chat.message.sent","version":1,"method":"webhook"}]}"#,
+    );
+    let api = OfficialApi::new(TokenManager::new(Credentials::new("token")))
+        .with_transport(server.clone());
+
+    let reconciled = api
+        .reconcile_event_subscriptions(668, &[EventType::new("chat.message.sent", 1)])
+        .await
+        .unwrap();
+    assert!(reconciled.created.is_empty() && reconciled.deleted.is_empty());
+    assert_eq!(server.requests().len(), 1);
+}
+
+const API_URL: &str = "https://api.kick.com/public/v1";
+
+#[tokio::test]
+async fn users_and_channels_are_fetched_by_id() {
+    let server = Server::default();
+    server.route(
+        "GET",
+        &format!("{API_URL}/users"),
+        200,
+        r#"{"data":[{"user_id":668,"name":"xQc","email":null,"profile_picture":"https://kick.com/xqc.png"}],"message":"OK"}"#,
+    );
+    server.route(
+        "GET",
+        &format!("{API_URL}/channels"),
+        200,
+        r#"{"data":[{"broadcaster_user_id":668,"slug":"xqc","stream_title":"JUST CHATTING",
+            "category":{"id":15,"name":"Just Chatting","thumbnail":null},
+            "stream":{"is_live":true,"viewer_count":51234}}],"message":"OK"}"#,
+    );
+    let api = OfficialApi::new(TokenManager::new(Credentials::new("token")))
+        .with_transport(server.clone());
+
+    let users = api.users(&[668, 1447541]).await.unwrap();
+    assert_eq!(users[0].user_id, 668);
+    assert_eq!(users[0].name, "xQc");
+    let channels = api.channels(&[668]).await.unwrap();
+    assert_eq!(channels[0].slug, "xqc");
+    assert_eq!(channels[0].category.as_ref().unwrap().name, "Just Chatting");
+    let stream = channels[0].stream.as_ref().unwrap();
+    assert!(stream.is_live);
+    assert_eq!(stream.viewer_count, Some(51234));
+    api.channels_by_slug(&["xqc"]).await.unwrap();
+    // Empty lists ask for the authenticated user.
+    api.users(&[]).await.unwrap();
+
+    let urls: Vec<_> = server
+        .requests()
+        .into_iter()
+        .inspect(|request| {
+            assert_eq!(request.method, reqwest::Method::GET);
+            assert_eq!(header(request, "Authorization"), Some("Bearer token"));
+        })
+        .map(|request| request.url)
+        .collect();
+    assert_eq!(
+        urls,
+        [
+            format!("{API_URL}/users?id=668&id=1447541"),
+            format!("{API_URL}/channels?broadcaster_user_id=668"),
+            format!("{API_URL}/channels?slug=xqc"),
+            format!("{API_URL}/users"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn chat_messages_are_sent_as_the_user() {
+    let server = Server::default();
+    server.route(
+        "POST",
+        &format!("{API_URL}/chat"),
+        200,
+        r#"{"data":{"is_sent":true,"message_id":"f2c9e1a0"},"message":"OK"}"#,
+    );
+    let api = OfficialApi::new(TokenManager::new(Credentials::new("token")))
+        .with_transport(server.clone());
+
+    let sent = api.send_chat_message(668, "hello", None).await.unwrap();
+    assert!(sent.is_sent);
+    assert_eq!(sent.message_id, "f2c9e1a0");
+    api.send_chat_message(668, "hi back", Some("f2c9e1a0"))
+        .await
+        .unwrap();
+
+    let bodies: Vec<serde_json::Value> = server
+        .requests()
+        .iter()
+        .map(|request| serde_json::from_slice(request.body.as_deref().unwrap()).unwrap())
+        .collect();
+    assert_eq!(
+        bodies,
+        [
+            serde_json::json!({ "broadcaster_user_id": 668, "content": "hello", "type": "user" }),
+            serde_json::json!({
+                "broadcaster_user_id": 668,
+                "content": "hi back",
+                "type": "user",
+                "reply_to_message_id": "f2c9e1a0",
+            }),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn rejected_requests_are_errors() {
+    let server = Server::default();
+    let api = OfficialApi::new(TokenManager::new(Credentials::new("token")))
+        .with_transport(server.clone())
+        .with_base_url("https://staging.kick.test/public/v1");
+
+    let error = api.channels(&[668]).await.unwrap_err();
+    assert!(
+        matches!(error, KickError::ApiError { status: 404, .. }),
+        "{error:?}"
+    );
+    assert_eq!(
+        server.requests()[0].url,
+        "https://staging.kick.test/public/v1/channels?broadcaster_user_id=668"
+    );
+}
@@ -0,0 +1,95 @@
+use kick_client::mock_server::MockPusherServer;
+use kick_client::otel::Telemetry;
+use kick_client::transport::ConnectOptions;
+use kick_client::KickClient;
+use opentelemetry::trace::Status;
+use opentelemetry_sdk::metrics::{InMemoryMetricExporter, PeriodicReader, SdkMeterProvider};
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider};
+
+struct Providers {
+    spans: InMemorySpanExporter,
+    metrics: InMemoryMetricExporter,
+    meter_provider: SdkMeterProvider,
+    telemetry: Telemetry,
+}
+
+fn providers() -> Providers {
+    let spans = InMemorySpanExporter::default();
+    let tracer_provider = SdkTracerProvider::builder()
+        .with_simple_exporter(spans.clone())
+        .build();
+    let metrics = InMemoryMetricExporter::default();
+    let meter_provider = SdkMeterProvider::builder()
+        .with_reader(PeriodicReader::builder(metrics.clone()).build())
+        .build();
+    let telemetry = Telemetry::from_providers(&meter_provider, &tracer_provider);
+    Providers {
+        spans,
+        metrics,
+        meter_provider,
+        telemetry,
+    }
+}
+
+impl Providers {
+    fn metric_names(&self) -> Vec<String> {
+        self.meter_provider.force_flush().unwrap();
+        let mut names = Vec::new();
+        for resource in self.metrics.get_finished_metrics().unwrap() {
+            for scope in resource.scope_metrics() {
+                names.extend(scope.metrics().map(|metric| metric.name().to_string()));
+            }
+        }
+        names
+    }
+}
+
+#[tokio::test]
+async fn connections_subscriptions_and_messages_are_recorded() {
+    let server = MockPusherServer::start().await.unwrap();
+    let providers = providers();
+    let options = ConnectOptions::new().with_telemetry(providers.telemetry.clone());
+
+    let mut client = KickClient::connect_with(&server.url(), vec![1234], &options)
+        .await
+        .unwrap();
+    client.read_message().await.unwrap().unwrap();
+    client.read_message().await.unwrap().unwrap();
+
+    let spans = providers.spans.get_finished_spans().unwrap();
+    assert_eq!(spans.len(), 1);
+    assert_eq!(spans[0].name, "kick_client.connect");
+    assert_eq!(spans[0].status, Status::Unset);
+    let names = providers.metric_names();
+    for name in [
+        "kick_client.connect.attempts",
+        "kick_client.subscribe.latency",
+        "kick_client.messages",
+    ] {
+        assert!(
+            names.iter().any(|n| n == name),
+            "no {} in {:?}",
+            name,
+            names
+        );
+    }
+}
+
+#[tokio::test]
+async fn failed_connections_are_recorded_as_errors() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    drop(listener);
+    let providers = providers();
+    let options = ConnectOptions::new().with_telemetry(providers.telemetry.clone());
+
+    assert!(KickClient::connect_with(&url, vec![1234], &options)
+        .await
+        .is_err());
+
+    let spans = providers.spans.get_finished_spans().unwrap();
+    assert!(matches!(spans[0].status, Status::Error { .. }));
+    assert!(providers
+        .metric_names()
+        .contains(&"kick_client.connect.attempts".to_string()));
+}
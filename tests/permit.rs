@@ -0,0 +1,117 @@
+use kick_client::commands::{Commands, DispatchOutcome};
+use kick_client::content::links;
+use kick_client::mock::MockKickClient;
+use kick_client::permit::LinkPermits;
+use kick_client::{ChatMessageEventData, KickChatMessage};
+use std::time::Duration;
+
+const CHATROOM: u32 = 1234;
+
+fn message(username: &str, badge: Option<&str>, content: &str) -> KickChatMessage {
+    let builder = ChatMessageEventData::builder(content)
+        .with_chatroom_id(CHATROOM)
+        .with_sender(1, username);
+    match badge {
+        Some(badge) => builder.with_badge(badge),
+        None => builder,
+    }
+    .into_message()
+}
+
+#[test]
+fn links_are_found_in_chat() {
+    let found: Vec<&str> =
+        links("see (https://Kick.com/xqc), www.example.org. v1.2 and [emote:1:a.bc]")
+            .iter()
+            .map(|link| link.text)
+            .collect();
+    assert_eq!(found, ["https://Kick.com/xqc", "www.example.org"]);
+    assert_eq!(links("clips.kick.com/abc?x=1")[0].host(), "clips.kick.com");
+}
+
+#[test]
+fn links_need_a_permit_or_an_allowed_host() {
+    let permits = LinkPermits::new().allow_host("www.kick.com");
+    let violation = permits
+        .check(&message(
+            "viewer",
+            None,
+            "go to spam.example and kick.com/xqc",
+        ))
+        .unwrap();
+    assert_eq!(violation.links, ["spam.example"]);
+    assert!(permits
+        .check(&message("viewer", None, "https://clips.kick.com/abc"))
+        .is_none());
+    assert!(permits
+        .check(&message("viewer", Some("vip"), "spam.example"))
+        .is_none());
+
+    permits.permit(CHATROOM, "Viewer", Duration::from_secs(60));
+    assert!(permits
+        .check(&message("viewer", None, "spam.example"))
+        .is_none());
+    // Permits are per chatroom.
+    assert!(!permits.is_permitted(1, "viewer"));
+
+    permits.revoke(CHATROOM, "VIEWER");
+    assert!(permits
+        .check(&message("viewer", None, "spam.example"))
+        .is_some());
+}
+
+#[test]
+fn permits_expire() {
+    let permits = LinkPermits::new();
+    permits.permit(CHATROOM, "viewer", Duration::from_millis(50));
+    assert!(permits.is_permitted(CHATROOM, "viewer"));
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(!permits.is_permitted(CHATROOM, "viewer"));
+    assert!(permits
+        .check(&message("viewer", None, "spam.example"))
+        .is_some());
+}
+
+#[tokio::test]
+async fn moderators_grant_permits_by_command() {
+    let permits = LinkPermits::new();
+    let mock = MockKickClient::new();
+    let mut commands = Commands::new("!").with_sender(mock.clone());
+    commands.register(permits.command());
+
+    assert!(matches!(
+        commands
+            .dispatch(&message("viewer", None, "!permit viewer"))
+            .await,
+        DispatchOutcome::NotPermitted(_)
+    ));
+    let outcome = commands
+        .dispatch(&message("mod", Some("moderator"), "!permit @Viewer 2m"))
+        .await;
+    assert!(matches!(outcome, DispatchOutcome::Handled(Ok(()))));
+    assert_eq!(mock.sent()[0].content, "@Viewer may post links for 120s");
+    assert!(permits.is_permitted(CHATROOM, "viewer"));
+}
+
+#[tokio::test]
+async fn huge_permit_durations_never_expire() {
+    let permits = LinkPermits::new();
+    let mock = MockKickClient::new();
+    let mut commands = Commands::new("!").with_sender(mock.clone());
+    commands.register(permits.command());
+
+    let outcome = commands
+        .dispatch(&message(
+            "mod",
+            Some("moderator"),
+            "!permit viewer 18446744073709551615",
+        ))
+        .await;
+    assert!(matches!(outcome, DispatchOutcome::Handled(Ok(()))));
+    assert!(permits.is_permitted(CHATROOM, "viewer"));
+    permits.purge_expired();
+    assert!(permits.is_permitted(CHATROOM, "viewer"));
+
+    permits.permit(CHATROOM, "other", Duration::MAX);
+    assert!(permits.is_permitted(CHATROOM, "other"));
+}
@@ -0,0 +1,105 @@
+use kick_client::polls::{PollEvent, PollTracker, VoteDelta};
+use kick_client::{fake, MessageData, PollDeleteEventData};
+
+const CHATROOM: u32 = 1234;
+
+#[test]
+fn polls_report_vote_deltas() {
+    let mut polls = PollTracker::new();
+    let started = polls.observe(&fake::poll_update(
+        CHATROOM,
+        "Best map?",
+        &[("Dust", 0), ("Mirage", 0)],
+        60,
+        60,
+    ));
+    assert!(matches!(
+        started,
+        Some(PollEvent::Started { chatroom_id: CHATROOM, ref poll }) if poll.title == "Best map?"
+    ));
+
+    let updated = polls.observe(&fake::poll_update(
+        CHATROOM,
+        "Best map?",
+        &[("Dust", 3), ("Mirage", 1)],
+        60,
+        45,
+    ));
+    let Some(PollEvent::Updated { deltas, .. }) = updated else {
+        panic!("expected an update, got {:?}", updated);
+    };
+    assert_eq!(
+        deltas,
+        [
+            VoteDelta {
+                option_id: 0,
+                label: "Dust".to_string(),
+                votes: 3,
+                delta: 3,
+            },
+            VoteDelta {
+                option_id: 1,
+                label: "Mirage".to_string(),
+                votes: 1,
+                delta: 1,
+            },
+        ]
+    );
+    assert_eq!(polls.poll(CHATROOM).unwrap().remaining, 45);
+    assert!(polls.poll(1).is_none());
+}
+
+#[test]
+fn polls_finish_once() {
+    let mut polls = PollTracker::new();
+    let options = [("Yes", 4), ("No", 2)];
+    polls.observe(&fake::poll_update(CHATROOM, "Raid?", &options, 30, 10));
+
+    // Updates sent once a poll ended lack its duration.
+    let finished = polls.observe(&fake::poll_update(CHATROOM, "Raid?", &options, 0, 0));
+    assert!(matches!(
+        finished,
+        Some(PollEvent::Finished { ref poll, .. }) if poll.options[0].votes == 4
+    ));
+    // The results keep being sent while they are displayed.
+    assert!(polls
+        .observe(&fake::poll_update(CHATROOM, "Raid?", &options, 0, 0))
+        .is_none());
+
+    // A rerun of the poll starts over.
+    assert!(matches!(
+        polls.observe(&fake::poll_update(
+            CHATROOM,
+            "Raid?",
+            &[("Yes", 0), ("No", 0)],
+            30,
+            30
+        )),
+        Some(PollEvent::Started { .. })
+    ));
+}
+
+#[test]
+fn polls_seen_ended_are_finished() {
+    let mut polls = PollTracker::new();
+    assert!(matches!(
+        polls.observe(&fake::poll_update(CHATROOM, "Late?", &[("Yes", 1)], 0, 0)),
+        Some(PollEvent::Finished { .. })
+    ));
+}
+
+#[test]
+fn deleted_polls_are_forgotten() {
+    let mut polls = PollTracker::new();
+    let delete = fake::message(CHATROOM, MessageData::PollDelete(PollDeleteEventData {}));
+    assert!(polls.observe(&delete).is_none());
+
+    polls.observe(&fake::poll_update(CHATROOM, "Raid?", &[("Yes", 0)], 30, 30));
+    assert!(matches!(
+        polls.observe(&delete),
+        Some(PollEvent::Deleted {
+            chatroom_id: CHATROOM
+        })
+    ));
+    assert!(polls.poll(CHATROOM).is_none());
+}
@@ -0,0 +1,34 @@
+use kick_client::mock_server::MockPusherServer;
+use kick_client::pool::KickClientPool;
+use kick_client::MessageData;
+
+#[tokio::test]
+async fn pools_shard_chatrooms_across_connections() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut pool = KickClientPool::new(server.url(), vec![1, 2, 3]).with_connections(2);
+    assert_eq!(pool.shards(), [vec![1, 3], vec![2]]);
+
+    let first = pool.recv().await.unwrap();
+    assert!(matches!(
+        first.message.data,
+        MessageData::PusherConnectionEstablished(_)
+    ));
+    for channel in ["chatrooms.1.v2", "chatrooms.2.v2", "chatrooms.3.v2"] {
+        server.wait_for_subscription(channel).await;
+    }
+    assert_eq!(server.connected(), 2);
+
+    server.broadcast(
+        "chatrooms.2.v2",
+        "App\\Events\\ChatroomClearEvent",
+        &serde_json::json!({ "id": "1" }),
+    );
+    let cleared = loop {
+        let pooled = pool.recv().await.unwrap();
+        if matches!(pooled.message.data, MessageData::ChatroomClear(_)) {
+            break pooled;
+        }
+    };
+    assert_eq!(cleared.connection, 1);
+    assert_eq!(cleared.message.chatroom_id(), Some(2));
+}
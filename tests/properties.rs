@@ -0,0 +1,194 @@
+use kick_client::{
+    ChatMessageEventData, ChatMessageSender, ChatMessageSenderBadge, ChatMessageSenderIdentity,
+    ChatroomClearEventData, GiftedSubscriptionsEventData, KickChatMessage, SubscriptionEventData,
+    User, UserBannedEventData,
+};
+use proptest::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+
+fn badge() -> impl Strategy<Value = ChatMessageSenderBadge> {
+    prop_oneof![
+        (any::<String>(), any::<String>(), any::<Option<u32>>()).prop_map(
+            |(r#type, text, count)| ChatMessageSenderBadge::FullBadge {
+                r#type,
+                text,
+                count,
+            }
+        ),
+        (any::<String>(), any::<String>())
+            .prop_map(|(r#type, text)| ChatMessageSenderBadge::SimpleBadge { r#type, text }),
+    ]
+}
+
+fn identity() -> impl Strategy<Value = ChatMessageSenderIdentity> {
+    (
+        any::<Option<String>>(),
+        prop::collection::vec(badge(), 0..4),
+    )
+        .prop_map(|(color, badges)| ChatMessageSenderIdentity { color, badges })
+}
+
+fn sender() -> impl Strategy<Value = ChatMessageSender> {
+    (
+        any::<u32>(),
+        any::<String>(),
+        any::<Option<String>>(),
+        identity(),
+    )
+        .prop_map(|(id, username, slug, identity)| ChatMessageSender {
+            id,
+            username,
+            slug,
+            identity,
+        })
+}
+
+fn chat_message() -> impl Strategy<Value = ChatMessageEventData> {
+    (
+        any::<String>(),
+        any::<u32>(),
+        any::<Option<String>>(),
+        any::<Option<String>>(),
+        any::<Option<String>>(),
+        sender(),
+    )
+        .prop_map(|(id, chatroom_id, content, r#type, created_at, sender)| {
+            ChatMessageEventData {
+                id,
+                chatroom_id,
+                content,
+                r#type,
+                created_at,
+                sender,
+            }
+        })
+}
+
+fn user() -> impl Strategy<Value = User> {
+    (any::<u32>(), any::<String>(), any::<String>()).prop_map(|(id, username, slug)| User {
+        id,
+        username,
+        slug,
+    })
+}
+
+fn user_banned() -> impl Strategy<Value = UserBannedEventData> {
+    (
+        any::<String>(),
+        user(),
+        user(),
+        any::<bool>(),
+        any::<Option<u64>>(),
+        any::<Option<String>>(),
+    )
+        .prop_map(|(id, user, banned_by, permanent, duration, expires_at)| {
+            UserBannedEventData {
+                id,
+                user,
+                banned_by,
+                permanent,
+                duration,
+                expires_at,
+            }
+        })
+}
+
+/// An envelope as Kick sends it, with its event name, channel and data.
+#[derive(Debug, Clone)]
+struct Envelope {
+    event: &'static str,
+    channel: Option<String>,
+    data: Value,
+}
+
+impl Envelope {
+    /// Returns the frame, with the data encoded as a JSON string.
+    fn frame(&self) -> String {
+        let mut frame = serde_json::json!({
+            "event": self.event,
+            "data": self.data.to_string(),
+        });
+        if let Some(channel) = &self.channel {
+            frame["channel"] = channel.as_str().into();
+        }
+        frame.to_string()
+    }
+}
+
+fn envelope() -> impl Strategy<Value = Envelope> {
+    let channel = any::<Option<u32>>().prop_map(|id| id.map(|id| format!("chatrooms.{id}.v2")));
+    (
+        channel,
+        prop_oneof![
+            chat_message().prop_map(|data| ("App\\Events\\ChatMessageEvent", to_value(data))),
+            user_banned().prop_map(|data| ("App\\Events\\UserBannedEvent", to_value(data))),
+            any::<String>().prop_map(|id| (
+                "App\\Events\\ChatroomClearEvent",
+                to_value(ChatroomClearEventData { id })
+            )),
+            (any::<u32>(), any::<String>(), any::<u32>()).prop_map(
+                |(chatroom_id, username, months)| (
+                    "App\\Events\\SubscriptionEvent",
+                    to_value(SubscriptionEventData {
+                        chatroom_id,
+                        username,
+                        months,
+                    })
+                )
+            ),
+            (any::<u32>(), any::<Vec<String>>(), any::<Option<String>>()).prop_map(
+                |(chatroom_id, gifted_usernames, gifter_username)| (
+                    "App\\Events\\GiftedSubscriptionsEvent",
+                    to_value(GiftedSubscriptionsEventData {
+                        chatroom_id,
+                        gifted_usernames,
+                        gifter_username,
+                    })
+                )
+            ),
+        ],
+    )
+        .prop_map(|(channel, (event, data))| Envelope {
+            event,
+            channel,
+            data,
+        })
+}
+
+fn to_value(data: impl Serialize) -> Value {
+    serde_json::to_value(data).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn badges_round_trip(badge in badge()) {
+        let json = serde_json::to_value(&badge).unwrap();
+        let parsed: ChatMessageSenderBadge = serde_json::from_value(json.clone()).unwrap();
+        prop_assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn identities_round_trip(identity in identity()) {
+        let json = serde_json::to_value(&identity).unwrap();
+        let parsed: ChatMessageSenderIdentity = serde_json::from_value(json.clone()).unwrap();
+        prop_assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+    }
+
+    #[test]
+    fn envelopes_round_trip(envelope in envelope()) {
+        let message: KickChatMessage = serde_json::from_str(&envelope.frame()).unwrap();
+        prop_assert_eq!(message.channel.as_deref(), envelope.channel.as_deref());
+        let frame = serde_json::to_string(&message).unwrap();
+        prop_assert_eq!(decode(&frame), decode(&envelope.frame()));
+    }
+}
+
+/// Parses a frame, decoding the JSON string of its data, so frames can be compared
+/// regardless of key order.
+fn decode(frame: &str) -> Value {
+    let mut frame: Value = serde_json::from_str(frame).unwrap();
+    let data = serde_json::from_str(frame["data"].as_str().unwrap()).unwrap();
+    frame["data"] = data;
+    frame
+}
@@ -0,0 +1,117 @@
+use kick_client::api::{HttpRequest, HttpResponse, HttpTransport, KickApi};
+use kick_client::metrics::Metrics;
+use kick_client::queue::{MessageQueue, OutgoingMessage, Priority};
+use kick_client::ratelimit::RateLimiter;
+use kick_client::KickError;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Accepts every request, keeping the chatroom and content of the messages sent.
+#[derive(Clone, Default)]
+struct Recording {
+    sent: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl HttpTransport for Recording {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, KickError> {
+        let chatroom_id = request.url.rsplit('/').next().unwrap().to_string();
+        let body: serde_json::Value = serde_json::from_slice(&request.body.unwrap()).unwrap();
+        let content = body["content"].as_str().unwrap().to_string();
+        self.sent.lock().unwrap().push((chatroom_id, content));
+        Ok(HttpResponse {
+            status: 200,
+            headers: Vec::new(),
+            body: b"{}".to_vec(),
+        })
+    }
+}
+
+fn drain(queue: &MessageQueue) -> Vec<String> {
+    std::iter::from_fn(|| queue.pop())
+        .map(|message| message.content)
+        .collect()
+}
+
+#[test]
+fn urgent_messages_are_sent_first() {
+    let queue = MessageQueue::new();
+    queue.enqueue(OutgoingMessage::new(1, "news", Priority::Announcement));
+    queue.enqueue(OutgoingMessage::new(1, "hi", Priority::Chatter));
+    queue.enqueue(OutgoingMessage::new(1, "banned", Priority::Moderation));
+    queue.enqueue(OutgoingMessage::new(1, "hello", Priority::Chatter));
+    queue.enqueue(OutgoingMessage::new(1, "timed out", Priority::Moderation));
+
+    assert_eq!(
+        drain(&queue),
+        ["banned", "timed out", "hi", "hello", "news"]
+    );
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn queued_messages_are_coalesced_per_chatroom() {
+    let queue = MessageQueue::new();
+    queue.enqueue(
+        OutgoingMessage::new(1, "Viewers: 10", Priority::Announcement).coalesce("viewers"),
+    );
+    queue
+        .enqueue(OutgoingMessage::new(2, "Viewers: 5", Priority::Announcement).coalesce("viewers"));
+    queue.enqueue(
+        OutgoingMessage::new(1, "Followers: 3", Priority::Announcement).coalesce("followers"),
+    );
+    queue.enqueue(
+        OutgoingMessage::new(1, "Viewers: 12", Priority::Announcement).coalesce("viewers"),
+    );
+    assert_eq!(queue.len(), 3);
+    assert_eq!(drain(&queue), ["Viewers: 12", "Viewers: 5", "Followers: 3"]);
+
+    // A replacement with another priority moves to its queue.
+    queue.enqueue(OutgoingMessage::new(1, "news", Priority::Announcement));
+    queue.enqueue(OutgoingMessage::new(1, "Poll: 1", Priority::Announcement).coalesce("poll"));
+    queue.enqueue(OutgoingMessage::new(1, "Poll: 2", Priority::Moderation).coalesce("poll"));
+    assert_eq!(drain(&queue), ["Poll: 2", "news"]);
+    assert_eq!(queue.dropped(), 0);
+}
+
+#[tokio::test]
+async fn chatrooms_in_slow_mode_do_not_hold_up_others() {
+    let limiter = RateLimiter::new(10, Duration::from_secs(1));
+    limiter.set_slow_mode(1, Some(Duration::from_millis(100)));
+    let transport = Recording::default();
+    let api = KickApi::new("token")
+        .with_transport(transport.clone())
+        .with_rate_limiter(limiter);
+    let queue = MessageQueue::new();
+    queue.enqueue(OutgoingMessage::new(1, "first", Priority::Moderation));
+    queue.enqueue(OutgoingMessage::new(1, "second", Priority::Moderation));
+    queue.enqueue(OutgoingMessage::new(2, "other", Priority::Announcement));
+    queue.close();
+
+    tokio::time::timeout(Duration::from_secs(5), queue.run(api))
+        .await
+        .unwrap();
+    let sent = transport.sent.lock().unwrap();
+    let sent: Vec<_> = sent
+        .iter()
+        .map(|(chatroom_id, content)| (chatroom_id.as_str(), content.as_str()))
+        .collect();
+    assert_eq!(sent, [("1", "first"), ("2", "other"), ("1", "second")]);
+}
+
+#[test]
+fn full_queues_drop_the_least_urgent_messages_first() {
+    let metrics = Metrics::new();
+    let queue = MessageQueue::new()
+        .with_limit(3)
+        .with_metrics(metrics.clone());
+    queue.enqueue(OutgoingMessage::new(1, "old news", Priority::Announcement));
+    queue.enqueue(OutgoingMessage::new(1, "news", Priority::Announcement));
+    queue.enqueue(OutgoingMessage::new(1, "hi", Priority::Chatter));
+    queue.enqueue(OutgoingMessage::new(1, "banned", Priority::Moderation));
+    queue.enqueue(OutgoingMessage::new(1, "timed out", Priority::Moderation));
+
+    assert_eq!(queue.len(), 3);
+    assert_eq!(queue.dropped(), 2);
+    assert_eq!(metrics.snapshot().dropped, 2);
+    assert_eq!(drain(&queue), ["banned", "timed out", "hi"]);
+}
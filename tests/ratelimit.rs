@@ -0,0 +1,85 @@
+use kick_client::fake;
+use kick_client::ratelimit::RateLimiter;
+use kick_client::MessageData;
+use std::time::Duration;
+use tokio::time::{advance, Instant};
+
+const CHATROOM: u32 = 1234;
+
+#[tokio::test(start_paused = true)]
+async fn bursts_are_limited_and_refilled() {
+    let limiter = RateLimiter::new(3, Duration::from_secs(3));
+    for _ in 0..3 {
+        limiter.try_acquire(CHATROOM).unwrap();
+    }
+    // The bucket is shared by every chatroom.
+    assert_eq!(limiter.try_acquire(1), Err(Duration::from_secs(1)));
+
+    advance(Duration::from_millis(500)).await;
+    assert_eq!(
+        limiter.try_acquire(CHATROOM),
+        Err(Duration::from_millis(500))
+    );
+    advance(Duration::from_millis(500)).await;
+    limiter.try_acquire(CHATROOM).unwrap();
+    assert!(limiter.try_acquire(CHATROOM).is_err());
+
+    // Refilling stops at the capacity.
+    advance(Duration::from_secs(60)).await;
+    for _ in 0..3 {
+        limiter.try_acquire(CHATROOM).unwrap();
+    }
+    assert!(limiter.try_acquire(CHATROOM).is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn acquiring_waits_for_a_token() {
+    let limiter = RateLimiter::new(1, Duration::from_secs(2));
+    let start = Instant::now();
+    limiter.acquire(CHATROOM).await;
+    assert_eq!(start.elapsed(), Duration::ZERO);
+    limiter.acquire(CHATROOM).await;
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_mode_spaces_messages_per_chatroom() {
+    let limiter = RateLimiter::new(10, Duration::from_secs(1));
+    limiter.set_slow_mode(CHATROOM, Some(Duration::from_secs(5)));
+    limiter.try_acquire(CHATROOM).unwrap();
+    assert_eq!(limiter.try_acquire(CHATROOM), Err(Duration::from_secs(5)));
+    limiter.try_acquire(1).unwrap();
+
+    advance(Duration::from_secs(5)).await;
+    limiter.try_acquire(CHATROOM).unwrap();
+
+    limiter.set_slow_mode(CHATROOM, None);
+    limiter.try_acquire(CHATROOM).unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn slow_mode_follows_chatroom_updates() {
+    let limiter = RateLimiter::new(10, Duration::from_secs(1));
+    let mut update = fake::chatroom_updated(CHATROOM);
+    update.slow_mode.enabled = true;
+    update.slow_mode.message_interval = 30;
+    limiter.observe(&fake::message(
+        CHATROOM,
+        MessageData::ChatroomUpdated(update.clone()),
+    ));
+    limiter.try_acquire(CHATROOM).unwrap();
+    assert_eq!(limiter.try_acquire(CHATROOM), Err(Duration::from_secs(30)));
+
+    update.slow_mode.enabled = false;
+    limiter.observe(&fake::message(
+        CHATROOM,
+        MessageData::ChatroomUpdated(update),
+    ));
+    limiter.try_acquire(CHATROOM).unwrap();
+
+    // Moderators are exempt from slow mode.
+    let exempt = RateLimiter::new(10, Duration::from_secs(1)).with_slow_mode(false);
+    exempt.set_slow_mode(CHATROOM, Some(Duration::from_secs(30)));
+    exempt.try_acquire(CHATROOM).unwrap();
+    exempt.try_acquire(CHATROOM).unwrap();
+}
@@ -0,0 +1,102 @@
+use futures_util::StreamExt;
+use kick_client::rebroadcast::RebroadcastServer;
+use kick_client::{fake, KickClient, MessageData};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+const CHATROOM: u32 = 1234;
+
+/// Waits until `count` clients receive the chatroom.
+async fn wait_for_subscribers(server: &RebroadcastServer, count: usize) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.subscribers(CHATROOM) < count {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await
+    .expect("clients to subscribe within 5 seconds");
+}
+
+#[tokio::test]
+async fn clients_receive_what_their_filters_select() {
+    let server = RebroadcastServer::bind("127.0.0.1:0").await.unwrap();
+
+    // A `KickClient` subscribing as it would to Kick.
+    let mut client = KickClient::new(&server.url(), vec![CHATROOM.into()])
+        .await
+        .unwrap();
+    // A plain WebSocket client selecting bans in the URL.
+    let url = format!("{}&chatrooms={}&kinds=ban", server.url(), CHATROOM);
+    let (mut bans, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    wait_for_subscribers(&server, 2).await;
+    assert_eq!(server.connected(), 2);
+
+    assert_eq!(
+        server.send(&fake::chat_message(4321, "Eve", "elsewhere")),
+        0
+    );
+    assert_eq!(
+        server.send(&fake::chat_message(CHATROOM, "Alice", "hello")),
+        1
+    );
+    assert_eq!(
+        server.send(&fake::user_banned(CHATROOM, "Bob", "Moderator", None)),
+        2
+    );
+
+    let mut kinds = Vec::new();
+    while kinds.len() < 4 {
+        let message = tokio::time::timeout(Duration::from_secs(5), client.read_message())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let MessageData::ChatMessage(data) = &message.data {
+            assert_eq!(data.content.as_deref(), Some("hello"));
+        }
+        kinds.push(message.data.kind());
+    }
+    assert_eq!(
+        kinds,
+        [
+            "connection_established",
+            "subscription_succeeded",
+            "chat",
+            "ban"
+        ]
+    );
+
+    let mut frames = Vec::new();
+    while frames.len() < 2 {
+        let frame = tokio::time::timeout(Duration::from_secs(5), bans.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let Message::Text(text) = frame {
+            frames.push(text.to_string());
+        }
+    }
+    assert!(frames[0].contains("pusher:connection_established"));
+    assert!(frames[1].contains("UserBannedEvent"), "{}", frames[1]);
+}
+
+#[tokio::test]
+async fn messages_to_slow_clients_are_dropped() {
+    let server = RebroadcastServer::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("{}&chatrooms={}", server.url(), CHATROOM);
+    let (mut client, _) = tokio_tungstenite::connect_async(url).await.unwrap();
+    wait_for_subscribers(&server, 1).await;
+    let greeting = client.next().await.unwrap().unwrap();
+    assert!(greeting
+        .to_text()
+        .unwrap()
+        .contains("connection_established"));
+
+    // Nothing is written while the test runs, so the queue fills up.
+    let sent = (0..1030)
+        .map(|_| server.send(&fake::chat_message(CHATROOM, "Alice", "spam")))
+        .sum::<usize>();
+    assert_eq!(sent, 1024);
+    assert_eq!(server.dropped(), 6);
+}
@@ -0,0 +1,71 @@
+use kick_client::mock_server::MockPusherServer;
+use kick_client::reconnect::{Deduplicator, ReconnectingClient};
+use kick_client::{fake, MessageData, PollDeleteEventData};
+use std::time::Duration;
+
+const CHATROOM: u32 = 1234;
+const CHANNEL: &str = "chatrooms.1234.v2";
+
+#[test]
+fn duplicates_are_recognized_within_the_window() {
+    let mut deduplicator = Deduplicator::new(2);
+    let first = fake::chat_message(CHATROOM, "alice", "first");
+    let second = fake::user_banned(CHATROOM, "spammer", "mod", None);
+    let third = fake::chat_message(CHATROOM, "alice", "third");
+
+    assert!(!deduplicator.is_duplicate(&first));
+    assert!(!deduplicator.is_duplicate(&second));
+    assert!(deduplicator.is_duplicate(&first));
+    assert!(deduplicator.is_duplicate(&second));
+
+    // The oldest ID is forgotten once the window is full.
+    assert!(!deduplicator.is_duplicate(&third));
+    assert!(!deduplicator.is_duplicate(&first));
+    assert!(deduplicator.is_duplicate(&third));
+}
+
+#[test]
+fn messages_without_ids_are_never_duplicates() {
+    let mut deduplicator = Deduplicator::new(10);
+    let delete = fake::message(CHATROOM, MessageData::PollDelete(PollDeleteEventData {}));
+    assert!(!deduplicator.is_duplicate(&delete));
+    assert!(!deduplicator.is_duplicate(&delete));
+
+    let mut disabled = Deduplicator::new(0);
+    let message = fake::chat_message(CHATROOM, "alice", "hi");
+    assert!(!disabled.is_duplicate(&message));
+    assert!(!disabled.is_duplicate(&message));
+}
+
+#[tokio::test]
+async fn short_disconnections_are_not_gaps() {
+    let server = MockPusherServer::start().await.unwrap();
+    let mut client = ReconnectingClient::new(server.url(), vec![CHATROOM.into()])
+        .with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+        .with_gap_threshold(Duration::from_secs(60));
+    client.read_message().await.unwrap().unwrap();
+    server.wait_for_subscription(CHANNEL).await;
+    server.disconnect_all();
+
+    let reader = tokio::spawn(async move {
+        let mut events = Vec::new();
+        loop {
+            let message = client.read_message().await.unwrap().unwrap();
+            let done = matches!(message.data, MessageData::ChatMessage(_));
+            events.push(message.data.kind());
+            if done {
+                return events;
+            }
+        }
+    });
+    server.wait_for_accepted(2).await;
+    server.wait_for_subscription(CHANNEL).await;
+    server.send(&fake::chat_message(CHATROOM, "alice", "after the drop"));
+
+    let events = tokio::time::timeout(Duration::from_secs(5), reader)
+        .await
+        .expect("timed out waiting for the reconnection")
+        .unwrap();
+    assert!(!events.contains(&"gap"), "{events:?}");
+    assert_eq!(events.last(), Some(&"chat"));
+}
@@ -0,0 +1,111 @@
+use kick_client::commands::{Commands, DispatchOutcome};
+use kick_client::fake;
+use kick_client::mock::MockKickClient;
+use kick_client::recording::{Pacing, RecordedFrame, Recorder, ReplayClient};
+use kick_client::{KickError, MessageData, MessageSource};
+use std::io::Cursor;
+use std::time::{Duration, Instant};
+
+/// Returns a recording of the frames, received the given milliseconds apart.
+fn recording(frames: &[(u64, String)]) -> Cursor<Vec<u8>> {
+    let mut lines = Vec::new();
+    for (received_at, frame) in frames {
+        let frame = RecordedFrame {
+            received_at: *received_at,
+            channel: None,
+            frame: frame.clone(),
+        };
+        serde_json::to_writer(&mut lines, &frame).unwrap();
+        lines.push(b'\n');
+    }
+    Cursor::new(lines)
+}
+
+fn frame(content: &str) -> String {
+    serde_json::to_string(&fake::chat_message(668, "viewer", content)).unwrap()
+}
+
+#[tokio::test]
+async fn recordings_replay_the_frames_received() {
+    let path = std::env::temp_dir().join(format!("kick_client_{}.jsonl", fake::id()));
+    let hello = frame("hello");
+    let mut recorder = Recorder::create(&path).unwrap();
+    recorder.record(&hello).unwrap();
+    recorder
+        .record(r#"{"event":"pusher:pong","data":"{}"}"#)
+        .unwrap();
+    recorder.record(&frame("bye")).unwrap();
+    drop(recorder);
+
+    let mut replay = ReplayClient::open(&path).unwrap();
+    let first = replay.read_frame().await.unwrap();
+    assert_eq!(first.channel.as_deref(), Some("chatrooms.668.v2"));
+    assert_eq!(first.frame, hello);
+    let mut kinds = Vec::new();
+    loop {
+        match replay.read_message().await {
+            Ok(Some(message)) => kinds.push(message.data.kind()),
+            Err(KickError::StreamEnded) => break,
+            other => panic!("unexpected read: {other:?}"),
+        }
+    }
+    assert_eq!(kinds, ["pong", "chat"]);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn replays_drive_message_handlers() {
+    let mock = MockKickClient::new();
+    let mut commands = Commands::new("!").with_sender(mock.clone());
+    commands.command("ping", |ctx| async move { ctx.reply("pong").await });
+    // Blank lines, as left by editing a recording by hand, are skipped.
+    let mut lines = recording(&[(0, frame("!ping"))]).into_inner();
+    lines.extend_from_slice(b"\n  \n");
+    lines.extend(recording(&[(20, frame("not a command"))]).into_inner());
+    let mut replay = ReplayClient::new(Cursor::new(lines));
+
+    let Some(message) = replay.read_message().await.unwrap() else {
+        panic!("expected a message");
+    };
+    assert!(
+        matches!(&message.data, MessageData::ChatMessage(data) if data.content.as_deref() == Some("!ping"))
+    );
+    assert!(matches!(
+        commands.dispatch(&message).await,
+        DispatchOutcome::Handled(Ok(()))
+    ));
+    assert!(matches!(
+        commands.run(&mut replay).await,
+        Err(KickError::StreamEnded)
+    ));
+    let sent = mock.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(
+        (sent[0].chatroom_id, sent[0].content.as_str()),
+        (668, "pong")
+    );
+}
+
+#[tokio::test]
+async fn accelerated_replays_shorten_the_gaps() {
+    async fn replay_time(pacing: Pacing) -> Duration {
+        let mut replay = ReplayClient::new(recording(&[
+            (1_000, frame("first")),
+            (1_400, frame("second")),
+            (2_000, frame("third")),
+        ]))
+        .with_pacing(pacing);
+        let started = Instant::now();
+        for _ in 0..3 {
+            MessageSource::read_message(&mut replay).await.unwrap();
+        }
+        started.elapsed()
+    }
+
+    assert!(replay_time(Pacing::Instant).await < Duration::from_millis(50));
+    let accelerated = replay_time(Pacing::Accelerated(10.0)).await;
+    assert!(
+        accelerated >= Duration::from_millis(100) && accelerated < Duration::from_millis(900),
+        "{accelerated:?}"
+    );
+}
@@ -0,0 +1,90 @@
+use futures_util::future::BoxFuture;
+use kick_client::mock_server::MockPusherServer;
+use kick_client::shutdown::Shutdown;
+use kick_client::sinks::{pipe, Sink};
+use kick_client::{fake, KickChatMessage, KickClient, KickError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const CHATROOM: u32 = 1234;
+
+/// A sink sharing what it was given and how many times it was flushed.
+#[derive(Clone, Default)]
+struct SharedSink {
+    written: Arc<Mutex<Vec<&'static str>>>,
+    flushes: Arc<AtomicUsize>,
+}
+
+impl Sink for SharedSink {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> BoxFuture<'a, Result<(), KickError>> {
+        self.written.lock().unwrap().push(message.data.kind());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<(), KickError>> {
+        self.flushes.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[tokio::test]
+async fn shutting_down_flushes_sinks_and_closes_connections() {
+    let server = MockPusherServer::start().await.unwrap();
+    let client: KickClient = KickClient::connect(&server.url(), vec![CHATROOM.into()])
+        .await
+        .unwrap();
+    server
+        .wait_for_subscription(&format!("chatrooms.{}.v2", CHATROOM))
+        .await;
+
+    let shutdown = Shutdown::new();
+    let sink = SharedSink::default();
+    let mut source = shutdown.token().until(client);
+    let task_sink = sink.clone();
+    assert!(shutdown.spawn("archive", async move {
+        pipe(&mut source, task_sink).await.unwrap();
+        source.into_inner().close().await.unwrap();
+    }));
+    let hooks = Arc::new(AtomicUsize::new(0));
+    let hook_count = hooks.clone();
+    shutdown.on_shutdown(move || async move {
+        hook_count.fetch_add(1, Ordering::SeqCst);
+    });
+
+    server.send(&fake::chat_message(CHATROOM, "Alice", "hello"));
+    while !sink.written.lock().unwrap().contains(&"chat") {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    let report = shutdown.shutdown().await;
+    assert_eq!(report.finished, 1);
+    assert!(report.aborted.is_empty());
+    assert!(!report.hooks_timed_out);
+    assert_eq!(sink.flushes.load(Ordering::SeqCst), 1);
+    assert_eq!(hooks.load(Ordering::SeqCst), 1);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while server.connected() > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("connection closed within 5 seconds");
+
+    assert!(!shutdown.spawn("late", async {}));
+}
+
+#[tokio::test]
+async fn tasks_still_running_at_the_deadline_are_aborted() {
+    let shutdown = Shutdown::new().with_deadline(Duration::from_millis(50));
+    let mut token = shutdown.token();
+    shutdown.spawn("stops", async move { token.triggered().await });
+    shutdown.spawn("stuck", futures_util::future::pending());
+
+    let report = shutdown.shutdown().await;
+    assert_eq!(report.finished, 1);
+    assert_eq!(report.aborted, ["stuck"]);
+}
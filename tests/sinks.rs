@@ -0,0 +1,497 @@
+use kick_client::fake;
+use kick_client::mock::MockKickClient;
+use kick_client::sinks::{pipe, Sink};
+use kick_client::{KickChatMessage, KickError};
+
+const CHATROOM: u32 = 1234;
+
+/// Returns a mock client replaying a short session: chat, a deletion, a ban and unban and
+/// a poll that gets votes.
+fn session() -> MockKickClient {
+    let mock = MockKickClient::new();
+    let hello = fake::chat_message(CHATROOM, "Alice", "hello");
+    let id = match &hello.data {
+        kick_client::MessageData::ChatMessage(data) => data.id.clone(),
+        _ => unreachable!(),
+    };
+    mock.push(hello)
+        .push(fake::chat_message(CHATROOM, "Bob", "spam"))
+        .push(fake::message_deleted(CHATROOM, id))
+        .push(fake::user_banned(CHATROOM, "Bob", "Moderator", Some(10)))
+        .push(fake::user_unbanned(CHATROOM, "Bob", "Moderator"))
+        .push(fake::poll_update(
+            CHATROOM,
+            "Best map?",
+            &[("Dust 2", 1), ("Mirage", 0)],
+            60,
+            50,
+        ))
+        .push(fake::poll_update(
+            CHATROOM,
+            "Best map?",
+            &[("Dust 2", 4), ("Mirage", 2)],
+            60,
+            40,
+        ));
+    mock
+}
+
+/// A sink keeping what it was given, counting flushes.
+#[derive(Default)]
+struct MemorySink {
+    kinds: Vec<&'static str>,
+    flushes: usize,
+}
+
+impl Sink for MemorySink {
+    fn write<'a>(
+        &'a mut self,
+        message: &'a KickChatMessage,
+    ) -> futures_util::future::BoxFuture<'a, Result<(), KickError>> {
+        self.kinds.push(message.data.kind());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn flush(&mut self) -> futures_util::future::BoxFuture<'_, Result<(), KickError>> {
+        self.flushes += 1;
+        Box::pin(async { Ok(()) })
+    }
+}
+
+#[tokio::test]
+async fn pipe_writes_every_message_then_flushes() {
+    let mut sink = MemorySink::default();
+    pipe(session(), &mut sink).await.unwrap();
+    assert_eq!(
+        sink.kinds,
+        [
+            "chat",
+            "chat",
+            "delete",
+            "ban",
+            "unban",
+            "poll_update",
+            "poll_update"
+        ]
+    );
+    assert_eq!(sink.flushes, 1);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn sqlite_sink_archives_a_session() {
+    use kick_client::sinks::sqlite::SqliteSink;
+
+    let path = std::env::temp_dir().join(format!("kick_client_{}.db", fake::id()));
+    pipe(
+        session(),
+        SqliteSink::open(&path).unwrap().with_batch_size(3),
+    )
+    .await
+    .unwrap();
+
+    let connection = rusqlite::Connection::open(&path).unwrap();
+    let count = |sql: &str| -> i64 { connection.query_row(sql, [], |row| row.get(0)).unwrap() };
+    assert_eq!(count("SELECT COUNT(*) FROM events"), 7);
+    assert_eq!(count("SELECT COUNT(*) FROM users"), 3);
+    assert_eq!(count("SELECT COUNT(*) FROM messages"), 2);
+    assert_eq!(count("SELECT COUNT(*) FROM messages WHERE deleted = 1"), 1);
+    assert_eq!(
+        count("SELECT COUNT(*) FROM bans WHERE duration = 10 AND unbanned_by IS NOT NULL"),
+        1
+    );
+    assert_eq!(count("SELECT COUNT(*) FROM polls"), 1);
+    assert_eq!(count("SELECT SUM(votes) FROM poll_options"), 6);
+    drop(connection);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn sqlite_sink_keeps_reruns_of_a_poll_apart() {
+    use kick_client::sinks::sqlite::SqliteSink;
+
+    let mock = MockKickClient::new();
+    let options = [("Red", 3), ("Blue", 1), ("Green", 0)];
+    mock.push(fake::poll_update(CHATROOM, "Who wins?", &options, 60, 30))
+        .push(fake::poll_update(CHATROOM, "Who wins?", &options, 60, 0))
+        // Updates sent while the results are displayed lack the duration.
+        .push(fake::poll_update(CHATROOM, "Who wins?", &options, 0, 0))
+        // The next stream asks again, with fewer options.
+        .push(fake::poll_update(
+            CHATROOM,
+            "Who wins?",
+            &[("Red", 0), ("Blue", 0)],
+            60,
+            60,
+        ))
+        .push(fake::poll_update(
+            CHATROOM,
+            "Who wins?",
+            &[("Red", 1), ("Blue", 5)],
+            60,
+            45,
+        ));
+    let connection = rusqlite::Connection::open_in_memory().unwrap();
+    let mut sink = SqliteSink::from_connection(connection).unwrap();
+    pipe(mock, &mut sink).await.unwrap();
+
+    let connection = sink.connection();
+    let polls: Vec<(i64, u32)> = connection
+        .prepare("SELECT id, remaining FROM polls ORDER BY id")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(polls.len(), 2);
+    assert_eq!((polls[0].1, polls[1].1), (0, 45));
+    let votes = |poll_id: i64| -> Vec<(String, u32)> {
+        connection
+            .prepare("SELECT label, votes FROM poll_options WHERE poll_id = ?1 ORDER BY option_id")
+            .unwrap()
+            .query_map([poll_id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    };
+    let votes_of = |options: &[(&str, u32)]| -> Vec<(String, u32)> {
+        options
+            .iter()
+            .map(|(label, votes)| (label.to_string(), *votes))
+            .collect()
+    };
+    assert_eq!(votes(polls[0].0), votes_of(&options));
+    assert_eq!(votes(polls[1].0), votes_of(&[("Red", 1), ("Blue", 5)]));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn sqlite_sink_caps_the_buffer_while_the_database_is_locked() {
+    use kick_client::metrics::Metrics;
+    use kick_client::sinks::sqlite::SqliteSink;
+
+    let path = std::env::temp_dir().join(format!("kick_client_{}.db", fake::id()));
+    let connection = rusqlite::Connection::open(&path).unwrap();
+    connection.busy_timeout(std::time::Duration::ZERO).unwrap();
+    let metrics = Metrics::new();
+    let mut sink = SqliteSink::from_connection(connection)
+        .unwrap()
+        .with_batch_size(1)
+        .with_buffer_limit(2)
+        .with_metrics(metrics.clone());
+
+    let lock = rusqlite::Connection::open(&path).unwrap();
+    lock.execute_batch("BEGIN EXCLUSIVE").unwrap();
+    for text in ["a", "b", "c", "d", "e"] {
+        let message = fake::chat_message(CHATROOM, "Alice", text);
+        assert!(sink.write(&message).await.is_err());
+    }
+    assert_eq!(sink.dropped(), 3);
+    assert_eq!(metrics.snapshot().dropped, 3);
+
+    lock.execute_batch("COMMIT").unwrap();
+    sink.flush().await.unwrap();
+    let content: Vec<String> = sink
+        .connection()
+        .prepare("SELECT content FROM messages ORDER BY content")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(content, ["d", "e"]);
+    drop(sink);
+    std::fs::remove_file(path).unwrap();
+}
+
+/// Returns a new empty directory for a test to write files to.
+fn temp_dir() -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("kick_client_{}", fake::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[tokio::test]
+async fn file_sink_rotates_by_size() {
+    use kick_client::sinks::file::{FileFormat, FileSink};
+
+    let dir = temp_dir();
+    let path = dir.join("chat.jsonl");
+    pipe(
+        session(),
+        FileSink::open(&path, FileFormat::Jsonl)
+            .unwrap()
+            .with_max_size(1),
+    )
+    .await
+    .unwrap();
+
+    let mut lines = Vec::new();
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let contents = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        lines.extend(contents.lines().map(str::to_string));
+    }
+    assert_eq!(lines.len(), 7);
+    for line in lines {
+        serde_json::from_str::<KickChatMessage>(&line).unwrap();
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn file_sink_writes_csv() {
+    use kick_client::sinks::file::{FileFormat, FileSink};
+
+    let dir = temp_dir();
+    let path = dir.join("chat.csv");
+    let mut sink = FileSink::open(&path, FileFormat::Csv).unwrap();
+    sink.write(&fake::chat_message(CHATROOM, "Alice", "hi, \"all\""))
+        .await
+        .unwrap();
+    sink.flush().await.unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let lines = contents.lines().collect::<Vec<_>>();
+    assert_eq!(
+        lines[0],
+        "received_at,channel,chatroom_id,kind,username,content,text"
+    );
+    let row = lines[1].split_once(',').unwrap().1;
+    assert!(
+        row.starts_with("chatrooms.1234.v2,1234,chat,Alice,\"hi, \"\"all\"\"\","),
+        "unexpected row {row}"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn file_sink_compresses_rotated_files() {
+    use kick_client::sinks::file::{FileFormat, FileSink};
+    use std::io::Read;
+
+    let dir = temp_dir();
+    let path = dir.join("chat.jsonl");
+    let mut sink = FileSink::open(&path, FileFormat::Jsonl)
+        .unwrap()
+        .with_compression(true);
+    sink.write(&fake::chat_message(CHATROOM, "Alice", "hello"))
+        .await
+        .unwrap();
+    let rotated = sink.rotate().unwrap();
+    assert_eq!(rotated.extension().unwrap(), "gz");
+
+    let mut contents = String::new();
+    flate2::read::GzDecoder::new(std::fs::File::open(&rotated).unwrap())
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert!(contents.contains("hello"));
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(feature = "http-sink")]
+#[tokio::test]
+async fn http_sink_retries_and_signs_batches() {
+    use kick_client::api::{HttpRequest, HttpResponse, HttpTransport};
+    use kick_client::sinks::http::{sign, HttpSink, SIGNATURE_HEADER};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Answers with the scripted statuses in order, then 200, keeping every request.
+    #[derive(Clone, Default)]
+    struct Scripted {
+        statuses: Arc<Mutex<Vec<u16>>>,
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+    }
+
+    impl HttpTransport for Scripted {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, KickError> {
+            self.requests.lock().unwrap().push(request);
+            let mut statuses = self.statuses.lock().unwrap();
+            let status = if statuses.is_empty() {
+                200
+            } else {
+                statuses.remove(0)
+            };
+            Ok(HttpResponse {
+                status,
+                headers: Vec::new(),
+                body: Vec::new(),
+            })
+        }
+    }
+
+    let transport = Scripted::default();
+    transport.statuses.lock().unwrap().extend([503, 429]);
+    let sink = HttpSink::new("https://hooks.example.com/kick")
+        .with_transport(transport.clone())
+        .with_secret("secret")
+        .with_batch_size(4)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(1));
+    pipe(session(), sink).await.unwrap();
+
+    let requests = transport.requests.lock().unwrap();
+    // The first batch of 4 is tried 3 times, then the remaining 3 messages are flushed.
+    assert_eq!(requests.len(), 4);
+    let mut posted = Vec::new();
+    for request in &requests[2..] {
+        let body = request.body.as_deref().unwrap();
+        let signature = request
+            .headers
+            .iter()
+            .find(|(name, _)| name == SIGNATURE_HEADER)
+            .map(|(_, value)| value.as_str());
+        assert_eq!(signature, Some(sign(b"secret", body).as_str()));
+        posted.extend(serde_json::from_slice::<Vec<KickChatMessage>>(body).unwrap());
+    }
+    assert_eq!(posted.len(), 7);
+}
+
+#[cfg(feature = "http-sink")]
+#[tokio::test]
+async fn http_sink_gives_up_on_client_errors() {
+    use kick_client::api::{HttpRequest, HttpResponse, HttpTransport};
+    use kick_client::sinks::http::HttpSink;
+
+    struct Rejecting;
+
+    impl HttpTransport for Rejecting {
+        async fn send(&self, _: HttpRequest) -> Result<HttpResponse, KickError> {
+            Ok(HttpResponse {
+                status: 400,
+                headers: Vec::new(),
+                body: b"bad request".to_vec(),
+            })
+        }
+    }
+
+    let mut sink = HttpSink::new("https://hooks.example.com/kick").with_transport(Rejecting);
+    let error = sink
+        .write(&fake::chat_message(CHATROOM, "Alice", "hello"))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(error, KickError::ApiError { status: 400, .. }),
+        "unexpected {error:?}"
+    );
+}
+
+#[cfg(feature = "http-sink")]
+#[tokio::test]
+async fn http_sink_caps_failed_messages_and_keeps_single_bodies() {
+    use kick_client::api::{HttpRequest, HttpResponse, HttpTransport};
+    use kick_client::metrics::Metrics;
+    use kick_client::sinks::http::HttpSink;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Rejects requests while `down`, keeping the bodies of the accepted ones.
+    #[derive(Clone, Default)]
+    struct Flaky {
+        down: Arc<AtomicBool>,
+        bodies: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl HttpTransport for Flaky {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, KickError> {
+            let status = if self.down.load(Ordering::SeqCst) {
+                400
+            } else {
+                self.bodies.lock().unwrap().push(request.body.unwrap());
+                200
+            };
+            Ok(HttpResponse {
+                status,
+                headers: Vec::new(),
+                body: Vec::new(),
+            })
+        }
+    }
+
+    let transport = Flaky::default();
+    transport.down.store(true, Ordering::SeqCst);
+    let metrics = Metrics::new();
+    let mut sink = HttpSink::new("https://hooks.example.com/kick")
+        .with_transport(transport.clone())
+        .with_buffer_limit(2)
+        .with_metrics(metrics.clone());
+    for text in ["a", "b", "c"] {
+        let message = fake::chat_message(CHATROOM, "Alice", text);
+        assert!(sink.write(&message).await.is_err());
+    }
+    assert_eq!(sink.dropped(), 1);
+    assert_eq!(metrics.snapshot().dropped, 1);
+
+    transport.down.store(false, Ordering::SeqCst);
+    sink.flush().await.unwrap();
+    let bodies = transport.bodies.lock().unwrap();
+    // Each message is still posted on its own, as a JSON object.
+    let contents: Vec<Option<String>> = bodies
+        .iter()
+        .map(|body| {
+            match serde_json::from_slice::<KickChatMessage>(body)
+                .unwrap()
+                .data
+            {
+                kick_client::MessageData::ChatMessage(data) => data.content,
+                other => panic!("unexpected {other:?}"),
+            }
+        })
+        .collect();
+    assert_eq!(contents, [Some("b".to_string()), Some("c".to_string())]);
+}
+
+#[cfg(feature = "discord")]
+#[tokio::test]
+async fn discord_sink_posts_selected_events_as_embeds() {
+    use kick_client::api::{HttpRequest, HttpResponse, HttpTransport};
+    use kick_client::sinks::discord::{embed, DiscordSink};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Recording {
+        requests: Arc<Mutex<Vec<HttpRequest>>>,
+    }
+
+    impl HttpTransport for Recording {
+        async fn send(&self, request: HttpRequest) -> Result<HttpResponse, KickError> {
+            self.requests.lock().unwrap().push(request);
+            Ok(HttpResponse {
+                status: 204,
+                headers: Vec::new(),
+                body: Vec::new(),
+            })
+        }
+    }
+
+    let transport = Recording::default();
+    let sink = DiscordSink::new("https://discord.com/api/webhooks/1/token")
+        .with_transport(transport.clone())
+        .with_username("Kick");
+    pipe(session(), sink).await.unwrap();
+
+    // Only the ban is selected by default.
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    let body: serde_json::Value =
+        serde_json::from_slice(requests[0].body.as_deref().unwrap()).unwrap();
+    assert_eq!(body["username"], "Kick");
+    assert_eq!(body["embeds"][0]["title"], "User timed out");
+    assert_eq!(
+        body["embeds"][0]["description"],
+        "**Bob** was banned by **Moderator** for 10 minutes"
+    );
+
+    let live: KickChatMessage = serde_json::from_str(
+        r#"{"event":"livestream.status.updated","channel":"the_streamer","data":{"broadcaster_user_id":668,"broadcaster_username":"the_streamer","is_live":true,"title":"Ranked grind","started_at":"2024-05-01T12:00:00Z","ended_at":null}}"#,
+    )
+    .unwrap();
+    let live = embed(&live);
+    assert_eq!(live["title"], "Ranked grind");
+    assert_eq!(live["description"], "**the\\_streamer** is live");
+    assert_eq!(live["url"], "https://kick.com/the_streamer");
+    assert_eq!(live["timestamp"], "2024-05-01T12:00:00Z");
+}
@@ -0,0 +1,83 @@
+use kick_client::fake;
+use kick_client::sse::SseServer;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const CHATROOM: u32 = 1234;
+
+/// Opens an SSE stream, returning the connection once the response headers were read.
+async fn subscribe(server: &SseServer, path: &str) -> (TcpStream, String) {
+    let mut stream = TcpStream::connect(server.local_addr()).await.unwrap();
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: localhost\r\nAccept: text/event-stream\r\n\r\n",
+        path
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let received = read_until(&mut stream, "\r\n\r\n").await;
+    (stream, received)
+}
+
+/// Reads from the stream until what was received contains `needle`.
+async fn read_until(stream: &mut TcpStream, needle: &str) -> String {
+    let mut received = String::new();
+    let mut buffer = [0; 4096];
+    while !received.contains(needle) {
+        let read = tokio::time::timeout(Duration::from_secs(5), stream.read(&mut buffer))
+            .await
+            .expect("data within 5 seconds")
+            .unwrap();
+        assert_ne!(read, 0, "connection closed, received {received:?}");
+        received.push_str(&String::from_utf8_lossy(&buffer[..read]));
+    }
+    received
+}
+
+#[tokio::test]
+async fn chatroom_streams_only_receive_their_chatroom() {
+    let server = SseServer::bind("127.0.0.1:0").await.unwrap();
+    let (mut chatroom, headers) = subscribe(&server, &format!("/events/{}", CHATROOM)).await;
+    assert!(headers.starts_with("HTTP/1.1 200"), "{headers}");
+    assert!(
+        headers.contains("content-type: text/event-stream"),
+        "{headers}"
+    );
+    assert!(
+        headers.contains("access-control-allow-origin: *"),
+        "{headers}"
+    );
+    let (mut all, _) = subscribe(&server, "/events").await;
+    assert_eq!(server.broadcaster().subscribers(), 2);
+
+    server
+        .send(&fake::chat_message(4321, "Eve", "elsewhere"))
+        .unwrap();
+    server
+        .send(&fake::chat_message(CHATROOM, "Alice", "hello"))
+        .unwrap();
+    server
+        .send(&fake::user_banned(CHATROOM, "Bob", "Moderator", None))
+        .unwrap();
+
+    let received = read_until(&mut chatroom, "event: ban").await;
+    assert!(!received.contains("elsewhere"), "{received}");
+    let chat = received
+        .lines()
+        .skip_while(|line| *line != "event: chat")
+        .nth(1)
+        .and_then(|line| line.strip_prefix("data: "))
+        .expect("a chat event");
+    let chat: serde_json::Value = serde_json::from_str(chat).unwrap();
+    assert_eq!(chat["data"]["content"], "hello");
+    assert_eq!(chat["data"]["sender"]["username"], "Alice");
+
+    let received = read_until(&mut all, "event: ban").await;
+    assert!(received.contains("elsewhere"), "{received}");
+}
+
+#[tokio::test]
+async fn invalid_chatrooms_are_rejected() {
+    let server = SseServer::bind("127.0.0.1:0").await.unwrap();
+    let (_, headers) = subscribe(&server, "/events/not-a-number").await;
+    assert!(headers.starts_with("HTTP/1.1 400"), "{headers}");
+}
@@ -0,0 +1,62 @@
+use kick_client::state::{ChatroomState, ChatroomStateTracker};
+use kick_client::{fake, MessageData};
+
+#[test]
+fn updates_are_converted() {
+    let mut update = fake::chatroom_updated(1);
+    assert_eq!(ChatroomState::from(&update), ChatroomState::default());
+
+    update.slow_mode.enabled = true;
+    update.slow_mode.message_interval = 5;
+    update.followers_mode.min_duration = 10;
+    update.subscribers_mode.enabled = true;
+    update.advanced_bot_protection.enabled = true;
+    update.advanced_bot_protection.remaining_time = 300;
+    assert_eq!(
+        ChatroomState::from(&update),
+        ChatroomState {
+            slow_mode: Some(5),
+            // A duration is ignored while the mode is disabled.
+            followers_mode: None,
+            subscribers_mode: true,
+            emotes_mode: false,
+            advanced_bot_protection: Some(300),
+        }
+    );
+}
+
+#[test]
+fn states_are_tracked_per_chatroom() {
+    let tracker = ChatroomStateTracker::new();
+    assert_eq!(tracker.get(1), None);
+
+    let mut update = fake::chatroom_updated(1);
+    update.emotes_mode.enabled = true;
+    tracker.observe(&fake::message(1, MessageData::ChatroomUpdated(update)));
+    tracker.observe(&fake::chat_message(2, "viewer", "hi"));
+
+    assert!(tracker.get(1).unwrap().emotes_mode);
+    assert_eq!(tracker.get(2), None);
+}
+
+#[tokio::test]
+async fn watchers_are_notified_of_changes_only() {
+    let tracker = ChatroomStateTracker::new();
+    let mut watcher = tracker.watch(1);
+    assert_eq!(*watcher.borrow_and_update(), None);
+
+    let mut update = fake::chatroom_updated(1);
+    tracker.apply(&update);
+    assert!(watcher.has_changed().unwrap());
+    assert_eq!(*watcher.borrow_and_update(), Some(ChatroomState::default()));
+
+    // The same settings again don't notify.
+    tracker.apply(&update);
+    assert!(!watcher.has_changed().unwrap());
+
+    update.followers_mode.enabled = true;
+    update.followers_mode.min_duration = 30;
+    tracker.apply(&update);
+    watcher.changed().await.unwrap();
+    assert_eq!(watcher.borrow().unwrap().followers_mode, Some(30));
+}
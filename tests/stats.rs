@@ -0,0 +1,74 @@
+use kick_client::fake;
+use kick_client::stats::ChatStats;
+use std::time::Duration;
+
+#[test]
+fn chatters_and_emotes_are_ranked() {
+    let mut stats = ChatStats::new().with_top(2);
+    for (username, content) in [
+        ("alice", "hi [emote:1:KEKW]"),
+        ("bob", "[emote:2:LULW] [emote:1:KEKW]"),
+        ("alice", "[emote:1:KEKW] again"),
+        ("carol", "[emote:2:LULW]"),
+        ("bob", "hello"),
+        ("alice", "[emote:3:Pog]"),
+    ] {
+        stats.observe(&fake::chat_message(1, username, content));
+    }
+
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.total_messages, 6);
+    assert_eq!(snapshot.unique_chatters, 3);
+    assert_eq!(
+        snapshot.top_chatters,
+        [("alice".to_string(), 3), ("bob".to_string(), 2)]
+    );
+    assert_eq!(
+        snapshot.top_emotes,
+        [("KEKW".to_string(), 3), ("LULW".to_string(), 2)]
+    );
+    // Less than a second has passed, so the rate is averaged over one second.
+    assert_eq!(snapshot.messages_per_second, 6.0);
+}
+
+#[test]
+fn moderation_is_counted() {
+    let mut stats = ChatStats::new();
+    stats.observe(&fake::user_banned(1, "spammer", "mod", None));
+    stats.observe(&fake::user_banned(1, "rude", "mod", Some(10)));
+    stats.observe(&fake::user_banned(1, "rude", "mod", Some(60)));
+    stats.observe(&fake::message_deleted(1, fake::id()));
+    stats.observe(&fake::chatroom_clear(1));
+
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.bans, 1);
+    assert_eq!(snapshot.timeouts, 2);
+    assert_eq!(snapshot.deleted_messages, 1);
+    assert_eq!(snapshot.total_messages, 0);
+}
+
+#[test]
+fn the_rate_only_counts_the_window() {
+    let mut stats = ChatStats::new().with_rate_window(Duration::from_millis(50));
+    stats.observe(&fake::chat_message(1, "alice", "first"));
+    std::thread::sleep(Duration::from_millis(60));
+    stats.observe(&fake::chat_message(1, "alice", "second"));
+
+    let snapshot = stats.snapshot();
+    assert_eq!(snapshot.total_messages, 2);
+    assert_eq!(snapshot.messages_per_second, 1.0);
+}
+
+#[test]
+fn reset_keeps_the_settings() {
+    let mut stats = ChatStats::new().with_top(1);
+    stats.observe(&fake::chat_message(1, "alice", "hi"));
+    stats.observe(&fake::chat_message(1, "bob", "hi"));
+    stats.reset();
+    assert_eq!(stats.snapshot().total_messages, 0);
+    assert_eq!(stats.snapshot().unique_chatters, 0);
+
+    stats.observe(&fake::chat_message(1, "alice", "hi"));
+    stats.observe(&fake::chat_message(1, "bob", "hi"));
+    assert_eq!(stats.snapshot().top_chatters.len(), 1);
+}
@@ -0,0 +1,67 @@
+use kick_client::fake;
+use kick_client::metrics::Metrics;
+use kick_client::statsd::StatsdEmitter;
+use kick_client::MessageData;
+use std::net::UdpSocket;
+use std::time::Duration;
+
+/// Binds an agent socket on localhost, returning it with its address.
+fn agent() -> (UdpSocket, String) {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let addr = socket.local_addr().unwrap().to_string();
+    (socket, addr)
+}
+
+fn receive(socket: &UdpSocket) -> String {
+    let mut buf = [0; 512];
+    let len = socket.recv(&mut buf).unwrap();
+    String::from_utf8(buf[..len].to_vec()).unwrap()
+}
+
+#[test]
+fn dogstatsd_tags_messages_by_kind() {
+    let (socket, addr) = agent();
+    let emitter = StatsdEmitter::dogstatsd(addr)
+        .unwrap()
+        .with_tag("env", "test");
+    let metrics = Metrics::new().with_statsd(emitter);
+
+    metrics.observe(&fake::message(
+        1234,
+        MessageData::Unsupported(None, "bad frame".to_string()),
+    ));
+    metrics.record_latency(Duration::from_millis(250));
+
+    assert_eq!(
+        receive(&socket),
+        "kick_client.messages:1|c|#env:test,kind:unsupported"
+    );
+    assert_eq!(
+        receive(&socket),
+        "kick_client.unsupported_messages:1|c|#env:test"
+    );
+    assert_eq!(receive(&socket), "kick_client.latency:250|ms|#env:test");
+    metrics.record_dropped(3);
+    assert_eq!(receive(&socket), "kick_client.dropped:3|c|#env:test");
+    assert_eq!(metrics.snapshot().dropped, 3);
+    assert_eq!(metrics.snapshot().unsupported_messages, 1);
+}
+
+#[test]
+fn plain_statsd_appends_kind_to_the_name() {
+    let (socket, addr) = agent();
+    let emitter = StatsdEmitter::connect(addr)
+        .unwrap()
+        .with_prefix("bot")
+        .with_tag("env", "test");
+    let metrics = Metrics::new().with_statsd(emitter.clone());
+
+    metrics.observe(&fake::chatroom_clear(1234));
+    emitter.gauge("chatrooms", 3, &[]);
+
+    assert_eq!(receive(&socket), "bot.messages.clear:1|c");
+    assert_eq!(receive(&socket), "bot.chatrooms:3|g");
+}
@@ -0,0 +1,48 @@
+use kick_client::mock_server::MockPusherServer;
+use kick_client::KickClient;
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Records the names of the spans created.
+#[derive(Clone, Default)]
+struct SpanNames(Arc<Mutex<Vec<&'static str>>>);
+
+impl Subscriber for SpanNames {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut names = self.0.lock().unwrap();
+        names.push(span.metadata().name());
+        Id::from_u64(names.len() as u64)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[tokio::test]
+async fn connecting_and_reading_run_in_spans() {
+    let server = MockPusherServer::start().await.unwrap();
+    let names = SpanNames::default();
+    let _default = tracing::subscriber::set_default(names.clone());
+
+    let mut client: KickClient = KickClient::connect(&server.url(), vec![1234])
+        .await
+        .unwrap();
+    client.read_message().await.unwrap().unwrap();
+
+    let names = names.0.lock().unwrap();
+    for name in ["connect", "subscribe", "read", "parse"] {
+        assert!(names.contains(&name), "no {} span in {:?}", name, names);
+    }
+}
@@ -0,0 +1,93 @@
+use kick_client::api::{HttpRequest, HttpResponse, HttpTransport, KickApi};
+use kick_client::KickError;
+use std::sync::{Arc, Mutex};
+
+/// Adds the clearance cookie and user agent Cloudflare expects, keeping the requests.
+#[derive(Clone, Default)]
+struct Cloudflare {
+    requests: Arc<Mutex<Vec<HttpRequest>>>,
+}
+
+impl HttpTransport for Cloudflare {
+    async fn send(&self, mut request: HttpRequest) -> Result<HttpResponse, KickError> {
+        request
+            .headers
+            .push(("User-Agent".to_string(), "Mozilla/5.0".to_string()));
+        request
+            .headers
+            .push(("Cookie".to_string(), "cf_clearance=cleared".to_string()));
+        self.requests.lock().unwrap().push(request);
+        Ok(HttpResponse {
+            status: 200,
+            headers: Vec::new(),
+            body: br#"{"auth":"app-key:signature"}"#.to_vec(),
+        })
+    }
+}
+
+/// Fails like a connection refused by the proxy.
+struct Unreachable;
+
+impl HttpTransport for Unreachable {
+    async fn send(&self, _request: HttpRequest) -> Result<HttpResponse, KickError> {
+        Err(KickError::HttpError(Box::new(std::io::Error::new(
+            std::io::ErrorKind::ConnectionRefused,
+            "proxy refused the connection",
+        ))))
+    }
+}
+
+fn header<'a>(request: &'a HttpRequest, name: &str) -> Option<&'a str> {
+    request
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+#[tokio::test]
+async fn requests_go_through_the_injected_transport() {
+    let transport = Cloudflare::default();
+    let api = KickApi::new("token").with_transport(transport.clone());
+
+    api.send_message(1234, "hello").await.unwrap();
+    // Channel authorizers share the transport of their API.
+    let auth = api
+        .channel_authorizer()
+        .authorize("1234.5678", "private-chatroom_1234")
+        .await
+        .unwrap();
+    assert_eq!(auth, "app-key:signature");
+
+    let requests = transport.requests.lock().unwrap();
+    let urls: Vec<_> = requests
+        .iter()
+        .map(|request| request.url.as_str())
+        .collect();
+    assert_eq!(
+        urls,
+        [
+            "https://kick.com/api/v2/messages/send/1234",
+            "https://kick.com/broadcasting/auth",
+        ]
+    );
+    for request in requests.iter() {
+        assert_eq!(request.method, reqwest::Method::POST);
+        assert_eq!(header(request, "Authorization"), Some("Bearer token"));
+        assert_eq!(header(request, "Accept"), Some("application/json"));
+        assert_eq!(header(request, "Content-Type"), Some("application/json"));
+        assert_eq!(header(request, "User-Agent"), Some("Mozilla/5.0"));
+        assert_eq!(header(request, "Cookie"), Some("cf_clearance=cleared"));
+    }
+}
+
+#[tokio::test]
+async fn transport_errors_are_returned() {
+    let api = KickApi::new("token").with_transport(Unreachable);
+
+    let error = api.send_message(1234, "hello").await.unwrap_err();
+    assert!(
+        matches!(&error, KickError::HttpError(err) if err.to_string() == "proxy refused the connection"),
+        "{error:?}"
+    );
+}
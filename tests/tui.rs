@@ -0,0 +1,73 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use kick_client::fake;
+use kick_client::tui::ChatViewer;
+use kick_client::MessageData;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+fn press(viewer: &mut ChatViewer, code: KeyCode) {
+    viewer.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+}
+
+fn contents(viewer: &ChatViewer) -> Vec<String> {
+    viewer
+        .visible()
+        .map(|message| match &message.data {
+            MessageData::ChatMessage(data) => data.content.clone().unwrap_or_default(),
+            _ => message.to_string(),
+        })
+        .collect()
+}
+
+#[test]
+fn tabs_pause_and_search_filter_the_view() {
+    let mut viewer = ChatViewer::new();
+    viewer.push(fake::chat_message(1, "Alice", "hello from one"));
+    viewer.push(fake::chat_message(2, "Bob", "hello from two"));
+    assert_eq!(contents(&viewer), ["hello from one", "hello from two"]);
+
+    press(&mut viewer, KeyCode::Tab);
+    assert_eq!(viewer.selected_chatroom(), Some(1));
+    assert_eq!(contents(&viewer), ["hello from one"]);
+    press(&mut viewer, KeyCode::Tab);
+    press(&mut viewer, KeyCode::Tab);
+    assert_eq!(viewer.selected_chatroom(), None);
+
+    press(&mut viewer, KeyCode::Char('p'));
+    viewer.push(fake::chat_message(1, "Alice", "while paused"));
+    assert!(viewer.is_paused());
+    assert_eq!(contents(&viewer).len(), 2);
+    press(&mut viewer, KeyCode::Char('p'));
+    assert_eq!(contents(&viewer).len(), 3);
+
+    press(&mut viewer, KeyCode::Char('/'));
+    for c in "bob".chars() {
+        press(&mut viewer, KeyCode::Char(c));
+    }
+    press(&mut viewer, KeyCode::Enter);
+    assert_eq!(contents(&viewer), ["hello from two"]);
+    press(&mut viewer, KeyCode::Esc);
+    assert_eq!(contents(&viewer).len(), 3);
+
+    press(&mut viewer, KeyCode::Char('q'));
+    assert!(viewer.is_done());
+}
+
+#[test]
+fn draws_the_latest_messages() {
+    let mut viewer = ChatViewer::new().with_scrollback(50);
+    for i in 0..100 {
+        viewer.push(fake::chat_message(1, "Alice", &format!("message {i}")));
+    }
+    let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    terminal.draw(|frame| viewer.draw(frame)).unwrap();
+    let screen = format!("{:?}", terminal.backend().buffer());
+    assert!(screen.contains("message 99"));
+    assert!(!screen.contains("message 90"));
+
+    press(&mut viewer, KeyCode::Home);
+    terminal.draw(|frame| viewer.draw(frame)).unwrap();
+    let screen = format!("{:?}", terminal.backend().buffer());
+    assert!(screen.contains("message 50"));
+    assert!(!screen.contains("message 49"));
+}
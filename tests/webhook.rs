@@ -0,0 +1,320 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use kick_client::webhook::{
+    parse_event, WebhookListener, WebhookVerifier, EVENT_TYPE_HEADER, MESSAGE_ID_HEADER,
+    SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use kick_client::{ChatMessageSenderBadge, KickError, MessageData, MessageSource};
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::{EncodePublicKey, LineEnding};
+use rsa::signature::{SignatureEncoding, Signer};
+use rsa::RsaPrivateKey;
+use sha2::Sha256;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const MESSAGE_ID: &str = "01JQ5Z7Y8Z0000000000000000";
+const TIMESTAMP: &str = "2025-03-01T12:00:00Z";
+const BODY: &[u8] = br#"{"hello":"world"}"#;
+
+/// Returns a key generated for the tests, shared as generating one is slow.
+fn key() -> &'static RsaPrivateKey {
+    static KEY: OnceLock<RsaPrivateKey> = OnceLock::new();
+    KEY.get_or_init(|| RsaPrivateKey::new(&mut rand::thread_rng(), 1024).unwrap())
+}
+
+fn verifier() -> WebhookVerifier {
+    let pem = key()
+        .to_public_key()
+        .to_public_key_pem(LineEnding::LF)
+        .unwrap();
+    WebhookVerifier::from_pem(&pem).unwrap()
+}
+
+/// Signs an event as Kick does, returning the base64-encoded signature.
+fn sign(message_id: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut signed = format!("{message_id}.{timestamp}.").into_bytes();
+    signed.extend_from_slice(body);
+    let signature = SigningKey::<Sha256>::new(key().clone()).sign(&signed);
+    STANDARD.encode(signature.to_bytes())
+}
+
+fn is_signature_error(result: Result<(), KickError>) -> bool {
+    matches!(result, Err(KickError::SignatureError(_)))
+}
+
+/// Posts an event to the listener, returning the whole response.
+async fn post(listener: &WebhookListener, headers: &[(&str, &str)], body: &[u8]) -> String {
+    let mut stream = TcpStream::connect(listener.local_addr()).await.unwrap();
+    let mut request = format!(
+        "POST /kick HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        request += &format!("{name}: {value}\r\n");
+    }
+    request += "\r\n";
+    stream.write_all(request.as_bytes()).await.unwrap();
+    stream.write_all(body).await.unwrap();
+    let mut response = String::new();
+    tokio::time::timeout(Duration::from_secs(5), stream.read_to_string(&mut response))
+        .await
+        .expect("response within 5 seconds")
+        .unwrap();
+    response
+}
+
+#[test]
+fn valid_signatures_are_accepted() {
+    let signature = sign(MESSAGE_ID, TIMESTAMP, BODY);
+    verifier()
+        .verify(MESSAGE_ID, TIMESTAMP, BODY, &signature)
+        .unwrap();
+}
+
+#[test]
+fn tampered_events_are_rejected() {
+    let verifier = verifier();
+    let signature = sign(MESSAGE_ID, TIMESTAMP, BODY);
+    assert!(is_signature_error(verifier.verify(
+        MESSAGE_ID,
+        TIMESTAMP,
+        br#"{"hello":"mallory"}"#,
+        &signature
+    )));
+    assert!(is_signature_error(verifier.verify(
+        MESSAGE_ID,
+        "2025-03-01T12:00:01Z",
+        BODY,
+        &signature
+    )));
+    assert!(is_signature_error(verifier.verify(
+        "01JQ5Z7Y8Z0000000000000001",
+        TIMESTAMP,
+        BODY,
+        &signature
+    )));
+}
+
+#[test]
+fn malformed_signatures_are_rejected() {
+    let verifier = verifier();
+    assert!(is_signature_error(verifier.verify(
+        MESSAGE_ID,
+        TIMESTAMP,
+        BODY,
+        "not base64!"
+    )));
+    assert!(is_signature_error(verifier.verify(
+        MESSAGE_ID,
+        TIMESTAMP,
+        BODY,
+        &STANDARD.encode(b"too short")
+    )));
+    assert!(WebhookVerifier::from_pem("not a key").is_err());
+}
+
+#[test]
+fn headers_are_verified() {
+    let verifier = verifier();
+    let signature = sign(MESSAGE_ID, TIMESTAMP, BODY);
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(MESSAGE_ID_HEADER, MESSAGE_ID.parse().unwrap());
+    headers.insert(TIMESTAMP_HEADER, TIMESTAMP.parse().unwrap());
+    headers.insert(SIGNATURE_HEADER, signature.parse().unwrap());
+    verifier.verify_headers(&headers, BODY).unwrap();
+
+    headers.remove(TIMESTAMP_HEADER);
+    let error = verifier.verify_headers(&headers, BODY).unwrap_err();
+    assert!(
+        error.to_string().contains(TIMESTAMP_HEADER),
+        "unexpected {error}"
+    );
+}
+
+#[tokio::test]
+async fn the_listener_rejects_unsigned_events() {
+    let listener = WebhookListener::bind("127.0.0.1:0", "/kick", Some(verifier()))
+        .await
+        .unwrap();
+    let unsigned = post(
+        &listener,
+        &[
+            (EVENT_TYPE_HEADER, "chat.message.sent"),
+            (MESSAGE_ID_HEADER, MESSAGE_ID),
+            (TIMESTAMP_HEADER, TIMESTAMP),
+        ],
+        BODY,
+    )
+    .await;
+    assert!(unsigned.starts_with("HTTP/1.1 401"), "{unsigned}");
+
+    let forged = sign(MESSAGE_ID, TIMESTAMP, b"{}");
+    let response = post(
+        &listener,
+        &[
+            (EVENT_TYPE_HEADER, "chat.message.sent"),
+            (MESSAGE_ID_HEADER, MESSAGE_ID),
+            (TIMESTAMP_HEADER, TIMESTAMP),
+            (SIGNATURE_HEADER, &forged),
+        ],
+        BODY,
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 401"), "{response}");
+
+    let signature = sign(MESSAGE_ID, TIMESTAMP, BODY);
+    let response = post(
+        &listener,
+        &[
+            (EVENT_TYPE_HEADER, "kicks.gifted"),
+            (MESSAGE_ID_HEADER, MESSAGE_ID),
+            (TIMESTAMP_HEADER, TIMESTAMP),
+            (SIGNATURE_HEADER, &signature),
+        ],
+        BODY,
+    )
+    .await;
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+}
+
+/// Returns the fixture payload of a webhook event type.
+fn payload(event_type: &str) -> String {
+    let path = format!(
+        "{}/tests/fixtures/webhook/{event_type}.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    std::fs::read_to_string(path).unwrap()
+}
+
+#[test]
+fn chat_messages_are_parsed() {
+    let message =
+        parse_event("chat.message.sent", payload("chat.message.sent").as_bytes()).unwrap();
+    assert_eq!(message.channel.as_deref(), Some("xqc"));
+    let MessageData::ChatMessage(data) = message.data else {
+        panic!("expected ChatMessage, got {:?}", message.data);
+    };
+    assert_eq!(data.id, "01JHKJ8W3Q6P5Z2X3Y4V5B6N7M");
+    assert_eq!(data.chatroom_id, 668);
+    assert_eq!(
+        data.content.as_deref(),
+        Some("hello chat [emote:37226:KEKW]")
+    );
+    assert_eq!(data.created_at.as_deref(), Some("2025-01-14T16:08:06Z"));
+    assert_eq!(data.sender.id, 1447541);
+    assert_eq!(data.sender.username, "SomeViewer");
+    assert_eq!(data.sender.slug.as_deref(), Some("someviewer"));
+    assert_eq!(data.sender.identity.color.as_deref(), Some("#E9113C"));
+    assert!(matches!(
+        &data.sender.identity.badges[..],
+        [
+            ChatMessageSenderBadge::SimpleBadge { r#type: moderator, .. },
+            ChatMessageSenderBadge::FullBadge { r#type: subscriber, count: Some(3), .. },
+        ] if moderator == "moderator" && subscriber == "subscriber"
+    ));
+}
+
+#[test]
+fn channel_events_are_parsed() {
+    let follow = parse_event("channel.followed", payload("channel.followed").as_bytes()).unwrap();
+    let MessageData::ChannelFollowed(data) = follow.data else {
+        panic!("expected ChannelFollowed, got {:?}", follow.data);
+    };
+    assert_eq!(data.broadcaster_user_id, 668);
+    assert_eq!(data.follower_user_id, 1447541);
+    assert_eq!(data.follower_username, "SomeViewer");
+
+    for (event_type, months) in [
+        ("channel.subscription.new", 1),
+        ("channel.subscription.renewal", 3),
+    ] {
+        let subscription = parse_event(event_type, payload(event_type).as_bytes()).unwrap();
+        let MessageData::SubscriptionEvent(data) = subscription.data else {
+            panic!("expected SubscriptionEvent, got {:?}", subscription.data);
+        };
+        assert_eq!(data.chatroom_id, 668);
+        assert_eq!(data.username, "SomeViewer");
+        assert_eq!(data.months, months);
+    }
+
+    let gifts = parse_event(
+        "channel.subscription.gifts",
+        payload("channel.subscription.gifts").as_bytes(),
+    )
+    .unwrap();
+    let MessageData::GiftedSubscriptions(data) = gifts.data else {
+        panic!("expected GiftedSubscriptions, got {:?}", gifts.data);
+    };
+    assert_eq!(data.gifted_usernames, ["LuckyOne", "LuckyTwo"]);
+    assert_eq!(data.gifter_username.as_deref(), Some("SomeViewer"));
+
+    let live = parse_event(
+        "livestream.status.updated",
+        payload("livestream.status.updated").as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(live.channel.as_deref(), Some("xqc"));
+    let MessageData::LivestreamStatusUpdated(data) = live.data else {
+        panic!("expected LivestreamStatusUpdated, got {:?}", live.data);
+    };
+    assert!(data.is_live);
+    assert_eq!(data.broadcaster_username, "xQc");
+    assert_eq!(data.title, "JUST CHATTING");
+    assert_eq!(data.started_at.as_deref(), Some("2025-01-14T16:00:00Z"));
+    assert_eq!(data.ended_at, None);
+}
+
+#[test]
+fn unknown_events_are_kept() {
+    let message = parse_event("kicks.gifted", br#"{"amount":100}"#).unwrap();
+    let MessageData::Unknown(Some(data)) = message.data else {
+        panic!("expected Unknown, got {:?}", message.data);
+    };
+    assert_eq!(data.event, "kicks.gifted");
+    assert_eq!(data.data, serde_json::json!({ "amount": 100 }));
+
+    let message = parse_event("kicks.gifted", b"not json").unwrap();
+    let MessageData::Unknown(Some(data)) = message.data else {
+        panic!("expected Unknown, got {:?}", message.data);
+    };
+    assert_eq!(data.data, serde_json::json!("not json"));
+
+    assert!(parse_event("channel.followed", b"{}").is_err());
+}
+
+#[tokio::test]
+async fn the_listener_delivers_events() {
+    let mut listener = WebhookListener::bind("127.0.0.1:0", "/kick", None)
+        .await
+        .unwrap();
+    for event_type in ["chat.message.sent", "kicks.gifted", "channel.followed"] {
+        let body = match event_type {
+            "kicks.gifted" => r#"{"amount":100}"#.to_string(),
+            // Doesn't match the event type, so it is delivered as unsupported.
+            "channel.followed" => "{}".to_string(),
+            event_type => payload(event_type),
+        };
+        let response = post(
+            &listener,
+            &[(EVENT_TYPE_HEADER, event_type)],
+            body.as_bytes(),
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    }
+    let response = post(&listener, &[], b"{}").await;
+    assert!(response.starts_with("HTTP/1.1 400"), "{response}");
+
+    let kinds: Vec<&str> = [
+        listener.read_message().await.unwrap().unwrap(),
+        listener.read_message().await.unwrap().unwrap(),
+        listener.read_message().await.unwrap().unwrap(),
+    ]
+    .iter()
+    .map(|message| message.data.kind())
+    .collect();
+    assert_eq!(kinds, ["chat", "unknown", "unsupported"]);
+}